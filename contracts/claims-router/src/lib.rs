@@ -0,0 +1,444 @@
+//! Stellarcade Claims Router Contract
+//!
+//! Lets a player collect winnings from every registered game contract in one
+//! transaction. Each game implements the standard `ClaimableGame` interface:
+//! `preview_payout(player)` reports the total currently claimable and
+//! `claim_payout(player)` pays it out. `claim_all` queries every registered
+//! game and claims from those with a non-zero preview, so a single player
+//! signature over the invocation tree authorizes all of the claims. A game
+//! whose calls fail is skipped, so one broken game cannot block the others.
+//! Speed-trivia implements the interface.
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, Address,
+    Env, Vec,
+};
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Maximum number of registered games (bounds the per-call iteration).
+pub const MAX_GAMES: u32 = 20;
+
+// ---------------------------------------------------------------------------
+// External contract clients
+// ---------------------------------------------------------------------------
+
+/// Interface a game contract implements to be claimable through the router.
+#[contractclient(name = "ClaimableGameClient")]
+pub trait ClaimableGame {
+    /// Total amount `player` could claim right now.
+    fn preview_payout(env: Env, player: Address) -> i128;
+    /// Pay out everything `player` can claim and return the amount paid.
+    fn claim_payout(env: Env, player: Address) -> i128;
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    NotAuthorized = 3,
+    GameAlreadyRegistered = 4,
+    GameNotRegistered = 5,
+    TooManyGames = 6,
+    NothingToClaim = 7,
+    Overflow = 8,
+}
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    /// Registered game contracts (Vec<Address>).
+    Games,
+}
+
+/// Claimable amount reported by one game.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimPreview {
+    pub game: Address,
+    pub amount: i128,
+}
+
+// ---------------------------------------------------------------------------
+// Events
+// ---------------------------------------------------------------------------
+
+#[contractevent]
+pub struct GameRegistered {
+    #[topic]
+    pub game: Address,
+}
+
+#[contractevent]
+pub struct GameUnregistered {
+    #[topic]
+    pub game: Address,
+}
+
+#[contractevent]
+pub struct PayoutRouted {
+    #[topic]
+    pub player: Address,
+    #[topic]
+    pub game: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct ClaimSkipped {
+    #[topic]
+    pub player: Address,
+    #[topic]
+    pub game: Address,
+}
+
+#[contractevent]
+pub struct ClaimsAggregated {
+    #[topic]
+    pub player: Address,
+    pub total: i128,
+    pub games_claimed: u32,
+}
+
+// ---------------------------------------------------------------------------
+// Contract
+// ---------------------------------------------------------------------------
+
+#[contract]
+pub struct ClaimsRouter;
+
+#[contractimpl]
+impl ClaimsRouter {
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::Games, &Vec::<Address>::new(&env));
+        Ok(())
+    }
+
+    /// Add a game contract to the router. Admin only.
+    pub fn register_game(env: Env, game: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+
+        let mut games = get_games(&env);
+        if games.contains(&game) {
+            return Err(Error::GameAlreadyRegistered);
+        }
+        if games.len() >= MAX_GAMES {
+            return Err(Error::TooManyGames);
+        }
+        games.push_back(game.clone());
+        env.storage().instance().set(&DataKey::Games, &games);
+
+        GameRegistered { game }.publish(&env);
+        Ok(())
+    }
+
+    /// Remove a game contract from the router. Admin only.
+    pub fn unregister_game(env: Env, game: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+
+        let mut games = get_games(&env);
+        let index = games
+            .first_index_of(&game)
+            .ok_or(Error::GameNotRegistered)?;
+        games.remove(index);
+        env.storage().instance().set(&DataKey::Games, &games);
+
+        GameUnregistered { game }.publish(&env);
+        Ok(())
+    }
+
+    /// Claimable amounts for `player`, one entry per game with a non-zero
+    /// preview. Games whose preview fails are left out.
+    pub fn preview_claims(env: Env, player: Address) -> Result<Vec<ClaimPreview>, Error> {
+        require_initialized(&env)?;
+
+        let mut previews = Vec::new(&env);
+        for game in get_games(&env).iter() {
+            let amount = preview(&env, &game, &player);
+            if amount > 0 {
+                previews.push_back(ClaimPreview { game, amount });
+            }
+        }
+        Ok(previews)
+    }
+
+    /// Claim from every registered game with a non-zero preview and return
+    /// the total paid. Player must authorize; the authorization covers the
+    /// nested `claim_payout` calls. A game whose claim fails is skipped and
+    /// its state rolled back, with a `ClaimSkipped` event.
+    ///
+    /// Emits `PayoutRouted` per game and `ClaimsAggregated` once.
+    pub fn claim_all(env: Env, player: Address) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let mut total: i128 = 0;
+        let mut games_claimed: u32 = 0;
+        for game in get_games(&env).iter() {
+            if preview(&env, &game, &player) <= 0 {
+                continue;
+            }
+            let amount = match ClaimableGameClient::new(&env, &game).try_claim_payout(&player) {
+                Ok(Ok(amount)) => amount,
+                _ => {
+                    ClaimSkipped {
+                        player: player.clone(),
+                        game,
+                    }
+                    .publish(&env);
+                    continue;
+                }
+            };
+            total = total.checked_add(amount).ok_or(Error::Overflow)?;
+            games_claimed = games_claimed.checked_add(1).ok_or(Error::Overflow)?;
+
+            PayoutRouted {
+                player: player.clone(),
+                game,
+                amount,
+            }
+            .publish(&env);
+        }
+
+        if games_claimed == 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        ClaimsAggregated {
+            player,
+            total,
+            games_claimed,
+        }
+        .publish(&env);
+
+        Ok(total)
+    }
+
+    pub fn get_games(env: Env) -> Vec<Address> {
+        get_games(&env)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn require_initialized(env: &Env) -> Result<(), Error> {
+    if !env.storage().instance().has(&DataKey::Admin) {
+        return Err(Error::NotInitialized);
+    }
+    Ok(())
+}
+
+fn require_admin(env: &Env) -> Result<(), Error> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)?;
+    admin.require_auth();
+    Ok(())
+}
+
+/// `game`'s preview for `player`, or 0 if the call fails.
+fn preview(env: &Env, game: &Address, player: &Address) -> i128 {
+    match ClaimableGameClient::new(env, game).try_preview_payout(player) {
+        Ok(Ok(amount)) => amount,
+        _ => 0,
+    }
+}
+
+fn get_games(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Games)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, Address, Env};
+
+    // -----------------------------
+    // Mock game contract
+    // -----------------------------
+
+    #[contract]
+    pub struct MockGame;
+
+    #[contracttype]
+    pub enum MockKey {
+        Owed(Address),
+        Paid(Address),
+        Broken,
+    }
+
+    #[contractimpl]
+    impl MockGame {
+        pub fn set_owed(env: Env, player: Address, amount: i128) {
+            env.storage()
+                .persistent()
+                .set(&MockKey::Owed(player), &amount);
+        }
+
+        pub fn set_broken(env: Env) {
+            env.storage().instance().set(&MockKey::Broken, &true);
+        }
+
+        pub fn paid(env: Env, player: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&MockKey::Paid(player))
+                .unwrap_or(0)
+        }
+
+        pub fn preview_payout(env: Env, player: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&MockKey::Owed(player))
+                .unwrap_or(0)
+        }
+
+        pub fn claim_payout(env: Env, player: Address) -> i128 {
+            player.require_auth();
+            if env.storage().instance().has(&MockKey::Broken) {
+                panic!("game unavailable");
+            }
+            let owed: i128 = env
+                .storage()
+                .persistent()
+                .get(&MockKey::Owed(player.clone()))
+                .unwrap_or(0);
+            env.storage()
+                .persistent()
+                .set(&MockKey::Owed(player.clone()), &0i128);
+            env.storage()
+                .persistent()
+                .set(&MockKey::Paid(player), &owed);
+            owed
+        }
+    }
+
+    fn setup(
+        env: &Env,
+    ) -> (
+        ClaimsRouterClient<'_>,
+        MockGameClient<'_>,
+        MockGameClient<'_>,
+    ) {
+        env.mock_all_auths();
+
+        let router_id = env.register(ClaimsRouter, ());
+        let router = ClaimsRouterClient::new(env, &router_id);
+        router.init(&Address::generate(env));
+
+        let game_a = MockGameClient::new(env, &env.register(MockGame, ()));
+        let game_b = MockGameClient::new(env, &env.register(MockGame, ()));
+        router.register_game(&game_a.address);
+        router.register_game(&game_b.address);
+
+        (router, game_a, game_b)
+    }
+
+    #[test]
+    fn test_preview_and_claim_all() {
+        let env = Env::default();
+        let (router, game_a, game_b) = setup(&env);
+        let player = Address::generate(&env);
+
+        game_a.set_owed(&player, &150);
+        game_b.set_owed(&player, &0);
+
+        let previews = router.preview_claims(&player);
+        assert_eq!(previews.len(), 1);
+        assert_eq!(previews.get(0).unwrap().game, game_a.address);
+        assert_eq!(previews.get(0).unwrap().amount, 150);
+
+        game_b.set_owed(&player, &50);
+        assert_eq!(router.claim_all(&player), 200);
+        assert_eq!(game_a.paid(&player), 150);
+        assert_eq!(game_b.paid(&player), 50);
+
+        let again = router.try_claim_all(&player);
+        assert_eq!(again, Err(Ok(Error::NothingToClaim)));
+    }
+
+    #[test]
+    fn test_register_and_unregister_games() {
+        let env = Env::default();
+        let (router, game_a, game_b) = setup(&env);
+
+        let dup = router.try_register_game(&game_a.address);
+        assert_eq!(dup, Err(Ok(Error::GameAlreadyRegistered)));
+
+        router.unregister_game(&game_a.address);
+        assert_eq!(router.get_games().len(), 1);
+        assert_eq!(router.get_games().get(0).unwrap(), game_b.address);
+
+        let missing = router.try_unregister_game(&game_a.address);
+        assert_eq!(missing, Err(Ok(Error::GameNotRegistered)));
+
+        // Claims skip games that are no longer registered.
+        let player = Address::generate(&env);
+        game_a.set_owed(&player, &75);
+        assert_eq!(
+            router.try_claim_all(&player),
+            Err(Ok(Error::NothingToClaim))
+        );
+    }
+
+    #[test]
+    fn test_game_limit_enforced() {
+        let env = Env::default();
+        let (router, _, _) = setup(&env);
+
+        for _ in 2..MAX_GAMES {
+            router.register_game(&Address::generate(&env));
+        }
+        let full = router.try_register_game(&Address::generate(&env));
+        assert_eq!(full, Err(Ok(Error::TooManyGames)));
+    }
+
+    #[test]
+    fn test_failing_game_is_skipped() {
+        let env = Env::default();
+        let (router, game_a, game_b) = setup(&env);
+        let player = Address::generate(&env);
+
+        game_a.set_owed(&player, &150);
+        game_a.set_broken();
+        game_b.set_owed(&player, &50);
+
+        assert_eq!(router.claim_all(&player), 50);
+        assert_eq!(game_a.paid(&player), 0);
+        assert_eq!(game_a.preview_payout(&player), 150);
+        assert_eq!(game_b.paid(&player), 50);
+    }
+}
@@ -7,33 +7,175 @@
 //! ## Game Flow
 //! 1. Admin calls `init` to configure the contract.
 //! 2. Player calls `place_prediction(player, color, wager, game_id)` to enter.
-//!    Multiple players can predict on the same game_id. Each player may only
-//!    submit one prediction per game.
+//!    Multiple players can predict on the same game_id. Each player may pick
+//!    only one color per game; repeating the same color tops up their stake.
 //! 3. Admin calls `resolve_prediction(game_id)` with the winning color.
-//!    Winners are determined and the pot split equally among correct predictors.
-//! 4. Anyone calls `get_game(game_id)` to inspect the final state.
+//!    Winners are counted; no payouts are pushed during resolution.
+//! 4. Each winner calls `claim(player, game_id)` to collect a share of the
+//!    pot proportional to their wager: `(pot - fee) * wager / winning_total`.
+//!    The last winner to claim also receives any rounding remainder.
+//! 5. Anyone calls `get_game(game_id)` to inspect the final state.
+//!
+//! ## Split Bets
+//! `place_split_prediction(player, splits, game_id)` spreads one wager over
+//! several colors in a single call, stored as one `PredictionEntry` per
+//! color. The player counts once toward `player_count` and once toward each
+//! chosen color's `color_counts`. A split is final: it cannot be topped up,
+//! and a player with a single-color prediction cannot split. At resolution
+//! the leg on the winning color (if any) is claimed like any other winning
+//! prediction; on cancellation every leg is refunded together.
+//!
+//! ## Escrow
+//! Wagers are debited from the player in the balance contract and held in
+//! this contract's own account there. Claims, refunds and fee withdrawals are
+//! paid back out of that account.
+//!
+//! ## Per-Game Tokens
+//! Games settle in the platform token held by `BalanceContract` unless the
+//! admin opens them with `open_token_game(game_id, close_time, token)`, which
+//! names another balance/token contract for that game, e.g. for promotional
+//! games in a partner asset. Escrow, claims and refunds of the game all go
+//! through its token. The jackpot and staking boost are funded in the
+//! platform token, so token games neither feed nor win the jackpot and pay
+//! no staking bonus: their house fee, and the whole pot if nobody wins,
+//! accrue to a per-token fee bucket withdrawn with `withdraw_token_fees`.
+//!
+//! ## Sponsored Pots
+//! Anyone may boost an existing game with `sponsor_pot(sponsor, game_id,
+//! amount)` while betting is open. The amount is escrowed in the game's
+//! token and added to `total_pot`, so it is shared among the winners (or
+//! rolls into the jackpot) exactly like wagers, and is subject to the house
+//! fee. Each sponsor's contribution is recorded for attribution. If the game
+//! is cancelled, sponsors recover their contribution with
+//! `refund_sponsorship(sponsor, game_id)`.
+//!
+//! ## Delegated Resolution
+//! The admin may allow any number of resolver addresses with
+//! `set_resolver(resolver, true)`. A resolver can settle games through
+//! `resolve_prediction_as(resolver, game_id, winning_color)` and nothing
+//! else: configuration, cancellation and fee withdrawal stay admin only, so a
+//! fleet of resolution bots can run frequent rounds while the admin key
+//! stays offline.
+//!
+//! ## Cancellation
+//! If a game cannot be resolved (the admin never resolves or the RNG fails),
+//! the admin calls `cancel_game(game_id)` to mark it `Cancelled`. Each player
+//! then calls `refund(player, game_id)` to recover their full wager.
+//!
+//! To protect funds from an absent admin, a game that is still `Open`
+//! `resolution_timeout` seconds after its `close_time` is considered expired:
+//! any of its players may call `claim_refund(player, game_id)`, which marks
+//! the game `Expired` and refunds their wager. Games without a deadline never
+//! expire.
 //!
 //! ## Colors
-//! Valid color values: 0 = Red, 1 = Green, 2 = Blue, 3 = Yellow.
+//! The number of outcomes is set once at `init` (`outcome_count`, 2–16) and
+//! valid color values are `0..outcome_count`. The first four are named
+//! 0 = Red, 1 = Green, 2 = Blue, 3 = Yellow; larger counts suit wheel-style
+//! games with more segments.
+//!
+//! ## Betting Window
+//! The admin may call `open_game(game_id, close_time)` to create a game with a
+//! deadline. `place_prediction` rejects predictions once the ledger timestamp
+//! reaches `close_time`. Games created implicitly by the first prediction have
+//! no deadline (`close_time == 0`).
+//!
+//! ## House Fee
+//! When a game with at least one winner is resolved, `house_edge_bps` of the
+//! pot is deducted as a house fee and accrued to the fee bucket. The admin
+//! calls `withdraw_fees` to route accrued fees to the fee recipient.
+//!
+//! ## Pot Milestones
+//! The admin may set a milestone step with `set_pot_milestone_step` (e.g.
+//! 10 000 tokens). Whenever a prediction, split prediction or sponsorship
+//! lifts a game's pot across a multiple of the step, `PotMilestone` is
+//! emitted with the highest multiple crossed, so off-chain services can react
+//! to pot growth from events alone.
+//!
+//! ## Commit-Reveal
+//! To prove the winning color was fixed before seeing the bets, the admin may
+//! call `commit_result(game_id, hash)` on a game opened with a `close_time`
+//! while betting is still open, where `hash = sha256(color_be_bytes || salt)`
+//! (4-byte big-endian color followed by a 32-byte salt). The game is then
+//! settled with `reveal_result(game_id, color, salt)`, which checks the
+//! preimage against the commitment; `resolve_prediction` is refused for
+//! committed games.
+//!
+//! ## Jackpot Rollover
+//! When a game resolves with no winners its whole pot rolls into the jackpot
+//! pool (`JackpotRolledOver`). The next game resolved with at least one
+//! winner adds the jackpot to its pot before the house fee is taken and
+//! shares it among its winners (`JackpotAwarded`). `get_jackpot` returns the
+//! current pool size.
+//!
+//! ## Weighted Games
+//! The admin may call `open_weighted_game(game_id, close_time, weights)` to
+//! create a game whose winning color is drawn at random, with one weight per
+//! color (e.g. 40/30/20/10). The weights are stored and published in
+//! `WeightsCommitted` before any bet is placed, so house-edge variants stay
+//! verifiable. Once the RNG contract has a result for the game,
+//! `resolve_weighted(game_id)` maps `result % total_weight` onto the
+//! cumulative weights to pick the color; manual resolution is refused.
 //!
 //! ## Storage Strategy
 //! - `instance()` storage: contract-level config (Admin, RngContract,
-//!   PrizePoolContract, BalanceContract). Small, bounded, single ledger entry.
+//!   PrizePoolContract, BalanceContract, HouseEdgeBps, FeeRecipient, MinWager,
+//!   MaxWager, OutcomeCount, PotMilestoneStep, ResolutionTimeout) and the
+//!   AccruedFees and JackpotPool counters. Small, bounded, single ledger
+//!   entry.
 //! - `persistent()` storage: per-game and per-player data (GameData,
-//!   PlayerList, Prediction). Each is an independent ledger entry with its own
+//!   PlayerList, Prediction, SplitPrediction, PlayerStats, OpenGames,
+//!   GameToken, TokenFees). Each is an independent ledger entry with its own
 //!   TTL extended on every write (~30 days).
 //!
+//! ## Referral Hook
+//! If the admin configures a referral contract with `set_referral_contract`,
+//! every wager of a player who has a referrer is reported to it as a
+//! `GamePlayed` referral event, and every winning claim, once credited to
+//! the player in the balance contract, as a `PrizeClaimed` event for the
+//! amount paid. Referrers thus earn on gameplay and prizes without an
+//! off-chain bridge. The referral contract must allow this contract as a game
+//! reporter; a failing report never blocks the wager or the claim.
+//!
+//! ## Staking Boost
+//! If the admin configures a staking contract with `set_staking_boost`, each
+//! winner's staked platform-token balance is queried at claim time. The winner
+//! receives a bonus of up to `max_boost_bps` of their share, scaling linearly
+//! with stake until `full_boost_stake` is reached. Bonuses are funded from the
+//! accrued fee bucket and are capped by its balance, so the pot itself is
+//! never diluted.
+//!
+//! ## Storage Health
+//! `get_storage_health` reports how many Game, PlayerList, Prediction, and
+//! PlayerStats entries the contract holds, along with an estimate of the
+//! instance TTL. None of these entries is ever deleted, so the counts only
+//! grow. Anyone may call `bump_all_config` to extend the instance (config)
+//! TTL.
+//!
+//! ## Upgrades
+//! The admin calls `upgrade(new_wasm_hash)` to swap the contract code in
+//! place. Storage is untouched, so games, predictions, and stats carry over
+//! without a migration; the new code must read the existing storage layout.
+//!
 //! ## Security
-//! - Only admin may resolve predictions.
-//! - Each player may predict at most once per game.
+//! - Only admin, or a resolver the admin allowed, may resolve predictions.
+//! - Each player may predict at most one color per game.
 //! - Resolving an already-resolved game is rejected.
+//! - Each winning prediction may be claimed at most once.
 //! - All arithmetic uses `checked_*` to prevent overflow.
 #![no_std]
 #![allow(unexpected_cfgs)]
+// `init` configures the contract in one atomic call; the lint also fires on
+// macro-generated client stubs.
+#![allow(clippy::too_many_arguments)]
 
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Vec,
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype,
+    symbol_short, Address, Bytes, BytesN, Env, Symbol, Vec,
 };
+use stellarcade_shared::storage::{bump_instance, count_new_entry, storage_health};
+pub use stellarcade_shared::storage::{KeyFamilyCount, StorageHealth, INSTANCE_BUMP_LEDGERS};
+pub use stellarcade_shared::GameStatus;
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -42,9 +184,33 @@ use soroban_sdk::{
 /// Persistent storage TTL in ledgers (~30 days at 5 s/ledger).
 pub const PERSISTENT_BUMP_LEDGERS: u32 = 518_400;
 
-/// Maximum number of players per game (bounds O(n) iteration in resolve).
+/// Upper bound on the staking boost, in basis points of a winner's share.
+pub const MAX_STAKING_BOOST_BPS: u32 = 1_000;
+
+/// Referral-system `EventType::GamePlayed`, reported for each wager.
+pub const REFERRAL_EVENT_GAME_PLAYED: u32 = 0;
+
+/// Referral-system `EventType::PrizeClaimed`, reported for each winning claim.
+pub const REFERRAL_EVENT_PRIZE_CLAIMED: u32 = 2;
+
+/// Maximum number of players per game (bounds the player list entry size).
 pub const MAX_PLAYERS_PER_GAME: u32 = 500;
 
+/// Maximum number of distinct sponsors per game (bounds the sponsor list).
+pub const MAX_SPONSORS_PER_GAME: u32 = 20;
+
+/// Maximum number of winners returned by one `get_winners` call.
+pub const MAX_WINNERS_PAGE: u32 = 100;
+
+/// Maximum number of games open at once (bounds the open-games index).
+pub const MAX_OPEN_GAMES: u32 = 200;
+
+/// Maximum number of game ids returned by one `list_open_games` call.
+pub const MAX_OPEN_GAMES_PAGE: u32 = 50;
+
+/// Basis-points divisor (10 000 = 100%).
+pub const BASIS_POINTS_DIVISOR: u32 = 10_000;
+
 // ---------------------------------------------------------------------------
 // Color constants
 // ---------------------------------------------------------------------------
@@ -53,7 +219,46 @@ pub const COLOR_RED: u32 = 0;
 pub const COLOR_GREEN: u32 = 1;
 pub const COLOR_BLUE: u32 = 2;
 pub const COLOR_YELLOW: u32 = 3;
-pub const COLOR_MAX: u32 = COLOR_YELLOW;
+/// Number of named colors; the outcome count of instances initialized
+/// before `outcome_count` was configurable.
+pub const COLOR_COUNT: u32 = COLOR_YELLOW + 1;
+/// Bounds for the `outcome_count` set at `init`.
+pub const MIN_OUTCOME_COUNT: u32 = 2;
+pub const MAX_OUTCOME_COUNT: u32 = 16;
+
+// ---------------------------------------------------------------------------
+// External contract clients
+// ---------------------------------------------------------------------------
+
+#[contractclient(name = "BalanceClient")]
+pub trait UserBalanceContract {
+    fn debit(env: Env, game: Address, user: Address, amount: i128, reason: Symbol);
+    fn credit(env: Env, game: Address, user: Address, amount: i128, reason: Symbol);
+    fn balance_of(env: Env, user: Address) -> i128;
+}
+
+#[contractclient(name = "StakingClient")]
+pub trait StakingContract {
+    fn staked_amount(env: Env, user: Address) -> i128;
+}
+
+#[contractclient(name = "ReferralClient")]
+pub trait ReferralContract {
+    fn get_referrer(env: Env, user: Address) -> Option<Address>;
+    fn record_referral_event(
+        env: Env,
+        caller: Address,
+        user: Address,
+        event_type: u32,
+        amount: i128,
+    );
+}
+
+#[contractclient(name = "RngClient")]
+pub trait RngContract {
+    fn is_ready(env: Env, game_id: u64) -> bool;
+    fn get_result(env: Env, game_id: u64) -> u32;
+}
 
 // ---------------------------------------------------------------------------
 // Error types
@@ -73,27 +278,46 @@ pub enum Error {
     AlreadyPredicted = 8,
     GameFull = 9,
     Overflow = 10,
+    GameNotResolved = 11,
+    PredictionNotFound = 12,
+    NoPayout = 13,
+    AlreadyClaimed = 14,
+    InvalidFee = 15,
+    NoFeesToWithdraw = 16,
+    GameAlreadyExists = 17,
+    BettingClosed = 18,
+    InvalidCloseTime = 19,
+    InvalidBoost = 20,
+    GameNotCancelled = 21,
+    WagerTooLow = 22,
+    WagerTooHigh = 23,
+    MigrationDisabled = 24,
+    InvalidMigrationData = 25,
+    InvalidOutcomeCount = 26,
+    InsufficientBalance = 27,
+    AlreadyCommitted = 28,
+    CommitmentNotFound = 29,
+    CommitmentMismatch = 30,
+    GameNotExpired = 31,
+    InvalidWeights = 32,
+    NotWeightedGame = 33,
+    RngNotReady = 34,
+    TooManyOpenGames = 35,
+    InvalidSplit = 36,
+    TooManySponsors = 37,
+    SponsorshipNotFound = 38,
+    InvalidTransition = 39,
 }
 
 // ---------------------------------------------------------------------------
 // Storage types
 // ---------------------------------------------------------------------------
 
-/// Lifecycle state of a prediction game.
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub enum GameStatus {
-    /// Accepting predictions.
-    Open = 0,
-    /// Resolved — winning color known, outcome recorded.
-    Resolved = 1,
-}
-
 /// Metadata and accumulated state for one prediction game.
 #[contracttype]
 #[derive(Clone)]
 pub struct GameData {
-    /// Total tokens wagered across all predictions.
+    /// Total tokens wagered across all predictions, plus sponsor bonuses.
     pub total_pot: i128,
     /// Number of distinct predictors.
     pub player_count: u32,
@@ -102,20 +326,72 @@ pub struct GameData {
     /// Winning color (only valid when status == Resolved).
     pub winning_color: u32,
     pub status: GameStatus,
+    /// Sum of wagers placed on the winning color (set at resolution).
+    pub winning_total: i128,
+    /// Total paid out to winners (or refunded, if cancelled) so far.
+    pub paid_out: i128,
+    /// Number of winners who have claimed.
+    pub claimed_count: u32,
+    /// House fee deducted from the pot at resolution.
+    pub fee: i128,
+    /// Ledger timestamp at which betting closes (0 = no deadline).
+    pub close_time: u64,
+    /// Sum of wagers per color, indexed by color value.
+    pub color_totals: Vec<i128>,
+    /// Number of predictors per color, indexed by color value.
+    pub color_counts: Vec<u32>,
 }
 
 /// A single player's prediction for a game.
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PredictionEntry {
     pub color: u32,
     pub wager: i128,
+    /// Set once the player has claimed their winnings or refund.
+    pub claimed: bool,
+}
+
+/// One sponsor's contribution to a game's pot.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sponsorship {
+    pub amount: i128,
+    /// Set once the contribution has been refunded from a cancelled game.
+    pub refunded: bool,
+}
+
+/// Lifetime aggregates for one player, updated on place, claim, and refund.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PlayerStats {
+    /// Games entered, excluding refunded (cancelled) games.
+    pub games_played: u32,
+    /// Total staked, including top-ups and excluding refunded wagers.
+    pub total_wagered: i128,
+    /// Total claimed from winning predictions, including staking bonuses.
+    pub total_won: i128,
+    /// Number of winning predictions claimed.
+    pub win_count: u32,
+}
+
+/// Staking boost parameters, set by `set_staking_boost`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakingBoost {
+    /// Contract queried for each winner's staked balance.
+    pub staking_contract: Address,
+    /// Boost applied at or above `full_boost_stake`, in basis points.
+    pub max_boost_bps: u32,
+    /// Stake required for the full boost; smaller stakes scale linearly.
+    pub full_boost_stake: i128,
 }
 
 /// Storage key discriminants.
 ///
-/// Instance keys (Admin, RngContract, PrizePoolContract, BalanceContract)
-/// hold small contract-level config in a single ledger entry.
+/// Instance keys (Admin, RngContract, PrizePoolContract, BalanceContract,
+/// HouseEdgeBps, FeeRecipient, MinWager, MaxWager, AccruedFees) hold small
+/// contract-level config in a single ledger entry.
 ///
 /// Persistent keys (Game, PlayerList, Prediction) are per-game and per-player,
 /// each stored as an independent ledger entry with its own TTL.
@@ -127,6 +403,31 @@ pub enum DataKey {
     RngContract,
     PrizePoolContract,
     BalanceContract,
+    /// House fee in basis points, deducted from each resolved pot.
+    HouseEdgeBps,
+    /// Address that receives withdrawn fees.
+    FeeRecipient,
+    /// Smallest accepted wager.
+    MinWager,
+    /// Largest accepted wager.
+    MaxWager,
+    /// Number of selectable outcomes (u32), fixed at init.
+    OutcomeCount,
+    /// Fees collected and not yet withdrawn.
+    AccruedFees,
+    /// Optional StakingBoost configuration.
+    StakingBoost,
+    /// Optional referral contract notified of wagers.
+    ReferralContract,
+    /// True while `import_game` is accepted.
+    MigrationEnabled,
+    /// Pot size between `PotMilestone` events; `0` disables them.
+    PotMilestoneStep,
+    /// Seconds after `close_time` before an unresolved game can be
+    /// refunded by its players; `0` disables expiry.
+    ResolutionTimeout,
+    /// Pots of winnerless games awaiting the next game with winners.
+    JackpotPool,
     // --- persistent() keys ---
     /// GameData keyed by game_id.
     Game(u64),
@@ -134,6 +435,27 @@ pub enum DataKey {
     PlayerList(u64),
     /// PredictionEntry keyed by (game_id, player).
     Prediction(u64, Address),
+    /// PlayerStats keyed by player.
+    PlayerStats(Address),
+    /// Committed `sha256(color || salt)` for a game's result.
+    ResultCommitment(u64),
+    /// Vec<u32> of per-color weights for a weighted game.
+    ColorWeights(u64),
+    /// Vec<u64> of the ids of all `Open` games, in creation order.
+    OpenGames,
+    /// Vec<PredictionEntry>, one per color, of a split prediction keyed by
+    /// (game_id, player).
+    SplitPrediction(u64, Address),
+    /// Balance/token contract of a game opened with `open_token_game`.
+    GameToken(u64),
+    /// i128 fees accrued by token games, keyed by token.
+    TokenFees(Address),
+    /// Address allowed to resolve games with `resolve_prediction_as`.
+    Resolver(Address),
+    /// Vec<Address> of a game's sponsors, in order of first contribution.
+    Sponsors(u64),
+    /// Sponsorship keyed by (game_id, sponsor).
+    Sponsorship(u64, Address),
 }
 
 // ---------------------------------------------------------------------------
@@ -150,6 +472,25 @@ pub struct PredictionPlaced {
     pub wager: i128,
 }
 
+#[contractevent]
+pub struct PredictionToppedUp {
+    #[topic]
+    pub game_id: u64,
+    #[topic]
+    pub player: Address,
+    pub color: u32,
+    pub amount: i128,
+    pub total_wager: i128,
+}
+
+#[contractevent]
+pub struct PotMilestone {
+    #[topic]
+    pub game_id: u64,
+    pub milestone: i128,
+    pub total_pot: i128,
+}
+
 #[contractevent]
 pub struct PredictionResolved {
     #[topic]
@@ -159,6 +500,156 @@ pub struct PredictionResolved {
     pub total_pot: i128,
 }
 
+#[contractevent]
+pub struct GameImported {
+    #[topic]
+    pub game_id: u64,
+    pub player_count: u32,
+    pub total_pot: i128,
+    pub escrowed: i128,
+}
+
+#[contractevent]
+pub struct ContractUpgraded {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct ResultCommitted {
+    #[topic]
+    pub game_id: u64,
+    pub hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct WeightsCommitted {
+    #[topic]
+    pub game_id: u64,
+    pub weights: Vec<u32>,
+}
+
+#[contractevent]
+pub struct WeightedColorDrawn {
+    #[topic]
+    pub game_id: u64,
+    pub roll: u32,
+    pub winning_color: u32,
+}
+
+#[contractevent]
+pub struct GameOpened {
+    #[topic]
+    pub game_id: u64,
+    pub close_time: u64,
+}
+
+#[contractevent]
+pub struct ResolverSet {
+    #[topic]
+    pub resolver: Address,
+    pub allowed: bool,
+}
+
+#[contractevent]
+pub struct GameTokenSet {
+    #[topic]
+    pub game_id: u64,
+    pub token: Address,
+}
+
+#[contractevent]
+pub struct GameCancelled {
+    #[topic]
+    pub game_id: u64,
+    pub total_pot: i128,
+    pub player_count: u32,
+}
+
+#[contractevent]
+pub struct GameExpired {
+    #[topic]
+    pub game_id: u64,
+    pub total_pot: i128,
+    pub player_count: u32,
+}
+
+#[contractevent]
+pub struct Refunded {
+    #[topic]
+    pub game_id: u64,
+    #[topic]
+    pub player: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct PotSponsored {
+    #[topic]
+    pub game_id: u64,
+    #[topic]
+    pub sponsor: Address,
+    pub amount: i128,
+    pub total_pot: i128,
+}
+
+#[contractevent]
+pub struct SponsorshipRefunded {
+    #[topic]
+    pub game_id: u64,
+    #[topic]
+    pub sponsor: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct JackpotRolledOver {
+    #[topic]
+    pub game_id: u64,
+    pub amount: i128,
+    pub jackpot: i128,
+}
+
+#[contractevent]
+pub struct JackpotAwarded {
+    #[topic]
+    pub game_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct FeeCollected {
+    #[topic]
+    pub game_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct FeesWithdrawn {
+    #[topic]
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct StakingBoostPaid {
+    #[topic]
+    pub game_id: u64,
+    #[topic]
+    pub player: Address,
+    pub staked: i128,
+    pub bonus: i128,
+}
+
+#[contractevent]
+pub struct WinningsClaimed {
+    #[topic]
+    pub game_id: u64,
+    #[topic]
+    pub player: Address,
+    pub amount: i128,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -174,14 +665,22 @@ impl ColorPrediction {
 
     /// Initialize the contract. May only be called once.
     ///
-    /// Stores admin, rng_contract, prize_pool_contract, and balance_contract
-    /// in instance storage. Subsequent calls return `AlreadyInitialized`.
+    /// Stores admin, rng_contract, prize_pool_contract, balance_contract, the
+    /// house fee configuration, and the wager limits in instance storage.
+    /// `house_edge_bps` must not exceed 10 000, and `min_wager` must be
+    /// positive and no greater than `max_wager`. Subsequent calls return
+    /// `AlreadyInitialized`.
     pub fn init(
         env: Env,
         admin: Address,
         rng_contract: Address,
         prize_pool_contract: Address,
         balance_contract: Address,
+        house_edge_bps: u32,
+        fee_recipient: Address,
+        min_wager: i128,
+        max_wager: i128,
+        outcome_count: u32,
     ) -> Result<(), Error> {
         if env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::AlreadyInitialized);
@@ -189,6 +688,14 @@ impl ColorPrediction {
 
         admin.require_auth();
 
+        if house_edge_bps > BASIS_POINTS_DIVISOR {
+            return Err(Error::InvalidFee);
+        }
+        validate_wager_limits(min_wager, max_wager)?;
+        if !(MIN_OUTCOME_COUNT..=MAX_OUTCOME_COUNT).contains(&outcome_count) {
+            return Err(Error::InvalidOutcomeCount);
+        }
+
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage()
             .instance()
@@ -199,220 +706,2430 @@ impl ColorPrediction {
         env.storage()
             .instance()
             .set(&DataKey::BalanceContract, &balance_contract);
+        env.storage()
+            .instance()
+            .set(&DataKey::HouseEdgeBps, &house_edge_bps);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeRecipient, &fee_recipient);
+        env.storage().instance().set(&DataKey::MinWager, &min_wager);
+        env.storage().instance().set(&DataKey::MaxWager, &max_wager);
+        env.storage()
+            .instance()
+            .set(&DataKey::OutcomeCount, &outcome_count);
+        bump_instance(&env);
 
         Ok(())
     }
 
     // -----------------------------------------------------------------------
-    // place_prediction
+    // Storage maintenance
     // -----------------------------------------------------------------------
 
-    /// Place a color prediction for an open game.
-    ///
-    /// `color` must be one of COLOR_RED (0), COLOR_GREEN (1), COLOR_BLUE (2),
-    /// COLOR_YELLOW (3). `wager` must be positive. Each player may predict
-    /// exactly once per game. The game is created implicitly on the first
-    /// prediction for a given `game_id`.
-    ///
-    /// Emits `PredictionPlaced`.
-    pub fn place_prediction(
-        env: Env,
-        player: Address,
-        color: u32,
-        wager: i128,
-        game_id: u64,
-    ) -> Result<(), Error> {
+    /// Extend the instance (config) TTL. Permissionless so operators or
+    /// keepers can prevent archival of contract configuration.
+    pub fn bump_all_config(env: Env) -> Result<(), Error> {
         require_initialized(&env)?;
-        player.require_auth();
+        bump_instance(&env);
+        Ok(())
+    }
 
-        if color > COLOR_MAX {
-            return Err(Error::InvalidColor);
-        }
-        if wager <= 0 {
-            return Err(Error::InvalidAmount);
+    /// Extend the TTL of a game's persistent state: the `Game` entry, its
+    /// `PlayerList`, every player's prediction (single or split), and any
+    /// result commitment or color weights. Permissionless, so keepers can
+    /// stop a long-running unresolved game from being archived with its
+    /// escrowed wagers.
+    pub fn extend_game_ttl(env: Env, game_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+        let game_key = DataKey::Game(game_id);
+        if !env.storage().persistent().has(&game_key) {
+            return Err(Error::GameNotFound);
         }
+        extend_persistent(&env, &game_key);
 
-        // Load or initialize the game.
-        let mut game: GameData = env
+        let list_key = DataKey::PlayerList(game_id);
+        let players: Vec<Address> = env
             .storage()
             .persistent()
-            .get(&DataKey::Game(game_id))
-            .unwrap_or(GameData {
-                total_pot: 0,
-                player_count: 0,
-                winner_count: 0,
-                winning_color: 0,
-                status: GameStatus::Open,
-            });
-
-        if game.status != GameStatus::Open {
-            return Err(Error::GameAlreadyResolved);
-        }
-
-        if game.player_count >= MAX_PLAYERS_PER_GAME {
-            return Err(Error::GameFull);
-        }
-
-        let prediction_key = DataKey::Prediction(game_id, player.clone());
-        if env.storage().persistent().has(&prediction_key) {
-            return Err(Error::AlreadyPredicted);
+            .get(&list_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        extend_persistent(&env, &list_key);
+        for player in players.iter() {
+            extend_persistent(&env, &DataKey::Prediction(game_id, player.clone()));
+            extend_persistent(&env, &DataKey::SplitPrediction(game_id, player));
         }
+        extend_persistent(&env, &DataKey::ResultCommitment(game_id));
+        extend_persistent(&env, &DataKey::ColorWeights(game_id));
+        extend_persistent(&env, &DataKey::GameToken(game_id));
 
-        // Record the prediction.
-        let entry = PredictionEntry { color, wager };
-        persist_set(&env, prediction_key, &entry);
-
-        // Register player in the list.
-        let mut players: Vec<Address> = env
+        let sponsors_key = DataKey::Sponsors(game_id);
+        let sponsors: Vec<Address> = env
             .storage()
             .persistent()
-            .get(&DataKey::PlayerList(game_id))
+            .get(&sponsors_key)
             .unwrap_or_else(|| Vec::new(&env));
-        players.push_back(player.clone());
-        persist_set(&env, DataKey::PlayerList(game_id), &players);
-
-        // Update game totals.
-        game.total_pot = game.total_pot.checked_add(wager).ok_or(Error::Overflow)?;
-        game.player_count = game.player_count.checked_add(1).ok_or(Error::Overflow)?;
-        persist_set(&env, DataKey::Game(game_id), &game);
-
-        // TODO: Invoke balance_contract to transfer `wager` tokens from player to this contract.
-
-        PredictionPlaced {
-            game_id,
-            player,
-            color,
-            wager,
+        extend_persistent(&env, &sponsors_key);
+        for sponsor in sponsors.iter() {
+            extend_persistent(&env, &DataKey::Sponsorship(game_id, sponsor));
         }
-        .publish(&env);
-
         Ok(())
     }
 
+    /// Report how many Game, PlayerList, Prediction and PlayerStats
+    /// entries the contract holds, with the estimated instance TTL.
+    pub fn get_storage_health(env: Env) -> StorageHealth {
+        storage_health(
+            &env,
+            &[
+                FAMILY_GAME,
+                FAMILY_PLAYER_LIST,
+                FAMILY_PREDICTION,
+                FAMILY_PLAYER_STATS,
+            ],
+        )
+    }
+
     // -----------------------------------------------------------------------
-    // resolve_prediction
+    // Fee configuration
     // -----------------------------------------------------------------------
 
-    /// Resolve a game by declaring the winning color. Admin only.
-    ///
-    /// `winning_color` must be a valid color value (0–3). Iterates all player
-    /// predictions (bounded by `MAX_PLAYERS_PER_GAME`) to count winners and
-    /// transitions the game to `Resolved`.
-    ///
-    /// If there are no winners, the entire pot remains in the contract.
-    ///
-    /// Emits `PredictionResolved`.
-    pub fn resolve_prediction(env: Env, game_id: u64, winning_color: u32) -> Result<(), Error> {
+    /// Update the house fee applied to future resolutions. Admin only.
+    pub fn set_house_edge(env: Env, house_edge_bps: u32) -> Result<(), Error> {
         let admin = get_admin(&env)?;
         admin.require_auth();
 
-        if winning_color > COLOR_MAX {
-            return Err(Error::InvalidColor);
+        if house_edge_bps > BASIS_POINTS_DIVISOR {
+            return Err(Error::InvalidFee);
         }
+        env.storage()
+            .instance()
+            .set(&DataKey::HouseEdgeBps, &house_edge_bps);
+        Ok(())
+    }
 
-        let mut game: GameData = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Game(game_id))
-            .ok_or(Error::GameNotFound)?;
+    /// Update the address that receives withdrawn fees. Admin only.
+    pub fn set_fee_recipient(env: Env, fee_recipient: Address) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
 
-        if game.status != GameStatus::Open {
-            return Err(Error::GameAlreadyResolved);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeRecipient, &fee_recipient);
+        Ok(())
+    }
+
+    /// Update the accepted wager range. Admin only.
+    ///
+    /// `min_wager` must be positive and no greater than `max_wager`.
+    pub fn set_wager_limits(env: Env, min_wager: i128, max_wager: i128) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        validate_wager_limits(min_wager, max_wager)?;
+        env.storage().instance().set(&DataKey::MinWager, &min_wager);
+        env.storage().instance().set(&DataKey::MaxWager, &max_wager);
+        Ok(())
+    }
+
+    /// Set the pot size between `PotMilestone` events. Admin only.
+    ///
+    /// `step` must not be negative; `0` disables milestone events.
+    pub fn set_pot_milestone_step(env: Env, step: i128) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        if step < 0 {
+            return Err(Error::InvalidAmount);
         }
+        env.storage()
+            .instance()
+            .set(&DataKey::PotMilestoneStep, &step);
+        Ok(())
+    }
 
-        let players: Vec<Address> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::PlayerList(game_id))
-            .unwrap_or_else(|| Vec::new(&env));
+    /// Set how long after `close_time` an unresolved game expires. Admin only.
+    ///
+    /// `timeout` is in seconds; `0` disables expiry refunds.
+    pub fn set_resolution_timeout(env: Env, timeout: u64) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
 
-        let mut winner_count: u32 = 0;
+        env.storage()
+            .instance()
+            .set(&DataKey::ResolutionTimeout, &timeout);
+        Ok(())
+    }
 
-        // Count winners (bounded by MAX_PLAYERS_PER_GAME).
-        for player in players.iter() {
-            let key = DataKey::Prediction(game_id, player.clone());
-            if let Some(entry) = env
-                .storage()
-                .persistent()
-                .get::<DataKey, PredictionEntry>(&key)
-            {
-                if entry.color == winning_color {
-                    winner_count = winner_count.checked_add(1).ok_or(Error::Overflow)?;
+    /// Configure the staking boost. Admin only.
+    ///
+    /// `max_boost_bps` may not exceed `MAX_STAKING_BOOST_BPS`, and
+    /// `full_boost_stake` must be positive. Pass `None` to disable the boost.
+    pub fn set_staking_boost(
+        env: Env,
+        staking_contract: Option<Address>,
+        max_boost_bps: u32,
+        full_boost_stake: i128,
+    ) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        match staking_contract {
+            Some(staking_contract) => {
+                if max_boost_bps > MAX_STAKING_BOOST_BPS || full_boost_stake <= 0 {
+                    return Err(Error::InvalidBoost);
                 }
+                env.storage().instance().set(
+                    &DataKey::StakingBoost,
+                    &StakingBoost {
+                        staking_contract,
+                        max_boost_bps,
+                        full_boost_stake,
+                    },
+                );
             }
+            None => env.storage().instance().remove(&DataKey::StakingBoost),
+        }
+        Ok(())
+    }
+
+    /// Set the referral contract notified of each wager. Admin only.
+    ///
+    /// Pass `None` to stop reporting wagers.
+    pub fn set_referral_contract(
+        env: Env,
+        referral_contract: Option<Address>,
+    ) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        match referral_contract {
+            Some(referral_contract) => env
+                .storage()
+                .instance()
+                .set(&DataKey::ReferralContract, &referral_contract),
+            None => env.storage().instance().remove(&DataKey::ReferralContract),
         }
+        Ok(())
+    }
 
-        // TODO: If winner_count > 0, invoke prize_pool_contract to distribute
-        // (game.total_pot / winner_count) tokens to each winner.
+    /// Allow or disallow `resolver` to settle games with
+    /// `resolve_prediction_as`. Admin only.
+    ///
+    /// Emits `ResolverSet`.
+    pub fn set_resolver(env: Env, resolver: Address, allowed: bool) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
 
-        game.status = GameStatus::Resolved;
-        game.winning_color = winning_color;
-        game.winner_count = winner_count;
-        persist_set(&env, DataKey::Game(game_id), &game);
+        let key = DataKey::Resolver(resolver.clone());
+        if allowed {
+            persist_set(&env, key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
 
-        PredictionResolved {
+        ResolverSet { resolver, allowed }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Route all accrued fees to the fee recipient. Admin only.
+    ///
+    /// Returns the amount withdrawn. Emits `FeesWithdrawn`.
+    pub fn withdraw_fees(env: Env) -> Result<i128, Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        let amount = get_accrued_fees(&env);
+        if amount <= 0 {
+            return Err(Error::NoFeesToWithdraw);
+        }
+        let recipient = get_fee_recipient(&env)?;
+
+        // Zero the bucket before any external call (reentrancy guard).
+        env.storage().instance().set(&DataKey::AccruedFees, &0i128);
+
+        let token = get_balance_contract(&env)?;
+        transfer_from_escrow(&env, &token, &recipient, amount, symbol_short!("fees"))?;
+
+        FeesWithdrawn { recipient, amount }.publish(&env);
+
+        Ok(amount)
+    }
+
+    /// Route the fees accrued by games in `token` to the fee recipient.
+    /// Admin only.
+    ///
+    /// Returns the amount withdrawn. Emits `FeesWithdrawn`.
+    pub fn withdraw_token_fees(env: Env, token: Address) -> Result<i128, Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        let amount = get_token_fees(&env, &token);
+        if amount <= 0 {
+            return Err(Error::NoFeesToWithdraw);
+        }
+        let recipient = get_fee_recipient(&env)?;
+
+        // Zero the bucket before any external call (reentrancy guard).
+        persist_set(&env, DataKey::TokenFees(token.clone()), &0i128);
+
+        transfer_from_escrow(&env, &token, &recipient, amount, symbol_short!("fees"))?;
+
+        FeesWithdrawn { recipient, amount }.publish(&env);
+
+        Ok(amount)
+    }
+
+    // -----------------------------------------------------------------------
+    // open_game
+    // -----------------------------------------------------------------------
+
+    /// Open a game with a betting deadline. Admin only.
+    ///
+    /// `close_time` is a ledger timestamp and must be in the future.
+    /// Predictions placed at or after `close_time` are rejected with
+    /// `BettingClosed`.
+    ///
+    /// Emits `GameOpened`.
+    pub fn open_game(env: Env, game_id: u64, close_time: u64) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        if env.storage().persistent().has(&DataKey::Game(game_id)) {
+            return Err(Error::GameAlreadyExists);
+        }
+        if close_time <= env.ledger().timestamp() {
+            return Err(Error::InvalidCloseTime);
+        }
+
+        persist_set(&env, DataKey::Game(game_id), &new_game(&env, close_time));
+        count_new_entry(&env, FAMILY_GAME);
+        add_open_game(&env, game_id)?;
+
+        GameOpened {
             game_id,
-            winning_color,
-            winner_count,
-            total_pot: game.total_pot,
+            close_time,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Open a game that settles in `token` instead of the platform token.
+    /// Admin only.
+    ///
+    /// `token` is a balance/token contract with the same interface as
+    /// `BalanceContract`; every wager, claim and refund of the game goes
+    /// through it. Otherwise behaves like `open_game`.
+    ///
+    /// Emits `GameOpened` and `GameTokenSet`.
+    pub fn open_token_game(
+        env: Env,
+        game_id: u64,
+        close_time: u64,
+        token: Address,
+    ) -> Result<(), Error> {
+        Self::open_game(env.clone(), game_id, close_time)?;
+        persist_set(&env, DataKey::GameToken(game_id), &token);
+
+        GameTokenSet { game_id, token }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Open a game whose winning color is drawn from `weights`. Admin only.
+    ///
+    /// `weights` holds one entry per color (`outcome_count` entries) and must
+    /// sum to a positive total; a color with weight `0` can never win. The
+    /// weights are fixed for the life of the game and the game can only be
+    /// settled with `resolve_weighted`. Otherwise behaves like `open_game`.
+    ///
+    /// Emits `GameOpened` and `WeightsCommitted`.
+    pub fn open_weighted_game(
+        env: Env,
+        game_id: u64,
+        close_time: u64,
+        weights: Vec<u32>,
+    ) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        if env.storage().persistent().has(&DataKey::Game(game_id)) {
+            return Err(Error::GameAlreadyExists);
+        }
+        if close_time <= env.ledger().timestamp() {
+            return Err(Error::InvalidCloseTime);
+        }
+        if weights.len() != get_outcome_count(&env) || total_weight(&weights)? == 0 {
+            return Err(Error::InvalidWeights);
+        }
+
+        persist_set(&env, DataKey::Game(game_id), &new_game(&env, close_time));
+        count_new_entry(&env, FAMILY_GAME);
+        add_open_game(&env, game_id)?;
+        persist_set(&env, DataKey::ColorWeights(game_id), &weights);
+
+        GameOpened {
+            game_id,
+            close_time,
         }
         .publish(&env);
+        WeightsCommitted { game_id, weights }.publish(&env);
 
         Ok(())
     }
 
     // -----------------------------------------------------------------------
-    // get_game
+    // place_prediction
     // -----------------------------------------------------------------------
 
-    /// Return the game state, or `None` if the game does not exist.
-    pub fn get_game(env: Env, game_id: u64) -> Option<GameData> {
-        env.storage().persistent().get(&DataKey::Game(game_id))
-    }
-}
+    /// Place a color prediction for an open game.
+    ///
+    /// `color` must be below the configured outcome count (`0..outcome_count`;
+    /// 0–3 are COLOR_RED, COLOR_GREEN, COLOR_BLUE, COLOR_YELLOW). `wager` must
+    /// be positive and within the configured `MinWager`/`MaxWager` range. Each
+    /// player may pick one color per game; calling again with the same color
+    /// tops up the existing stake (the aggregated stake must stay within
+    /// `MaxWager`), while a different color fails with `AlreadyPredicted`. The
+    /// game is created implicitly on the first prediction for a given
+    /// `game_id` unless it was opened with `open_game`, in which case
+    /// predictions are only accepted before its `close_time`.
+    ///
+    /// Emits `PredictionPlaced`, or `PredictionToppedUp` for a top-up.
+    pub fn place_prediction(
+        env: Env,
+        player: Address,
+        color: u32,
+        wager: i128,
+        game_id: u64,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        player.require_auth();
 
-// ---------------------------------------------------------------------------
-// Internal helpers
-// ---------------------------------------------------------------------------
+        if color >= get_outcome_count(&env) {
+            return Err(Error::InvalidColor);
+        }
+        if wager <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let (min_wager, max_wager) = get_wager_limits(&env);
+        if wager < min_wager {
+            return Err(Error::WagerTooLow);
+        }
+        if wager > max_wager {
+            return Err(Error::WagerTooHigh);
+        }
 
-fn require_initialized(env: &Env) -> Result<(), Error> {
-    if !env.storage().instance().has(&DataKey::Admin) {
-        return Err(Error::NotInitialized);
+        let balance_client = BalanceClient::new(&env, &game_token(&env, game_id)?);
+        if balance_client.balance_of(&player) < wager {
+            return Err(Error::InsufficientBalance);
+        }
+
+        // Load or initialize the game.
+        let existing: Option<GameData> = env.storage().persistent().get(&DataKey::Game(game_id));
+        let is_new_game = existing.is_none();
+        let mut game = existing.unwrap_or_else(|| new_game(&env, 0));
+
+        if game.status != GameStatus::Open {
+            return Err(Error::GameAlreadyResolved);
+        }
+
+        if game.close_time != 0 && env.ledger().timestamp() >= game.close_time {
+            return Err(Error::BettingClosed);
+        }
+
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::SplitPrediction(game_id, player.clone()))
+        {
+            return Err(Error::AlreadyPredicted);
+        }
+        let prediction_key = DataKey::Prediction(game_id, player.clone());
+        let existing_entry: Option<PredictionEntry> =
+            env.storage().persistent().get(&prediction_key);
+        let is_top_up = existing_entry.is_some();
+
+        // Record the prediction, aggregating a top-up into the existing entry.
+        let entry = match existing_entry {
+            Some(mut entry) => {
+                if entry.color != color {
+                    return Err(Error::AlreadyPredicted);
+                }
+                entry.wager = entry.wager.checked_add(wager).ok_or(Error::Overflow)?;
+                if entry.wager > max_wager {
+                    return Err(Error::WagerTooHigh);
+                }
+                entry
+            }
+            None => {
+                if game.player_count >= MAX_PLAYERS_PER_GAME {
+                    return Err(Error::GameFull);
+                }
+                PredictionEntry {
+                    color,
+                    wager,
+                    claimed: false,
+                }
+            }
+        };
+        persist_set(&env, prediction_key, &entry);
+
+        if !is_top_up {
+            count_new_entry(&env, FAMILY_PREDICTION);
+
+            // Register player in the list.
+            let mut players: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::PlayerList(game_id))
+                .unwrap_or_else(|| Vec::new(&env));
+            if players.is_empty() {
+                count_new_entry(&env, FAMILY_PLAYER_LIST);
+            }
+            players.push_back(player.clone());
+            persist_set(&env, DataKey::PlayerList(game_id), &players);
+
+            game.player_count = game.player_count.checked_add(1).ok_or(Error::Overflow)?;
+            let color_count = game
+                .color_counts
+                .get(color)
+                .unwrap_or(0)
+                .checked_add(1)
+                .ok_or(Error::Overflow)?;
+            game.color_counts.set(color, color_count);
+        }
+
+        // Update game totals.
+        let previous_pot = game.total_pot;
+        game.total_pot = game.total_pot.checked_add(wager).ok_or(Error::Overflow)?;
+        let color_total = game
+            .color_totals
+            .get(color)
+            .unwrap_or(0)
+            .checked_add(wager)
+            .ok_or(Error::Overflow)?;
+        game.color_totals.set(color, color_total);
+        persist_set(&env, DataKey::Game(game_id), &game);
+        if is_new_game {
+            count_new_entry(&env, FAMILY_GAME);
+            add_open_game(&env, game_id)?;
+        }
+
+        let mut stats = get_player_stats(&env, &player);
+        if !is_top_up {
+            stats.games_played = stats.games_played.checked_add(1).ok_or(Error::Overflow)?;
+        }
+        stats.total_wagered = stats
+            .total_wagered
+            .checked_add(wager)
+            .ok_or(Error::Overflow)?;
+        set_player_stats(&env, &player, &stats)?;
+
+        // Escrow the wager in this contract's account.
+        let game_addr = env.current_contract_address();
+        balance_client.debit(&game_addr, &player, &wager, &symbol_short!("wager"));
+        balance_client.credit(&game_addr, &game_addr, &wager, &symbol_short!("escrow"));
+        report_referral(&env, &player, REFERRAL_EVENT_GAME_PLAYED, wager);
+
+        if is_top_up {
+            PredictionToppedUp {
+                game_id,
+                player,
+                color,
+                amount: wager,
+                total_wager: entry.wager,
+            }
+            .publish(&env);
+        } else {
+            PredictionPlaced {
+                game_id,
+                player,
+                color,
+                wager,
+            }
+            .publish(&env);
+        }
+
+        publish_pot_milestone(&env, game_id, previous_pot, game.total_pot);
+
+        Ok(())
     }
-    Ok(())
-}
 
-fn get_admin(env: &Env) -> Result<Address, Error> {
-    env.storage()
-        .instance()
-        .get(&DataKey::Admin)
-        .ok_or(Error::NotInitialized)
-}
+    /// Split one wager across several colors in a single call.
+    ///
+    /// `splits` is a list of `(color, amount)` pairs with at least two
+    /// distinct colors; each amount must be positive and their sum is the
+    /// wager checked against `MinWager`/`MaxWager` and the player's balance.
+    /// Each leg is stored as its own `PredictionEntry`. Fails with
+    /// `InvalidSplit` for fewer than two legs or a repeated color, and with
+    /// `AlreadyPredicted` if the player already predicted on the game.
+    ///
+    /// Emits `PredictionPlaced` for each leg.
+    pub fn place_split_prediction(
+        env: Env,
+        player: Address,
+        splits: Vec<(u32, i128)>,
+        game_id: u64,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        player.require_auth();
 
-/// Persist a value in persistent storage and extend its TTL.
-fn persist_set<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: DataKey, val: &V) {
-    env.storage().persistent().set(&key, val);
-    env.storage()
-        .persistent()
-        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
-}
+        let outcome_count = get_outcome_count(&env);
+        if splits.len() < 2 {
+            return Err(Error::InvalidSplit);
+        }
+        let mut wager: i128 = 0;
+        let mut legs: Vec<PredictionEntry> = Vec::new(&env);
+        for (color, amount) in splits.iter() {
+            if color >= outcome_count {
+                return Err(Error::InvalidColor);
+            }
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            if legs.iter().any(|leg| leg.color == color) {
+                return Err(Error::InvalidSplit);
+            }
+            wager = wager.checked_add(amount).ok_or(Error::Overflow)?;
+            legs.push_back(PredictionEntry {
+                color,
+                wager: amount,
+                claimed: false,
+            });
+        }
+        let (min_wager, max_wager) = get_wager_limits(&env);
+        if wager < min_wager {
+            return Err(Error::WagerTooLow);
+        }
+        if wager > max_wager {
+            return Err(Error::WagerTooHigh);
+        }
 
-// ---------------------------------------------------------------------------
-// Tests
-// ---------------------------------------------------------------------------
+        let balance_client = BalanceClient::new(&env, &game_token(&env, game_id)?);
+        if balance_client.balance_of(&player) < wager {
+            return Err(Error::InsufficientBalance);
+        }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Env};
+        let existing: Option<GameData> = env.storage().persistent().get(&DataKey::Game(game_id));
+        let is_new_game = existing.is_none();
+        let mut game = existing.unwrap_or_else(|| new_game(&env, 0));
 
-    fn setup(
-        env: &Env,
-    ) -> (
-        ColorPredictionClient<'_>,
-        Address,
-        Address,
-        Address,
+        if game.status != GameStatus::Open {
+            return Err(Error::GameAlreadyResolved);
+        }
+        if game.close_time != 0 && env.ledger().timestamp() >= game.close_time {
+            return Err(Error::BettingClosed);
+        }
+
+        let split_key = DataKey::SplitPrediction(game_id, player.clone());
+        if env.storage().persistent().has(&split_key)
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::Prediction(game_id, player.clone()))
+        {
+            return Err(Error::AlreadyPredicted);
+        }
+        if game.player_count >= MAX_PLAYERS_PER_GAME {
+            return Err(Error::GameFull);
+        }
+
+        persist_set(&env, split_key, &legs);
+        count_new_entry(&env, FAMILY_PREDICTION);
+
+        let mut players: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PlayerList(game_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        if players.is_empty() {
+            count_new_entry(&env, FAMILY_PLAYER_LIST);
+        }
+        players.push_back(player.clone());
+        persist_set(&env, DataKey::PlayerList(game_id), &players);
+
+        let previous_pot = game.total_pot;
+        game.player_count = game.player_count.checked_add(1).ok_or(Error::Overflow)?;
+        game.total_pot = game.total_pot.checked_add(wager).ok_or(Error::Overflow)?;
+        for leg in legs.iter() {
+            let color_count = game
+                .color_counts
+                .get(leg.color)
+                .unwrap_or(0)
+                .checked_add(1)
+                .ok_or(Error::Overflow)?;
+            game.color_counts.set(leg.color, color_count);
+            let color_total = game
+                .color_totals
+                .get(leg.color)
+                .unwrap_or(0)
+                .checked_add(leg.wager)
+                .ok_or(Error::Overflow)?;
+            game.color_totals.set(leg.color, color_total);
+        }
+        persist_set(&env, DataKey::Game(game_id), &game);
+        if is_new_game {
+            count_new_entry(&env, FAMILY_GAME);
+            add_open_game(&env, game_id)?;
+        }
+
+        let mut stats = get_player_stats(&env, &player);
+        stats.games_played = stats.games_played.checked_add(1).ok_or(Error::Overflow)?;
+        stats.total_wagered = stats
+            .total_wagered
+            .checked_add(wager)
+            .ok_or(Error::Overflow)?;
+        set_player_stats(&env, &player, &stats)?;
+
+        let game_addr = env.current_contract_address();
+        balance_client.debit(&game_addr, &player, &wager, &symbol_short!("wager"));
+        balance_client.credit(&game_addr, &game_addr, &wager, &symbol_short!("escrow"));
+        report_referral(&env, &player, REFERRAL_EVENT_GAME_PLAYED, wager);
+
+        for leg in legs.iter() {
+            PredictionPlaced {
+                game_id,
+                player: player.clone(),
+                color: leg.color,
+                wager: leg.wager,
+            }
+            .publish(&env);
+        }
+
+        publish_pot_milestone(&env, game_id, previous_pot, game.total_pot);
+
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // sponsor_pot
+    // -----------------------------------------------------------------------
+
+    /// Add `amount` of the game's token to an open game's pot on behalf of
+    /// `sponsor`. The game must already exist and its betting window must
+    /// still be open. The bonus is escrowed like a wager and included in
+    /// `total_pot`, so winners share it pro rata; it does not count toward
+    /// any color total. Repeat calls add to the sponsor's contribution.
+    ///
+    /// Fails with `TooManySponsors` once a game has `MAX_SPONSORS_PER_GAME`
+    /// distinct sponsors.
+    ///
+    /// Emits `PotSponsored`, and `PotMilestone` when a milestone is crossed.
+    pub fn sponsor_pot(
+        env: Env,
+        sponsor: Address,
+        game_id: u64,
+        amount: i128,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        sponsor.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut game: GameData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)?;
+        if game.status != GameStatus::Open {
+            return Err(Error::GameAlreadyResolved);
+        }
+        if game.close_time != 0 && env.ledger().timestamp() >= game.close_time {
+            return Err(Error::BettingClosed);
+        }
+
+        let balance_client = BalanceClient::new(&env, &game_token(&env, game_id)?);
+        if balance_client.balance_of(&sponsor) < amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let sponsorship_key = DataKey::Sponsorship(game_id, sponsor.clone());
+        let mut sponsorship = match env
+            .storage()
+            .persistent()
+            .get::<_, Sponsorship>(&sponsorship_key)
+        {
+            Some(existing) => existing,
+            None => {
+                let mut sponsors: Vec<Address> = env
+                    .storage()
+                    .persistent()
+                    .get(&DataKey::Sponsors(game_id))
+                    .unwrap_or_else(|| Vec::new(&env));
+                if sponsors.len() >= MAX_SPONSORS_PER_GAME {
+                    return Err(Error::TooManySponsors);
+                }
+                sponsors.push_back(sponsor.clone());
+                persist_set(&env, DataKey::Sponsors(game_id), &sponsors);
+                Sponsorship {
+                    amount: 0,
+                    refunded: false,
+                }
+            }
+        };
+        sponsorship.amount = sponsorship
+            .amount
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        persist_set(&env, sponsorship_key, &sponsorship);
+
+        let previous_pot = game.total_pot;
+        game.total_pot = game.total_pot.checked_add(amount).ok_or(Error::Overflow)?;
+        persist_set(&env, DataKey::Game(game_id), &game);
+
+        let game_addr = env.current_contract_address();
+        balance_client.debit(&game_addr, &sponsor, &amount, &symbol_short!("sponsor"));
+        balance_client.credit(&game_addr, &game_addr, &amount, &symbol_short!("escrow"));
+
+        PotSponsored {
+            game_id,
+            sponsor,
+            amount,
+            total_pot: game.total_pot,
+        }
+        .publish(&env);
+
+        publish_pot_milestone(&env, game_id, previous_pot, game.total_pot);
+
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // resolve_prediction
+    // -----------------------------------------------------------------------
+
+    /// Resolve a game by declaring the winning color. Admin only.
+    ///
+    /// `winning_color` must be below the outcome count. Winner count and
+    /// winning stake are read from the per-color totals kept by
+    /// `place_prediction`, so no predictions are iterated. The game
+    /// transitions to `Resolved`. Payouts are not pushed here;
+    /// winners collect them with `claim`.
+    ///
+    /// If there are winners, any jackpot is added to the pot, then the house
+    /// fee is deducted from it and accrued to the fee bucket. If there are no
+    /// winners, the entire pot rolls over into the jackpot.
+    ///
+    /// Fails with `AlreadyCommitted` for games with a result commitment or
+    /// committed color weights.
+    ///
+    /// Emits `PredictionResolved`, and `FeeCollected` when a fee is taken.
+    pub fn resolve_prediction(env: Env, game_id: u64, winning_color: u32) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+        resolve_manual(&env, game_id, winning_color)
+    }
+
+    /// Resolve a game on behalf of an allowed resolver.
+    ///
+    /// Same as `resolve_prediction`, but authorized by `resolver`, which must
+    /// have been allowed with `set_resolver`; otherwise fails with
+    /// `NotAuthorized`.
+    pub fn resolve_prediction_as(
+        env: Env,
+        resolver: Address,
+        game_id: u64,
+        winning_color: u32,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        resolver.require_auth();
+        if !env.storage().persistent().has(&DataKey::Resolver(resolver)) {
+            return Err(Error::NotAuthorized);
+        }
+        resolve_manual(&env, game_id, winning_color)
+    }
+
+    /// Resolve a weighted game from the RNG contract's result. Admin only.
+    ///
+    /// The roll is `result % total_weight`; the winning color is the first
+    /// whose cumulative weight exceeds the roll, so each color wins with
+    /// probability `weight / total_weight`. Fails with `NotWeightedGame` if
+    /// the game was not opened with `open_weighted_game` and with
+    /// `RngNotReady` until the RNG contract has a result for `game_id`.
+    ///
+    /// Emits `WeightedColorDrawn`, `PredictionResolved`, and `FeeCollected`
+    /// when a fee is taken.
+    pub fn resolve_weighted(env: Env, game_id: u64) -> Result<u32, Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        let weights: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ColorWeights(game_id))
+            .ok_or(Error::NotWeightedGame)?;
+
+        let rng_addr: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::RngContract)
+            .ok_or(Error::NotInitialized)?;
+        let rng = RngClient::new(&env, &rng_addr);
+        if !rng.is_ready(&game_id) {
+            return Err(Error::RngNotReady);
+        }
+
+        let roll = rng.get_result(&game_id) % total_weight(&weights)?;
+        let winning_color = weighted_color(&weights, roll);
+
+        resolve_game(&env, game_id, winning_color)?;
+
+        WeightedColorDrawn {
+            game_id,
+            roll,
+            winning_color,
+        }
+        .publish(&env);
+
+        Ok(winning_color)
+    }
+
+    // -----------------------------------------------------------------------
+    // commit / reveal
+    // -----------------------------------------------------------------------
+
+    /// Commit to a game's winning color before betting closes. Admin only.
+    ///
+    /// `hash` is `sha256(color_be_bytes || salt)`, with the color encoded as
+    /// 4 big-endian bytes followed by a 32-byte salt. The game must have been
+    /// opened with `open_game` (`GameNotFound` otherwise); games without a
+    /// `close_time` are refused with `InvalidCloseTime`, since a commitment
+    /// only proves anything if it lands before a known betting deadline.
+    /// Once committed, the game can only be resolved through
+    /// `reveal_result`. Fails with `BettingClosed` at or after the game's
+    /// `close_time` and with `AlreadyCommitted` on a second commit.
+    ///
+    /// Emits `ResultCommitted`.
+    pub fn commit_result(env: Env, game_id: u64, hash: BytesN<32>) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        let key = DataKey::ResultCommitment(game_id);
+        if env.storage().persistent().has(&key)
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::ColorWeights(game_id))
+        {
+            return Err(Error::AlreadyCommitted);
+        }
+
+        let game: GameData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)?;
+        if game.status != GameStatus::Open {
+            return Err(Error::GameAlreadyResolved);
+        }
+        if game.close_time == 0 {
+            return Err(Error::InvalidCloseTime);
+        }
+        if env.ledger().timestamp() >= game.close_time {
+            return Err(Error::BettingClosed);
+        }
+
+        persist_set(&env, key, &hash);
+
+        ResultCommitted { game_id, hash }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Resolve a committed game by revealing its color and salt. Admin only.
+    ///
+    /// Fails with `CommitmentNotFound` if no result was committed and with
+    /// `CommitmentMismatch` if `sha256(color_be_bytes || salt)` differs from
+    /// the commitment. Otherwise resolves exactly like `resolve_prediction`.
+    ///
+    /// Emits `PredictionResolved`, and `FeeCollected` when a fee is taken.
+    pub fn reveal_result(
+        env: Env,
+        game_id: u64,
+        color: u32,
+        salt: BytesN<32>,
+    ) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        let committed: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ResultCommitment(game_id))
+            .ok_or(Error::CommitmentNotFound)?;
+        if result_hash(&env, color, &salt) != committed {
+            return Err(Error::CommitmentMismatch);
+        }
+
+        resolve_game(&env, game_id, color)
+    }
+
+    // -----------------------------------------------------------------------
+    // claim
+    // -----------------------------------------------------------------------
+
+    /// Claim a winning prediction's share of a resolved game's pot.
+    ///
+    /// If a staking boost is configured, a bonus proportional to the player's
+    /// stake is added from the fee bucket (see `set_staking_boost`).
+    ///
+    /// Each winner receives `(total_pot - fee) * wager / winning_total`. Integer
+    /// division leaves a small remainder, which is paid to the last winner to
+    /// claim so the full pot is always distributed. The prediction is marked
+    /// claimed before any transfer so it can never be paid twice.
+    ///
+    /// With a referral contract configured, the amount credited is reported
+    /// as a `PrizeClaimed` referral event in the same call.
+    ///
+    /// Emits `WinningsClaimed`.
+    pub fn claim(env: Env, player: Address, game_id: u64) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let mut game: GameData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)?;
+
+        if game.status != GameStatus::Resolved {
+            return Err(Error::GameNotResolved);
+        }
+
+        let (prediction_key, mut legs) = load_prediction_legs(&env, game_id, &player)?;
+        let leg_index = legs
+            .iter()
+            .position(|leg| leg.color == game.winning_color)
+            .ok_or(Error::NoPayout)? as u32;
+        let mut entry = legs.get_unchecked(leg_index);
+
+        if entry.claimed {
+            return Err(Error::AlreadyClaimed);
+        }
+
+        let distributable = game
+            .total_pot
+            .checked_sub(game.fee)
+            .ok_or(Error::Overflow)?;
+        let claimed_count = game.claimed_count.checked_add(1).ok_or(Error::Overflow)?;
+        let amount = if claimed_count == game.winner_count {
+            // Last winner sweeps the rounding remainder.
+            distributable
+                .checked_sub(game.paid_out)
+                .ok_or(Error::Overflow)?
+        } else {
+            distributable
+                .checked_mul(entry.wager)
+                .and_then(|v| v.checked_div(game.winning_total))
+                .ok_or(Error::Overflow)?
+        };
+        if amount <= 0 {
+            return Err(Error::NoPayout);
+        }
+
+        let token_override = get_token_override(&env, game_id);
+        let (staked, bonus) = match token_override {
+            Some(_) => (0, 0),
+            None => staking_bonus(&env, &player, amount)?,
+        };
+
+        // Mark claimed before any external call (reentrancy guard).
+        entry.claimed = true;
+        legs.set(leg_index, entry);
+        store_prediction_legs(&env, prediction_key, &legs);
+
+        game.claimed_count = claimed_count;
+        game.paid_out = game.paid_out.checked_add(amount).ok_or(Error::Overflow)?;
+        persist_set(&env, DataKey::Game(game_id), &game);
+
+        if bonus > 0 {
+            let accrued = get_accrued_fees(&env)
+                .checked_sub(bonus)
+                .ok_or(Error::Overflow)?;
+            env.storage()
+                .instance()
+                .set(&DataKey::AccruedFees, &accrued);
+
+            StakingBoostPaid {
+                game_id,
+                player: player.clone(),
+                staked,
+                bonus,
+            }
+            .publish(&env);
+        }
+        let amount = amount.checked_add(bonus).ok_or(Error::Overflow)?;
+
+        let mut stats = get_player_stats(&env, &player);
+        stats.total_won = stats.total_won.checked_add(amount).ok_or(Error::Overflow)?;
+        stats.win_count = stats.win_count.checked_add(1).ok_or(Error::Overflow)?;
+        set_player_stats(&env, &player, &stats)?;
+
+        let token = token_override.map_or_else(|| get_balance_contract(&env), Ok)?;
+        transfer_from_escrow(&env, &token, &player, amount, symbol_short!("win"))?;
+        report_referral(&env, &player, REFERRAL_EVENT_PRIZE_CLAIMED, amount);
+
+        WinningsClaimed {
+            game_id,
+            player,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    // -----------------------------------------------------------------------
+    // cancel_game / refund
+    // -----------------------------------------------------------------------
+
+    /// Cancel an open game so every player can reclaim their wager. Admin only.
+    ///
+    /// Emits `GameCancelled`.
+    pub fn cancel_game(env: Env, game_id: u64) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        let mut game: GameData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)?;
+
+        if game.status != GameStatus::Open {
+            return Err(Error::GameAlreadyResolved);
+        }
+
+        set_status(&mut game, GameStatus::Cancelled)?;
+        persist_set(&env, DataKey::Game(game_id), &game);
+        remove_open_game(&env, game_id);
+
+        GameCancelled {
+            game_id,
+            total_pot: game.total_pot,
+            player_count: game.player_count,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Refund a player's full wager from a cancelled or expired game.
+    ///
+    /// Returns the amount refunded. A prediction can be refunded once.
+    ///
+    /// Emits `Refunded`.
+    pub fn refund(env: Env, player: Address, game_id: u64) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let game: GameData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)?;
+
+        if !matches!(game.status, GameStatus::Cancelled | GameStatus::Expired) {
+            return Err(Error::GameNotCancelled);
+        }
+
+        refund_prediction(&env, &player, game_id, game)
+    }
+
+    /// Return a sponsor's contribution from a cancelled or expired game.
+    ///
+    /// Returns the amount refunded. A contribution can be refunded once.
+    ///
+    /// Emits `SponsorshipRefunded`.
+    pub fn refund_sponsorship(env: Env, sponsor: Address, game_id: u64) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        sponsor.require_auth();
+
+        let mut game: GameData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)?;
+        if !matches!(game.status, GameStatus::Cancelled | GameStatus::Expired) {
+            return Err(Error::GameNotCancelled);
+        }
+
+        let sponsorship_key = DataKey::Sponsorship(game_id, sponsor.clone());
+        let mut sponsorship: Sponsorship = env
+            .storage()
+            .persistent()
+            .get(&sponsorship_key)
+            .ok_or(Error::SponsorshipNotFound)?;
+        if sponsorship.refunded {
+            return Err(Error::AlreadyClaimed);
+        }
+
+        // Mark refunded before any external call (reentrancy guard).
+        sponsorship.refunded = true;
+        persist_set(&env, sponsorship_key, &sponsorship);
+
+        game.paid_out = game
+            .paid_out
+            .checked_add(sponsorship.amount)
+            .ok_or(Error::Overflow)?;
+        persist_set(&env, DataKey::Game(game_id), &game);
+
+        let token = game_token(&env, game_id)?;
+        transfer_from_escrow(
+            &env,
+            &token,
+            &sponsor,
+            sponsorship.amount,
+            symbol_short!("refund"),
+        )?;
+
+        SponsorshipRefunded {
+            game_id,
+            sponsor,
+            amount: sponsorship.amount,
+        }
+        .publish(&env);
+
+        Ok(sponsorship.amount)
+    }
+
+    /// Refund a player's wager from a game the admin failed to resolve.
+    ///
+    /// The game must still be `Open` at least `resolution_timeout` seconds
+    /// after its `close_time` (or already cancelled or expired). The first
+    /// call marks the game `Expired` so it can no longer be resolved; every
+    /// player then recovers their full wager exactly as with `refund`. Fails
+    /// with `GameNotExpired` before the timeout, when expiry is disabled, or
+    /// for games without a deadline.
+    ///
+    /// Emits `GameExpired` on the first call, and `Refunded`.
+    pub fn claim_refund(env: Env, player: Address, game_id: u64) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let mut game: GameData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)?;
+
+        match game.status {
+            GameStatus::Cancelled | GameStatus::Expired => {}
+            GameStatus::Open => {
+                let timeout = get_resolution_timeout(&env);
+                let expires_at = game.close_time.saturating_add(timeout);
+                if timeout == 0 || game.close_time == 0 || env.ledger().timestamp() < expires_at {
+                    return Err(Error::GameNotExpired);
+                }
+
+                set_status(&mut game, GameStatus::Expired)?;
+                persist_set(&env, DataKey::Game(game_id), &game);
+                remove_open_game(&env, game_id);
+
+                GameExpired {
+                    game_id,
+                    total_pot: game.total_pot,
+                    player_count: game.player_count,
+                }
+                .publish(&env);
+            }
+            _ => return Err(Error::GameAlreadyResolved),
+        }
+
+        refund_prediction(&env, &player, game_id, game)
+    }
+
+    // -----------------------------------------------------------------------
+    // Migration
+    // -----------------------------------------------------------------------
+
+    /// Enable or disable `import_game`. Admin only.
+    ///
+    /// Enable only while migrating a legacy deployment into a fresh instance
+    /// and disable again before opening the contract to players.
+    pub fn set_migration_mode(env: Env, enabled: bool) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MigrationEnabled, &enabled);
+        Ok(())
+    }
+
+    /// Import a game from a legacy deployment. Admin only; requires
+    /// migration mode.
+    ///
+    /// `predictions[i]` is the entry of `players[i]`. Per-color totals and
+    /// counts are rebuilt from the predictions, so legacy records without
+    /// them can be imported as-is. The import is rejected with
+    /// `InvalidMigrationData` unless the predictions agree with the game's
+    /// `total_pot` and `player_count` and, for resolved games, with
+    /// `winner_count` and `winning_total`, and unless `paid_out`,
+    /// `claimed_count`, `fee` and the `claimed` flags match what this
+    /// contract would have recorded for the game.
+    ///
+    /// The game's unpaid balance (see `imported_liability`) is debited from
+    /// the admin and escrowed, so every imported prize and refund is backed.
+    ///
+    /// Emits `GameImported`.
+    pub fn import_game(
+        env: Env,
+        game_id: u64,
+        game: GameData,
+        players: Vec<Address>,
+        predictions: Vec<PredictionEntry>,
+    ) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        let enabled: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::MigrationEnabled)
+            .unwrap_or(false);
+        if !enabled {
+            return Err(Error::MigrationDisabled);
+        }
+        if env.storage().persistent().has(&DataKey::Game(game_id)) {
+            return Err(Error::GameAlreadyExists);
+        }
+        if players.len() != predictions.len()
+            || players.len() != game.player_count
+            || players.len() > MAX_PLAYERS_PER_GAME
+            || !matches!(
+                game.status,
+                GameStatus::Open
+                    | GameStatus::Resolved
+                    | GameStatus::Cancelled
+                    | GameStatus::Expired
+            )
+        {
+            return Err(Error::InvalidMigrationData);
+        }
+
+        let mut imported = game;
+        let fresh = new_game(&env, 0);
+        imported.color_totals = fresh.color_totals;
+        imported.color_counts = fresh.color_counts;
+
+        let outcome_count = get_outcome_count(&env);
+        let mut total_pot: i128 = 0;
+        for (player, entry) in players.iter().zip(predictions.iter()) {
+            if entry.color >= outcome_count || entry.wager <= 0 {
+                return Err(Error::InvalidMigrationData);
+            }
+            let key = DataKey::Prediction(game_id, player);
+            // The game is new, so an existing entry means a duplicate player.
+            if env.storage().persistent().has(&key) {
+                return Err(Error::InvalidMigrationData);
+            }
+
+            total_pot = total_pot.checked_add(entry.wager).ok_or(Error::Overflow)?;
+            let color_total = imported
+                .color_totals
+                .get(entry.color)
+                .unwrap_or(0)
+                .checked_add(entry.wager)
+                .ok_or(Error::Overflow)?;
+            imported.color_totals.set(entry.color, color_total);
+            let color_count = imported
+                .color_counts
+                .get(entry.color)
+                .unwrap_or(0)
+                .checked_add(1)
+                .ok_or(Error::Overflow)?;
+            imported.color_counts.set(entry.color, color_count);
+
+            persist_set(&env, key, &entry);
+            count_new_entry(&env, FAMILY_PREDICTION);
+        }
+
+        if total_pot != imported.total_pot {
+            return Err(Error::InvalidMigrationData);
+        }
+        if imported.status == GameStatus::Resolved
+            && (imported.winning_color >= outcome_count
+                || imported.winner_count
+                    != imported
+                        .color_counts
+                        .get(imported.winning_color)
+                        .unwrap_or(0)
+                || imported.winning_total
+                    != imported
+                        .color_totals
+                        .get(imported.winning_color)
+                        .unwrap_or(0))
+        {
+            return Err(Error::InvalidMigrationData);
+        }
+
+        let escrowed = imported_liability(&imported, &predictions)?;
+        if escrowed > 0 {
+            let balance_client = BalanceClient::new(&env, &get_balance_contract(&env)?);
+            let game_addr = env.current_contract_address();
+            balance_client.debit(&game_addr, &admin, &escrowed, &symbol_short!("import"));
+            balance_client.credit(&game_addr, &game_addr, &escrowed, &symbol_short!("escrow"));
+        }
+
+        if !players.is_empty() {
+            persist_set(&env, DataKey::PlayerList(game_id), &players);
+            count_new_entry(&env, FAMILY_PLAYER_LIST);
+        }
+        persist_set(&env, DataKey::Game(game_id), &imported);
+        count_new_entry(&env, FAMILY_GAME);
+        if imported.status == GameStatus::Open {
+            add_open_game(&env, game_id)?;
+        }
+
+        GameImported {
+            game_id,
+            player_count: imported.player_count,
+            total_pot,
+            escrowed,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Whether `import_game` is currently accepted.
+    pub fn is_migration_enabled(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::MigrationEnabled)
+            .unwrap_or(false)
+    }
+
+    // -----------------------------------------------------------------------
+    // Upgrade
+    // -----------------------------------------------------------------------
+
+    /// Replace the contract code with the already-uploaded `new_wasm_hash`.
+    /// Admin only. Persistent and instance storage are kept as-is.
+    ///
+    /// Emits `ContractUpgraded`.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let admin = get_admin(&env)?;
+        admin.require_auth();
+
+        ContractUpgraded {
+            new_wasm_hash: new_wasm_hash.clone(),
+        }
+        .publish(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // get_game
+    // -----------------------------------------------------------------------
+
+    /// Return the game state, or `None` if the game does not exist.
+    pub fn get_game(env: Env, game_id: u64) -> Option<GameData> {
+        env.storage().persistent().get(&DataKey::Game(game_id))
+    }
+
+    /// List a resolved game's winners with their payouts, in prediction order.
+    ///
+    /// Skips the first `start` winners and returns at most `limit` (capped
+    /// at `MAX_WINNERS_PAGE`). Each payout is the pro-rata share
+    /// `(total_pot - fee) * wager / winning_total`; the rounding remainder
+    /// swept by the last claimer and any staking bonus are not included.
+    /// Fails with `GameNotResolved` until the game is resolved.
+    pub fn get_winners(
+        env: Env,
+        game_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<(Address, i128)>, Error> {
+        let game: GameData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)?;
+        if game.status != GameStatus::Resolved {
+            return Err(Error::GameNotResolved);
+        }
+
+        let limit = limit.min(MAX_WINNERS_PAGE);
+        let mut winners = Vec::new(&env);
+        if limit == 0 || game.winner_count == 0 {
+            return Ok(winners);
+        }
+
+        let distributable = game
+            .total_pot
+            .checked_sub(game.fee)
+            .ok_or(Error::Overflow)?;
+        let players: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PlayerList(game_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        let mut seen: u32 = 0;
+        for player in players.iter() {
+            let entry = match load_prediction_legs(&env, game_id, &player) {
+                Ok((_, legs)) => match legs.iter().find(|leg| leg.color == game.winning_color) {
+                    Some(entry) => entry,
+                    None => continue,
+                },
+                Err(_) => continue,
+            };
+            seen += 1;
+            if seen <= start {
+                continue;
+            }
+            let share = distributable
+                .checked_mul(entry.wager)
+                .and_then(|v| v.checked_div(game.winning_total))
+                .ok_or(Error::Overflow)?;
+            winners.push_back((player, share));
+            if winners.len() >= limit {
+                break;
+            }
+        }
+        Ok(winners)
+    }
+
+    /// Return up to `limit` ids of open games, skipping the first `start`.
+    ///
+    /// Games are listed in the order they were created and leave the list
+    /// once resolved or cancelled. `limit` is capped at
+    /// `MAX_OPEN_GAMES_PAGE`.
+    pub fn list_open_games(env: Env, start: u32, limit: u32) -> Vec<u64> {
+        let open = get_open_games(&env);
+        let end = start
+            .saturating_add(limit.min(MAX_OPEN_GAMES_PAGE))
+            .min(open.len());
+        if start >= end {
+            return Vec::new(&env);
+        }
+        open.slice(start..end)
+    }
+
+    /// Return a player's prediction for a game, or `None` if they have not
+    /// predicted. The `claimed` flag shows whether it was paid or refunded.
+    pub fn get_prediction(env: Env, game_id: u64, player: Address) -> Option<PredictionEntry> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Prediction(game_id, player))
+    }
+
+    /// Return the legs of a player's split prediction for a game, or `None`
+    /// if they did not split.
+    pub fn get_split_prediction(
+        env: Env,
+        game_id: u64,
+        player: Address,
+    ) -> Option<Vec<PredictionEntry>> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SplitPrediction(game_id, player))
+    }
+
+    /// Quote the payout a new `wager` on `color` would earn if the game
+    /// resolved on `color` with the pools as they stand plus that wager.
+    ///
+    /// Applies the current house edge and, for platform-token games, adds the
+    /// current jackpot to the pot as resolution would; excludes any staking
+    /// boost bonus and the rounding remainder paid to the last claimer.
+    /// Unknown games are quoted as empty platform-token games. Fails with
+    /// `InvalidColor`, `InvalidAmount`, or `GameAlreadyResolved` where
+    /// `place_prediction` would.
+    pub fn quote_payout(env: Env, game_id: u64, color: u32, wager: i128) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        if color >= get_outcome_count(&env) {
+            return Err(Error::InvalidColor);
+        }
+        if wager <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let (pot, color_total) = match env
+            .storage()
+            .persistent()
+            .get::<_, GameData>(&DataKey::Game(game_id))
+        {
+            Some(game) => {
+                if game.status != GameStatus::Open {
+                    return Err(Error::GameAlreadyResolved);
+                }
+                (game.total_pot, game.color_totals.get(color).unwrap_or(0))
+            }
+            None => (0, 0),
+        };
+
+        let jackpot = match get_token_override(&env, game_id) {
+            Some(_) => 0,
+            None => get_jackpot(&env),
+        };
+        let pot = pot
+            .checked_add(wager)
+            .and_then(|v| v.checked_add(jackpot))
+            .ok_or(Error::Overflow)?;
+        let winning_total = color_total.checked_add(wager).ok_or(Error::Overflow)?;
+        let net_pot = pot
+            .checked_sub(house_fee(&env, pot)?)
+            .ok_or(Error::Overflow)?;
+        net_pot
+            .checked_mul(wager)
+            .and_then(|v| v.checked_div(winning_total))
+            .ok_or(Error::Overflow)
+    }
+
+    /// Return the sponsors of a game, in order of first contribution.
+    pub fn get_sponsors(env: Env, game_id: u64) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Sponsors(game_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Return one sponsor's total contribution to a game and whether it has
+    /// been refunded, or `None` if they never sponsored it.
+    pub fn get_sponsorship(env: Env, game_id: u64, sponsor: Address) -> Option<Sponsorship> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Sponsorship(game_id, sponsor))
+    }
+
+    /// Return a player's lifetime stats (all zero if they have never played).
+    pub fn get_player_stats(env: Env, player: Address) -> PlayerStats {
+        get_player_stats(&env, &player)
+    }
+
+    /// Return the balance/token contract a game settles in.
+    pub fn get_game_token(env: Env, game_id: u64) -> Result<Address, Error> {
+        game_token(&env, game_id)
+    }
+
+    /// Return the fees accrued by games in `token` and not yet withdrawn.
+    pub fn get_token_fees(env: Env, token: Address) -> i128 {
+        get_token_fees(&env, &token)
+    }
+
+    /// Return whether `resolver` may settle games.
+    pub fn is_resolver(env: Env, resolver: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Resolver(resolver))
+    }
+
+    /// Return the fees collected and not yet withdrawn.
+    pub fn get_accrued_fees(env: Env) -> i128 {
+        get_accrued_fees(&env)
+    }
+
+    /// Return the accepted wager range as `(min_wager, max_wager)`.
+    pub fn get_wager_limits(env: Env) -> (i128, i128) {
+        get_wager_limits(&env)
+    }
+
+    /// Return the number of selectable outcomes.
+    pub fn get_outcome_count(env: Env) -> u32 {
+        get_outcome_count(&env)
+    }
+
+    /// Return the resolution timeout in seconds; `0` when expiry is disabled.
+    pub fn get_resolution_timeout(env: Env) -> u64 {
+        get_resolution_timeout(&env)
+    }
+
+    /// Return the pot milestone step; `0` when milestones are disabled.
+    pub fn get_pot_milestone_step(env: Env) -> i128 {
+        get_pot_milestone_step(&env)
+    }
+
+    /// Return the jackpot carried over from games without winners.
+    pub fn get_jackpot(env: Env) -> i128 {
+        get_jackpot(&env)
+    }
+
+    /// Return the result commitment for a game, if one was made.
+    pub fn get_result_commitment(env: Env, game_id: u64) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ResultCommitment(game_id))
+    }
+
+    /// Return the committed color weights of a weighted game, if any.
+    pub fn get_color_weights(env: Env, game_id: u64) -> Option<Vec<u32>> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ColorWeights(game_id))
+    }
+
+    /// Return the staking boost configuration, if any.
+    pub fn get_staking_boost(env: Env) -> Option<StakingBoost> {
+        env.storage().instance().get(&DataKey::StakingBoost)
+    }
+
+    /// Return the referral contract notified of wagers, if any.
+    pub fn get_referral_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ReferralContract)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn require_initialized(env: &Env) -> Result<(), Error> {
+    if !env.storage().instance().has(&DataKey::Admin) {
+        return Err(Error::NotInitialized);
+    }
+    Ok(())
+}
+
+fn get_admin(env: &Env) -> Result<Address, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)
+}
+
+/// Move `game` along the shared lifecycle, rejecting transitions it does
+/// not allow.
+fn set_status(game: &mut GameData, next: GameStatus) -> Result<(), Error> {
+    game.status
+        .transition(next)
+        .map_err(|_| Error::InvalidTransition)
+}
+
+fn new_game(env: &Env, close_time: u64) -> GameData {
+    let mut color_totals = Vec::new(env);
+    let mut color_counts = Vec::new(env);
+    for _ in 0..get_outcome_count(env) {
+        color_totals.push_back(0i128);
+        color_counts.push_back(0u32);
+    }
+    GameData {
+        total_pot: 0,
+        player_count: 0,
+        winner_count: 0,
+        winning_color: 0,
+        status: GameStatus::Open,
+        winning_total: 0,
+        paid_out: 0,
+        claimed_count: 0,
+        fee: 0,
+        close_time,
+        color_totals,
+        color_counts,
+    }
+}
+
+/// Settle a game whose result was not committed up front. Shared by
+/// `resolve_prediction` and `resolve_prediction_as`.
+fn resolve_manual(env: &Env, game_id: u64, winning_color: u32) -> Result<(), Error> {
+    if env
+        .storage()
+        .persistent()
+        .has(&DataKey::ResultCommitment(game_id))
+        || env
+            .storage()
+            .persistent()
+            .has(&DataKey::ColorWeights(game_id))
+    {
+        return Err(Error::AlreadyCommitted);
+    }
+    resolve_game(env, game_id, winning_color)
+}
+
+/// Settle a game with `winning_color`. Shared by `resolve_prediction` and
+/// `reveal_result`; the caller has already authorized the admin.
+fn resolve_game(env: &Env, game_id: u64, winning_color: u32) -> Result<(), Error> {
+    if winning_color >= get_outcome_count(env) {
+        return Err(Error::InvalidColor);
+    }
+
+    let mut game: GameData = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .ok_or(Error::GameNotFound)?;
+
+    if game.status != GameStatus::Open {
+        return Err(Error::GameAlreadyResolved);
+    }
+
+    let winner_count = game.color_counts.get(winning_color).unwrap_or(0);
+    let winning_total = game.color_totals.get(winning_color).unwrap_or(0);
+
+    // The jackpot is held in the platform token; token games never touch it.
+    let token_override = get_token_override(env, game_id);
+    let jackpot = match token_override {
+        Some(_) => 0,
+        None => get_jackpot(env),
+    };
+    let fee = if winner_count > 0 {
+        game.total_pot = game.total_pot.checked_add(jackpot).ok_or(Error::Overflow)?;
+        house_fee(env, game.total_pot)?
+    } else {
+        0
+    };
+
+    set_status(&mut game, GameStatus::Resolved)?;
+    game.winning_color = winning_color;
+    game.winner_count = winner_count;
+    game.winning_total = winning_total;
+    game.fee = fee;
+    persist_set(env, DataKey::Game(game_id), &game);
+    remove_open_game(env, game_id);
+
+    if fee > 0 {
+        if let Some(token) = &token_override {
+            add_token_fees(env, token, fee)?;
+        } else {
+            let accrued = get_accrued_fees(env)
+                .checked_add(fee)
+                .ok_or(Error::Overflow)?;
+            env.storage()
+                .instance()
+                .set(&DataKey::AccruedFees, &accrued);
+        }
+
+        FeeCollected {
+            game_id,
+            recipient: get_fee_recipient(env)?,
+            amount: fee,
+        }
+        .publish(env);
+    }
+
+    if winner_count > 0 {
+        if jackpot > 0 {
+            env.storage().instance().set(&DataKey::JackpotPool, &0i128);
+            JackpotAwarded {
+                game_id,
+                amount: jackpot,
+            }
+            .publish(env);
+        }
+    } else if game.total_pot > 0 {
+        if let Some(token) = &token_override {
+            // A winnerless token game's pot goes to its token's fee bucket.
+            add_token_fees(env, token, game.total_pot)?;
+            FeeCollected {
+                game_id,
+                recipient: get_fee_recipient(env)?,
+                amount: game.total_pot,
+            }
+            .publish(env);
+        } else {
+            let rolled = jackpot.checked_add(game.total_pot).ok_or(Error::Overflow)?;
+            env.storage().instance().set(&DataKey::JackpotPool, &rolled);
+            JackpotRolledOver {
+                game_id,
+                amount: game.total_pot,
+                jackpot: rolled,
+            }
+            .publish(env);
+        }
+    }
+
+    PredictionResolved {
+        game_id,
+        winning_color,
+        winner_count,
+        total_pot: game.total_pot,
+    }
+    .publish(env);
+
+    Ok(())
+}
+
+/// `sha256(color_be_bytes || salt)`, the preimage layout of a result
+/// commitment.
+fn result_hash(env: &Env, color: u32, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &color.to_be_bytes());
+    preimage.append(&Bytes::from(salt.clone()));
+    env.crypto().sha256(&preimage).into()
+}
+
+/// Sum of a weight table, failing with `Overflow` if it exceeds `u32`.
+fn total_weight(weights: &Vec<u32>) -> Result<u32, Error> {
+    let mut total: u32 = 0;
+    for w in weights.iter() {
+        total = total.checked_add(w).ok_or(Error::Overflow)?;
+    }
+    Ok(total)
+}
+
+/// Map `roll` (`< total_weight`) onto the cumulative weights.
+fn weighted_color(weights: &Vec<u32>, roll: u32) -> u32 {
+    let mut cumulative: u32 = 0;
+    for (color, w) in weights.iter().enumerate() {
+        cumulative += w;
+        if roll < cumulative {
+            return color as u32;
+        }
+    }
+    weights.len() - 1
+}
+
+/// Refund `player`'s wager from a cancelled `game`. The caller has already
+/// authorized the player and checked the game is cancelled.
+fn refund_prediction(
+    env: &Env,
+    player: &Address,
+    game_id: u64,
+    mut game: GameData,
+) -> Result<i128, Error> {
+    let (prediction_key, mut legs) = load_prediction_legs(env, game_id, player)?;
+
+    // All legs of a split are refunded together.
+    let mut wager: i128 = 0;
+    for i in 0..legs.len() {
+        let mut leg = legs.get_unchecked(i);
+        if leg.claimed {
+            return Err(Error::AlreadyClaimed);
+        }
+        wager = wager.checked_add(leg.wager).ok_or(Error::Overflow)?;
+        // Mark refunded before any external call (reentrancy guard).
+        leg.claimed = true;
+        legs.set(i, leg);
+    }
+    store_prediction_legs(env, prediction_key, &legs);
+
+    game.paid_out = game.paid_out.checked_add(wager).ok_or(Error::Overflow)?;
+    persist_set(env, DataKey::Game(game_id), &game);
+
+    // A cancelled game does not count toward the player's history.
+    let mut stats = get_player_stats(env, player);
+    stats.games_played = stats.games_played.saturating_sub(1);
+    stats.total_wagered = stats
+        .total_wagered
+        .checked_sub(wager)
+        .ok_or(Error::Overflow)?
+        .max(0);
+    set_player_stats(env, player, &stats)?;
+
+    let token = game_token(env, game_id)?;
+    transfer_from_escrow(env, &token, player, wager, symbol_short!("refund"))?;
+
+    Refunded {
+        game_id,
+        player: player.clone(),
+        amount: wager,
+    }
+    .publish(env);
+
+    Ok(wager)
+}
+
+/// Load a player's prediction for a game as a list of legs: the single
+/// `Prediction` entry, or every leg of a split. Returns the key to write
+/// the legs back to with `store_prediction_legs`.
+fn load_prediction_legs(
+    env: &Env,
+    game_id: u64,
+    player: &Address,
+) -> Result<(DataKey, Vec<PredictionEntry>), Error> {
+    let key = DataKey::Prediction(game_id, player.clone());
+    if let Some(entry) = env
+        .storage()
+        .persistent()
+        .get::<DataKey, PredictionEntry>(&key)
+    {
+        return Ok((key, Vec::from_array(env, [entry])));
+    }
+    let key = DataKey::SplitPrediction(game_id, player.clone());
+    let legs = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(Error::PredictionNotFound)?;
+    Ok((key, legs))
+}
+
+fn store_prediction_legs(env: &Env, key: DataKey, legs: &Vec<PredictionEntry>) {
+    match key {
+        DataKey::Prediction(..) => persist_set(env, key, &legs.get_unchecked(0)),
+        _ => persist_set(env, key, legs),
+    }
+}
+
+fn get_resolution_timeout(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ResolutionTimeout)
+        .unwrap_or(0)
+}
+
+fn validate_wager_limits(min_wager: i128, max_wager: i128) -> Result<(), Error> {
+    if min_wager <= 0 || max_wager < min_wager {
+        return Err(Error::InvalidAmount);
+    }
+    Ok(())
+}
+
+fn get_wager_limits(env: &Env) -> (i128, i128) {
+    let min_wager = env
+        .storage()
+        .instance()
+        .get(&DataKey::MinWager)
+        .unwrap_or(1);
+    let max_wager = env
+        .storage()
+        .instance()
+        .get(&DataKey::MaxWager)
+        .unwrap_or(i128::MAX);
+    (min_wager, max_wager)
+}
+
+/// Check an imported game's settlement fields against its `predictions` and
+/// return what the game still owes its players: the unclaimed part of the
+/// pot after fees, or nothing for a winnerless game whose pot rolled into
+/// the legacy jackpot.
+///
+/// Open games must have nothing paid or claimed. Cancelled and expired
+/// games may only have refunded (claimed) entries, and `paid_out` must be
+/// their sum. Resolved games may only have claimed winning entries, counted
+/// by `claimed_count`, and `paid_out` must be what `claim` pays them.
+fn imported_liability(game: &GameData, predictions: &Vec<PredictionEntry>) -> Result<i128, Error> {
+    let mut claimed_count: u32 = 0;
+    let mut claimed_total: i128 = 0;
+    let mut claimed_share: i128 = 0;
+    let distributable = game
+        .total_pot
+        .checked_sub(game.fee)
+        .ok_or(Error::Overflow)?;
+    for entry in predictions.iter() {
+        if !entry.claimed {
+            continue;
+        }
+        if game.status == GameStatus::Resolved && entry.color != game.winning_color {
+            return Err(Error::InvalidMigrationData);
+        }
+        claimed_count = claimed_count.checked_add(1).ok_or(Error::Overflow)?;
+        claimed_total = claimed_total
+            .checked_add(entry.wager)
+            .ok_or(Error::Overflow)?;
+        if game.status == GameStatus::Resolved {
+            let share = distributable
+                .checked_mul(entry.wager)
+                .and_then(|v| v.checked_div(game.winning_total))
+                .ok_or(Error::Overflow)?;
+            claimed_share = claimed_share.checked_add(share).ok_or(Error::Overflow)?;
+        }
+    }
+
+    let valid = match game.status {
+        GameStatus::Open => {
+            claimed_count == 0 && game.paid_out == 0 && game.claimed_count == 0 && game.fee == 0
+        }
+        GameStatus::Cancelled | GameStatus::Expired => {
+            game.paid_out == claimed_total && game.claimed_count == 0 && game.fee == 0
+        }
+        _ if game.winner_count == 0 => {
+            game.paid_out == 0 && game.claimed_count == 0 && game.fee == 0
+        }
+        _ => {
+            // The last winner to claim sweeps the rounding remainder.
+            let expected = if claimed_count == game.winner_count {
+                distributable
+            } else {
+                claimed_share
+            };
+            game.fee >= 0
+                && distributable >= 0
+                && game.claimed_count == claimed_count
+                && game.paid_out == expected
+        }
+    };
+    if !valid {
+        return Err(Error::InvalidMigrationData);
+    }
+
+    if game.status == GameStatus::Resolved {
+        if game.winner_count == 0 {
+            return Ok(0);
+        }
+        return distributable
+            .checked_sub(game.paid_out)
+            .ok_or(Error::Overflow);
+    }
+    game.total_pot
+        .checked_sub(game.paid_out)
+        .ok_or(Error::Overflow)
+}
+
+/// House fee on a resolved pot at the current `HouseEdgeBps`.
+fn house_fee(env: &Env, pot: i128) -> Result<i128, Error> {
+    let house_edge_bps: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::HouseEdgeBps)
+        .unwrap_or(0);
+    pot.checked_mul(house_edge_bps as i128)
+        .and_then(|v| v.checked_div(BASIS_POINTS_DIVISOR as i128))
+        .ok_or(Error::Overflow)
+}
+
+fn get_outcome_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::OutcomeCount)
+        .unwrap_or(COLOR_COUNT)
+}
+
+fn get_pot_milestone_step(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::PotMilestoneStep)
+        .unwrap_or(0)
+}
+
+/// Emit `PotMilestone` for the highest multiple of the milestone step the pot
+/// crossed growing from `previous_pot` to `total_pot`, if any.
+fn publish_pot_milestone(env: &Env, game_id: u64, previous_pot: i128, total_pot: i128) {
+    let step = get_pot_milestone_step(env);
+    if step > 0 && total_pot / step > previous_pot / step {
+        PotMilestone {
+            game_id,
+            milestone: total_pot / step * step,
+            total_pot,
+        }
+        .publish(env);
+    }
+}
+
+fn get_jackpot(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::JackpotPool)
+        .unwrap_or(0)
+}
+
+fn get_balance_contract(env: &Env) -> Result<Address, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::BalanceContract)
+        .ok_or(Error::NotInitialized)
+}
+
+fn get_token_override(env: &Env, game_id: u64) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::GameToken(game_id))
+}
+
+/// Balance/token contract a game settles in: its override, if any,
+/// otherwise the platform `BalanceContract`.
+fn game_token(env: &Env, game_id: u64) -> Result<Address, Error> {
+    match get_token_override(env, game_id) {
+        Some(token) => Ok(token),
+        None => get_balance_contract(env),
+    }
+}
+
+fn get_token_fees(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TokenFees(token.clone()))
+        .unwrap_or(0)
+}
+
+fn add_token_fees(env: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+    let fees = get_token_fees(env, token)
+        .checked_add(amount)
+        .ok_or(Error::Overflow)?;
+    persist_set(env, DataKey::TokenFees(token.clone()), &fees);
+    Ok(())
+}
+
+/// Move `amount` from this contract's escrow account to `to` in the balance
+/// contract `token`.
+fn transfer_from_escrow(
+    env: &Env,
+    token: &Address,
+    to: &Address,
+    amount: i128,
+    reason: Symbol,
+) -> Result<(), Error> {
+    let balance_client = BalanceClient::new(env, token);
+    let game_addr = env.current_contract_address();
+    balance_client.debit(&game_addr, &game_addr, &amount, &symbol_short!("payout"));
+    balance_client.credit(&game_addr, to, &amount, &reason);
+    Ok(())
+}
+
+/// Report a wager or prize claim to the referral contract if `player` has a
+/// referrer. Errors from the referral contract are ignored so they cannot
+/// block play.
+fn report_referral(env: &Env, player: &Address, event_type: u32, amount: i128) {
+    let referral: Address = match env.storage().instance().get(&DataKey::ReferralContract) {
+        Some(referral) => referral,
+        None => return,
+    };
+    let client = ReferralClient::new(env, &referral);
+    if let Ok(Ok(Some(_))) = client.try_get_referrer(player) {
+        let _ = client.try_record_referral_event(
+            &env.current_contract_address(),
+            player,
+            &event_type,
+            &amount,
+        );
+    }
+}
+
+fn get_fee_recipient(env: &Env) -> Result<Address, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::FeeRecipient)
+        .ok_or(Error::NotInitialized)
+}
+
+fn get_accrued_fees(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::AccruedFees)
+        .unwrap_or(0)
+}
+
+/// Compute the staking bonus on a winning `share`.
+///
+/// Returns `(staked, bonus)`. The bonus is `share * boost_bps / 10_000`, where
+/// `boost_bps` scales linearly with stake up to `max_boost_bps`, and is capped
+/// by the fee bucket balance. Returns `(0, 0)` when no boost is configured.
+/// A staking contract that fails to answer counts as no stake, so it can
+/// never block a claim.
+fn staking_bonus(env: &Env, player: &Address, share: i128) -> Result<(i128, i128), Error> {
+    let boost: StakingBoost = match env.storage().instance().get(&DataKey::StakingBoost) {
+        Some(boost) => boost,
+        None => return Ok((0, 0)),
+    };
+
+    let staked = match StakingClient::new(env, &boost.staking_contract).try_staked_amount(player) {
+        Ok(Ok(staked)) => staked,
+        _ => 0,
+    };
+    if staked <= 0 {
+        return Ok((staked, 0));
+    }
+
+    let boost_bps = if staked >= boost.full_boost_stake {
+        boost.max_boost_bps as i128
+    } else {
+        staked
+            .checked_mul(boost.max_boost_bps as i128)
+            .and_then(|v| v.checked_div(boost.full_boost_stake))
+            .ok_or(Error::Overflow)?
+    };
+    let bonus = share
+        .checked_mul(boost_bps)
+        .and_then(|v| v.checked_div(BASIS_POINTS_DIVISOR as i128))
+        .ok_or(Error::Overflow)?;
+
+    Ok((staked, bonus.min(get_accrued_fees(env)).max(0)))
+}
+
+const FAMILY_GAME: Symbol = symbol_short!("game");
+const FAMILY_PLAYER_LIST: Symbol = symbol_short!("players");
+const FAMILY_PREDICTION: Symbol = symbol_short!("predict");
+const FAMILY_PLAYER_STATS: Symbol = symbol_short!("stats");
+
+fn get_player_stats(env: &Env, player: &Address) -> PlayerStats {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlayerStats(player.clone()))
+        .unwrap_or_default()
+}
+
+fn set_player_stats(env: &Env, player: &Address, stats: &PlayerStats) -> Result<(), Error> {
+    let key = DataKey::PlayerStats(player.clone());
+    if !env.storage().persistent().has(&key) {
+        count_new_entry(env, FAMILY_PLAYER_STATS);
+    }
+    persist_set(env, key, stats);
+    Ok(())
+}
+
+fn get_open_games(env: &Env) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OpenGames)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Append a newly created game to the open-games index.
+fn add_open_game(env: &Env, game_id: u64) -> Result<(), Error> {
+    let mut open = get_open_games(env);
+    if open.len() >= MAX_OPEN_GAMES {
+        return Err(Error::TooManyOpenGames);
+    }
+    open.push_back(game_id);
+    persist_set(env, DataKey::OpenGames, &open);
+    Ok(())
+}
+
+/// Drop a game from the open-games index once it is resolved or cancelled.
+fn remove_open_game(env: &Env, game_id: u64) {
+    let mut open = get_open_games(env);
+    if let Some(index) = open.first_index_of(game_id) {
+        open.remove(index);
+        persist_set(env, DataKey::OpenGames, &open);
+    }
+}
+
+/// Persist a value in persistent storage and extend its TTL.
+fn persist_set<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: DataKey, val: &V) {
+    env.storage().persistent().set(&key, val);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+}
+
+/// Extend the TTL of a persistent entry, if it exists.
+fn extend_persistent(env: &Env, key: &DataKey) {
+    if env.storage().persistent().has(key) {
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_BUMP_LEDGERS,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        contract, contractimpl, contracttype,
+        testutils::{Address as _, Events, Ledger},
+        vec, Env, Event as _,
+    };
+
+    const MIN_WAGER: i128 = 1;
+    const MAX_WAGER: i128 = 1_000_000;
+
+    /// Balance of every account the mock balance contract has not seen yet.
+    const MOCK_STARTING_BALANCE: i128 = 1_000_000_000;
+
+    #[contract]
+    pub struct MockBalance;
+
+    #[contracttype]
+    pub enum BalanceKey {
+        Balance(Address),
+    }
+
+    #[contractimpl]
+    impl MockBalance {
+        pub fn set_balance(env: Env, user: Address, amount: i128) {
+            env.storage()
+                .persistent()
+                .set(&BalanceKey::Balance(user), &amount);
+        }
+
+        pub fn credit(env: Env, _game: Address, user: Address, amount: i128, _reason: Symbol) {
+            let bal = Self::balance_of(env.clone(), user.clone());
+            Self::set_balance(env, user, bal + amount);
+        }
+
+        pub fn debit(env: Env, _game: Address, user: Address, amount: i128, _reason: Symbol) {
+            let bal = Self::balance_of(env.clone(), user.clone());
+            assert!(bal >= amount, "insufficient balance");
+            Self::set_balance(env, user, bal - amount);
+        }
+
+        pub fn balance_of(env: Env, user: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&BalanceKey::Balance(user))
+                .unwrap_or(MOCK_STARTING_BALANCE)
+        }
+    }
+
+    #[contract]
+    pub struct MockReferral;
+
+    #[contracttype]
+    pub enum ReferralKey {
+        Referrer(Address),
+        Volume(Address),
+        Prizes(Address),
+    }
+
+    #[contractimpl]
+    impl MockReferral {
+        pub fn set_referrer(env: Env, user: Address, referrer: Address) {
+            env.storage()
+                .persistent()
+                .set(&ReferralKey::Referrer(user), &referrer);
+        }
+
+        pub fn get_referrer(env: Env, user: Address) -> Option<Address> {
+            env.storage().persistent().get(&ReferralKey::Referrer(user))
+        }
+
+        pub fn record_referral_event(
+            env: Env,
+            caller: Address,
+            user: Address,
+            event_type: u32,
+            amount: i128,
+        ) {
+            caller.require_auth();
+            if event_type == REFERRAL_EVENT_PRIZE_CLAIMED {
+                let prizes = Self::prizes_of(env.clone(), user.clone());
+                env.storage()
+                    .persistent()
+                    .set(&ReferralKey::Prizes(user), &(prizes + amount));
+                return;
+            }
+            assert_eq!(event_type, REFERRAL_EVENT_GAME_PLAYED);
+            let volume = Self::volume_of(env.clone(), user.clone());
+            env.storage()
+                .persistent()
+                .set(&ReferralKey::Volume(user), &(volume + amount));
+        }
+
+        pub fn prizes_of(env: Env, user: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&ReferralKey::Prizes(user))
+                .unwrap_or(0)
+        }
+
+        pub fn volume_of(env: Env, user: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&ReferralKey::Volume(user))
+                .unwrap_or(0)
+        }
+    }
+
+    #[contract]
+    pub struct MockRng;
+
+    #[contractimpl]
+    impl MockRng {
+        pub fn set_result(env: Env, game_id: u64, result: u32) {
+            env.storage().persistent().set(&game_id, &result);
+        }
+
+        pub fn is_ready(env: Env, game_id: u64) -> bool {
+            env.storage().persistent().has(&game_id)
+        }
+
+        pub fn get_result(env: Env, game_id: u64) -> u32 {
+            env.storage().persistent().get(&game_id).unwrap()
+        }
+    }
+
+    fn setup(
+        env: &Env,
+    ) -> (
+        ColorPredictionClient<'_>,
+        Address,
+        Address,
+        Address,
         Address,
     ) {
         let id = env.register(ColorPrediction, ());
@@ -420,339 +3137,1903 @@ mod test {
         let admin = Address::generate(env);
         let rng = Address::generate(env);
         let prize_pool = Address::generate(env);
-        let balance = Address::generate(env);
+        let balance = env.register(MockBalance, ());
+        env.mock_all_auths();
+        client.init(
+            &admin,
+            &rng,
+            &prize_pool,
+            &balance,
+            &0u32,
+            &Address::generate(env),
+            &MIN_WAGER,
+            &MAX_WAGER,
+            &COLOR_COUNT,
+        );
+        (client, admin, rng, prize_pool, balance)
+    }
+
+    // ------------------------------------------------------------------
+    // 1. Happy path: place predictions → resolve → inspect state
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_full_happy_path() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 1;
+        let winner = Address::generate(&env);
+        let loser = Address::generate(&env);
+
+        client.place_prediction(&winner, &COLOR_RED, &100i128, &game_id);
+        client.place_prediction(&loser, &COLOR_BLUE, &100i128, &game_id);
+
+        client.resolve_prediction(&game_id, &COLOR_RED);
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.status, GameStatus::Resolved);
+        assert_eq!(game.winning_color, COLOR_RED);
+        assert_eq!(game.winner_count, 1);
+        assert_eq!(game.total_pot, 200);
+        assert_eq!(game.player_count, 2);
+    }
+
+    // ------------------------------------------------------------------
+    // 2. All players win when all predict the correct color
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_all_winners() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 2;
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        let p3 = Address::generate(&env);
+
+        client.place_prediction(&p1, &COLOR_GREEN, &50i128, &game_id);
+        client.place_prediction(&p2, &COLOR_GREEN, &50i128, &game_id);
+        client.place_prediction(&p3, &COLOR_GREEN, &50i128, &game_id);
+
+        client.resolve_prediction(&game_id, &COLOR_GREEN);
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.winner_count, 3);
+        assert_eq!(game.total_pot, 150);
+    }
+
+    // ------------------------------------------------------------------
+    // 3. No winners when all predict the wrong color
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_no_winners() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 3;
+        let player = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_RED, &200i128, &game_id);
+
+        client.resolve_prediction(&game_id, &COLOR_BLUE);
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.winner_count, 0);
+        assert_eq!(game.status, GameStatus::Resolved);
+    }
+
+    // ------------------------------------------------------------------
+    // 4. Prediction on a second color rejected
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_duplicate_prediction_rejected() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 4;
+        let player = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_RED, &100i128, &game_id);
+
+        let result = client.try_place_prediction(&player, &COLOR_GREEN, &100i128, &game_id);
+        assert_eq!(result, Err(Ok(Error::AlreadyPredicted)));
+    }
+
+    // ------------------------------------------------------------------
+    // 5. Prediction on a resolved game rejected
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_predict_on_resolved_game_rejected() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 5;
+        let p1 = Address::generate(&env);
+        client.place_prediction(&p1, &COLOR_RED, &100i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_RED);
+
+        let late = Address::generate(&env);
+        let result = client.try_place_prediction(&late, &COLOR_RED, &100i128, &game_id);
+        assert!(result.is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 6. Double resolve rejected
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_double_resolve_rejected() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 6;
+        let player = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_YELLOW, &10i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_YELLOW);
+
+        let result = client.try_resolve_prediction(&game_id, &COLOR_YELLOW);
+        assert!(result.is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 7. Invalid color rejected on place_prediction
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_invalid_color_on_place_rejected() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 7;
+        let player = Address::generate(&env);
+        let result = client.try_place_prediction(&player, &99u32, &100i128, &game_id);
+        assert!(result.is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 8. Invalid color rejected on resolve_prediction
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_invalid_color_on_resolve_rejected() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 8;
+        let player = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_RED, &100i128, &game_id);
+
+        let result = client.try_resolve_prediction(&game_id, &99u32);
+        assert!(result.is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 9. Zero wager rejected
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_zero_wager_rejected() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 9;
+        let player = Address::generate(&env);
+        let result = client.try_place_prediction(&player, &COLOR_RED, &0i128, &game_id);
+        assert!(result.is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 10. Negative wager rejected
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_negative_wager_rejected() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 10;
+        let player = Address::generate(&env);
+        let result = client.try_place_prediction(&player, &COLOR_RED, &-50i128, &game_id);
+        assert!(result.is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 11. Non-admin cannot resolve
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_non_admin_cannot_resolve() {
+        let env = Env::default();
+        let (client, admin, rng, prize_pool, balance) = setup(&env);
+
+        let id2 = env.register(ColorPrediction, ());
+        let client2 = ColorPredictionClient::new(&env, &id2);
+        env.mock_all_auths();
+        client2.init(
+            &admin,
+            &rng,
+            &prize_pool,
+            &balance,
+            &0u32,
+            &Address::generate(&env),
+            &MIN_WAGER,
+            &MAX_WAGER,
+            &COLOR_COUNT,
+        );
+
+        let game_id: u64 = 11;
+        let player = Address::generate(&env);
+        client2.place_prediction(&player, &COLOR_RED, &100i128, &game_id);
+
+        let imposter = Address::generate(&env);
+        env.mock_auths(&[soroban_sdk::testutils::MockAuth {
+            address: &imposter,
+            invoke: &soroban_sdk::testutils::MockAuthInvoke {
+                contract: &id2,
+                fn_name: "resolve_prediction",
+                args: soroban_sdk::vec![
+                    &env,
+                    soroban_sdk::IntoVal::into_val(&game_id, &env),
+                    soroban_sdk::IntoVal::into_val(&COLOR_RED, &env),
+                ],
+                sub_invokes: &[],
+            },
+        }]);
+
+        let result = client2.try_resolve_prediction(&game_id, &COLOR_RED);
+        assert!(result.is_err());
+
+        let _ = client;
+    }
+
+    // ------------------------------------------------------------------
+    // 12. Cannot initialize twice
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_cannot_init_twice() {
+        let env = Env::default();
+        let (client, admin, rng, prize_pool, balance) = setup(&env);
+        env.mock_all_auths();
+
+        let result = client.try_init(
+            &admin,
+            &rng,
+            &prize_pool,
+            &balance,
+            &0u32,
+            &Address::generate(&env),
+            &MIN_WAGER,
+            &MAX_WAGER,
+            &COLOR_COUNT,
+        );
+        assert!(result.is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 13. Resolve non-existent game rejected
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_resolve_nonexistent_game_rejected() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let result = client.try_resolve_prediction(&999u64, &COLOR_RED);
+        assert!(result.is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 14. get_game returns None for unknown game
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_get_game_none_for_unknown() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let result = client.get_game(&9999u64);
+        assert!(result.is_none());
+    }
+
+    // ------------------------------------------------------------------
+    // 15. Multiple games are independent
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_multiple_games_independent() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+
+        client.place_prediction(&p1, &COLOR_RED, &100i128, &1u64);
+        client.place_prediction(&p2, &COLOR_BLUE, &200i128, &2u64);
+
+        client.resolve_prediction(&1u64, &COLOR_RED);
+        client.resolve_prediction(&2u64, &COLOR_GREEN);
+
+        let game1 = client.get_game(&1u64).unwrap();
+        let game2 = client.get_game(&2u64).unwrap();
+
+        assert_eq!(game1.winner_count, 1);
+        assert_eq!(game1.total_pot, 100);
+        assert_eq!(game2.winner_count, 0);
+        assert_eq!(game2.total_pot, 200);
+    }
+
+    // ------------------------------------------------------------------
+    // 16. All four valid colors can be used
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_all_valid_colors_accepted() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        for (game_id, color) in [
+            (20u64, COLOR_RED),
+            (21u64, COLOR_GREEN),
+            (22u64, COLOR_BLUE),
+            (23u64, COLOR_YELLOW),
+        ] {
+            let player = Address::generate(&env);
+            client.place_prediction(&player, &color, &10i128, &game_id);
+            client.resolve_prediction(&game_id, &color);
+            let game = client.get_game(&game_id).unwrap();
+            assert_eq!(game.winner_count, 1);
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // 17. Winners claim an equal share of the pot
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_claim_winnings() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 30;
+        let w1 = Address::generate(&env);
+        let w2 = Address::generate(&env);
+        let loser = Address::generate(&env);
+
+        client.place_prediction(&w1, &COLOR_RED, &100i128, &game_id);
+        client.place_prediction(&w2, &COLOR_RED, &100i128, &game_id);
+        client.place_prediction(&loser, &COLOR_BLUE, &100i128, &game_id);
+
+        // Claims are not possible before resolution.
+        let early = client.try_claim(&w1, &game_id);
+        assert_eq!(early, Err(Ok(Error::GameNotResolved)));
+
+        client.resolve_prediction(&game_id, &COLOR_RED);
+
+        assert_eq!(client.claim(&w1, &game_id), 150);
+        assert_eq!(client.claim(&w2, &game_id), 150);
+
+        let lost = client.try_claim(&loser, &game_id);
+        assert_eq!(lost, Err(Ok(Error::NoPayout)));
+    }
+
+    // ------------------------------------------------------------------
+    // 18. Payouts are proportional to wager; remainder goes to last claimer
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_proportional_payout_with_remainder() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 32;
+        let small = Address::generate(&env);
+        let large = Address::generate(&env);
+        let loser = Address::generate(&env);
+
+        client.place_prediction(&small, &COLOR_BLUE, &100i128, &game_id);
+        client.place_prediction(&large, &COLOR_BLUE, &200i128, &game_id);
+        client.place_prediction(&loser, &COLOR_RED, &100i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_BLUE);
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.winning_total, 300);
+
+        // 400 * 100 / 300 = 133; the last claimer takes 400 - 133 = 267.
+        assert_eq!(client.claim(&small, &game_id), 133);
+        assert_eq!(client.claim(&large, &game_id), 267);
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.paid_out, game.total_pot);
+        assert_eq!(game.claimed_count, 2);
+    }
+
+    // ------------------------------------------------------------------
+    // 19. Double claim rejected
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_double_claim_rejected() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 31;
+        let player = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_GREEN, &100i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_GREEN);
+
+        client.claim(&player, &game_id);
+        let again = client.try_claim(&player, &game_id);
+        assert_eq!(again, Err(Ok(Error::AlreadyClaimed)));
+
+        let stranger = Address::generate(&env);
+        let none = client.try_claim(&stranger, &game_id);
+        assert_eq!(none, Err(Ok(Error::PredictionNotFound)));
+    }
+
+    // ------------------------------------------------------------------
+    // 20. House fee is deducted from the pot and accrued
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_house_fee_deducted_and_withdrawn() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        client.set_house_edge(&500u32); // 5%
+
+        let game_id: u64 = 33;
+        let winner = Address::generate(&env);
+        let loser = Address::generate(&env);
+        client.place_prediction(&winner, &COLOR_RED, &500i128, &game_id);
+        client.place_prediction(&loser, &COLOR_GREEN, &500i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_RED);
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.fee, 50);
+        assert_eq!(client.get_accrued_fees(), 50);
+
+        // Winner receives the pot net of the fee.
+        assert_eq!(client.claim(&winner, &game_id), 950);
+
+        assert_eq!(client.withdraw_fees(), 50);
+        assert_eq!(client.get_accrued_fees(), 0);
+        let again = client.try_withdraw_fees();
+        assert_eq!(again, Err(Ok(Error::NoFeesToWithdraw)));
+    }
+
+    // ------------------------------------------------------------------
+    // 21. No fee is taken when nobody wins; invalid fee rejected
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_no_fee_without_winners() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        client.set_house_edge(&500u32);
+
+        let game_id: u64 = 34;
+        let player = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_RED, &1_000i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_BLUE);
+
+        assert_eq!(client.get_game(&game_id).unwrap().fee, 0);
+        assert_eq!(client.get_accrued_fees(), 0);
+
+        let result = client.try_set_house_edge(&10_001u32);
+        assert_eq!(result, Err(Ok(Error::InvalidFee)));
+    }
+
+    // ------------------------------------------------------------------
+    // 22. Predictions rejected after the betting window closes
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_betting_window_enforced() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+
+        let game_id: u64 = 40;
+        let early = Address::generate(&env);
+        let late = Address::generate(&env);
+
+        let past = client.try_open_game(&game_id, &1_000u64);
+        assert_eq!(past, Err(Ok(Error::InvalidCloseTime)));
+
+        client.open_game(&game_id, &2_000u64);
+        assert_eq!(client.get_game(&game_id).unwrap().close_time, 2_000);
+
+        let dup = client.try_open_game(&game_id, &3_000u64);
+        assert_eq!(dup, Err(Ok(Error::GameAlreadyExists)));
+
+        client.place_prediction(&early, &COLOR_RED, &100i128, &game_id);
+
+        env.ledger().set_timestamp(2_000);
+        let result = client.try_place_prediction(&late, &COLOR_RED, &100i128, &game_id);
+        assert_eq!(result, Err(Ok(Error::BettingClosed)));
+
+        client.resolve_prediction(&game_id, &COLOR_RED);
+        assert_eq!(client.claim(&early, &game_id), 100);
+    }
+
+    // ------------------------------------------------------------------
+    // 23. Storage health reports entry counts and instance TTL
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_storage_health_and_bump() {
+        let env = Env::default();
+        env.ledger().set_sequence_number(100);
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        client.place_prediction(&p1, &COLOR_RED, &100i128, &1u64);
+        client.place_prediction(&p2, &COLOR_BLUE, &100i128, &1u64);
+        client.open_game(&2u64, &1_000u64);
+
+        let health = client.get_storage_health();
+        assert_eq!(health.last_bump_ledger, 100);
+        assert_eq!(health.instance_ttl, INSTANCE_BUMP_LEDGERS);
+        let game = health.families.get(0).unwrap();
+        assert_eq!(game.family, FAMILY_GAME);
+        assert_eq!(game.count, 2);
+        assert_eq!(health.families.get(1).unwrap().count, 1);
+        assert_eq!(health.families.get(2).unwrap().count, 2);
+
+        env.ledger().set_sequence_number(1_100);
+        assert_eq!(
+            client.get_storage_health().instance_ttl,
+            INSTANCE_BUMP_LEDGERS - 1_000
+        );
+
+        client.bump_all_config();
+        let health = client.get_storage_health();
+        assert_eq!(health.last_bump_ledger, 1_100);
+        assert_eq!(health.instance_ttl, INSTANCE_BUMP_LEDGERS);
+    }
+
+    // ------------------------------------------------------------------
+    // 24. Staking boost paid from the fee bucket, capped by its balance
+    // ------------------------------------------------------------------
+
+    #[contract]
+    pub struct MockStaking;
+
+    #[contractimpl]
+    impl MockStaking {
+        pub fn set_stake(env: Env, user: Address, amount: i128) {
+            env.storage().persistent().set(&user, &amount);
+        }
+
+        pub fn staked_amount(env: Env, user: Address) -> i128 {
+            env.storage().persistent().get(&user).unwrap_or(0)
+        }
+    }
+
+    #[test]
+    fn test_staking_boost_funded_from_fees() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let staking_id = env.register(MockStaking, ());
+        let staking = MockStakingClient::new(&env, &staking_id);
+
+        let invalid =
+            client.try_set_staking_boost(&Some(staking_id.clone()), &1_001u32, &1_000i128);
+        assert_eq!(invalid, Err(Ok(Error::InvalidBoost)));
+
+        client.set_house_edge(&1_000u32); // 10%
+        client.set_staking_boost(&Some(staking_id.clone()), &1_000u32, &1_000i128);
+
+        let staker = Address::generate(&env);
+        let half_staker = Address::generate(&env);
+        let loser = Address::generate(&env);
+        staking.set_stake(&staker, &5_000i128);
+        staking.set_stake(&half_staker, &500i128);
+
+        let game_id: u64 = 50;
+        client.place_prediction(&staker, &COLOR_RED, &500i128, &game_id);
+        client.place_prediction(&half_staker, &COLOR_RED, &500i128, &game_id);
+        client.place_prediction(&loser, &COLOR_BLUE, &1_000i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_RED);
+        assert_eq!(client.get_accrued_fees(), 200);
+
+        // Share 900, full boost 10% = 90.
+        assert_eq!(client.claim(&staker, &game_id), 990);
+        assert_eq!(client.get_accrued_fees(), 110);
+
+        // Share 900, half stake gives 5% = 45.
+        assert_eq!(client.claim(&half_staker, &game_id), 945);
+        assert_eq!(client.get_accrued_fees(), 65);
+
+        // Bonus capped by the remaining fee bucket.
+        let game_id: u64 = 51;
+        client.set_house_edge(&0u32);
+        client.place_prediction(&staker, &COLOR_RED, &1_000i128, &game_id);
+        client.place_prediction(&loser, &COLOR_BLUE, &1_000i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_RED);
+        assert_eq!(client.claim(&staker, &game_id), 2_065);
+        assert_eq!(client.get_accrued_fees(), 0);
+
+        // A staking contract that cannot answer (here one without
+        // `staked_amount`) pays no bonus but never blocks the claim.
+        let broken = env.register(MockRng, ());
+        client.set_staking_boost(&Some(broken), &1_000u32, &1_000i128);
+        client.set_house_edge(&1_000u32);
+        let game_id: u64 = 52;
+        client.place_prediction(&staker, &COLOR_RED, &1_000i128, &game_id);
+        client.place_prediction(&loser, &COLOR_BLUE, &1_000i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_RED);
+        assert_eq!(client.claim(&staker, &game_id), 1_800);
+        assert_eq!(client.get_accrued_fees(), 200);
+
+        client.set_staking_boost(&None, &0u32, &0i128);
+        assert_eq!(client.get_staking_boost(), None);
+    }
+
+    // ------------------------------------------------------------------
+    // 25. Cancelled game refunds every wager once
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_cancel_and_refund() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 60;
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        client.place_prediction(&p1, &COLOR_RED, &300i128, &game_id);
+        client.place_prediction(&p2, &COLOR_BLUE, &200i128, &game_id);
+
+        let early = client.try_refund(&p1, &game_id);
+        assert_eq!(early, Err(Ok(Error::GameNotCancelled)));
+
+        client.cancel_game(&game_id);
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.status, GameStatus::Cancelled);
+
+        // No further predictions, resolution, or claims.
+        let late = client.try_place_prediction(&outsider, &COLOR_RED, &100i128, &game_id);
+        assert_eq!(late, Err(Ok(Error::GameAlreadyResolved)));
+        let resolve = client.try_resolve_prediction(&game_id, &COLOR_RED);
+        assert_eq!(resolve, Err(Ok(Error::GameAlreadyResolved)));
+        let claim = client.try_claim(&p1, &game_id);
+        assert_eq!(claim, Err(Ok(Error::GameNotResolved)));
+
+        assert_eq!(client.refund(&p1, &game_id), 300);
+        assert_eq!(client.refund(&p2, &game_id), 200);
+        assert_eq!(client.get_game(&game_id).unwrap().paid_out, 500);
+
+        let again = client.try_refund(&p1, &game_id);
+        assert_eq!(again, Err(Ok(Error::AlreadyClaimed)));
+        let none = client.try_refund(&outsider, &game_id);
+        assert_eq!(none, Err(Ok(Error::PredictionNotFound)));
+    }
+
+    #[test]
+    fn test_cannot_cancel_resolved_game() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 61;
+        let player = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_RED, &100i128, &game_id);
+        client.resolve_prediction(&game_id, &COLOR_RED);
+
+        let result = client.try_cancel_game(&game_id);
+        assert_eq!(result, Err(Ok(Error::GameAlreadyResolved)));
+        let missing = client.try_cancel_game(&999u64);
+        assert_eq!(missing, Err(Ok(Error::GameNotFound)));
+    }
+
+    // ------------------------------------------------------------------
+    // 26. Wager limits enforced and adjustable by the admin
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_wager_limits() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        assert_eq!(client.get_wager_limits(), (MIN_WAGER, MAX_WAGER));
+        client.set_wager_limits(&50i128, &500i128);
+
+        let game_id: u64 = 70;
+        let player = Address::generate(&env);
+        let low = client.try_place_prediction(&player, &COLOR_RED, &49i128, &game_id);
+        assert_eq!(low, Err(Ok(Error::WagerTooLow)));
+        let high = client.try_place_prediction(&player, &COLOR_RED, &501i128, &game_id);
+        assert_eq!(high, Err(Ok(Error::WagerTooHigh)));
+        client.place_prediction(&player, &COLOR_RED, &500i128, &game_id);
+
+        let invalid = client.try_set_wager_limits(&500i128, &50i128);
+        assert_eq!(invalid, Err(Ok(Error::InvalidAmount)));
+        let zero = client.try_set_wager_limits(&0i128, &50i128);
+        assert_eq!(zero, Err(Ok(Error::InvalidAmount)));
+    }
+
+    // ------------------------------------------------------------------
+    // 27. Per-color totals track live stakes and drive resolution
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_per_color_totals() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 80;
+        client.place_prediction(&Address::generate(&env), &COLOR_RED, &100i128, &game_id);
+        client.place_prediction(&Address::generate(&env), &COLOR_RED, &50i128, &game_id);
+        client.place_prediction(&Address::generate(&env), &COLOR_BLUE, &70i128, &game_id);
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.color_totals.len(), COLOR_COUNT);
+        assert_eq!(game.color_totals.get(COLOR_RED).unwrap(), 150);
+        assert_eq!(game.color_totals.get(COLOR_GREEN).unwrap(), 0);
+        assert_eq!(game.color_totals.get(COLOR_BLUE).unwrap(), 70);
+        assert_eq!(game.color_counts.get(COLOR_RED).unwrap(), 2);
+        assert_eq!(game.color_counts.get(COLOR_BLUE).unwrap(), 1);
+
+        client.resolve_prediction(&game_id, &COLOR_RED);
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.winner_count, 2);
+        assert_eq!(game.winning_total, 150);
+    }
+
+    // ------------------------------------------------------------------
+    // 28. Same-color predictions top up the existing stake
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_wager_top_up() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+        client.set_wager_limits(&10i128, &300i128);
+
+        let game_id: u64 = 90;
+        let player = Address::generate(&env);
+        let other = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_GREEN, &100i128, &game_id);
+        client.place_prediction(&player, &COLOR_GREEN, &150i128, &game_id);
+        client.place_prediction(&other, &COLOR_RED, &50i128, &game_id);
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.total_pot, 300);
+        assert_eq!(game.player_count, 2);
+        assert_eq!(game.color_totals.get(COLOR_GREEN).unwrap(), 250);
+        assert_eq!(game.color_counts.get(COLOR_GREEN).unwrap(), 1);
+
+        // The aggregated stake is bounded by the max wager.
+        let over = client.try_place_prediction(&player, &COLOR_GREEN, &60i128, &game_id);
+        assert_eq!(over, Err(Ok(Error::WagerTooHigh)));
+
+        client.resolve_prediction(&game_id, &COLOR_GREEN);
+        assert_eq!(client.claim(&player, &game_id), 300);
+    }
+
+    // ------------------------------------------------------------------
+    // 29. Legacy games import behind the migration flag
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_import_game_migration() {
+        let env = Env::default();
+        let (client, admin, _, _, balance_id) = setup(&env);
+        env.mock_all_auths();
+        let balance = MockBalanceClient::new(&env, &balance_id);
+
+        let winner = Address::generate(&env);
+        let loser = Address::generate(&env);
+        let players = vec![&env, winner.clone(), loser.clone()];
+        let predictions = vec![
+            &env,
+            PredictionEntry {
+                color: COLOR_BLUE,
+                wager: 300,
+                claimed: false,
+            },
+            PredictionEntry {
+                color: COLOR_RED,
+                wager: 100,
+                claimed: false,
+            },
+        ];
+        let mut legacy = env.as_contract(&client.address, || new_game(&env, 0));
+        legacy.total_pot = 400;
+        legacy.player_count = 2;
+        legacy.winner_count = 1;
+        legacy.winning_color = COLOR_BLUE;
+        legacy.status = GameStatus::Resolved;
+        legacy.winning_total = 300;
+        // Legacy records carry no per-color totals.
+        legacy.color_totals = Vec::new(&env);
+        legacy.color_counts = Vec::new(&env);
+
+        let game_id: u64 = 100;
+        let disabled = client.try_import_game(&game_id, &legacy, &players, &predictions);
+        assert_eq!(disabled, Err(Ok(Error::MigrationDisabled)));
+
+        client.set_migration_mode(&true);
+        let mut inconsistent = legacy.clone();
+        inconsistent.total_pot = 500;
+        let bad = client.try_import_game(&game_id, &inconsistent, &players, &predictions);
+        assert_eq!(bad, Err(Ok(Error::InvalidMigrationData)));
+
+        // Settlement fields must match the claimed flags.
+        let mut inconsistent = legacy.clone();
+        inconsistent.paid_out = 100;
+        let bad = client.try_import_game(&game_id, &inconsistent, &players, &predictions);
+        assert_eq!(bad, Err(Ok(Error::InvalidMigrationData)));
+        let mut claimed_loser = predictions.clone();
+        claimed_loser.set(
+            1,
+            PredictionEntry {
+                color: COLOR_RED,
+                wager: 100,
+                claimed: true,
+            },
+        );
+        let bad = client.try_import_game(&game_id, &legacy, &players, &claimed_loser);
+        assert_eq!(bad, Err(Ok(Error::InvalidMigrationData)));
+
+        // The unpaid pot is escrowed from the admin.
+        client.import_game(&game_id, &legacy, &players, &predictions);
+        client.set_migration_mode(&false);
+        assert!(!client.is_migration_enabled());
+        assert_eq!(balance.balance_of(&admin), MOCK_STARTING_BALANCE - 400);
+        assert_eq!(
+            balance.balance_of(&client.address),
+            MOCK_STARTING_BALANCE + 400
+        );
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.color_totals.get(COLOR_BLUE).unwrap(), 300);
+        assert_eq!(game.color_counts.get(COLOR_RED).unwrap(), 1);
+        assert_eq!(client.claim(&winner, &game_id), 400);
+        assert_eq!(client.try_claim(&loser, &game_id), Err(Ok(Error::NoPayout)));
+    }
+
+    // ------------------------------------------------------------------
+    // 30. get_prediction exposes a player's own bet and claim status
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_get_prediction() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let game_id: u64 = 110;
+        let player = Address::generate(&env);
+        assert_eq!(client.get_prediction(&game_id, &player), None);
+
+        client.place_prediction(&player, &COLOR_YELLOW, &40i128, &game_id);
+        let expected = PredictionEntry {
+            color: COLOR_YELLOW,
+            wager: 40,
+            claimed: false,
+        };
+        assert_eq!(client.get_prediction(&game_id, &player), Some(expected));
+
+        client.resolve_prediction(&game_id, &COLOR_YELLOW);
+        client.claim(&player, &game_id);
+        assert!(client.get_prediction(&game_id, &player).unwrap().claimed);
+    }
+
+    // ------------------------------------------------------------------
+    // 31. Lifetime player stats follow place, claim, and refund
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_player_stats() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let player = Address::generate(&env);
+        let rival = Address::generate(&env);
+        assert_eq!(client.get_player_stats(&player), PlayerStats::default());
+
+        // Won game with a top-up.
+        client.place_prediction(&player, &COLOR_RED, &100i128, &120u64);
+        client.place_prediction(&player, &COLOR_RED, &50i128, &120u64);
+        client.place_prediction(&rival, &COLOR_BLUE, &150i128, &120u64);
+        client.resolve_prediction(&120u64, &COLOR_RED);
+        client.claim(&player, &120u64);
+
+        // Lost game.
+        client.place_prediction(&player, &COLOR_GREEN, &30i128, &121u64);
+        client.resolve_prediction(&121u64, &COLOR_BLUE);
+
+        // Cancelled game is removed from the history on refund.
+        client.place_prediction(&player, &COLOR_GREEN, &70i128, &122u64);
+        client.cancel_game(&122u64);
+        client.refund(&player, &122u64);
+
+        let stats = client.get_player_stats(&player);
+        assert_eq!(stats.games_played, 2);
+        assert_eq!(stats.total_wagered, 180);
+        assert_eq!(stats.total_won, 300);
+        assert_eq!(stats.win_count, 1);
+        assert_eq!(client.get_player_stats(&rival).win_count, 0);
+    }
+
+    // ------------------------------------------------------------------
+    // 32. upgrade is admin-gated
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_upgrade_requires_admin() {
+        let env = Env::default();
+        let hash = BytesN::from_array(&env, &[7u8; 32]);
+
+        let uninit = ColorPredictionClient::new(&env, &env.register(ColorPrediction, ()));
+        assert_eq!(uninit.try_upgrade(&hash), Err(Ok(Error::NotInitialized)));
+
+        let (client, _, _, _, _) = setup(&env);
+        env.set_auths(&[]);
+        assert!(client.try_upgrade(&hash).is_err());
+    }
+
+    // ------------------------------------------------------------------
+    // 33. Crossing a pot milestone emits PotMilestone
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_pot_milestone_events() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
         env.mock_all_auths();
-        client.init(&admin, &rng, &prize_pool, &balance);
-        (client, admin, rng, prize_pool, balance)
+        client.set_pot_milestone_step(&1_000i128);
+        assert_eq!(client.get_pot_milestone_step(), 1_000);
+
+        let game_id: u64 = 120;
+        let contract_events = |env: &Env| {
+            env.events()
+                .all()
+                .filter_by_contract(&client.address)
+                .events()
+                .len()
+        };
+
+        // 0 -> 900: no milestone, only PredictionPlaced.
+        client.place_prediction(&Address::generate(&env), &COLOR_RED, &900i128, &game_id);
+        assert_eq!(contract_events(&env), 1);
+
+        // 900 -> 2 500 crosses 1 000 and 2 000; one event for the highest.
+        client.place_prediction(&Address::generate(&env), &COLOR_BLUE, &1_600i128, &game_id);
+        assert_eq!(contract_events(&env), 2);
+        let milestone = PotMilestone {
+            game_id,
+            milestone: 2_000,
+            total_pot: 2_500,
+        };
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert_eq!(
+            events.events().last(),
+            Some(&milestone.to_xdr(&env, &client.address))
+        );
+
+        // 2 500 -> 2 999 stays below the next multiple.
+        client.place_prediction(&Address::generate(&env), &COLOR_GREEN, &499i128, &game_id);
+        assert_eq!(contract_events(&env), 1);
+
+        assert_eq!(
+            client.try_set_pot_milestone_step(&-1i128),
+            Err(Ok(Error::InvalidAmount))
+        );
     }
 
     // ------------------------------------------------------------------
-    // 1. Happy path: place predictions → resolve → inspect state
+    // 34. outcome_count configures the number of selectable outcomes
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_full_happy_path() {
+    fn test_configurable_outcome_count() {
         let env = Env::default();
-        let (client, _, _, _, _) = setup(&env);
         env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let balance = env.register(MockBalance, ());
+        let client = ColorPredictionClient::new(&env, &env.register(ColorPrediction, ()));
+        let init = |outcome_count: u32| {
+            client.try_init(
+                &admin,
+                &Address::generate(&env),
+                &Address::generate(&env),
+                &balance,
+                &0u32,
+                &Address::generate(&env),
+                &MIN_WAGER,
+                &MAX_WAGER,
+                &outcome_count,
+            )
+        };
+        assert_eq!(init(1), Err(Ok(Error::InvalidOutcomeCount)));
+        assert_eq!(init(17), Err(Ok(Error::InvalidOutcomeCount)));
+        assert_eq!(init(12), Ok(Ok(())));
+        assert_eq!(client.get_outcome_count(), 12);
 
-        let game_id: u64 = 1;
+        let game_id: u64 = 130;
         let winner = Address::generate(&env);
         let loser = Address::generate(&env);
+        client.place_prediction(&winner, &11u32, &100i128, &game_id);
+        client.place_prediction(&loser, &COLOR_YELLOW, &100i128, &game_id);
+        let out_of_range = client.try_place_prediction(&loser, &12u32, &100i128, &(game_id + 1));
+        assert_eq!(out_of_range, Err(Ok(Error::InvalidColor)));
+        assert_eq!(client.get_game(&game_id).unwrap().color_totals.len(), 12);
 
-        client.place_prediction(&winner, &COLOR_RED, &100i128, &game_id);
-        client.place_prediction(&loser, &COLOR_BLUE, &100i128, &game_id);
+        assert_eq!(
+            client.try_resolve_prediction(&game_id, &12u32),
+            Err(Ok(Error::InvalidColor))
+        );
+        client.resolve_prediction(&game_id, &11u32);
+        assert_eq!(client.claim(&winner, &game_id), 200);
+    }
+
+    // ------------------------------------------------------------------
+    // 35. quote_payout projects the payout from live pools and the edge
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_quote_payout() {
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
+        env.mock_all_auths();
+        client.set_house_edge(&1_000u32); // 10%
+
+        let game_id: u64 = 140;
+        // Empty game: the whole pot is the wager, less the edge.
+        assert_eq!(client.quote_payout(&game_id, &COLOR_RED, &100i128), 90);
+
+        client.place_prediction(&Address::generate(&env), &COLOR_RED, &100i128, &game_id);
+        client.place_prediction(&Address::generate(&env), &COLOR_BLUE, &300i128, &game_id);
 
+        // Pot 500, fee 50, red stake 200: 450 * 100 / 200 = 225.
+        let quote = client.quote_payout(&game_id, &COLOR_RED, &100i128);
+        assert_eq!(quote, 225);
+
+        let player = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_RED, &100i128, &game_id);
         client.resolve_prediction(&game_id, &COLOR_RED);
+        assert_eq!(client.claim(&player, &game_id), quote);
 
-        let game = client.get_game(&game_id).unwrap();
-        assert_eq!(game.status, GameStatus::Resolved);
-        assert_eq!(game.winning_color, COLOR_RED);
-        assert_eq!(game.winner_count, 1);
-        assert_eq!(game.total_pot, 200);
-        assert_eq!(game.player_count, 2);
+        assert_eq!(
+            client.try_quote_payout(&game_id, &COLOR_RED, &100i128),
+            Err(Ok(Error::GameAlreadyResolved))
+        );
+        assert_eq!(
+            client.try_quote_payout(&(game_id + 1), &COLOR_COUNT, &100i128),
+            Err(Ok(Error::InvalidColor))
+        );
     }
 
     // ------------------------------------------------------------------
-    // 2. All players win when all predict the correct color
+    // 36. Wagers are escrowed through the balance contract
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_all_winners() {
+    fn test_wagers_escrowed_in_balance_contract() {
         let env = Env::default();
-        let (client, _, _, _, _) = setup(&env);
+        let (client, _, _, _, balance_id) = setup(&env);
         env.mock_all_auths();
+        let balance = MockBalanceClient::new(&env, &balance_id);
+        let escrow = client.address.clone();
+        client.set_house_edge(&1_000u32); // 10%
+        let recipient = Address::generate(&env);
+        client.set_fee_recipient(&recipient);
 
-        let game_id: u64 = 2;
-        let p1 = Address::generate(&env);
-        let p2 = Address::generate(&env);
-        let p3 = Address::generate(&env);
+        let winner = Address::generate(&env);
+        let loser = Address::generate(&env);
+        balance.set_balance(&winner, &150);
+        balance.set_balance(&loser, &100);
+        balance.set_balance(&escrow, &0);
 
-        client.place_prediction(&p1, &COLOR_GREEN, &50i128, &game_id);
-        client.place_prediction(&p2, &COLOR_GREEN, &50i128, &game_id);
-        client.place_prediction(&p3, &COLOR_GREEN, &50i128, &game_id);
+        let game_id: u64 = 150;
+        client.place_prediction(&winner, &COLOR_GREEN, &100i128, &game_id);
+        client.place_prediction(&loser, &COLOR_RED, &100i128, &game_id);
+        assert_eq!(balance.balance_of(&winner), 50);
+        assert_eq!(balance.balance_of(&escrow), 200);
+
+        let short = client.try_place_prediction(&winner, &COLOR_GREEN, &100i128, &game_id);
+        assert_eq!(short, Err(Ok(Error::InsufficientBalance)));
 
         client.resolve_prediction(&game_id, &COLOR_GREEN);
+        client.claim(&winner, &game_id);
+        assert_eq!(balance.balance_of(&winner), 230);
+        client.withdraw_fees();
+        assert_eq!(balance.balance_of(&recipient), MOCK_STARTING_BALANCE + 20);
+        assert_eq!(balance.balance_of(&escrow), 0);
 
-        let game = client.get_game(&game_id).unwrap();
-        assert_eq!(game.winner_count, 3);
-        assert_eq!(game.total_pot, 150);
+        // Cancelled games refund from escrow.
+        assert_eq!(balance.balance_of(&loser), 0);
+        balance.set_balance(&loser, &100);
+        client.place_prediction(&loser, &COLOR_RED, &100i128, &(game_id + 1));
+        client.cancel_game(&(game_id + 1));
+        assert_eq!(balance.balance_of(&loser), 0);
+        client.refund(&loser, &(game_id + 1));
+        assert_eq!(balance.balance_of(&loser), 100);
+        assert_eq!(balance.balance_of(&escrow), 0);
     }
 
     // ------------------------------------------------------------------
-    // 3. No winners when all predict the wrong color
+    // 37. Committed results resolve only through a matching reveal
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_no_winners() {
+    fn test_commit_reveal_result() {
         let env = Env::default();
         let (client, _, _, _, _) = setup(&env);
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+
+        let salt = BytesN::from_array(&env, &[7u8; 32]);
+        let commit_for = |color: u32| {
+            let mut preimage = Bytes::from_array(&env, &color.to_be_bytes());
+            preimage.append(&Bytes::from(salt.clone()));
+            BytesN::from(env.crypto().sha256(&preimage))
+        };
+
+        let game_id: u64 = 160;
+        client.open_game(&game_id, &2_000);
+        client.commit_result(&game_id, &commit_for(COLOR_BLUE));
+        assert_eq!(
+            client.get_result_commitment(&game_id),
+            Some(commit_for(COLOR_BLUE))
+        );
+        let again = client.try_commit_result(&game_id, &commit_for(COLOR_RED));
+        assert_eq!(again, Err(Ok(Error::AlreadyCommitted)));
 
-        let game_id: u64 = 3;
         let player = Address::generate(&env);
-        client.place_prediction(&player, &COLOR_RED, &200i128, &game_id);
+        client.place_prediction(&player, &COLOR_BLUE, &100i128, &game_id);
 
-        client.resolve_prediction(&game_id, &COLOR_BLUE);
+        // The committed game cannot be resolved directly or to another color.
+        let direct = client.try_resolve_prediction(&game_id, &COLOR_RED);
+        assert_eq!(direct, Err(Ok(Error::AlreadyCommitted)));
+        let wrong_color = client.try_reveal_result(&game_id, &COLOR_RED, &salt);
+        assert_eq!(wrong_color, Err(Ok(Error::CommitmentMismatch)));
+        let wrong_salt = BytesN::from_array(&env, &[8u8; 32]);
+        let wrong = client.try_reveal_result(&game_id, &COLOR_BLUE, &wrong_salt);
+        assert_eq!(wrong, Err(Ok(Error::CommitmentMismatch)));
 
+        client.reveal_result(&game_id, &COLOR_BLUE, &salt);
         let game = client.get_game(&game_id).unwrap();
-        assert_eq!(game.winner_count, 0);
         assert_eq!(game.status, GameStatus::Resolved);
+        assert_eq!(game.winning_color, COLOR_BLUE);
+
+        // Commits are refused once betting has closed.
+        client.open_game(&(game_id + 1), &1_500);
+        env.ledger().set_timestamp(1_500);
+        let late = client.try_commit_result(&(game_id + 1), &commit_for(COLOR_RED));
+        assert_eq!(late, Err(Ok(Error::BettingClosed)));
+        let missing = client.try_reveal_result(&(game_id + 1), &COLOR_RED, &salt);
+        assert_eq!(missing, Err(Ok(Error::CommitmentNotFound)));
+
+        // Only games opened with a betting deadline accept a commitment.
+        let unknown = client.try_commit_result(&(game_id + 2), &commit_for(COLOR_RED));
+        assert_eq!(unknown, Err(Ok(Error::GameNotFound)));
+        client.place_prediction(&player, &COLOR_RED, &100i128, &(game_id + 2));
+        let no_deadline = client.try_commit_result(&(game_id + 2), &commit_for(COLOR_RED));
+        assert_eq!(no_deadline, Err(Ok(Error::InvalidCloseTime)));
     }
 
     // ------------------------------------------------------------------
-    // 4. Duplicate prediction rejected
+    // 38. Players recover wagers from games left unresolved past the timeout
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_duplicate_prediction_rejected() {
+    fn test_claim_refund_after_resolution_timeout() {
         let env = Env::default();
-        let (client, _, _, _, _) = setup(&env);
+        let (client, _, _, _, balance_id) = setup(&env);
         env.mock_all_auths();
+        let balance = MockBalanceClient::new(&env, &balance_id);
+        env.ledger().set_timestamp(1_000);
 
-        let game_id: u64 = 4;
-        let player = Address::generate(&env);
-        client.place_prediction(&player, &COLOR_RED, &100i128, &game_id);
+        let game_id: u64 = 170;
+        client.open_game(&game_id, &2_000);
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        client.place_prediction(&p1, &COLOR_RED, &100i128, &game_id);
+        client.place_prediction(&p2, &COLOR_BLUE, &50i128, &game_id);
 
-        let result = client.try_place_prediction(&player, &COLOR_GREEN, &100i128, &game_id);
-        assert!(result.is_err());
+        // Expiry is disabled until a timeout is configured.
+        env.ledger().set_timestamp(10_000);
+        let disabled = client.try_claim_refund(&p1, &game_id);
+        assert_eq!(disabled, Err(Ok(Error::GameNotExpired)));
+
+        client.set_resolution_timeout(&3_600);
+        assert_eq!(client.get_resolution_timeout(), 3_600);
+        env.ledger().set_timestamp(5_599);
+        let early = client.try_claim_refund(&p1, &game_id);
+        assert_eq!(early, Err(Ok(Error::GameNotExpired)));
+
+        env.ledger().set_timestamp(5_600);
+        assert_eq!(client.claim_refund(&p1, &game_id), 100);
+        assert_eq!(
+            client.get_game(&game_id).unwrap().status,
+            GameStatus::Expired
+        );
+        assert_eq!(balance.balance_of(&p1), MOCK_STARTING_BALANCE);
+
+        // The game can no longer be resolved; other players still get refunds,
+        // through either entry point.
+        let late = client.try_resolve_prediction(&game_id, &COLOR_RED);
+        assert_eq!(late, Err(Ok(Error::GameAlreadyResolved)));
+        let cancel = client.try_cancel_game(&game_id);
+        assert_eq!(cancel, Err(Ok(Error::GameAlreadyResolved)));
+        assert_eq!(client.refund(&p2, &game_id), 50);
+        let again = client.try_claim_refund(&p1, &game_id);
+        assert_eq!(again, Err(Ok(Error::AlreadyClaimed)));
+
+        // Games without a deadline never expire.
+        client.place_prediction(&p1, &COLOR_RED, &100i128, &(game_id + 1));
+        env.ledger().set_timestamp(1_000_000);
+        let open_ended = client.try_claim_refund(&p1, &(game_id + 1));
+        assert_eq!(open_ended, Err(Ok(Error::GameNotExpired)));
     }
 
     // ------------------------------------------------------------------
-    // 5. Prediction on a resolved game rejected
+    // 39. get_winners pages through winners and their payouts
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_predict_on_resolved_game_rejected() {
+    fn test_get_winners_paginated() {
         let env = Env::default();
         let (client, _, _, _, _) = setup(&env);
         env.mock_all_auths();
+        client.set_house_edge(&1_000u32); // 10%
 
-        let game_id: u64 = 5;
-        let p1 = Address::generate(&env);
-        client.place_prediction(&p1, &COLOR_RED, &100i128, &game_id);
-        client.resolve_prediction(&game_id, &COLOR_RED);
+        let game_id: u64 = 180;
+        let w1 = Address::generate(&env);
+        let loser = Address::generate(&env);
+        let w2 = Address::generate(&env);
+        let w3 = Address::generate(&env);
+        client.place_prediction(&w1, &COLOR_GREEN, &100i128, &game_id);
+        client.place_prediction(&loser, &COLOR_RED, &300i128, &game_id);
+        client.place_prediction(&w2, &COLOR_GREEN, &200i128, &game_id);
+        client.place_prediction(&w3, &COLOR_GREEN, &100i128, &game_id);
 
-        let late = Address::generate(&env);
-        let result = client.try_place_prediction(&late, &COLOR_RED, &100i128, &game_id);
-        assert!(result.is_err());
+        let early = client.try_get_winners(&game_id, &0, &10);
+        assert_eq!(early, Err(Ok(Error::GameNotResolved)));
+
+        client.resolve_prediction(&game_id, &COLOR_GREEN);
+
+        // Pot 700, fee 70, 630 shared over a winning stake of 400.
+        let first = client.get_winners(&game_id, &0, &2);
+        assert_eq!(first, vec![&env, (w1.clone(), 157), (w2.clone(), 315)]);
+        let rest = client.get_winners(&game_id, &2, &2);
+        assert_eq!(rest, vec![&env, (w3.clone(), 157)]);
+        assert_eq!(client.get_winners(&game_id, &3, &2).len(), 0);
+
+        // Claims pay the listed share.
+        assert_eq!(client.claim(&w1, &game_id), 157);
     }
 
     // ------------------------------------------------------------------
-    // 6. Double resolve rejected
+    // 40. Weighted games draw the color from the committed weights
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_double_resolve_rejected() {
+    fn test_weighted_game_resolution() {
         let env = Env::default();
-        let (client, _, _, _, _) = setup(&env);
+        let (_, admin, _, prize_pool, balance) = setup(&env);
+        let rng_id = env.register(MockRng, ());
+        let rng = MockRngClient::new(&env, &rng_id);
+        let client = ColorPredictionClient::new(&env, &env.register(ColorPrediction, ()));
         env.mock_all_auths();
+        client.init(
+            &admin,
+            &rng_id,
+            &prize_pool,
+            &balance,
+            &0u32,
+            &Address::generate(&env),
+            &MIN_WAGER,
+            &MAX_WAGER,
+            &COLOR_COUNT,
+        );
+        env.ledger().set_timestamp(1_000);
 
-        let game_id: u64 = 6;
-        let player = Address::generate(&env);
-        client.place_prediction(&player, &COLOR_YELLOW, &10i128, &game_id);
-        client.resolve_prediction(&game_id, &COLOR_YELLOW);
+        let game_id: u64 = 190;
+        let weights = vec![&env, 40u32, 30, 20, 10];
+        let short = client.try_open_weighted_game(&game_id, &2_000, &vec![&env, 50u32, 50]);
+        assert_eq!(short, Err(Ok(Error::InvalidWeights)));
+        let zero = client.try_open_weighted_game(&game_id, &2_000, &vec![&env, 0u32, 0, 0, 0]);
+        assert_eq!(zero, Err(Ok(Error::InvalidWeights)));
+        client.open_weighted_game(&game_id, &2_000, &weights);
+        assert_eq!(client.get_color_weights(&game_id), Some(weights));
 
-        let result = client.try_resolve_prediction(&game_id, &COLOR_YELLOW);
-        assert!(result.is_err());
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        client.place_prediction(&p1, &COLOR_BLUE, &100i128, &game_id);
+        client.place_prediction(&p2, &COLOR_RED, &100i128, &game_id);
+
+        // Manual resolution and late commitments are refused.
+        let manual = client.try_resolve_prediction(&game_id, &COLOR_RED);
+        assert_eq!(manual, Err(Ok(Error::AlreadyCommitted)));
+        let commit = client.try_commit_result(&game_id, &BytesN::from_array(&env, &[0u8; 32]));
+        assert_eq!(commit, Err(Ok(Error::AlreadyCommitted)));
+
+        let early = client.try_resolve_weighted(&game_id);
+        assert_eq!(early, Err(Ok(Error::RngNotReady)));
+
+        // 1_075 % 100 = 75 falls in Blue's band [70, 90).
+        rng.set_result(&game_id, &1_075);
+        assert_eq!(client.resolve_weighted(&game_id), COLOR_BLUE);
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.status, GameStatus::Resolved);
+        assert_eq!(game.winning_color, COLOR_BLUE);
+        assert_eq!(client.claim(&p1, &game_id), 200);
+
+        // Plain games cannot be resolved through the weighted path.
+        client.place_prediction(&p1, &COLOR_RED, &100i128, &(game_id + 1));
+        let plain = client.try_resolve_weighted(&(game_id + 1));
+        assert_eq!(plain, Err(Ok(Error::NotWeightedGame)));
     }
 
     // ------------------------------------------------------------------
-    // 7. Invalid color rejected on place_prediction
+    // 41. A winnerless pot rolls into the next game with winners
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_invalid_color_on_place_rejected() {
+    fn test_jackpot_rollover() {
         let env = Env::default();
         let (client, _, _, _, _) = setup(&env);
         env.mock_all_auths();
+        client.set_house_edge(&1_000u32); // 10%
 
-        let game_id: u64 = 7;
-        let player = Address::generate(&env);
-        let result = client.try_place_prediction(&player, &99u32, &100i128, &game_id);
-        assert!(result.is_err());
+        let p1 = Address::generate(&env);
+        let p2 = Address::generate(&env);
+        client.place_prediction(&p1, &COLOR_RED, &100i128, &200);
+        client.place_prediction(&p2, &COLOR_GREEN, &200i128, &200);
+        client.resolve_prediction(&200, &COLOR_BLUE);
+        assert_eq!(client.get_game(&200).unwrap().fee, 0);
+        assert_eq!(client.get_jackpot(), 300);
+
+        // A second winnerless game grows the jackpot.
+        client.place_prediction(&p1, &COLOR_RED, &50i128, &201);
+        client.resolve_prediction(&201, &COLOR_BLUE);
+        assert_eq!(client.get_jackpot(), 350);
+
+        // Cancelled games neither take nor add to the jackpot.
+        client.place_prediction(&p1, &COLOR_RED, &10i128, &202);
+        client.cancel_game(&202);
+        assert_eq!(client.get_jackpot(), 350);
+
+        // Pot 100 + jackpot 350 = 450, fee 45, the lone winner takes 405.
+        assert_eq!(client.quote_payout(&203, &COLOR_GREEN, &100i128), 405);
+        client.place_prediction(&p2, &COLOR_GREEN, &100i128, &203);
+        client.resolve_prediction(&203, &COLOR_GREEN);
+        let game = client.get_game(&203).unwrap();
+        assert_eq!(game.total_pot, 450);
+        assert_eq!(game.fee, 45);
+        assert_eq!(client.get_jackpot(), 0);
+        assert_eq!(client.claim(&p2, &203), 405);
     }
 
     // ------------------------------------------------------------------
-    // 8. Invalid color rejected on resolve_prediction
+    // 42. list_open_games tracks games until they resolve or cancel
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_invalid_color_on_resolve_rejected() {
+    fn test_list_open_games() {
         let env = Env::default();
         let (client, _, _, _, _) = setup(&env);
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
 
-        let game_id: u64 = 8;
         let player = Address::generate(&env);
-        client.place_prediction(&player, &COLOR_RED, &100i128, &game_id);
+        client.open_game(&210, &2_000);
+        client.place_prediction(&player, &COLOR_RED, &10i128, &211);
+        client.open_game(&212, &2_000);
+        client.commit_result(&212, &BytesN::from_array(&env, &[1u8; 32]));
+        client.place_prediction(&player, &COLOR_RED, &10i128, &213);
+        assert_eq!(
+            client.list_open_games(&0, &10),
+            vec![&env, 210u64, 211, 212, 213]
+        );
+        assert_eq!(client.list_open_games(&1, &2), vec![&env, 211u64, 212]);
+        assert_eq!(client.list_open_games(&4, &2).len(), 0);
 
-        let result = client.try_resolve_prediction(&game_id, &99u32);
-        assert!(result.is_err());
+        client.resolve_prediction(&211, &COLOR_RED);
+        client.cancel_game(&213);
+        assert_eq!(client.list_open_games(&0, &10), vec![&env, 210u64, 212]);
+
+        // Topping up an existing game does not list it twice.
+        client.place_prediction(&player, &COLOR_GREEN, &10i128, &210);
+        client.place_prediction(&player, &COLOR_GREEN, &10i128, &210);
+        assert_eq!(client.list_open_games(&0, &10), vec![&env, 210u64, 212]);
     }
 
     // ------------------------------------------------------------------
-    // 9. Zero wager rejected
+    // 43. Split bets spread one wager over several colors
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_zero_wager_rejected() {
+    fn test_split_prediction() {
         let env = Env::default();
-        let (client, _, _, _, _) = setup(&env);
+        let (client, _, _, _, balance) = setup(&env);
         env.mock_all_auths();
+        let balance = MockBalanceClient::new(&env, &balance);
 
-        let game_id: u64 = 9;
-        let player = Address::generate(&env);
-        let result = client.try_place_prediction(&player, &COLOR_RED, &0i128, &game_id);
-        assert!(result.is_err());
+        let game_id: u64 = 220;
+        let hedger = Address::generate(&env);
+        let rival = Address::generate(&env);
+
+        let single =
+            client.try_place_split_prediction(&hedger, &vec![&env, (COLOR_RED, 10i128)], &game_id);
+        assert_eq!(single, Err(Ok(Error::InvalidSplit)));
+        let repeated = client.try_place_split_prediction(
+            &hedger,
+            &vec![&env, (COLOR_RED, 10i128), (COLOR_RED, 20)],
+            &game_id,
+        );
+        assert_eq!(repeated, Err(Ok(Error::InvalidSplit)));
+
+        client.place_split_prediction(
+            &hedger,
+            &vec![&env, (COLOR_RED, 300i128), (COLOR_GREEN, 100)],
+            &game_id,
+        );
+        client.place_prediction(&rival, &COLOR_GREEN, &100i128, &game_id);
+        assert_eq!(balance.balance_of(&hedger), MOCK_STARTING_BALANCE - 400);
+
+        let game = client.get_game(&game_id).unwrap();
+        assert_eq!(game.total_pot, 500);
+        assert_eq!(game.player_count, 2);
+        assert_eq!(game.color_counts.get(COLOR_GREEN), Some(2));
+        assert_eq!(game.color_totals.get(COLOR_GREEN), Some(200));
+        assert_eq!(
+            client
+                .get_split_prediction(&game_id, &hedger)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(client.get_prediction(&game_id, &hedger), None);
+
+        // A split is final.
+        let top_up = client.try_place_prediction(&hedger, &COLOR_RED, &10i128, &game_id);
+        assert_eq!(top_up, Err(Ok(Error::AlreadyPredicted)));
+
+        // Green wins: the hedger's green leg shares the pot with the rival.
+        client.resolve_prediction(&game_id, &COLOR_GREEN);
+        assert_eq!(client.get_winners(&game_id, &0, &10).len(), 2);
+        assert_eq!(client.claim(&hedger, &game_id), 250);
+        let again = client.try_claim(&hedger, &game_id);
+        assert_eq!(again, Err(Ok(Error::AlreadyClaimed)));
+        assert_eq!(client.claim(&rival, &game_id), 250);
+
+        // Cancelled games refund every leg at once.
+        client.place_split_prediction(
+            &hedger,
+            &vec![&env, (COLOR_BLUE, 50i128), (COLOR_YELLOW, 70)],
+            &(game_id + 1),
+        );
+        client.cancel_game(&(game_id + 1));
+        assert_eq!(client.refund(&hedger, &(game_id + 1)), 120);
+        let twice = client.try_refund(&hedger, &(game_id + 1));
+        assert_eq!(twice, Err(Ok(Error::AlreadyClaimed)));
     }
 
     // ------------------------------------------------------------------
-    // 10. Negative wager rejected
+    // 44. Wagers of referred players are reported to the referral contract
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_negative_wager_rejected() {
+    fn test_referral_hook_reports_wagers() {
         let env = Env::default();
         let (client, _, _, _, _) = setup(&env);
         env.mock_all_auths();
+        let referral_id = env.register(MockReferral, ());
+        let referral = MockReferralClient::new(&env, &referral_id);
 
-        let game_id: u64 = 10;
-        let player = Address::generate(&env);
-        let result = client.try_place_prediction(&player, &COLOR_RED, &-50i128, &game_id);
-        assert!(result.is_err());
+        let referred = Address::generate(&env);
+        let unreferred = Address::generate(&env);
+        referral.set_referrer(&referred, &Address::generate(&env));
+
+        // Nothing is reported until a referral contract is configured.
+        client.place_prediction(&referred, &COLOR_RED, &100i128, &230);
+        assert_eq!(referral.volume_of(&referred), 0);
+
+        client.set_referral_contract(&Some(referral_id.clone()));
+        assert_eq!(client.get_referral_contract(), Some(referral_id));
+        client.place_prediction(&referred, &COLOR_RED, &50i128, &230);
+        client.place_split_prediction(
+            &referred,
+            &vec![&env, (COLOR_RED, 20i128), (COLOR_BLUE, 30)],
+            &231,
+        );
+        client.place_prediction(&unreferred, &COLOR_RED, &100i128, &230);
+        assert_eq!(referral.volume_of(&referred), 100);
+        assert_eq!(referral.volume_of(&unreferred), 0);
+
+        client.set_referral_contract(&None);
+        client.place_prediction(&referred, &COLOR_GREEN, &100i128, &232);
+        assert_eq!(referral.volume_of(&referred), 100);
     }
 
     // ------------------------------------------------------------------
-    // 11. Non-admin cannot resolve
+    // 45. extend_game_ttl bumps every entry of a game
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_non_admin_cannot_resolve() {
-        let env = Env::default();
-        let (client, admin, rng, prize_pool, balance) = setup(&env);
+    fn test_extend_game_ttl() {
+        use soroban_sdk::testutils::storage::Persistent as _;
 
-        let id2 = env.register(ColorPrediction, ());
-        let client2 = ColorPredictionClient::new(&env, &id2);
+        let env = Env::default();
+        let (client, _, _, _, _) = setup(&env);
         env.mock_all_auths();
-        client2.init(&admin, &rng, &prize_pool, &balance);
 
-        let game_id: u64 = 11;
-        let player = Address::generate(&env);
-        client2.place_prediction(&player, &COLOR_RED, &100i128, &game_id);
+        let game_id: u64 = 250;
+        client.open_game(&game_id, &1_000);
+        let single = Address::generate(&env);
+        let split = Address::generate(&env);
+        client.place_prediction(&single, &COLOR_RED, &10i128, &game_id);
+        client.place_split_prediction(
+            &split,
+            &vec![&env, (COLOR_GREEN, 10i128), (COLOR_BLUE, 10i128)],
+            &game_id,
+        );
+        client.commit_result(&game_id, &BytesN::from_array(&env, &[1u8; 32]));
 
-        let imposter = Address::generate(&env);
-        env.mock_auths(&[soroban_sdk::testutils::MockAuth {
-            address: &imposter,
-            invoke: &soroban_sdk::testutils::MockAuthInvoke {
-                contract: &id2,
-                fn_name: "resolve_prediction",
-                args: soroban_sdk::vec![
-                    &env,
-                    soroban_sdk::IntoVal::into_val(&game_id, &env),
-                    soroban_sdk::IntoVal::into_val(&COLOR_RED, &env),
-                ],
-                sub_invokes: &[],
-            },
-        }]);
+        let keys = [
+            DataKey::Game(game_id),
+            DataKey::PlayerList(game_id),
+            DataKey::Prediction(game_id, single.clone()),
+            DataKey::SplitPrediction(game_id, split.clone()),
+            DataKey::ResultCommitment(game_id),
+        ];
+        let ttls = |env: &Env| {
+            env.as_contract(&client.address, || {
+                keys.clone()
+                    .map(|key| env.storage().persistent().get_ttl(&key))
+            })
+        };
 
-        let result = client2.try_resolve_prediction(&game_id, &COLOR_RED);
-        assert!(result.is_err());
+        env.ledger().with_mut(|l| l.sequence_number += 100_000);
+        assert_eq!(ttls(&env), [PERSISTENT_BUMP_LEDGERS - 100_000; 5]);
 
-        let _ = client;
+        client.extend_game_ttl(&game_id);
+        assert_eq!(ttls(&env), [PERSISTENT_BUMP_LEDGERS; 5]);
+
+        let result = client.try_extend_game_ttl(&251);
+        assert_eq!(result, Err(Ok(Error::GameNotFound)));
     }
 
     // ------------------------------------------------------------------
-    // 12. Cannot initialize twice
+    // 46. Token games escrow and pay out in their own token
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_cannot_init_twice() {
+    fn test_token_game_settles_in_its_token() {
         let env = Env::default();
-        let (client, admin, rng, prize_pool, balance) = setup(&env);
+        let (client, _, _, _, balance_id) = setup(&env);
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+        let platform = MockBalanceClient::new(&env, &balance_id);
+        let promo_id = env.register(MockBalance, ());
+        let promo = MockBalanceClient::new(&env, &promo_id);
+        let escrow = client.address.clone();
+        client.set_house_edge(&1_000u32); // 10%
+        let recipient = Address::generate(&env);
+        client.set_fee_recipient(&recipient);
 
-        let result = client.try_init(&admin, &rng, &prize_pool, &balance);
-        assert!(result.is_err());
+        let winner = Address::generate(&env);
+        let loser = Address::generate(&env);
+        for account in [&winner, &loser, &escrow, &recipient] {
+            promo.set_balance(account, &0);
+            platform.set_balance(account, &0);
+        }
+        promo.set_balance(&winner, &100);
+        promo.set_balance(&loser, &100);
+
+        // Seed a jackpot in the platform token; the token game must not touch it.
+        platform.set_balance(&loser, &50);
+        client.place_prediction(&loser, &COLOR_RED, &50i128, &260);
+        client.resolve_prediction(&260, &COLOR_BLUE);
+        assert_eq!(client.get_jackpot(), 50);
+
+        client.open_token_game(&261, &2_000, &promo_id);
+        assert_eq!(client.get_game_token(&261), promo_id);
+        assert_eq!(client.get_game_token(&262), balance_id);
+        client.place_prediction(&winner, &COLOR_GREEN, &100i128, &261);
+        assert_eq!(client.quote_payout(&261, &COLOR_RED, &100i128), 180);
+        client.place_prediction(&loser, &COLOR_RED, &100i128, &261);
+        assert_eq!(promo.balance_of(&escrow), 200);
+        assert_eq!(platform.balance_of(&escrow), 50);
+
+        client.resolve_prediction(&261, &COLOR_GREEN);
+        assert_eq!(client.get_jackpot(), 50);
+        assert_eq!(client.get_token_fees(&promo_id), 20);
+        assert_eq!(client.get_accrued_fees(), 0);
+        assert_eq!(client.claim(&winner, &261), 180);
+        assert_eq!(promo.balance_of(&winner), 180);
+
+        // A winnerless token game feeds its token's fee bucket, not the jackpot.
+        promo.set_balance(&loser, &100);
+        client.open_token_game(&263, &2_000, &promo_id);
+        client.place_prediction(&loser, &COLOR_RED, &100i128, &263);
+        client.resolve_prediction(&263, &COLOR_BLUE);
+        assert_eq!(client.get_jackpot(), 50);
+        assert_eq!(client.get_token_fees(&promo_id), 120);
+
+        assert_eq!(client.withdraw_token_fees(&promo_id), 120);
+        assert_eq!(promo.balance_of(&recipient), 120);
+        assert_eq!(promo.balance_of(&escrow), 0);
+        let result = client.try_withdraw_token_fees(&promo_id);
+        assert_eq!(result, Err(Ok(Error::NoFeesToWithdraw)));
+
+        // Refunds of a cancelled token game come back in its token.
+        promo.set_balance(&loser, &100);
+        client.open_token_game(&264, &2_000, &promo_id);
+        client.place_prediction(&loser, &COLOR_RED, &100i128, &264);
+        client.cancel_game(&264);
+        assert_eq!(client.refund(&loser, &264), 100);
+        assert_eq!(promo.balance_of(&loser), 100);
+        assert_eq!(platform.balance_of(&escrow), 50);
     }
 
     // ------------------------------------------------------------------
-    // 13. Resolve non-existent game rejected
+    // 47. Allowed resolvers settle games but hold no admin powers
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_resolve_nonexistent_game_rejected() {
+    fn test_resolver_allowlist() {
         let env = Env::default();
         let (client, _, _, _, _) = setup(&env);
         env.mock_all_auths();
 
-        let result = client.try_resolve_prediction(&999u64, &COLOR_RED);
-        assert!(result.is_err());
+        let bot = Address::generate(&env);
+        let player = Address::generate(&env);
+        client.place_prediction(&player, &COLOR_RED, &100i128, &270);
+
+        let result = client.try_resolve_prediction_as(&bot, &270, &COLOR_RED);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+        client.set_resolver(&bot, &true);
+        assert!(client.is_resolver(&bot));
+        client.resolve_prediction_as(&bot, &270, &COLOR_RED);
+        assert_eq!(
+            env.auths()[0].0,
+            bot,
+            "the resolver, not the admin, authorizes the call"
+        );
+        assert_eq!(client.get_game(&270).unwrap().status, GameStatus::Resolved);
+
+        // Committed games still resolve only through a reveal.
+        client.open_game(&271, &1_000);
+        client.commit_result(&271, &BytesN::from_array(&env, &[1u8; 32]));
+        let result = client.try_resolve_prediction_as(&bot, &271, &COLOR_RED);
+        assert_eq!(result, Err(Ok(Error::AlreadyCommitted)));
+
+        client.set_resolver(&bot, &false);
+        assert!(!client.is_resolver(&bot));
+        client.place_prediction(&player, &COLOR_RED, &100i128, &272);
+        let result = client.try_resolve_prediction_as(&bot, &272, &COLOR_RED);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
     }
 
     // ------------------------------------------------------------------
-    // 14. get_game returns None for unknown game
+    // 48. Sponsor bonuses join the pot and are refunded on cancellation
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_get_game_none_for_unknown() {
+    fn test_sponsor_pot() {
         let env = Env::default();
-        let (client, _, _, _, _) = setup(&env);
+        let (client, _, _, _, balance_id) = setup(&env);
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+        let balance = MockBalanceClient::new(&env, &balance_id);
+        let escrow = client.address.clone();
+        balance.set_balance(&escrow, &0);
 
-        let result = client.get_game(&9999u64);
-        assert!(result.is_none());
+        let brand = Address::generate(&env);
+        let winner = Address::generate(&env);
+        let loser = Address::generate(&env);
+        balance.set_balance(&brand, &1_000);
+
+        let result = client.try_sponsor_pot(&brand, &280, &300i128);
+        assert_eq!(result, Err(Ok(Error::GameNotFound)));
+
+        client.open_game(&280, &2_000);
+        client.sponsor_pot(&brand, &280, &200i128);
+        client.sponsor_pot(&brand, &280, &100i128);
+        client.place_prediction(&winner, &COLOR_GREEN, &100i128, &280);
+        client.place_prediction(&loser, &COLOR_RED, &100i128, &280);
+
+        let sponsors = client.get_sponsors(&280);
+        assert_eq!(sponsors, Vec::from_array(&env, [brand.clone()]));
+        assert_eq!(
+            client.get_sponsorship(&280, &brand),
+            Some(Sponsorship {
+                amount: 300,
+                refunded: false,
+            })
+        );
+        let game = client.get_game(&280).unwrap();
+        assert_eq!(game.total_pot, 500);
+        assert_eq!(game.color_totals.get(COLOR_GREEN).unwrap(), 100);
+        assert_eq!(balance.balance_of(&brand), 700);
+        assert_eq!(balance.balance_of(&escrow), 500);
+
+        client.resolve_prediction(&280, &COLOR_GREEN);
+        assert_eq!(client.claim(&winner, &280), 500);
+
+        // Sponsorship closes with the betting window.
+        client.open_game(&281, &2_000);
+        client.sponsor_pot(&brand, &281, &400i128);
+        env.ledger().set_timestamp(2_000);
+        let result = client.try_sponsor_pot(&brand, &281, &100i128);
+        assert_eq!(result, Err(Ok(Error::BettingClosed)));
+
+        let result = client.try_refund_sponsorship(&brand, &281);
+        assert_eq!(result, Err(Ok(Error::GameNotCancelled)));
+        client.cancel_game(&281);
+        assert_eq!(client.refund_sponsorship(&brand, &281), 400);
+        assert_eq!(balance.balance_of(&brand), 700);
+        assert_eq!(client.get_game(&281).unwrap().paid_out, 400);
+        let result = client.try_refund_sponsorship(&brand, &281);
+        assert_eq!(result, Err(Ok(Error::AlreadyClaimed)));
+        let result = client.try_refund_sponsorship(&winner, &281);
+        assert_eq!(result, Err(Ok(Error::SponsorshipNotFound)));
     }
 
     // ------------------------------------------------------------------
-    // 15. Multiple games are independent
+    // 49. Winning claims are credited and reported as PrizeClaimed
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_multiple_games_independent() {
+    fn test_referral_hook_reports_prize_claims() {
         let env = Env::default();
-        let (client, _, _, _, _) = setup(&env);
+        let (client, _, _, _, balance_id) = setup(&env);
         env.mock_all_auths();
+        let balance = MockBalanceClient::new(&env, &balance_id);
+        let referral_id = env.register(MockReferral, ());
+        let referral = MockReferralClient::new(&env, &referral_id);
+        client.set_referral_contract(&Some(referral_id));
 
-        let p1 = Address::generate(&env);
-        let p2 = Address::generate(&env);
-
-        client.place_prediction(&p1, &COLOR_RED, &100i128, &1u64);
-        client.place_prediction(&p2, &COLOR_BLUE, &200i128, &2u64);
+        let referred = Address::generate(&env);
+        let unreferred = Address::generate(&env);
+        let loser = Address::generate(&env);
+        referral.set_referrer(&referred, &Address::generate(&env));
+        referral.set_referrer(&loser, &Address::generate(&env));
 
-        client.resolve_prediction(&1u64, &COLOR_RED);
-        client.resolve_prediction(&2u64, &COLOR_GREEN);
+        client.place_prediction(&referred, &COLOR_RED, &100i128, &290);
+        client.place_prediction(&unreferred, &COLOR_RED, &100i128, &290);
+        client.place_prediction(&loser, &COLOR_BLUE, &100i128, &290);
+        client.resolve_prediction(&290, &COLOR_RED);
 
-        let game1 = client.get_game(&1u64).unwrap();
-        let game2 = client.get_game(&2u64).unwrap();
+        let before = balance.balance_of(&referred);
+        assert_eq!(client.claim(&referred, &290), 150);
+        assert_eq!(balance.balance_of(&referred), before + 150);
+        assert_eq!(referral.prizes_of(&referred), 150);
+        assert_eq!(referral.volume_of(&referred), 100);
 
-        assert_eq!(game1.winner_count, 1);
-        assert_eq!(game1.total_pot, 100);
-        assert_eq!(game2.winner_count, 0);
-        assert_eq!(game2.total_pot, 200);
+        client.claim(&unreferred, &290);
+        assert_eq!(referral.prizes_of(&unreferred), 0);
+        assert_eq!(client.try_claim(&loser, &290), Err(Ok(Error::NoPayout)));
+        assert_eq!(referral.prizes_of(&loser), 0);
     }
 
     // ------------------------------------------------------------------
-    // 16. All four valid colors can be used
+    // 50. Imported games escrow only what they still owe
     // ------------------------------------------------------------------
 
     #[test]
-    fn test_all_valid_colors_accepted() {
+    fn test_import_game_escrows_unpaid_balance() {
         let env = Env::default();
-        let (client, _, _, _, _) = setup(&env);
+        let (client, admin, _, _, balance_id) = setup(&env);
         env.mock_all_auths();
+        let balance = MockBalanceClient::new(&env, &balance_id);
+        client.set_migration_mode(&true);
 
-        for (game_id, color) in [
-            (20u64, COLOR_RED),
-            (21u64, COLOR_GREEN),
-            (22u64, COLOR_BLUE),
-            (23u64, COLOR_YELLOW),
-        ] {
-            let player = Address::generate(&env);
-            client.place_prediction(&player, &color, &10i128, &game_id);
-            client.resolve_prediction(&game_id, &color);
-            let game = client.get_game(&game_id).unwrap();
-            assert_eq!(game.winner_count, 1);
-        }
+        let claimed = Address::generate(&env);
+        let unclaimed = Address::generate(&env);
+        let players = vec![&env, claimed.clone(), unclaimed.clone()];
+        let mut predictions = vec![
+            &env,
+            PredictionEntry {
+                color: COLOR_BLUE,
+                wager: 100,
+                claimed: true,
+            },
+            PredictionEntry {
+                color: COLOR_BLUE,
+                wager: 300,
+                claimed: false,
+            },
+        ];
+
+        // Resolved with a 40 fee; the first winner already took 90 of 360.
+        let mut legacy = env.as_contract(&client.address, || new_game(&env, 0));
+        legacy.total_pot = 400;
+        legacy.player_count = 2;
+        legacy.winner_count = 2;
+        legacy.winning_color = COLOR_BLUE;
+        legacy.status = GameStatus::Resolved;
+        legacy.winning_total = 400;
+        legacy.fee = 40;
+        legacy.paid_out = 90;
+        legacy.claimed_count = 1;
+
+        let mut miscounted = legacy.clone();
+        miscounted.claimed_count = 0;
+        let bad = client.try_import_game(&1, &miscounted, &players, &predictions);
+        assert_eq!(bad, Err(Ok(Error::InvalidMigrationData)));
+
+        client.import_game(&1, &legacy, &players, &predictions);
+        assert_eq!(balance.balance_of(&admin), MOCK_STARTING_BALANCE - 270);
+        assert_eq!(client.claim(&unclaimed, &1), 270);
+        assert_eq!(
+            client.try_claim(&claimed, &1),
+            Err(Ok(Error::AlreadyClaimed))
+        );
+
+        // Cancelled: only the unrefunded wager is owed, and fees are not allowed.
+        let mut cancelled = env.as_contract(&client.address, || new_game(&env, 0));
+        cancelled.total_pot = 400;
+        cancelled.player_count = 2;
+        cancelled.status = GameStatus::Cancelled;
+        cancelled.paid_out = 100;
+        let mut with_fee = cancelled.clone();
+        with_fee.fee = 10;
+        let bad = client.try_import_game(&2, &with_fee, &players, &predictions);
+        assert_eq!(bad, Err(Ok(Error::InvalidMigrationData)));
+
+        client.import_game(&2, &cancelled, &players, &predictions);
+        assert_eq!(balance.balance_of(&admin), MOCK_STARTING_BALANCE - 570);
+        assert_eq!(client.refund(&unclaimed, &2), 300);
+
+        // Open games cannot carry claimed entries.
+        let mut open = cancelled.clone();
+        open.status = GameStatus::Open;
+        open.paid_out = 0;
+        let bad = client.try_import_game(&3, &open, &players, &predictions);
+        assert_eq!(bad, Err(Ok(Error::InvalidMigrationData)));
+        predictions.set(
+            0,
+            PredictionEntry {
+                color: COLOR_BLUE,
+                wager: 100,
+                claimed: false,
+            },
+        );
+        client.import_game(&3, &open, &players, &predictions);
+        assert_eq!(balance.balance_of(&admin), MOCK_STARTING_BALANCE - 970);
     }
 }
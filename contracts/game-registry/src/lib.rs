@@ -0,0 +1,506 @@
+//! Stellarcade Game Registry Contract
+//!
+//! Keeps the list of live game contracts and the latest health report from
+//! each: contract version, paused status, and open liability (funds the game
+//! still owes players). Games either push a report with `report_health`, or
+//! anyone pulls one with `refresh_health`, which reads the game's standard
+//! `HealthSource` interface. `get_platform_health` aggregates every report so
+//! the ops dashboard has a single source of truth.
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, Address,
+    Env, Vec,
+};
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Maximum number of registered games (bounds the aggregation loop).
+pub const MAX_GAMES: u32 = 20;
+
+/// Default age in seconds after which a report counts as stale.
+pub const DEFAULT_STALE_AFTER: u64 = 3_600;
+
+/// Persistent storage TTL in ledgers (~30 days at 5 s/ledger).
+pub const PERSISTENT_BUMP_LEDGERS: u32 = 518_400;
+
+// ---------------------------------------------------------------------------
+// External contract clients
+// ---------------------------------------------------------------------------
+
+/// Interface a game contract implements so the registry can pull its health.
+#[contractclient(name = "HealthSourceClient")]
+pub trait HealthSource {
+    /// Version of the deployed game code.
+    fn version(env: Env) -> u32;
+    /// Whether the game is currently paused.
+    fn is_paused(env: Env) -> bool;
+    /// Total the game still owes players on unsettled or unclaimed games.
+    fn open_liability(env: Env) -> i128;
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    NotAuthorized = 3,
+    GameAlreadyRegistered = 4,
+    GameNotRegistered = 5,
+    TooManyGames = 6,
+    InvalidAmount = 7,
+    Overflow = 8,
+}
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    /// Registered game contracts (Vec<Address>).
+    Games,
+    /// Seconds after which a report is stale (u64).
+    StaleAfter,
+    /// Latest `GameHealth` per game — persistent storage.
+    Health(Address),
+}
+
+/// Latest health report for one game.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameHealth {
+    pub version: u32,
+    pub paused: bool,
+    pub open_liability: i128,
+    /// Ledger timestamp of the report.
+    pub reported_at: u64,
+}
+
+/// One registered game and its latest report.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameHealthEntry {
+    pub game: Address,
+    /// False if the game has never reported; `health` is then zeroed.
+    pub reported: bool,
+    pub health: GameHealth,
+    /// True if the game never reported or its report is older than the
+    /// stale threshold.
+    pub stale: bool,
+}
+
+/// Platform-wide snapshot returned by `get_platform_health`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlatformHealth {
+    pub game_count: u32,
+    pub paused_count: u32,
+    pub stale_count: u32,
+    /// Sum of the open liability in every game's latest report.
+    pub total_open_liability: i128,
+    pub games: Vec<GameHealthEntry>,
+}
+
+// ---------------------------------------------------------------------------
+// Events
+// ---------------------------------------------------------------------------
+
+#[contractevent]
+pub struct GameRegistered {
+    #[topic]
+    pub game: Address,
+}
+
+#[contractevent]
+pub struct GameUnregistered {
+    #[topic]
+    pub game: Address,
+}
+
+#[contractevent]
+pub struct HealthReported {
+    #[topic]
+    pub game: Address,
+    pub version: u32,
+    pub paused: bool,
+    pub open_liability: i128,
+}
+
+// ---------------------------------------------------------------------------
+// Contract
+// ---------------------------------------------------------------------------
+
+#[contract]
+pub struct GameRegistry;
+
+#[contractimpl]
+impl GameRegistry {
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::Games, &Vec::<Address>::new(&env));
+        Ok(())
+    }
+
+    /// Add a game contract to the registry. Admin only.
+    pub fn register_game(env: Env, game: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+
+        let mut games = get_games(&env);
+        if games.contains(&game) {
+            return Err(Error::GameAlreadyRegistered);
+        }
+        if games.len() >= MAX_GAMES {
+            return Err(Error::TooManyGames);
+        }
+        games.push_back(game.clone());
+        env.storage().instance().set(&DataKey::Games, &games);
+
+        GameRegistered { game }.publish(&env);
+        Ok(())
+    }
+
+    /// Remove a game contract and its health report. Admin only.
+    pub fn unregister_game(env: Env, game: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+
+        let mut games = get_games(&env);
+        let index = games
+            .first_index_of(&game)
+            .ok_or(Error::GameNotRegistered)?;
+        games.remove(index);
+        env.storage().instance().set(&DataKey::Games, &games);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Health(game.clone()));
+
+        GameUnregistered { game }.publish(&env);
+        Ok(())
+    }
+
+    /// Set the age in seconds after which a report counts as stale. Admin
+    /// only.
+    pub fn set_stale_after(env: Env, seconds: u64) -> Result<(), Error> {
+        require_admin(&env)?;
+        env.storage().instance().set(&DataKey::StaleAfter, &seconds);
+        Ok(())
+    }
+
+    /// Push a health report. Must be called by the registered game itself.
+    ///
+    /// Emits `HealthReported`.
+    pub fn report_health(
+        env: Env,
+        game: Address,
+        version: u32,
+        paused: bool,
+        open_liability: i128,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        game.require_auth();
+        store_health(&env, game, version, paused, open_liability)
+    }
+
+    /// Pull a health report from a registered game through its
+    /// `HealthSource` interface. Anyone may call.
+    ///
+    /// Emits `HealthReported`.
+    pub fn refresh_health(env: Env, game: Address) -> Result<GameHealth, Error> {
+        require_initialized(&env)?;
+
+        let source = HealthSourceClient::new(&env, &game);
+        let version = source.version();
+        let paused = source.is_paused();
+        let open_liability = source.open_liability();
+        store_health(&env, game.clone(), version, paused, open_liability)?;
+        get_health(&env, &game).ok_or(Error::GameNotRegistered)
+    }
+
+    /// Latest health report for a game, or `None` if it never reported.
+    pub fn get_game_health(env: Env, game: Address) -> Option<GameHealth> {
+        get_health(&env, &game)
+    }
+
+    /// Aggregate the latest report from every registered game.
+    pub fn get_platform_health(env: Env) -> Result<PlatformHealth, Error> {
+        require_initialized(&env)?;
+
+        let now = env.ledger().timestamp();
+        let stale_after = get_stale_after(&env);
+        let mut platform = PlatformHealth {
+            game_count: 0,
+            paused_count: 0,
+            stale_count: 0,
+            total_open_liability: 0,
+            games: Vec::new(&env),
+        };
+        for game in get_games(&env).iter() {
+            let health = get_health(&env, &game);
+            let stale = match &health {
+                Some(h) => now.saturating_sub(h.reported_at) > stale_after,
+                None => true,
+            };
+            if let Some(h) = &health {
+                if h.paused {
+                    platform.paused_count += 1;
+                }
+                platform.total_open_liability = platform
+                    .total_open_liability
+                    .checked_add(h.open_liability)
+                    .ok_or(Error::Overflow)?;
+            }
+            if stale {
+                platform.stale_count += 1;
+            }
+            platform.game_count += 1;
+            platform.games.push_back(GameHealthEntry {
+                game,
+                reported: health.is_some(),
+                health: health.unwrap_or(GameHealth {
+                    version: 0,
+                    paused: false,
+                    open_liability: 0,
+                    reported_at: 0,
+                }),
+                stale,
+            });
+        }
+        Ok(platform)
+    }
+
+    pub fn get_games(env: Env) -> Vec<Address> {
+        get_games(&env)
+    }
+
+    pub fn get_stale_after(env: Env) -> u64 {
+        get_stale_after(&env)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn require_initialized(env: &Env) -> Result<(), Error> {
+    if !env.storage().instance().has(&DataKey::Admin) {
+        return Err(Error::NotInitialized);
+    }
+    Ok(())
+}
+
+fn require_admin(env: &Env) -> Result<(), Error> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)?;
+    admin.require_auth();
+    Ok(())
+}
+
+fn get_games(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Games)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn get_stale_after(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::StaleAfter)
+        .unwrap_or(DEFAULT_STALE_AFTER)
+}
+
+fn get_health(env: &Env, game: &Address) -> Option<GameHealth> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Health(game.clone()))
+}
+
+/// Validate and store a report for a registered game.
+fn store_health(
+    env: &Env,
+    game: Address,
+    version: u32,
+    paused: bool,
+    open_liability: i128,
+) -> Result<(), Error> {
+    if !get_games(env).contains(&game) {
+        return Err(Error::GameNotRegistered);
+    }
+    if open_liability < 0 {
+        return Err(Error::InvalidAmount);
+    }
+
+    let key = DataKey::Health(game.clone());
+    env.storage().persistent().set(
+        &key,
+        &GameHealth {
+            version,
+            paused,
+            open_liability,
+            reported_at: env.ledger().timestamp(),
+        },
+    );
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+
+    HealthReported {
+        game,
+        version,
+        paused,
+        open_liability,
+    }
+    .publish(env);
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, Env,
+    };
+
+    // -----------------------------
+    // Mock game contract
+    // -----------------------------
+
+    #[contract]
+    pub struct MockGame;
+
+    #[contracttype]
+    pub enum MockKey {
+        Paused,
+        Liability,
+    }
+
+    #[contractimpl]
+    impl MockGame {
+        pub fn set_state(env: Env, paused: bool, liability: i128) {
+            env.storage().instance().set(&MockKey::Paused, &paused);
+            env.storage()
+                .instance()
+                .set(&MockKey::Liability, &liability);
+        }
+
+        pub fn version(_env: Env) -> u32 {
+            3
+        }
+
+        pub fn is_paused(env: Env) -> bool {
+            env.storage()
+                .instance()
+                .get(&MockKey::Paused)
+                .unwrap_or(false)
+        }
+
+        pub fn open_liability(env: Env) -> i128 {
+            env.storage()
+                .instance()
+                .get(&MockKey::Liability)
+                .unwrap_or(0)
+        }
+    }
+
+    fn setup(env: &Env) -> (GameRegistryClient<'_>, MockGameClient<'_>, Address) {
+        env.mock_all_auths();
+        env.ledger().set_timestamp(10_000);
+
+        let registry_id = env.register(GameRegistry, ());
+        let registry = GameRegistryClient::new(env, &registry_id);
+        registry.init(&Address::generate(env));
+
+        let pulled = MockGameClient::new(env, &env.register(MockGame, ()));
+        let pushing = Address::generate(env);
+        registry.register_game(&pulled.address);
+        registry.register_game(&pushing);
+
+        (registry, pulled, pushing)
+    }
+
+    #[test]
+    fn test_push_and_pull_reports_aggregate() {
+        let env = Env::default();
+        let (registry, pulled, pushing) = setup(&env);
+
+        registry.report_health(&pushing, &7, &false, &1_200);
+        pulled.set_state(&true, &300);
+        let pulled_health = registry.refresh_health(&pulled.address);
+        assert_eq!(
+            pulled_health,
+            GameHealth {
+                version: 3,
+                paused: true,
+                open_liability: 300,
+                reported_at: 10_000,
+            }
+        );
+
+        let platform = registry.get_platform_health();
+        assert_eq!(platform.game_count, 2);
+        assert_eq!(platform.paused_count, 1);
+        assert_eq!(platform.stale_count, 0);
+        assert_eq!(platform.total_open_liability, 1_500);
+        assert_eq!(platform.games.get(1).unwrap().game, pushing);
+        assert_eq!(platform.games.get(1).unwrap().health.version, 7);
+    }
+
+    #[test]
+    fn test_stale_and_missing_reports() {
+        let env = Env::default();
+        let (registry, pulled, pushing) = setup(&env);
+
+        registry.report_health(&pushing, &1, &false, &50);
+        assert_eq!(registry.get_platform_health().stale_count, 1);
+
+        env.ledger().set_timestamp(10_000 + DEFAULT_STALE_AFTER + 1);
+        let platform = registry.get_platform_health();
+        assert_eq!(platform.stale_count, 2);
+        assert!(!platform.games.get(0).unwrap().reported);
+
+        registry.set_stale_after(&(DEFAULT_STALE_AFTER * 2));
+        registry.refresh_health(&pulled.address);
+        assert_eq!(registry.get_platform_health().stale_count, 0);
+    }
+
+    #[test]
+    fn test_reports_require_registration() {
+        let env = Env::default();
+        let (registry, _, pushing) = setup(&env);
+
+        let stranger = Address::generate(&env);
+        let result = registry.try_report_health(&stranger, &1, &false, &0);
+        assert_eq!(result, Err(Ok(Error::GameNotRegistered)));
+        let result = registry.try_report_health(&pushing, &1, &false, &-1);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+        registry.report_health(&pushing, &1, &false, &10);
+        registry.unregister_game(&pushing);
+        assert_eq!(registry.get_game_health(&pushing), None);
+        assert_eq!(registry.get_platform_health().game_count, 1);
+    }
+}
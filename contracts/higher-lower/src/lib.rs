@@ -2,13 +2,54 @@
 //!
 //! A simple prediction game: players wager on whether the outcome is higher
 //! or lower than a fixed anchor value.
+//!
+//! Every placed bet gets a receipt: a SHA-256 hash over (player, game_id,
+//! prediction, wager, ledger sequence) stored on-chain and retrievable with
+//! `get_receipt`, giving players and support a compact reference for disputes.
+//!
+//! `get_storage_health` reports how many Game, Receipt and free-roll counter
+//! entries the contract holds (none is ever deleted) and an estimate of the
+//! instance TTL; `bump_all_config` lets anyone extend the instance (config)
+//! TTL before it is archived.
+//!
+//! Free-roll games let players flagged by the missions contract play without a
+//! wager. The prize is reserved from a promo budget held by the contract when
+//! the game is placed and released back to the budget if the player loses.
+//!
+//! A player may cancel a paid bet with `cancel_game` while the RNG result is
+//! not yet available and within `CANCEL_WINDOW_LEDGERS` of placement. The
+//! wager is refunded minus a `CANCEL_FEE_BPS` anti-spam fee kept by the house.
+//!
+//! The admin may cap the house's exposure to a share of its bankroll with
+//! `set_exposure_limit`. Open liabilities (the payout every unresolved game
+//! could still claim) are tracked as games are placed and settled, and
+//! `get_max_allowed_wager` reports the largest wager that fits under the cap.
+//!
+//! Besides the RNG contract configured at init, the admin may whitelist other
+//! RNG providers with `set_rng_provider`. A bet placed with
+//! `place_prediction_with_rng` records its provider on the game and is
+//! resolved against it, so randomness backends can be A/B tested and
+//! migrated one game at a time.
+//!
+//! A provider that traps, errors, or returns an outcome above `MAX_OUTCOME`
+//! at resolution does not leave the game stuck: `resolve_game` refunds the
+//! wager in full (or returns a free roll's prize to the promo budget), marks
+//! the game `rng_failed`, emits `RngFailure`, and counts the failure against
+//! the provider (`get_rng_failures`).
+//!
+//! When configured, `resolve_game` notifies the leaderboard and missions
+//! contracts of the result and records a game-played event for referred
+//! players with the referral system. Each notification is isolated: a failing
+//! subscriber emits `SubscriberFailed` and never reverts the payout.
 #![no_std]
 #![allow(unexpected_cfgs)]
 
 use soroban_sdk::{
     contract, contractclient, contracterror, contractevent, contractimpl, contracttype,
-    symbol_short, Address, Env, Symbol,
+    symbol_short, xdr::ToXdr, Address, BytesN, Env, Symbol, Vec,
 };
+use stellarcade_shared::storage::{bump_instance, count_new_entry, storage_health};
+pub use stellarcade_shared::storage::{KeyFamilyCount, StorageHealth, INSTANCE_BUMP_LEDGERS};
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -17,6 +58,18 @@ use soroban_sdk::{
 pub const MIN_WAGER: i128 = 1;
 pub const MAX_WAGER: i128 = 1_000_000_000;
 pub const ANCHOR_VALUE: u32 = 50;
+/// Largest valid RNG outcome; anything above is treated as a provider failure.
+pub const MAX_OUTCOME: u32 = 100;
+/// Ledgers after placement during which a player may cancel (~1 minute).
+pub const CANCEL_WINDOW_LEDGERS: u32 = 12;
+/// Share of the wager kept by the house on cancellation, in basis points.
+pub const CANCEL_FEE_BPS: i128 = 50;
+
+/// Version of this contract's code, reported to the game registry.
+pub const CONTRACT_VERSION: u32 = 1;
+
+/// Referral-system event type for a completed game (`EventType::GamePlayed`).
+pub const REFERRAL_EVENT_GAME_PLAYED: u32 = 0;
 
 // ---------------------------------------------------------------------------
 // External contract clients
@@ -35,6 +88,39 @@ pub trait UserBalanceContract {
     fn balance_of(env: Env, user: Address) -> i128;
 }
 
+#[contractclient(name = "MissionsClient")]
+pub trait MissionsContract {
+    fn is_free_roll_eligible(env: Env, user: Address) -> bool;
+}
+
+/// Subset of the referral-system interface used to report play volume.
+#[contractclient(name = "ReferralClient")]
+pub trait ReferralContract {
+    fn get_referrer(env: Env, user: Address) -> Option<Address>;
+    fn record_referral_event(
+        env: Env,
+        caller: Address,
+        user: Address,
+        event_type: u32,
+        amount: i128,
+    );
+}
+
+/// Interface implemented by the leaderboard and missions contracts to follow
+/// game results.
+#[contractclient(name = "ResolutionSubscriberClient")]
+pub trait ResolutionSubscriber {
+    fn on_game_resolved(
+        env: Env,
+        game: Address,
+        game_id: u64,
+        player: Address,
+        wager: i128,
+        win: bool,
+        payout: i128,
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -55,6 +141,15 @@ pub enum Error {
     InsufficientBalance = 10,
     HouseInsufficientFunds = 11,
     Overflow = 12,
+    FreeRollsNotConfigured = 13,
+    NotEligible = 14,
+    FreeRollLimitReached = 15,
+    PromoBudgetExhausted = 16,
+    CancelWindowClosed = 17,
+    GameCancelled = 18,
+    ExposureLimitExceeded = 19,
+    InvalidExposureLimit = 20,
+    RngProviderNotAllowed = 21,
 }
 
 // ---------------------------------------------------------------------------
@@ -78,6 +173,82 @@ pub struct GameData {
     pub outcome: u32,
     pub win: bool,
     pub payout: i128,
+    /// Prize reserved from the promo budget; `0` for paid games.
+    pub free_roll_prize: i128,
+    /// Ledger sequence the bet was placed in.
+    pub placed_ledger: u32,
+    /// Set when the player cancelled the bet before resolution.
+    pub cancelled: bool,
+    /// RNG contract the game is resolved against.
+    pub rng_contract: Address,
+    /// Set when the RNG provider failed at resolution and the game was refunded.
+    pub rng_failed: bool,
+}
+
+/// Where a game stands, as shown to players.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GameSummaryStatus {
+    Pending = 0,
+    Won = 1,
+    Lost = 2,
+    Cancelled = 3,
+    Refunded = 4,
+}
+
+/// Flattened view of a game for direct wallet display.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameSummary {
+    pub game_id: u64,
+    pub player: Address,
+    /// `higher` or `lower`.
+    pub prediction: Symbol,
+    /// `0` for free rolls.
+    pub wager: i128,
+    pub anchor: u32,
+    /// RNG outcome; `0` until the game is resolved against one.
+    pub outcome: u32,
+    pub win: bool,
+    /// Amount credited to the player: the winnings, or the refund of a
+    /// cancelled or RNG-failed game.
+    pub payout: i128,
+    pub free_roll: bool,
+    pub status: GameSummaryStatus,
+}
+
+/// Free-roll settings set by the admin.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FreeRollConfig {
+    /// Contract that flags players eligible for free rolls.
+    pub missions_contract: Address,
+    /// Prize paid from the promo budget on a winning free roll.
+    pub prize: i128,
+    /// Lifetime free rolls allowed per player.
+    pub max_per_player: u32,
+}
+
+/// Contracts notified when a game resolves. Unset slots are skipped, which
+/// bounds every resolution to at most three cross-contract notifications.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResolutionSubscribers {
+    pub leaderboard: Option<Address>,
+    pub missions: Option<Address>,
+    pub referral: Option<Address>,
+}
+
+/// Verifiable record of a placed bet, keyed by its hash.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BetReceipt {
+    pub player: Address,
+    pub game_id: u64,
+    pub prediction: Prediction,
+    pub wager: i128,
+    /// Ledger sequence the bet was placed in.
+    pub ledger: u32,
 }
 
 #[contracttype]
@@ -87,6 +258,21 @@ pub enum DataKey {
     PrizePoolContract,
     BalanceContract,
     Game(u64),
+    Receipt(BytesN<32>),
+    FreeRollConfig,
+    /// Unreserved promo funds held by the contract (i128).
+    PromoBudget,
+    /// Free rolls placed by a player (u32).
+    FreeRollCount(Address),
+    ResolutionSubscribers,
+    /// Share of the bankroll open liabilities may reach, in basis points (u32).
+    ExposureLimitBps,
+    /// Sum of the payouts unresolved games could still claim (i128).
+    OpenLiability,
+    /// Whitelisted RNG providers besides the default (Vec<Address>).
+    RngProviders,
+    /// Failed resolutions attributed to an RNG provider (u32).
+    RngFailures(Address),
 }
 
 // ---------------------------------------------------------------------------
@@ -100,6 +286,33 @@ pub struct PredictionPlaced {
     pub player: Address,
     pub prediction: u32,
     pub wager: i128,
+    pub receipt: BytesN<32>,
+}
+
+#[contractevent]
+pub struct PromoBudgetFunded {
+    #[topic]
+    pub funder: Address,
+    pub amount: i128,
+    pub budget: i128,
+}
+
+#[contractevent]
+pub struct PromoBudgetWithdrawn {
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+    pub budget: i128,
+}
+
+#[contractevent]
+pub struct FreeRollPlaced {
+    #[topic]
+    pub game_id: u64,
+    pub player: Address,
+    pub prediction: u32,
+    pub prize: i128,
+    pub receipt: BytesN<32>,
 }
 
 #[contractevent]
@@ -111,6 +324,36 @@ pub struct GameResolved {
     pub payout: i128,
 }
 
+#[contractevent]
+pub struct GameCancelled {
+    #[topic]
+    pub game_id: u64,
+    pub player: Address,
+    pub refund: i128,
+    pub fee: i128,
+}
+
+/// The game's RNG provider failed at resolution and the game was refunded.
+/// `reason` is `ready` or `result` for the call that failed, or `range` for
+/// an outcome above `MAX_OUTCOME`.
+#[contractevent]
+pub struct RngFailure {
+    #[topic]
+    pub game_id: u64,
+    #[topic]
+    pub provider: Address,
+    pub reason: Symbol,
+    pub refund: i128,
+}
+
+/// A resolution subscriber rejected its notification; the game still settled.
+#[contractevent]
+pub struct SubscriberFailed {
+    #[topic]
+    pub game_id: u64,
+    pub subscriber: Address,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -141,59 +384,268 @@ impl HigherLower {
         env.storage()
             .instance()
             .set(&DataKey::BalanceContract, &balance_contract);
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Extend the instance (config) TTL. Permissionless.
+    pub fn bump_all_config(env: Env) -> Result<(), Error> {
+        require_initialized(&env)?;
+        bump_instance(&env);
         Ok(())
     }
 
+    /// Report how many Game, Receipt and free-roll counter entries the
+    /// contract holds, with the estimated instance TTL.
+    pub fn get_storage_health(env: Env) -> StorageHealth {
+        storage_health(&env, &[FAMILY_GAME, FAMILY_RECEIPT, FAMILY_FREE_ROLL])
+    }
+
+    /// Place a bet and return its receipt hash.
     pub fn place_prediction(
         env: Env,
         player: Address,
         prediction: u32,
         wager: i128,
         game_id: u64,
+    ) -> Result<BytesN<32>, Error> {
+        require_initialized(&env)?;
+        let rng_contract = get_rng_contract(&env)?;
+        place_paid_game(&env, player, prediction, wager, game_id, rng_contract)
+    }
+
+    /// Place a bet resolved against `rng_provider`, which must be the default
+    /// RNG contract or a whitelisted provider. Returns the receipt hash.
+    pub fn place_prediction_with_rng(
+        env: Env,
+        player: Address,
+        prediction: u32,
+        wager: i128,
+        game_id: u64,
+        rng_provider: Address,
+    ) -> Result<BytesN<32>, Error> {
+        require_initialized(&env)?;
+        if rng_provider != get_rng_contract(&env)?
+            && !get_rng_providers(&env).contains(&rng_provider)
+        {
+            return Err(Error::RngProviderNotAllowed);
+        }
+        place_paid_game(&env, player, prediction, wager, game_id, rng_provider)
+    }
+
+    /// Add or remove an RNG provider players may choose with
+    /// `place_prediction_with_rng`. Games already placed keep their provider.
+    /// Admin only.
+    pub fn set_rng_provider(env: Env, provider: Address, allowed: bool) -> Result<(), Error> {
+        require_admin(&env)?;
+        let mut providers = get_rng_providers(&env);
+        let index = providers.first_index_of(&provider);
+        match (allowed, index) {
+            (true, None) => providers.push_back(provider),
+            (false, Some(index)) => {
+                providers.remove(index);
+            }
+            _ => return Ok(()),
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::RngProviders, &providers);
+        Ok(())
+    }
+
+    /// Whitelisted RNG providers besides the default one.
+    pub fn get_rng_providers(env: Env) -> Vec<Address> {
+        get_rng_providers(&env)
+    }
+
+    /// Number of games refunded because `provider` failed at resolution.
+    pub fn get_rng_failures(env: Env, provider: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RngFailures(provider))
+            .unwrap_or(0)
+    }
+
+    /// Configure free-roll games. Admin only.
+    pub fn set_free_roll_config(
+        env: Env,
+        missions_contract: Address,
+        prize: i128,
+        max_per_player: u32,
+    ) -> Result<(), Error> {
+        require_admin(&env)?;
+        require_wager_bounds(prize)?;
+        env.storage().instance().set(
+            &DataKey::FreeRollConfig,
+            &FreeRollConfig {
+                missions_contract,
+                prize,
+                max_per_player,
+            },
+        );
+        Ok(())
+    }
+
+    /// Cap open liabilities at `max_exposure_bps` of the house bankroll
+    /// (its balance excluding the promo budget). `0` removes the cap.
+    /// Admin only.
+    pub fn set_exposure_limit(env: Env, max_exposure_bps: u32) -> Result<(), Error> {
+        require_admin(&env)?;
+        if max_exposure_bps > 10_000 {
+            return Err(Error::InvalidExposureLimit);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::ExposureLimitBps, &max_exposure_bps);
+        Ok(())
+    }
+
+    /// Set the contracts notified on every resolution. Pass `None` to leave a
+    /// slot unsubscribed. Admin only.
+    pub fn set_resolution_subscribers(
+        env: Env,
+        leaderboard: Option<Address>,
+        missions: Option<Address>,
+        referral: Option<Address>,
     ) -> Result<(), Error> {
+        require_admin(&env)?;
+        env.storage().instance().set(
+            &DataKey::ResolutionSubscribers,
+            &ResolutionSubscribers {
+                leaderboard,
+                missions,
+                referral,
+            },
+        );
+        Ok(())
+    }
+
+    /// Move `amount` from `funder`'s balance into the contract's promo budget.
+    pub fn fund_promo_budget(env: Env, funder: Address, amount: i128) -> Result<i128, Error> {
         require_initialized(&env)?;
-        player.require_auth();
+        funder.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidWager);
+        }
 
-        let prediction = parse_prediction(prediction)?;
-        require_wager_bounds(wager)?;
+        let balance_contract = get_balance_contract(&env)?;
+        let game_addr = env.current_contract_address();
+        let balance_client = BalanceClient::new(&env, &balance_contract);
+        if balance_client.balance_of(&funder) < amount {
+            return Err(Error::InsufficientBalance);
+        }
 
-        let key = DataKey::Game(game_id);
-        if env.storage().persistent().has(&key) {
-            return Err(Error::GameAlreadyExists);
+        let budget = get_promo_budget(&env)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        env.storage().instance().set(&DataKey::PromoBudget, &budget);
+
+        balance_client.debit(&game_addr, &funder, &amount, &symbol_short!("promo"));
+        balance_client.credit(&game_addr, &game_addr, &amount, &symbol_short!("promo"));
+
+        PromoBudgetFunded {
+            funder,
+            amount,
+            budget,
+        }
+        .publish(&env);
+
+        Ok(budget)
+    }
+
+    /// Move `amount` of the unreserved promo budget out to `to`, e.g. back to
+    /// the treasury when a promotion ends. Prizes already reserved by pending
+    /// free rolls cannot be withdrawn. Admin only. Returns the new budget.
+    pub fn withdraw_promo_budget(env: Env, to: Address, amount: i128) -> Result<i128, Error> {
+        require_admin(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidWager);
+        }
+        let budget = get_promo_budget(&env);
+        if amount > budget {
+            return Err(Error::PromoBudgetExhausted);
         }
+        let budget = budget - amount;
+        env.storage().instance().set(&DataKey::PromoBudget, &budget);
 
         let balance_contract = get_balance_contract(&env)?;
         let game_addr = env.current_contract_address();
         let balance_client = BalanceClient::new(&env, &balance_contract);
+        balance_client.debit(&game_addr, &game_addr, &amount, &symbol_short!("promo"));
+        balance_client.credit(&game_addr, &to, &amount, &symbol_short!("promo"));
 
-        let player_balance = balance_client.balance_of(&player);
-        if player_balance < wager {
-            return Err(Error::InsufficientBalance);
+        PromoBudgetWithdrawn { to, amount, budget }.publish(&env);
+
+        Ok(budget)
+    }
+
+    /// Place a zero-wager game for a player flagged by the missions contract.
+    /// The configured prize is reserved from the promo budget up front.
+    pub fn place_free_roll(
+        env: Env,
+        player: Address,
+        prediction: u32,
+        game_id: u64,
+    ) -> Result<BytesN<32>, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let prediction = parse_prediction(prediction)?;
+        let config: FreeRollConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::FreeRollConfig)
+            .ok_or(Error::FreeRollsNotConfigured)?;
+
+        if env.storage().persistent().has(&DataKey::Game(game_id)) {
+            return Err(Error::GameAlreadyExists);
+        }
+
+        let count_key = DataKey::FreeRollCount(player.clone());
+        let used: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        if used >= config.max_per_player {
+            return Err(Error::FreeRollLimitReached);
+        }
+
+        let budget = get_promo_budget(&env);
+        if budget < config.prize {
+            return Err(Error::PromoBudgetExhausted);
         }
 
-        balance_client.debit(&game_addr, &player, &wager, &symbol_short!("wager"));
-        balance_client.credit(&game_addr, &game_addr, &wager, &symbol_short!("escrow"));
+        let missions = MissionsClient::new(&env, &config.missions_contract);
+        if !missions.is_free_roll_eligible(&player) {
+            return Err(Error::NotEligible);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::PromoBudget, &(budget - config.prize));
+        if used == 0 {
+            count_new_entry(&env, FAMILY_FREE_ROLL);
+        }
+        env.storage().persistent().set(&count_key, &(used + 1));
 
-        let game = GameData {
-            player: player.clone(),
+        let rng_contract = get_rng_contract(&env)?;
+        let receipt_hash = store_game(
+            &env,
+            &player,
+            game_id,
             prediction,
-            wager,
-            resolved: false,
-            outcome: 0,
-            win: false,
-            payout: 0,
-        };
-        env.storage().persistent().set(&key, &game);
+            0,
+            config.prize,
+            rng_contract,
+        )?;
 
-        PredictionPlaced {
+        FreeRollPlaced {
             game_id,
             player,
             prediction: prediction as u32,
-            wager,
+            prize: config.prize,
+            receipt: receipt_hash.clone(),
         }
         .publish(&env);
 
-        Ok(())
+        Ok(receipt_hash)
     }
 
     pub fn resolve_game(env: Env, game_id: u64) -> Result<(), Error> {
@@ -209,32 +661,58 @@ impl HigherLower {
         if game.resolved {
             return Err(Error::AlreadyResolved);
         }
-
-        let rng_contract = get_rng_contract(&env)?;
-        let rng_client = RngClient::new(&env, &rng_contract);
-        if !rng_client.is_ready(&game_id) {
-            return Err(Error::RngNotReady);
+        if game.cancelled {
+            return Err(Error::GameCancelled);
         }
-        let outcome = rng_client.get_result(&game_id);
+
+        let rng_client = RngClient::new(&env, &game.rng_contract);
+        let outcome = match rng_client.try_is_ready(&game_id) {
+            Ok(Ok(true)) => match rng_client.try_get_result(&game_id) {
+                Ok(Ok(outcome)) if outcome <= MAX_OUTCOME => outcome,
+                Ok(Ok(_)) => {
+                    return refund_rng_failure(&env, game_id, game, symbol_short!("range"))
+                }
+                _ => return refund_rng_failure(&env, game_id, game, symbol_short!("result")),
+            },
+            Ok(Ok(false)) => return Err(Error::RngNotReady),
+            _ => return refund_rng_failure(&env, game_id, game, symbol_short!("ready")),
+        };
 
         let win = match game.prediction {
             Prediction::Higher => outcome > ANCHOR_VALUE,
             Prediction::Lower => outcome < ANCHOR_VALUE,
         };
 
-        let payout = if win {
-            game.wager.checked_mul(2).ok_or(Error::Overflow)?
-        } else {
+        let payout = if !win {
             0
+        } else if game.free_roll_prize > 0 {
+            game.free_roll_prize
+        } else {
+            game.wager.checked_mul(2).ok_or(Error::Overflow)?
         };
 
+        // A losing free roll releases its reserved prize back to the budget.
+        if !win && game.free_roll_prize > 0 {
+            let budget = get_promo_budget(&env)
+                .checked_add(game.free_roll_prize)
+                .ok_or(Error::Overflow)?;
+            env.storage().instance().set(&DataKey::PromoBudget, &budget);
+        }
+
         let balance_contract = get_balance_contract(&env)?;
         let game_addr = env.current_contract_address();
         let balance_client = BalanceClient::new(&env, &balance_contract);
 
         if payout > 0 {
-            let house_balance = balance_client.balance_of(&game_addr);
-            if house_balance < payout {
+            // The unreserved promo budget only funds free rolls; a free roll's
+            // prize was already reserved out of it at placement.
+            let mut available = balance_client.balance_of(&game_addr);
+            if game.free_roll_prize == 0 {
+                available = available
+                    .checked_sub(get_promo_budget(&env))
+                    .ok_or(Error::Overflow)?;
+            }
+            if available < payout {
                 return Err(Error::HouseInsufficientFunds);
             }
 
@@ -247,6 +725,7 @@ impl HigherLower {
         game.win = win;
         game.payout = payout;
         env.storage().persistent().set(&key, &game);
+        release_liability(&env, &game)?;
 
         GameResolved {
             game_id,
@@ -256,46 +735,233 @@ impl HigherLower {
         }
         .publish(&env);
 
+        notify_subscribers(&env, game_id, &game);
+
         Ok(())
     }
 
+    /// Cancel an unresolved paid bet and refund the wager minus the
+    /// `CANCEL_FEE_BPS` anti-spam fee, which stays with the house.
+    ///
+    /// Only the player may cancel, only before the RNG result is ready, and
+    /// only within `CANCEL_WINDOW_LEDGERS` of placement. Returns the refund.
+    pub fn cancel_game(env: Env, player: Address, game_id: u64) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let key = DataKey::Game(game_id);
+        let mut game: GameData = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::GameNotFound)?;
+
+        if game.player != player || game.free_roll_prize > 0 {
+            return Err(Error::NotAuthorized);
+        }
+        if game.resolved {
+            return Err(Error::AlreadyResolved);
+        }
+        if game.cancelled {
+            return Err(Error::GameCancelled);
+        }
+        let window_end = game.placed_ledger.saturating_add(CANCEL_WINDOW_LEDGERS);
+        if env.ledger().sequence() > window_end {
+            return Err(Error::CancelWindowClosed);
+        }
+        let rng_client = RngClient::new(&env, &game.rng_contract);
+        if rng_client.is_ready(&game_id) {
+            return Err(Error::CancelWindowClosed);
+        }
+
+        let fee = cancel_fee(game.wager)?;
+        let refund = game.wager.checked_sub(fee).ok_or(Error::Overflow)?;
+
+        game.cancelled = true;
+        env.storage().persistent().set(&key, &game);
+        release_liability(&env, &game)?;
+
+        if refund > 0 {
+            let balance_client = BalanceClient::new(&env, &get_balance_contract(&env)?);
+            let game_addr = env.current_contract_address();
+            balance_client.debit(&game_addr, &game_addr, &refund, &symbol_short!("payout"));
+            balance_client.credit(&game_addr, &player, &refund, &symbol_short!("refund"));
+        }
+
+        GameCancelled {
+            game_id,
+            player,
+            refund,
+            fee,
+        }
+        .publish(&env);
+
+        Ok(refund)
+    }
+
     pub fn get_game(env: Env, game_id: u64) -> Option<GameData> {
         env.storage().persistent().get(&DataKey::Game(game_id))
     }
-}
 
-// ---------------------------------------------------------------------------
-// Internal helpers
-// ---------------------------------------------------------------------------
+    /// Return a game as a flat, display-ready summary, with the prediction
+    /// as a label and a single status in place of the raw flags.
+    pub fn get_game_summary(env: Env, game_id: u64) -> Result<Option<GameSummary>, Error> {
+        let game: GameData = match env.storage().persistent().get(&DataKey::Game(game_id)) {
+            Some(game) => game,
+            None => return Ok(None),
+        };
 
-fn require_initialized(env: &Env) -> Result<(), Error> {
-    if !env.storage().instance().has(&DataKey::Admin) {
-        return Err(Error::NotInitialized);
+        let (status, payout) = if game.cancelled {
+            let fee = cancel_fee(game.wager)?;
+            (
+                GameSummaryStatus::Cancelled,
+                game.wager.checked_sub(fee).ok_or(Error::Overflow)?,
+            )
+        } else if game.rng_failed {
+            (GameSummaryStatus::Refunded, game.wager)
+        } else if !game.resolved {
+            (GameSummaryStatus::Pending, 0)
+        } else if game.win {
+            (GameSummaryStatus::Won, game.payout)
+        } else {
+            (GameSummaryStatus::Lost, 0)
+        };
+        let prediction = match game.prediction {
+            Prediction::Higher => symbol_short!("higher"),
+            Prediction::Lower => symbol_short!("lower"),
+        };
+
+        Ok(Some(GameSummary {
+            game_id,
+            player: game.player,
+            prediction,
+            wager: game.wager,
+            anchor: ANCHOR_VALUE,
+            outcome: game.outcome,
+            win: game.win,
+            payout,
+            free_roll: game.free_roll_prize > 0,
+            status,
+        }))
     }
-    Ok(())
-}
 
-fn require_wager_bounds(wager: i128) -> Result<(), Error> {
-    if wager < MIN_WAGER || wager > MAX_WAGER {
-        return Err(Error::InvalidWager);
+    pub fn get_free_roll_config(env: Env) -> Option<FreeRollConfig> {
+        env.storage().instance().get(&DataKey::FreeRollConfig)
     }
-    Ok(())
-}
 
-fn parse_prediction(value: u32) -> Result<Prediction, Error> {
-    match value {
-        0 => Ok(Prediction::Higher),
-        1 => Ok(Prediction::Lower),
-        _ => Err(Error::InvalidPrediction),
+    pub fn get_resolution_subscribers(env: Env) -> Option<ResolutionSubscribers> {
+        env.storage()
+            .instance()
+            .get(&DataKey::ResolutionSubscribers)
     }
-}
 
-fn get_rng_contract(env: &Env) -> Result<Address, Error> {
-    env.storage()
-        .instance()
-        .get(&DataKey::RngContract)
-        .ok_or(Error::NotInitialized)
-}
+    /// Promo funds not reserved by pending free rolls.
+    pub fn get_promo_budget(env: Env) -> i128 {
+        get_promo_budget(&env)
+    }
+
+    pub fn get_free_roll_count(env: Env, player: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::FreeRollCount(player))
+            .unwrap_or(0)
+    }
+
+    /// Largest wager `place_prediction` would accept for `prediction` right
+    /// now, given the house bankroll, the exposure limit and the payouts
+    /// already owed to unresolved games. Returns `0` when no wager fits.
+    pub fn get_max_allowed_wager(env: Env, prediction: u32) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        parse_prediction(prediction)?;
+        let balance_client = BalanceClient::new(&env, &get_balance_contract(&env)?);
+        max_allowed_wager(&env, &balance_client)
+    }
+
+    pub fn get_exposure_limit(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ExposureLimitBps)
+            .unwrap_or(0)
+    }
+
+    /// Payouts unresolved games could still claim.
+    pub fn get_open_liability(env: Env) -> i128 {
+        get_open_liability(&env)
+    }
+
+    /// Game-registry `HealthSource`: version of the deployed code.
+    pub fn version(_env: Env) -> u32 {
+        CONTRACT_VERSION
+    }
+
+    /// Game-registry `HealthSource`: higher-lower has no pause switch, so it
+    /// always reports `false`.
+    pub fn is_paused(_env: Env) -> bool {
+        false
+    }
+
+    /// Game-registry `HealthSource`: same as `get_open_liability`.
+    pub fn open_liability(env: Env) -> i128 {
+        get_open_liability(&env)
+    }
+
+    /// Look up a bet receipt by the hash returned from `place_prediction`.
+    pub fn get_receipt(env: Env, receipt_hash: BytesN<32>) -> Option<BetReceipt> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Receipt(receipt_hash))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn require_initialized(env: &Env) -> Result<(), Error> {
+    if !env.storage().instance().has(&DataKey::Admin) {
+        return Err(Error::NotInitialized);
+    }
+    Ok(())
+}
+
+fn require_admin(env: &Env) -> Result<(), Error> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)?;
+    admin.require_auth();
+    Ok(())
+}
+
+fn require_wager_bounds(wager: i128) -> Result<(), Error> {
+    if wager < MIN_WAGER || wager > MAX_WAGER {
+        return Err(Error::InvalidWager);
+    }
+    Ok(())
+}
+
+fn parse_prediction(value: u32) -> Result<Prediction, Error> {
+    match value {
+        0 => Ok(Prediction::Higher),
+        1 => Ok(Prediction::Lower),
+        _ => Err(Error::InvalidPrediction),
+    }
+}
+
+fn get_rng_contract(env: &Env) -> Result<Address, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::RngContract)
+        .ok_or(Error::NotInitialized)
+}
+
+fn get_rng_providers(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::RngProviders)
+        .unwrap_or_else(|| Vec::new(env))
+}
 
 fn get_balance_contract(env: &Env) -> Result<Address, Error> {
     env.storage()
@@ -304,6 +970,291 @@ fn get_balance_contract(env: &Env) -> Result<Address, Error> {
         .ok_or(Error::NotInitialized)
 }
 
+fn get_promo_budget(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::PromoBudget)
+        .unwrap_or(0)
+}
+
+fn get_open_liability(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::OpenLiability)
+        .unwrap_or(0)
+}
+
+/// Payout a game could still claim: its reserved prize for a free roll,
+/// otherwise the 2x win on its wager.
+fn game_liability(game: &GameData) -> Result<i128, Error> {
+    if game.free_roll_prize > 0 {
+        Ok(game.free_roll_prize)
+    } else {
+        game.wager.checked_mul(2).ok_or(Error::Overflow)
+    }
+}
+
+/// Remove a settled or cancelled game from the open liabilities.
+fn release_liability(env: &Env, game: &GameData) -> Result<(), Error> {
+    let liability = get_open_liability(env)
+        .checked_sub(game_liability(game)?)
+        .ok_or(Error::Overflow)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::OpenLiability, &liability);
+    Ok(())
+}
+
+/// Largest paid wager that keeps open liabilities within the exposure limit.
+///
+/// Placing `w` escrows it into the bankroll and adds a `2w` liability, so it
+/// fits while `(open + 2w) * 10_000 <= (bankroll + w) * bps`, i.e.
+/// `w <= (bankroll * bps - open * 10_000) / (20_000 - bps)`.
+fn max_allowed_wager(env: &Env, balance_client: &BalanceClient) -> Result<i128, Error> {
+    let bps: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::ExposureLimitBps)
+        .unwrap_or(0);
+    if bps == 0 {
+        return Ok(MAX_WAGER);
+    }
+    let bankroll = balance_client
+        .balance_of(&env.current_contract_address())
+        .checked_sub(get_promo_budget(env))
+        .ok_or(Error::Overflow)?;
+    let headroom = bankroll
+        .checked_mul(bps as i128)
+        .and_then(|v| v.checked_sub(get_open_liability(env).checked_mul(10_000)?))
+        .ok_or(Error::Overflow)?;
+    let max = (headroom / (20_000 - bps as i128)).min(MAX_WAGER);
+    Ok(if max < MIN_WAGER { 0 } else { max })
+}
+
+/// Shared body of `place_prediction` and `place_prediction_with_rng`.
+fn place_paid_game(
+    env: &Env,
+    player: Address,
+    prediction: u32,
+    wager: i128,
+    game_id: u64,
+    rng_contract: Address,
+) -> Result<BytesN<32>, Error> {
+    player.require_auth();
+
+    let prediction = parse_prediction(prediction)?;
+    require_wager_bounds(wager)?;
+
+    let key = DataKey::Game(game_id);
+    if env.storage().persistent().has(&key) {
+        return Err(Error::GameAlreadyExists);
+    }
+
+    let balance_contract = get_balance_contract(env)?;
+    let game_addr = env.current_contract_address();
+    let balance_client = BalanceClient::new(env, &balance_contract);
+
+    if wager > max_allowed_wager(env, &balance_client)? {
+        return Err(Error::ExposureLimitExceeded);
+    }
+
+    let player_balance = balance_client.balance_of(&player);
+    if player_balance < wager {
+        return Err(Error::InsufficientBalance);
+    }
+
+    balance_client.debit(&game_addr, &player, &wager, &symbol_short!("wager"));
+    balance_client.credit(&game_addr, &game_addr, &wager, &symbol_short!("escrow"));
+
+    let receipt_hash = store_game(env, &player, game_id, prediction, wager, 0, rng_contract)?;
+
+    PredictionPlaced {
+        game_id,
+        player,
+        prediction: prediction as u32,
+        wager,
+        receipt: receipt_hash.clone(),
+    }
+    .publish(env);
+
+    Ok(receipt_hash)
+}
+
+/// Write a new game and its receipt, returning the receipt hash.
+fn store_game(
+    env: &Env,
+    player: &Address,
+    game_id: u64,
+    prediction: Prediction,
+    wager: i128,
+    free_roll_prize: i128,
+    rng_contract: Address,
+) -> Result<BytesN<32>, Error> {
+    let game = GameData {
+        player: player.clone(),
+        prediction,
+        wager,
+        resolved: false,
+        outcome: 0,
+        win: false,
+        payout: 0,
+        free_roll_prize,
+        placed_ledger: env.ledger().sequence(),
+        cancelled: false,
+        rng_contract,
+        rng_failed: false,
+    };
+    let liability = get_open_liability(env)
+        .checked_add(game_liability(&game)?)
+        .ok_or(Error::Overflow)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::OpenLiability, &liability);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), &game);
+    count_new_entry(env, FAMILY_GAME);
+
+    let receipt = BetReceipt {
+        player: player.clone(),
+        game_id,
+        prediction,
+        wager,
+        ledger: env.ledger().sequence(),
+    };
+    let receipt_hash: BytesN<32> = env.crypto().sha256(&receipt.clone().to_xdr(env)).into();
+    env.storage()
+        .persistent()
+        .set(&DataKey::Receipt(receipt_hash.clone()), &receipt);
+    count_new_entry(env, FAMILY_RECEIPT);
+
+    Ok(receipt_hash)
+}
+
+/// Anti-spam fee withheld from the refund of a cancelled `wager`.
+fn cancel_fee(wager: i128) -> Result<i128, Error> {
+    Ok(wager.checked_mul(CANCEL_FEE_BPS).ok_or(Error::Overflow)? / 10_000)
+}
+
+/// Settle a game whose RNG provider failed at resolution: refund the wager
+/// in full (a free roll's prize goes back to the promo budget), close the
+/// game, and count the failure against the provider.
+fn refund_rng_failure(
+    env: &Env,
+    game_id: u64,
+    mut game: GameData,
+    reason: Symbol,
+) -> Result<(), Error> {
+    let refund = if game.free_roll_prize > 0 {
+        let budget = get_promo_budget(env)
+            .checked_add(game.free_roll_prize)
+            .ok_or(Error::Overflow)?;
+        env.storage().instance().set(&DataKey::PromoBudget, &budget);
+        0
+    } else {
+        game.wager
+    };
+
+    if refund > 0 {
+        let balance_client = BalanceClient::new(env, &get_balance_contract(env)?);
+        let game_addr = env.current_contract_address();
+        balance_client.debit(&game_addr, &game_addr, &refund, &symbol_short!("payout"));
+        balance_client.credit(&game_addr, &game.player, &refund, &symbol_short!("refund"));
+    }
+
+    game.resolved = true;
+    game.rng_failed = true;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), &game);
+    release_liability(env, &game)?;
+
+    let failures_key = DataKey::RngFailures(game.rng_contract.clone());
+    let failures: u32 = env.storage().persistent().get(&failures_key).unwrap_or(0);
+    env.storage().persistent().set(
+        &failures_key,
+        &failures.checked_add(1).ok_or(Error::Overflow)?,
+    );
+
+    RngFailure {
+        game_id,
+        provider: game.rng_contract,
+        reason,
+        refund,
+    }
+    .publish(env);
+
+    Ok(())
+}
+
+/// Notify each configured subscriber of a resolved game. Calls go through
+/// `try_` so a subscriber that traps or errors is rolled back on its own and
+/// reported with `SubscriberFailed` instead of reverting the resolution.
+fn notify_subscribers(env: &Env, game_id: u64, game: &GameData) {
+    let subscribers: ResolutionSubscribers = match env
+        .storage()
+        .instance()
+        .get(&DataKey::ResolutionSubscribers)
+    {
+        Some(subscribers) => subscribers,
+        None => return,
+    };
+    let game_addr = env.current_contract_address();
+    for subscriber in [subscribers.leaderboard, subscribers.missions]
+        .into_iter()
+        .flatten()
+    {
+        let result = ResolutionSubscriberClient::new(env, &subscriber).try_on_game_resolved(
+            &game_addr,
+            &game_id,
+            &game.player,
+            &game.wager,
+            &game.win,
+            &game.payout,
+        );
+        if !matches!(result, Ok(Ok(()))) {
+            SubscriberFailed {
+                game_id,
+                subscriber,
+            }
+            .publish(env);
+        }
+    }
+    // Free rolls carry no wager, and the referral system rejects zero amounts.
+    if let Some(referral) = subscribers.referral.filter(|_| game.wager > 0) {
+        if !report_referral(env, &referral, &game.player, game.wager) {
+            SubscriberFailed {
+                game_id,
+                subscriber: referral,
+            }
+            .publish(env);
+        }
+    }
+}
+
+/// Record a game-played event for `player` with the referral system. Players
+/// without a referrer are skipped. Returns `false` if either call failed.
+fn report_referral(env: &Env, referral: &Address, player: &Address, wager: i128) -> bool {
+    let client = ReferralClient::new(env, referral);
+    match client.try_get_referrer(player) {
+        Ok(Ok(Some(_))) => matches!(
+            client.try_record_referral_event(
+                &env.current_contract_address(),
+                player,
+                &REFERRAL_EVENT_GAME_PLAYED,
+                &wager,
+            ),
+            Ok(Ok(()))
+        ),
+        Ok(Ok(None)) => true,
+        _ => false,
+    }
+}
+
+const FAMILY_GAME: Symbol = symbol_short!("game");
+const FAMILY_RECEIPT: Symbol = symbol_short!("receipt");
+const FAMILY_FREE_ROLL: Symbol = symbol_short!("freeroll");
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -312,10 +1263,51 @@ fn get_balance_contract(env: &Env) -> Result<Address, Error> {
 mod test {
     use super::*;
     use soroban_sdk::{
-        contract, contractimpl, contracttype, testutils::Address as _, token::StellarAssetClient,
-        Address, Env,
+        contract, contractimpl, contracttype,
+        testutils::{Address as _, Events as _, Ledger},
+        Address, Env, Event as _,
     };
-    use stellarcade_user_balance::{UserBalance, UserBalanceClient};
+
+    // -----------------------------
+    // Mock balance contract
+    // -----------------------------
+
+    #[contract]
+    pub struct MockBalance;
+
+    #[contracttype]
+    pub enum BalanceKey {
+        Balance(Address),
+    }
+
+    #[contractimpl]
+    impl MockBalance {
+        pub fn set_balance(env: Env, user: Address, amount: i128) {
+            env.storage()
+                .persistent()
+                .set(&BalanceKey::Balance(user), &amount);
+        }
+
+        pub fn credit(env: Env, game: Address, user: Address, amount: i128, _reason: Symbol) {
+            game.require_auth();
+            let balance = Self::balance_of(env.clone(), user.clone());
+            Self::set_balance(env, user, balance + amount);
+        }
+
+        pub fn debit(env: Env, game: Address, user: Address, amount: i128, _reason: Symbol) {
+            game.require_auth();
+            let balance = Self::balance_of(env.clone(), user.clone());
+            assert!(balance >= amount, "insufficient balance");
+            Self::set_balance(env, user, balance - amount);
+        }
+
+        pub fn balance_of(env: Env, user: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&BalanceKey::Balance(user))
+                .unwrap_or(0)
+        }
+    }
 
     // -----------------------------
     // Mock RNG contract
@@ -328,6 +1320,7 @@ mod test {
     pub enum RngKey {
         Result(u64),
         Ready(u64),
+        Broken(u64),
     }
 
     #[contractimpl]
@@ -344,7 +1337,20 @@ mod test {
                 .unwrap_or(false)
         }
 
+        /// Report the result as ready but trap when it is fetched.
+        pub fn set_broken(env: Env, game_id: u64) {
+            env.storage()
+                .persistent()
+                .set(&RngKey::Broken(game_id), &true);
+            env.storage()
+                .persistent()
+                .set(&RngKey::Ready(game_id), &true);
+        }
+
         pub fn get_result(env: Env, game_id: u64) -> u32 {
+            if env.storage().persistent().has(&RngKey::Broken(game_id)) {
+                panic!("rng backend unavailable");
+            }
             env.storage()
                 .persistent()
                 .get(&RngKey::Result(game_id))
@@ -352,10 +1358,144 @@ mod test {
         }
     }
 
-    fn create_token<'a>(env: &'a Env, token_admin: &Address) -> (Address, StellarAssetClient<'a>) {
-        let contract = env.register_stellar_asset_contract_v2(token_admin.clone());
-        let client = StellarAssetClient::new(env, &contract.address());
-        (contract.address(), client)
+    // -----------------------------
+    // Mock missions contract
+    // -----------------------------
+
+    #[contract]
+    pub struct MockMissions;
+
+    #[contracttype]
+    pub enum MissionsKey {
+        Eligible(Address),
+    }
+
+    #[contractimpl]
+    impl MockMissions {
+        pub fn set_eligible(env: Env, user: Address, eligible: bool) {
+            env.storage()
+                .persistent()
+                .set(&MissionsKey::Eligible(user), &eligible);
+        }
+
+        pub fn is_free_roll_eligible(env: Env, user: Address) -> bool {
+            env.storage()
+                .persistent()
+                .get(&MissionsKey::Eligible(user))
+                .unwrap_or(false)
+        }
+    }
+
+    // -----------------------------
+    // Mock resolution subscriber
+    // -----------------------------
+
+    #[contract]
+    pub struct MockSubscriber;
+
+    #[contracttype]
+    pub enum SubscriberKey {
+        Failing,
+        Calls,
+        LastPayout,
+    }
+
+    #[contractimpl]
+    impl MockSubscriber {
+        pub fn set_failing(env: Env, failing: bool) {
+            env.storage()
+                .instance()
+                .set(&SubscriberKey::Failing, &failing);
+        }
+
+        pub fn calls(env: Env) -> u32 {
+            env.storage()
+                .instance()
+                .get(&SubscriberKey::Calls)
+                .unwrap_or(0)
+        }
+
+        pub fn last_payout(env: Env) -> i128 {
+            env.storage()
+                .instance()
+                .get(&SubscriberKey::LastPayout)
+                .unwrap_or(0)
+        }
+
+        pub fn on_game_resolved(
+            env: Env,
+            _game: Address,
+            _game_id: u64,
+            _player: Address,
+            _wager: i128,
+            _win: bool,
+            payout: i128,
+        ) {
+            let calls = Self::calls(env.clone()) + 1;
+            env.storage().instance().set(&SubscriberKey::Calls, &calls);
+            env.storage()
+                .instance()
+                .set(&SubscriberKey::LastPayout, &payout);
+            let failing: bool = env
+                .storage()
+                .instance()
+                .get(&SubscriberKey::Failing)
+                .unwrap_or(false);
+            if failing {
+                panic!("subscriber unavailable");
+            }
+        }
+    }
+
+    // -----------------------------
+    // Mock referral system
+    // -----------------------------
+
+    #[contract]
+    pub struct MockReferral;
+
+    #[contracttype]
+    pub enum MockReferralKey {
+        Referrer(Address),
+        Volume(Address),
+    }
+
+    #[contractimpl]
+    impl MockReferral {
+        pub fn set_referrer(env: Env, user: Address, referrer: Address) {
+            env.storage()
+                .instance()
+                .set(&MockReferralKey::Referrer(user), &referrer);
+        }
+
+        pub fn get_referrer(env: Env, user: Address) -> Option<Address> {
+            env.storage()
+                .instance()
+                .get(&MockReferralKey::Referrer(user))
+        }
+
+        pub fn volume(env: Env, user: Address) -> i128 {
+            env.storage()
+                .instance()
+                .get(&MockReferralKey::Volume(user))
+                .unwrap_or(0)
+        }
+
+        pub fn record_referral_event(
+            env: Env,
+            caller: Address,
+            user: Address,
+            event_type: u32,
+            amount: i128,
+        ) {
+            caller.require_auth();
+            assert_eq!(event_type, REFERRAL_EVENT_GAME_PLAYED);
+            assert!(amount > 0, "invalid amount");
+            let volume = Self::volume(env.clone(), user.clone()) + amount;
+            env.storage()
+                .instance()
+                .set(&MockReferralKey::Volume(user), &volume);
+        }
     }
 
     fn setup(
@@ -365,20 +1505,16 @@ mod test {
         Address, // admin
         Address, // player
         Address, // house
-        UserBalanceClient<'_>,
+        MockBalanceClient<'_>,
         MockRngClient<'_>,
     ) {
         env.mock_all_auths();
 
         let admin = Address::generate(env);
         let player = Address::generate(env);
-        let token_admin = Address::generate(env);
-
-        let (token_addr, token_sac) = create_token(env, &token_admin);
 
-        let balance_id = env.register(UserBalance, ());
-        let balance_client = UserBalanceClient::new(env, &balance_id);
-        balance_client.init(&admin, &token_addr);
+        let balance_id = env.register(MockBalance, ());
+        let balance_client = MockBalanceClient::new(env, &balance_id);
 
         let rng_id = env.register(MockRng, ());
         let rng_client = MockRngClient::new(env, &rng_id);
@@ -390,13 +1526,8 @@ mod test {
 
         higher_lower_client.init(&admin, &rng_id, &Address::generate(env), &balance_id);
 
-        balance_client.authorize_game(&admin, &higher_lower_id);
-
-        token_sac.mint(&player, &1_000);
-        token_sac.mint(&house, &5_000);
-
-        balance_client.deposit(&player, &1_000);
-        balance_client.deposit(&house, &5_000);
+        balance_client.set_balance(&player, &1_000);
+        balance_client.set_balance(&house, &5_000);
 
         (
             higher_lower_client,
@@ -463,6 +1594,58 @@ mod test {
         assert_eq!(balance.balance_of(&house), 5_100);
     }
 
+    #[test]
+    fn test_game_summary_flattens_status_for_wallets() {
+        let env = Env::default();
+        let (client, _admin, player, _house, _balance, rng) = setup(&env);
+        assert_eq!(client.get_game_summary(&40), None);
+
+        client.place_prediction(&player, &0, &100, &40);
+        let summary = client.get_game_summary(&40).unwrap();
+        assert_eq!(summary.prediction, symbol_short!("higher"));
+        assert_eq!(summary.anchor, ANCHOR_VALUE);
+        assert_eq!(summary.status, GameSummaryStatus::Pending);
+        assert!(!summary.free_roll);
+
+        rng.set_result(&40, &80);
+        client.resolve_game(&40);
+        let summary = client.get_game_summary(&40).unwrap();
+        assert_eq!(summary.status, GameSummaryStatus::Won);
+        assert_eq!((summary.outcome, summary.payout), (80, 200));
+
+        client.place_prediction(&player, &1, &100, &41);
+        rng.set_result(&41, &80);
+        client.resolve_game(&41);
+        let summary = client.get_game_summary(&41).unwrap();
+        assert_eq!(summary.prediction, symbol_short!("lower"));
+        assert_eq!(summary.status, GameSummaryStatus::Lost);
+        assert_eq!(summary.payout, 0);
+
+        client.place_prediction(&player, &1, &400, &42);
+        client.cancel_game(&player, &42);
+        let summary = client.get_game_summary(&42).unwrap();
+        assert_eq!(summary.status, GameSummaryStatus::Cancelled);
+        assert_eq!(summary.payout, 398);
+    }
+
+    #[test]
+    fn test_receipt_recorded_on_placement() {
+        let env = Env::default();
+        let (client, _admin, player, _house, _balance, _rng) = setup(&env);
+
+        let hash = client.place_prediction(&player, &1, &250, &8);
+
+        let receipt = client.get_receipt(&hash).unwrap();
+        assert_eq!(receipt.player, player);
+        assert_eq!(receipt.game_id, 8);
+        assert_eq!(receipt.prediction, Prediction::Lower);
+        assert_eq!(receipt.wager, 250);
+        assert_eq!(receipt.ledger, env.ledger().sequence());
+
+        let expected: BytesN<32> = env.crypto().sha256(&receipt.to_xdr(&env)).into();
+        assert_eq!(hash, expected);
+    }
+
     #[test]
     fn test_invalid_prediction_rejected() {
         let env = Env::default();
@@ -477,7 +1660,7 @@ mod test {
         let env = Env::default();
         let (client, _admin, player, _house, balance, _rng) = setup(&env);
 
-        balance.withdraw(&player, &1_000);
+        balance.set_balance(&player, &0);
 
         let result = client.try_place_prediction(&player, &0, &100, &5);
         assert!(result.is_err());
@@ -507,4 +1690,321 @@ mod test {
         let result = client.try_resolve_game(&7);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_storage_health_and_bump() {
+        let env = Env::default();
+        env.ledger().set_sequence_number(5);
+        let (client, _admin, player, _house, _balance, _rng) = setup(&env);
+
+        client.place_prediction(&player, &0, &100, &1);
+        client.place_prediction(&player, &1, &100, &2);
+
+        let health = client.get_storage_health();
+        assert_eq!(health.last_bump_ledger, 5);
+        assert_eq!(health.instance_ttl, INSTANCE_BUMP_LEDGERS);
+        assert_eq!(health.families.get(0).unwrap().count, 2);
+        assert_eq!(health.families.get(1).unwrap().count, 2);
+
+        env.ledger().set_sequence_number(105);
+        assert_eq!(
+            client.get_storage_health().instance_ttl,
+            INSTANCE_BUMP_LEDGERS - 100
+        );
+        client.bump_all_config();
+        assert_eq!(client.get_storage_health().last_bump_ledger, 105);
+    }
+
+    fn setup_free_rolls(
+        env: &Env,
+        client: &HigherLowerClient<'_>,
+        house: &Address,
+        player: &Address,
+    ) -> MockMissionsClient<'static> {
+        let missions_id = env.register(MockMissions, ());
+        let missions = MockMissionsClient::new(env, &missions_id);
+        missions.set_eligible(player, &true);
+        client.set_free_roll_config(&missions_id, &300, &2);
+        // The house acts as treasury and moves part of its balance into promo.
+        client.fund_promo_budget(house, &600);
+        missions
+    }
+
+    #[test]
+    fn test_free_roll_win_paid_from_promo_budget() {
+        let env = Env::default();
+        let (client, _admin, player, house, balance, rng) = setup(&env);
+        setup_free_rolls(&env, &client, &house, &player);
+        assert_eq!(client.get_promo_budget(), 600);
+
+        client.place_free_roll(&player, &0, &10);
+        assert_eq!(client.get_promo_budget(), 300);
+        assert_eq!(client.get_free_roll_count(&player), 1);
+        assert_eq!(balance.balance_of(&player), 1_000);
+
+        rng.set_result(&10, &90);
+        client.resolve_game(&10);
+
+        let game = client.get_game(&10).unwrap();
+        assert!(game.win);
+        assert_eq!(game.wager, 0);
+        assert_eq!(game.payout, 300);
+        assert_eq!(balance.balance_of(&player), 1_300);
+        assert_eq!(balance.balance_of(&house), 4_700);
+        assert_eq!(client.get_promo_budget(), 300);
+    }
+
+    #[test]
+    fn test_promo_budget_is_kept_out_of_paid_payouts_and_withdrawable() {
+        let env = Env::default();
+        let (client, _admin, player, house, balance, rng) = setup(&env);
+        let treasury = Address::generate(&env);
+
+        // 5_500 in the house after the wager, 4_700 of it promo: a 1_000
+        // payout cannot be covered from the 800 left.
+        client.fund_promo_budget(&house, &4_700);
+        client.place_prediction(&player, &0, &500, &12);
+        rng.set_result(&12, &90);
+        let result = client.try_resolve_game(&12);
+        assert_eq!(result, Err(Ok(Error::HouseInsufficientFunds)));
+
+        let too_much = client.try_withdraw_promo_budget(&treasury, &4_701);
+        assert_eq!(too_much, Err(Ok(Error::PromoBudgetExhausted)));
+        assert_eq!(client.withdraw_promo_budget(&treasury, &4_500), 200);
+        assert_eq!(client.get_promo_budget(), 200);
+        assert_eq!(balance.balance_of(&treasury), 4_500);
+        assert_eq!(balance.balance_of(&house), 1_000);
+        let result = client.try_resolve_game(&12);
+        assert_eq!(result, Err(Ok(Error::HouseInsufficientFunds)));
+    }
+
+    #[test]
+    fn test_free_roll_loss_releases_reserved_prize() {
+        let env = Env::default();
+        let (client, _admin, player, house, balance, rng) = setup(&env);
+        setup_free_rolls(&env, &client, &house, &player);
+
+        client.place_free_roll(&player, &1, &11);
+        rng.set_result(&11, &90);
+        client.resolve_game(&11);
+
+        assert!(!client.get_game(&11).unwrap().win);
+        assert_eq!(client.get_promo_budget(), 600);
+        assert_eq!(balance.balance_of(&player), 1_000);
+    }
+
+    #[test]
+    fn test_free_roll_eligibility_limit_and_budget() {
+        let env = Env::default();
+        let (client, _admin, player, house, _balance, _rng) = setup(&env);
+        let outsider = Address::generate(&env);
+
+        let unconfigured = client.try_place_free_roll(&player, &0, &20);
+        assert_eq!(unconfigured, Err(Ok(Error::FreeRollsNotConfigured)));
+
+        setup_free_rolls(&env, &client, &house, &player);
+
+        let ineligible = client.try_place_free_roll(&outsider, &0, &20);
+        assert_eq!(ineligible, Err(Ok(Error::NotEligible)));
+
+        client.place_free_roll(&player, &0, &21);
+        client.place_free_roll(&player, &0, &22);
+        let limited = client.try_place_free_roll(&player, &0, &23);
+        assert_eq!(limited, Err(Ok(Error::FreeRollLimitReached)));
+
+        // Both pending games hold the whole budget in reserve.
+        let second = Address::generate(&env);
+        let missions_id = client.get_free_roll_config().unwrap().missions_contract;
+        MockMissionsClient::new(&env, &missions_id).set_eligible(&second, &true);
+        let exhausted = client.try_place_free_roll(&second, &0, &24);
+        assert_eq!(exhausted, Err(Ok(Error::PromoBudgetExhausted)));
+    }
+
+    #[test]
+    fn test_resolution_notifies_subscribers_with_failure_isolation() {
+        let env = Env::default();
+        let (client, _admin, player, house, balance, rng) = setup(&env);
+
+        let leaderboard = MockSubscriberClient::new(&env, &env.register(MockSubscriber, ()));
+        let missions = MockSubscriberClient::new(&env, &env.register(MockSubscriber, ()));
+        let referral = MockReferralClient::new(&env, &env.register(MockReferral, ()));
+        missions.set_failing(&true);
+        client.set_resolution_subscribers(
+            &Some(leaderboard.address.clone()),
+            &Some(missions.address.clone()),
+            &Some(referral.address.clone()),
+        );
+
+        client.place_prediction(&player, &0, &100, &30);
+        rng.set_result(&30, &80);
+        client.resolve_game(&30);
+
+        // The failing subscriber's writes are rolled back; the payout stands.
+        assert_eq!(balance.balance_of(&player), 1_100);
+        assert_eq!(leaderboard.calls(), 1);
+        assert_eq!(leaderboard.last_payout(), 200);
+        assert_eq!(missions.calls(), 0);
+        // Players without a referrer are not reported.
+        assert_eq!(referral.volume(&player), 0);
+
+        // Clearing a slot stops its notifications.
+        referral.set_referrer(&player, &Address::generate(&env));
+        client.set_resolution_subscribers(&None, &None, &Some(referral.address.clone()));
+        client.place_prediction(&player, &1, &100, &31);
+        rng.set_result(&31, &80);
+        client.resolve_game(&31);
+        assert_eq!(leaderboard.calls(), 1);
+        assert_eq!(referral.volume(&player), 100);
+
+        // Free rolls have no wager to report, so the referral slot is skipped.
+        setup_free_rolls(&env, &client, &house, &player);
+        client.place_free_roll(&player, &0, &32);
+        rng.set_result(&32, &80);
+        client.resolve_game(&32);
+        let failed = SubscriberFailed {
+            game_id: 32,
+            subscriber: referral.address.clone(),
+        }
+        .to_xdr(&env, &client.address);
+        let events = env.events().all().filter_by_contract(&client.address);
+        assert!(!events.events().contains(&failed));
+        assert_eq!(referral.volume(&player), 100);
+    }
+
+    #[test]
+    fn test_player_cancel_before_rng_refunds_minus_fee() {
+        let env = Env::default();
+        let (client, _admin, player, house, balance, rng) = setup(&env);
+
+        client.place_prediction(&player, &0, &400, &20);
+        let stranger = Address::generate(&env);
+        let result = client.try_cancel_game(&stranger, &20);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+        // 0.5% of 400 stays with the house.
+        assert_eq!(client.cancel_game(&player, &20), 398);
+        assert_eq!(balance.balance_of(&player), 998);
+        assert_eq!(balance.balance_of(&house), 5_002);
+        assert!(client.get_game(&20).unwrap().cancelled);
+
+        rng.set_result(&20, &80);
+        let result = client.try_resolve_game(&20);
+        assert_eq!(result, Err(Ok(Error::GameCancelled)));
+        let result = client.try_cancel_game(&player, &20);
+        assert_eq!(result, Err(Ok(Error::GameCancelled)));
+
+        // Too late once the RNG result is ready.
+        client.place_prediction(&player, &0, &100, &21);
+        rng.set_result(&21, &80);
+        let result = client.try_cancel_game(&player, &21);
+        assert_eq!(result, Err(Ok(Error::CancelWindowClosed)));
+
+        // Too late once the window has passed.
+        client.place_prediction(&player, &0, &100, &22);
+        env.ledger()
+            .with_mut(|l| l.sequence_number += CANCEL_WINDOW_LEDGERS + 1);
+        let result = client.try_cancel_game(&player, &22);
+        assert_eq!(result, Err(Ok(Error::CancelWindowClosed)));
+    }
+
+    #[test]
+    fn test_max_allowed_wager_tracks_exposure() {
+        let env = Env::default();
+        let (client, _admin, player, _house, _balance, rng) = setup(&env);
+
+        assert_eq!(client.get_max_allowed_wager(&0), MAX_WAGER);
+        let result = client.try_set_exposure_limit(&10_001);
+        assert_eq!(result, Err(Ok(Error::InvalidExposureLimit)));
+        let result = client.try_get_max_allowed_wager(&2);
+        assert_eq!(result, Err(Ok(Error::InvalidPrediction)));
+
+        // 20% of a 5_000 bankroll: (5_000 * 2_000) / 18_000.
+        client.set_exposure_limit(&2_000);
+        assert_eq!(client.get_max_allowed_wager(&0), 555);
+        assert_eq!(client.get_max_allowed_wager(&1), 555);
+        let result = client.try_place_prediction(&player, &0, &556, &40);
+        assert_eq!(result, Err(Ok(Error::ExposureLimitExceeded)));
+
+        client.place_prediction(&player, &0, &555, &40);
+        assert_eq!(client.get_open_liability(), 1_110);
+        assert_eq!(client.get_max_allowed_wager(&0), 0);
+        // The game-registry health views report the same liability.
+        assert_eq!(client.open_liability(), 1_110);
+        assert_eq!(client.version(), CONTRACT_VERSION);
+        assert!(!client.is_paused());
+
+        // Settling the game frees its liability; the lost wager grows the bankroll.
+        rng.set_result(&40, &20);
+        client.resolve_game(&40);
+        assert_eq!(client.get_open_liability(), 0);
+        assert_eq!(client.get_max_allowed_wager(&0), 617);
+    }
+
+    #[test]
+    fn test_game_resolves_against_chosen_rng_provider() {
+        let env = Env::default();
+        let (client, _admin, player, _house, balance, rng) = setup(&env);
+        let alt_id = env.register(MockRng, ());
+        let alt = MockRngClient::new(&env, &alt_id);
+
+        let result = client.try_place_prediction_with_rng(&player, &0, &100, &50, &alt_id);
+        assert_eq!(result, Err(Ok(Error::RngProviderNotAllowed)));
+
+        client.set_rng_provider(&alt_id, &true);
+        assert_eq!(
+            client.get_rng_providers(),
+            Vec::from_array(&env, [alt_id.clone()])
+        );
+        client.place_prediction_with_rng(&player, &0, &100, &50, &alt_id);
+        client.place_prediction(&player, &0, &100, &51);
+        assert_eq!(client.get_game(&50).unwrap().rng_contract, alt_id);
+        assert_eq!(client.get_game(&51).unwrap().rng_contract, rng.address);
+
+        // Only the game's own provider is consulted.
+        rng.set_result(&50, &20);
+        let result = client.try_resolve_game(&50);
+        assert_eq!(result, Err(Ok(Error::RngNotReady)));
+        alt.set_result(&50, &80);
+        client.resolve_game(&50);
+        assert!(client.get_game(&50).unwrap().win);
+        assert_eq!(balance.balance_of(&player), 1_000);
+
+        // Delisting a provider does not strand games already placed with it.
+        client.place_prediction_with_rng(&player, &1, &100, &52, &alt_id);
+        client.set_rng_provider(&alt_id, &false);
+        assert!(client.get_rng_providers().is_empty());
+        alt.set_result(&52, &20);
+        client.resolve_game(&52);
+        assert!(client.get_game(&52).unwrap().win);
+    }
+
+    #[test]
+    fn test_rng_failure_refunds_and_counts_against_provider() {
+        let env = Env::default();
+        let (client, _admin, player, house, balance, rng) = setup(&env);
+
+        client.place_prediction(&player, &0, &100, &60);
+        client.place_prediction(&player, &1, &200, &61);
+        assert_eq!(balance.balance_of(&player), 700);
+
+        // A trapping provider refunds the wager in full.
+        rng.set_broken(&60);
+        client.resolve_game(&60);
+        let game = client.get_game(&60).unwrap();
+        assert!(game.resolved && game.rng_failed && !game.win);
+        assert_eq!(game.payout, 0);
+        assert_eq!(balance.balance_of(&player), 800);
+
+        // So does an outcome outside the valid range.
+        rng.set_result(&61, &(MAX_OUTCOME + 1));
+        client.resolve_game(&61);
+        assert!(client.get_game(&61).unwrap().rng_failed);
+        assert_eq!(balance.balance_of(&player), 1_000);
+        assert_eq!(balance.balance_of(&house), 5_000);
+        assert_eq!(client.get_open_liability(), 0);
+        assert_eq!(client.get_rng_failures(&rng.address), 2);
+
+        let result = client.try_resolve_game(&60);
+        assert_eq!(result, Err(Ok(Error::AlreadyResolved)));
+    }
 }
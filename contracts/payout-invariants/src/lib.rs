@@ -0,0 +1,98 @@
+//! Property-based payout invariants for the Stellarcade wagering games.
+//!
+//! The tests in `tests/` drive color-prediction, price-prediction and
+//! higher-lower through random sequences of bets, settlements, claims and
+//! refunds, and check that every unit escrowed is accounted for:
+//! `escrow in == payouts + fees + refunds`, with whatever a game legitimately
+//! carries over (e.g. a jackpot) still held by the contract. This crate only
+//! holds the shared fixtures; it is never deployed.
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype, testutils::EnvTestConfig, Address, Env, Symbol,
+};
+
+/// Fresh test environment with all auths mocked. Snapshots are disabled:
+/// each property runs hundreds of cases and would write one file per case.
+pub fn new_env() -> Env {
+    let env = Env::new_with_config(EnvTestConfig {
+        capture_snapshot_at_drop: false,
+    });
+    env.mock_all_auths();
+    env
+}
+
+#[contracttype]
+enum LedgerKey {
+    Balance(Address),
+}
+
+/// Internal-balance ledger with the `debit`/`credit`/`balance_of` interface
+/// color-prediction and higher-lower escrow through. Unlike a permissive
+/// mock, it rejects debits that exceed the balance, so an over-payment shows
+/// up as a failed call.
+#[contract]
+pub struct MockLedger;
+
+#[contractimpl]
+impl MockLedger {
+    pub fn mint(env: Env, user: Address, amount: i128) {
+        let balance = Self::balance_of(env.clone(), user.clone());
+        env.storage()
+            .persistent()
+            .set(&LedgerKey::Balance(user), &(balance + amount));
+    }
+
+    pub fn credit(env: Env, _game: Address, user: Address, amount: i128, _reason: Symbol) {
+        assert!(amount >= 0, "negative credit");
+        Self::mint(env, user, amount);
+    }
+
+    pub fn debit(env: Env, _game: Address, user: Address, amount: i128, _reason: Symbol) {
+        let balance = Self::balance_of(env.clone(), user.clone());
+        assert!(amount >= 0 && balance >= amount, "insufficient balance");
+        env.storage()
+            .persistent()
+            .set(&LedgerKey::Balance(user), &(balance - amount));
+    }
+
+    pub fn balance_of(env: Env, user: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&LedgerKey::Balance(user))
+            .unwrap_or(0)
+    }
+}
+
+/// Price oracle whose price the test sets directly.
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, asset: Symbol, price: i128) {
+        env.storage().persistent().set(&asset, &price);
+    }
+
+    pub fn get_price(env: Env, asset: Symbol) -> i128 {
+        env.storage().persistent().get(&asset).unwrap_or(0)
+    }
+}
+
+/// RNG whose per-game result the test sets directly.
+#[contract]
+pub struct MockRng;
+
+#[contractimpl]
+impl MockRng {
+    pub fn set_result(env: Env, game_id: u64, result: u32) {
+        env.storage().persistent().set(&game_id, &result);
+    }
+
+    pub fn is_ready(env: Env, game_id: u64) -> bool {
+        env.storage().persistent().has(&game_id)
+    }
+
+    pub fn get_result(env: Env, game_id: u64) -> u32 {
+        env.storage().persistent().get(&game_id).unwrap()
+    }
+}
@@ -0,0 +1,179 @@
+//! Color-prediction: wagers in == claims + refunds + fees + jackpot.
+
+use proptest::prelude::*;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use stellarcade_color_prediction::{ColorPrediction, ColorPredictionClient};
+use stellarcade_payout_invariants::{new_env, MockLedger, MockLedgerClient};
+
+const PLAYERS: usize = 5;
+const COLORS: u32 = 4;
+const STARTING_BALANCE: i128 = 1_000_000;
+
+#[derive(Clone, Debug)]
+enum Bet {
+    Single {
+        player: usize,
+        color: u32,
+        wager: i128,
+    },
+    Split {
+        player: usize,
+        first: u32,
+        second: u32,
+        wager: i128,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct Game {
+    bets: Vec<Bet>,
+    winning_color: u32,
+    cancelled: bool,
+}
+
+fn bet() -> impl Strategy<Value = Bet> {
+    prop_oneof![
+        4 => (0..PLAYERS, 0..COLORS, 1i128..5_000)
+            .prop_map(|(player, color, wager)| Bet::Single { player, color, wager }),
+        1 => (0..PLAYERS, 0..COLORS, 0..COLORS, 1i128..5_000).prop_map(
+            |(player, first, second, wager)| Bet::Split { player, first, second, wager }
+        ),
+    ]
+}
+
+fn game() -> impl Strategy<Value = Game> {
+    (
+        prop::collection::vec(bet(), 0..8),
+        0..COLORS,
+        prop::bool::weighted(0.2),
+    )
+        .prop_map(|(bets, winning_color, cancelled)| Game {
+            bets,
+            winning_color,
+            cancelled,
+        })
+}
+
+struct Totals {
+    wagered: i128,
+    claimed: i128,
+    refunded: i128,
+}
+
+fn play(
+    env: &Env,
+    client: &ColorPredictionClient,
+    players: &[Address],
+    game_id: u64,
+    game: &Game,
+    totals: &mut Totals,
+) {
+    for bet in &game.bets {
+        match *bet {
+            Bet::Single {
+                player,
+                color,
+                wager,
+            } => {
+                if let Ok(Ok(())) =
+                    client.try_place_prediction(&players[player], &color, &wager, &game_id)
+                {
+                    totals.wagered += wager;
+                }
+            }
+            Bet::Split {
+                player,
+                first,
+                second,
+                wager,
+            } => {
+                let splits = vec![env, (first, wager), (second, wager)];
+                if let Ok(Ok(())) =
+                    client.try_place_split_prediction(&players[player], &splits, &game_id)
+                {
+                    totals.wagered += 2 * wager;
+                }
+            }
+        }
+    }
+    if client.get_game(&game_id).is_none() {
+        return;
+    }
+
+    if game.cancelled {
+        client.cancel_game(&game_id);
+        for player in players {
+            if let Ok(Ok(amount)) = client.try_refund(player, &game_id) {
+                totals.refunded += amount;
+            }
+        }
+        return;
+    }
+
+    client.resolve_prediction(&game_id, &game.winning_color);
+    for player in players {
+        if let Ok(Ok(amount)) = client.try_claim(player, &game_id) {
+            totals.claimed += amount;
+        }
+    }
+    let settled = client.get_game(&game_id).unwrap();
+    if settled.winner_count > 0 {
+        // Every winner claimed, so the distributable pot is paid out exactly.
+        assert_eq!(settled.paid_out, settled.total_pot - settled.fee);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn escrow_balances_after_every_game(
+        house_edge_bps in 0u32..2_000,
+        games in prop::collection::vec(game(), 1..5),
+    ) {
+        let env = new_env();
+        let ledger_id = env.register(MockLedger, ());
+        let ledger = MockLedgerClient::new(&env, &ledger_id);
+        let contract_id = env.register(ColorPrediction, ());
+        let client = ColorPredictionClient::new(&env, &contract_id);
+        let fee_recipient = Address::generate(&env);
+        client.init(
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &ledger_id,
+            &house_edge_bps,
+            &fee_recipient,
+            &1,
+            &10_000,
+            &COLORS,
+        );
+
+        let players: Vec<Address> = (0..PLAYERS).map(|_| Address::generate(&env)).collect();
+        for player in &players {
+            ledger.mint(player, &STARTING_BALANCE);
+        }
+
+        let mut totals = Totals { wagered: 0, claimed: 0, refunded: 0 };
+        for (game_id, game) in games.iter().enumerate() {
+            play(&env, &client, &players, game_id as u64, game, &mut totals);
+        }
+        let fees = match client.try_withdraw_fees() {
+            Ok(Ok(amount)) => amount,
+            _ => 0,
+        };
+
+        let jackpot = client.get_jackpot();
+        prop_assert_eq!(
+            totals.wagered,
+            totals.claimed + totals.refunded + fees + jackpot
+        );
+        prop_assert_eq!(ledger.balance_of(&contract_id), jackpot);
+        prop_assert_eq!(ledger.balance_of(&fee_recipient), fees);
+        let held: i128 = players.iter().map(|p| ledger.balance_of(p)).sum();
+        prop_assert_eq!(
+            held,
+            STARTING_BALANCE * PLAYERS as i128 - totals.wagered + totals.claimed + totals.refunded
+        );
+    }
+}
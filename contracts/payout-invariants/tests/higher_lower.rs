@@ -0,0 +1,113 @@
+//! Higher-lower: every unit the house gains or loses is a wager, a 2x
+//! payout or a cancellation refund, and open liabilities drain to zero.
+
+use proptest::prelude::*;
+use soroban_sdk::{testutils::Address as _, Address};
+use stellarcade_higher_lower::{Error, HigherLower, HigherLowerClient};
+use stellarcade_payout_invariants::{
+    new_env, MockLedger, MockLedgerClient, MockRng, MockRngClient,
+};
+
+const PLAYERS: usize = 4;
+const STARTING_BALANCE: i128 = 100_000;
+const HOUSE_BANKROLL: i128 = 50_000;
+
+#[derive(Clone, Debug)]
+struct Bet {
+    player: usize,
+    prediction: u32,
+    wager: i128,
+    /// RNG outcome, or `None` to cancel the bet before the result is ready.
+    outcome: Option<u32>,
+}
+
+fn bet() -> impl Strategy<Value = Bet> {
+    (
+        0..PLAYERS,
+        0u32..2,
+        1i128..5_000,
+        prop::option::weighted(0.85, 0u32..100),
+    )
+        .prop_map(|(player, prediction, wager, outcome)| Bet {
+            player,
+            prediction,
+            wager,
+            outcome,
+        })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn house_balance_matches_settlements(
+        exposure_bps in prop::option::of(1u32..=10_000),
+        bets in prop::collection::vec(bet(), 1..12),
+    ) {
+        let env = new_env();
+        let ledger_id = env.register(MockLedger, ());
+        let ledger = MockLedgerClient::new(&env, &ledger_id);
+        let rng_id = env.register(MockRng, ());
+        let rng = MockRngClient::new(&env, &rng_id);
+        let contract_id = env.register(HigherLower, ());
+        let client = HigherLowerClient::new(&env, &contract_id);
+        client.init(
+            &Address::generate(&env),
+            &rng_id,
+            &Address::generate(&env),
+            &ledger_id,
+        );
+        if let Some(bps) = exposure_bps {
+            client.set_exposure_limit(&bps);
+        }
+
+        let players: Vec<Address> = (0..PLAYERS).map(|_| Address::generate(&env)).collect();
+        for player in &players {
+            ledger.mint(player, &STARTING_BALANCE);
+        }
+        ledger.mint(&contract_id, &HOUSE_BANKROLL);
+
+        let (mut wagered, mut paid, mut refunded) = (0i128, 0i128, 0i128);
+        let mut placed = Vec::new();
+        for (game_id, bet) in bets.iter().enumerate() {
+            let game_id = game_id as u64;
+            let player = &players[bet.player];
+            let max = client.get_max_allowed_wager(&bet.prediction);
+            let result = client.try_place_prediction(player, &bet.prediction, &bet.wager, &game_id);
+            if bet.wager > max {
+                // The view is exactly the limit the contract enforces.
+                prop_assert_eq!(result, Err(Ok(Error::ExposureLimitExceeded)));
+                continue;
+            }
+            prop_assert!(result.is_ok());
+            wagered += bet.wager;
+            placed.push((game_id, bet));
+        }
+
+        for (game_id, bet) in placed {
+            match bet.outcome {
+                Some(outcome) => {
+                    rng.set_result(&game_id, &outcome);
+                    client.resolve_game(&game_id);
+                    let game = client.get_game(&game_id).unwrap();
+                    prop_assert_eq!(game.payout, if game.win { 2 * bet.wager } else { 0 });
+                    paid += game.payout;
+                }
+                None => {
+                    refunded += client.cancel_game(&players[bet.player], &game_id);
+                }
+            }
+        }
+
+        prop_assert_eq!(client.get_open_liability(), 0);
+        prop_assert_eq!(
+            ledger.balance_of(&contract_id),
+            HOUSE_BANKROLL + wagered - paid - refunded
+        );
+        let held: i128 = players.iter().map(|p| ledger.balance_of(p)).sum();
+        prop_assert_eq!(
+            held + ledger.balance_of(&contract_id),
+            STARTING_BALANCE * PLAYERS as i128 + HOUSE_BANKROLL
+        );
+    }
+}
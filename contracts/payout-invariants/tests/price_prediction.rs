@@ -0,0 +1,126 @@
+//! Price-prediction: wagers in == payouts + fee bucket (fees and dust).
+
+use proptest::prelude::*;
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address,
+};
+use stellarcade_payout_invariants::{new_env, MockOracle, MockOracleClient};
+use stellarcade_price_prediction::{
+    PricePrediction, PricePredictionClient, FEE_MODE_POOL, FEE_MODE_WINNINGS,
+};
+
+const PLAYERS: usize = 5;
+const STARTING_BALANCE: i128 = 1_000_000;
+
+#[derive(Clone, Debug)]
+struct Bet {
+    player: usize,
+    direction: u32,
+    wager: i128,
+    /// Pay the winnings to this player instead of the bettor.
+    beneficiary: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct Round {
+    bets: Vec<Bet>,
+    /// Close price relative to an open price of 1_000 (flat rounds push).
+    price_move: i128,
+}
+
+fn bet() -> impl Strategy<Value = Bet> {
+    (
+        0..PLAYERS,
+        0u32..2,
+        1i128..5_000,
+        prop::option::weighted(0.2, 0..PLAYERS),
+    )
+        .prop_map(|(player, direction, wager, beneficiary)| Bet {
+            player,
+            direction,
+            wager,
+            beneficiary,
+        })
+}
+
+fn round() -> impl Strategy<Value = Round> {
+    (prop::collection::vec(bet(), 0..8), -2i128..=2)
+        .prop_map(|(bets, price_move)| Round { bets, price_move })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn escrow_balances_after_every_round(
+        house_edge_bps in 0i128..2_000,
+        winnings_mode in any::<bool>(),
+        rounds in prop::collection::vec(round(), 1..5),
+    ) {
+        let env = new_env();
+        let token_admin = Address::generate(&env);
+        let token_id = env.register_stellar_asset_contract_v2(token_admin).address();
+        let token = TokenClient::new(&env, &token_id);
+        let oracle_id = env.register(MockOracle, ());
+        let oracle = MockOracleClient::new(&env, &oracle_id);
+        let contract_id = env.register(PricePrediction, ());
+        let client = PricePredictionClient::new(&env, &contract_id);
+        let fee_mode = if winnings_mode { FEE_MODE_WINNINGS } else { FEE_MODE_POOL };
+        client.init(
+            &Address::generate(&env),
+            &oracle_id,
+            &token_id,
+            &1,
+            &10_000,
+            &house_edge_bps,
+            &fee_mode,
+        );
+
+        let players: Vec<Address> = (0..PLAYERS).map(|_| Address::generate(&env)).collect();
+        for player in &players {
+            StellarAssetClient::new(&env, &token_id).mint(player, &STARTING_BALANCE);
+        }
+        let asset = symbol_short!("XLM");
+
+        for (round_id, round) in rounds.iter().enumerate() {
+            let round_id = round_id as u64;
+            let open_time = round_id * 10_000;
+            env.ledger().set_timestamp(open_time);
+            oracle.set_price(&asset, &1_000);
+            client.open_market(&round_id, &asset, &(open_time + 100));
+
+            for bet in &round.bets {
+                let player = &players[bet.player];
+                let _ = match bet.beneficiary {
+                    Some(b) => client.try_place_prediction_for(
+                        player, &round_id, &bet.direction, &bet.wager, &players[b],
+                    ),
+                    None => client.try_place_prediction(
+                        player, &round_id, &bet.direction, &bet.wager,
+                    ),
+                };
+            }
+
+            env.ledger().set_timestamp(open_time + 100);
+            oracle.set_price(&asset, &(1_000 + round.price_move));
+            client.settle_round(&round_id);
+            for player in &players {
+                let _ = client.try_claim(player, &round_id);
+            }
+            client.close_claims(&round_id);
+
+            let settled = client.get_round(&round_id);
+            prop_assert_eq!(settled.paid_out + settled.dust, settled.net_pool);
+        }
+
+        // Players only ever trade with the contract, so what they lost in
+        // total is exactly what the contract holds, and all of it is fees.
+        let held: i128 = players.iter().map(|p| token.balance(p)).sum();
+        let lost = STARTING_BALANCE * PLAYERS as i128 - held;
+        prop_assert_eq!(token.balance(&contract_id), lost);
+        prop_assert_eq!(client.get_fee_bucket(), lost);
+    }
+}
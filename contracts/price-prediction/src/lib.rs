@@ -10,24 +10,81 @@
 //!    closing price, outcome determined, net pool calculated.
 //! 4. Winners call `claim` → proportional share of net pool transferred.
 //!
+//! ## Beneficiaries
+//! A bettor may name a beneficiary at bet time with `place_prediction_for`
+//! (e.g. a cold wallet or team treasury). `claim` still requires the
+//! bettor's auth, but winnings and push refunds are transferred to the
+//! beneficiary instead.
+//!
 //! ## Pari-Mutuel Settlement
 //! - Total pool = sum of all wagers from both sides.
-//! - House fee = total_pool × house_edge_bps / 10000.
+//! - House fee = fee_base × house_edge_bps / 10000, where the fee base is
+//!   chosen at init: the whole pool (`FEE_MODE_POOL`) or only the losing
+//!   side, i.e. the winners' gross winnings (`FEE_MODE_WINNINGS`).
 //! - Net pool = total_pool − fee.
 //! - Each winner receives: net_pool × (their_wager / total_winning_side).
+//! - Integer division leaves rounding dust (net pool minus the sum of paid
+//!   shares). Once every winner has claimed, or `CLAIM_WINDOW` after
+//!   settlement, anyone calls `close_claims(round_id)` to move that dust and
+//!   any unclaimed shares into the fee bucket, so the contract's token
+//!   balance reconciles exactly with its accounting.
+//! - A winner whose share rounds down to zero is paid `MIN_PAYOUT` from that
+//!   dust, from a floor reserve set aside out of the fee at settlement, or
+//!   from the fee bucket when neither has any to spare.
 //!
 //! ## Push Rules
 //! A round is a push (all bets refunded) when:
 //! - Close price equals open price (flat).
 //! - No bets were placed.
 //! - Only one side has bets (no opposing risk).
+//!
+//! ## Losing-Streak Rebates
+//! House fees collected at settlement accrue into a fee bucket. Streaks are
+//! counted in round-id order: `settle_loss` and `claim` walk the player's
+//! bets up to their first unsettled round, so the order in which a player
+//! claims cannot skip a win or push. After `rebate_streak` consecutive
+//! losses, `rebate_bps` of the amount lost over the streak is credited and
+//! can be withdrawn with `claim_rebate`, funded from the fee bucket. Any win
+//! or push resets the streak.
+//!
+//! ## Settlement Preview
+//! `preview_settlement(round_id)` reads the oracle now and returns the
+//! outcome, fee and net pool the round would settle with at this moment,
+//! without writing any state. It works before `close_time`, so dashboards
+//! can show the currently winning side of a live round.
+//!
+//! ## Result Attestations
+//! After settlement the admin (oracle operator) may attach a detached
+//! signature over the XDR-encoded `AttestationMessage` (round_id, open price,
+//! close price, outcome) with `attest_round`. The contract stores it without
+//! verification; third parties fetch it with `get_attestation` and check it
+//! off-chain against the operator's published key.
+//!
+//! ## Exposure Cap
+//! The admin may cap each player's exposure — the sum of their wagers in
+//! rounds that have not settled yet — with `set_exposure_cap`. A bet that
+//! would take the player over the cap fails with `ExposureCapExceeded`. A
+//! wager stops counting once its round settles, and the round is dropped
+//! from the player's open list when they claim or count the loss.
+//!
+//! ## Storage Health
+//! `get_storage_health` reports how many Round, Bet, Streak and Attestation
+//! entries the contract holds, with an estimate of the instance TTL. These
+//! entries are never deleted, so the counts only grow. Anyone may call
+//! `bump_all_config` to extend the instance (config) TTL.
 #![no_std]
 #![allow(unexpected_cfgs)]
+// `init` configures the contract in one atomic call; the lint also fires on
+// macro-generated client stubs.
+#![allow(clippy::too_many_arguments)]
 
 use soroban_sdk::{
     contract, contractclient, contracterror, contractevent, contractimpl, contracttype,
-    token::TokenClient, Address, Env, Symbol,
+    symbol_short, token::TokenClient, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec,
 };
+use stellarcade_shared::storage::{bump_instance, count_new_entry, storage_health};
+pub use stellarcade_shared::storage::{KeyFamilyCount, StorageHealth, INSTANCE_BUMP_LEDGERS};
+pub use stellarcade_shared::GameStatus;
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -43,6 +100,18 @@ pub const OUTCOME_UP: u32 = 0;
 pub const OUTCOME_DOWN: u32 = 1;
 pub const OUTCOME_FLAT: u32 = 2;
 
+/// House edge charged on the whole pool.
+pub const FEE_MODE_POOL: u32 = 0;
+/// House edge charged only on the winners' gross winnings (the losing side).
+pub const FEE_MODE_WINNINGS: u32 = 1;
+
+/// Payout floor for a winner whose proportional share rounds down to zero.
+pub const MIN_PAYOUT: i128 = 1;
+
+/// Seconds after settlement during which winners may claim before
+/// `close_claims` can sweep their unclaimed shares (30 days).
+pub const CLAIM_WINDOW: u64 = 2_592_000;
+
 // ---------------------------------------------------------------------------
 // External contract clients
 // ---------------------------------------------------------------------------
@@ -80,6 +149,15 @@ pub enum Error {
     Overflow            = 18,
     InvalidCloseTime    = 19,
     InvalidPrice        = 20,
+    NotALoss            = 21,
+    NoRebate            = 22,
+    InsufficientFees    = 23,
+    AlreadyAttested     = 24,
+    ClaimsOutstanding   = 25,
+    ClaimsAlreadyClosed = 26,
+    InvalidFeeMode      = 27,
+    ExposureCapExceeded = 28,
+    InvalidTransition   = 29,
 }
 
 // ---------------------------------------------------------------------------
@@ -102,8 +180,27 @@ pub enum DataKey {
     MinWager,
     MaxWager,
     HouseEdgeBps,
+    /// `FEE_MODE_POOL` or `FEE_MODE_WINNINGS`; unset means `FEE_MODE_POOL`.
+    FeeMode,
+    /// Consecutive losses required before a rebate is credited (0 = disabled).
+    RebateStreak,
+    /// Rebate in basis points of the amount lost over the streak.
+    RebateBps,
+    /// Accumulated house fees available to fund rebates.
+    FeeBucket,
     Round(u64),
     Bet(BetKey),
+    /// Per-player losing streak and pending rebate.
+    Streak(Address),
+    /// Operator signature over a settled round's result.
+    Attestation(u64),
+    /// Cap on a player's total wagers in unsettled rounds (0 = no cap).
+    ExposureCap,
+    /// Rounds a player has bet in that were unsettled when last checked.
+    OpenRounds(Address),
+    /// Rounds a player has bet in that their streak has not counted yet,
+    /// in round-id order.
+    StreakRounds(Address),
 }
 
 #[contracttype]
@@ -115,11 +212,28 @@ pub struct RoundData {
     pub close_time: u64,
     pub total_up: i128,
     pub total_down: i128,
-    pub settled: bool,
+    /// Number of bets on each side.
+    pub up_bets: u32,
+    pub down_bets: u32,
+    /// `Open` until `settle_round` moves the round to `Resolved`.
+    pub status: GameStatus,
     pub outcome: u32,
     pub is_push: bool,
     pub net_pool: i128,
     pub winning_total: i128,
+    /// Sum of winner shares paid out so far.
+    pub paid_out: i128,
+    /// Sum of winning wagers that have claimed.
+    pub claimed_wager: i128,
+    /// Rounding remainder and unclaimed shares routed to the fee bucket by
+    /// `close_claims`.
+    pub dust: i128,
+    pub claims_closed: bool,
+    /// Ledger time of settlement; claims may be closed `CLAIM_WINDOW` later.
+    pub settled_at: u64,
+    /// Fee held back at settlement for `MIN_PAYOUT` floors, one per winning
+    /// bet at most. What is left returns to the fee bucket at `close_claims`.
+    pub floor_reserve: i128,
 }
 
 #[contracttype]
@@ -128,6 +242,54 @@ pub struct BetData {
     pub direction: u32,
     pub wager: i128,
     pub claimed: bool,
+    /// Receives the claim payout instead of the bettor, if set.
+    pub beneficiary: Option<Address>,
+}
+
+/// A player's current losing streak and rebate balance.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreakData {
+    /// Consecutive settled losses since the last win, push, or rebate.
+    pub losses: u32,
+    /// Total wagered across the losses in the current streak.
+    pub lost_amount: i128,
+    /// Rebates credited and not yet claimed.
+    pub pending_rebate: i128,
+}
+
+/// The settled result an attestation signature covers. Operators sign the
+/// XDR encoding of this struct (see `get_attestation_message`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttestationMessage {
+    pub round_id: u64,
+    pub open_price: i128,
+    pub close_price: i128,
+    pub outcome: u32,
+}
+
+/// Result of settling a round at a given close price, as computed by
+/// `settle_round` and reported by `preview_settlement`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementPreview {
+    pub close_price: i128,
+    pub outcome: u32,
+    pub is_push: bool,
+    pub fee: i128,
+    pub net_pool: i128,
+    pub winning_total: i128,
+}
+
+/// A detached operator signature attached to a settled round.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundAttestation {
+    pub message: AttestationMessage,
+    pub signature: BytesN<64>,
+    /// Ledger sequence the attestation was recorded in.
+    pub ledger: u32,
 }
 
 // ---------------------------------------------------------------------------
@@ -169,9 +331,42 @@ pub struct Claimed {
     pub round_id: u64,
     #[topic]
     pub player: Address,
+    /// The bettor, or their beneficiary if one was named.
+    pub recipient: Address,
     pub payout: i128,
 }
 
+#[contractevent]
+pub struct ClaimsClosed {
+    #[topic]
+    pub round_id: u64,
+    pub paid_out: i128,
+    pub dust: i128,
+}
+
+#[contractevent]
+pub struct RoundAttested {
+    #[topic]
+    pub round_id: u64,
+    pub signature: BytesN<64>,
+}
+
+#[contractevent]
+pub struct RebateCredited {
+    #[topic]
+    pub player: Address,
+    pub losses: u32,
+    pub lost_amount: i128,
+    pub rebate: i128,
+}
+
+#[contractevent]
+pub struct RebateClaimed {
+    #[topic]
+    pub player: Address,
+    pub amount: i128,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -184,6 +379,8 @@ impl PricePrediction {
     /// Initialize the price prediction game.
     ///
     /// `house_edge_bps`: house edge in basis points (e.g., 500 = 5%).
+    /// `fee_mode`: `FEE_MODE_POOL` charges the edge on the whole pool,
+    /// `FEE_MODE_WINNINGS` only on the losing side the winners take.
     pub fn init(
         env: Env,
         admin: Address,
@@ -192,11 +389,15 @@ impl PricePrediction {
         min_wager: i128,
         max_wager: i128,
         house_edge_bps: i128,
+        fee_mode: u32,
     ) -> Result<(), Error> {
         if env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::AlreadyInitialized);
         }
         admin.require_auth();
+        if fee_mode != FEE_MODE_POOL && fee_mode != FEE_MODE_WINNINGS {
+            return Err(Error::InvalidFeeMode);
+        }
 
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::OracleContract, &oracle_contract);
@@ -204,9 +405,24 @@ impl PricePrediction {
         env.storage().instance().set(&DataKey::MinWager, &min_wager);
         env.storage().instance().set(&DataKey::MaxWager, &max_wager);
         env.storage().instance().set(&DataKey::HouseEdgeBps, &house_edge_bps);
+        env.storage().instance().set(&DataKey::FeeMode, &fee_mode);
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Extend the instance (config) TTL. Permissionless.
+    pub fn bump_all_config(env: Env) -> Result<(), Error> {
+        require_initialized(&env)?;
+        bump_instance(&env);
         Ok(())
     }
 
+    /// Report how many Round, Bet, Streak and Attestation entries the
+    /// contract holds, with the estimated instance TTL.
+    pub fn get_storage_health(env: Env) -> StorageHealth {
+        storage_health(&env, &[FAMILY_ROUND, FAMILY_BET, FAMILY_STREAK, FAMILY_ATTESTATION])
+    }
+
     /// Open a new prediction market round. Admin only.
     ///
     /// Queries the oracle for the current price of `asset` to set the
@@ -243,16 +459,25 @@ impl PricePrediction {
             close_time,
             total_up: 0,
             total_down: 0,
-            settled: false,
+            up_bets: 0,
+            down_bets: 0,
+            status: GameStatus::Open,
             outcome: 0,
             is_push: false,
             net_pool: 0,
             winning_total: 0,
+            paid_out: 0,
+            claimed_wager: 0,
+            dust: 0,
+            claims_closed: false,
+            settled_at: 0,
+            floor_reserve: 0,
         };
         env.storage().persistent().set(&round_key, &round);
         env.storage()
             .persistent()
             .extend_ttl(&round_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+        count_new_entry(&env, FAMILY_ROUND);
 
         MarketOpened { round_id, asset, open_price, close_time }.publish(&env);
         Ok(())
@@ -270,79 +495,23 @@ impl PricePrediction {
         direction: u32,
         wager: i128,
     ) -> Result<(), Error> {
-        require_initialized(&env)?;
-        player.require_auth();
-
-        if direction != DIRECTION_UP && direction != DIRECTION_DOWN {
-            return Err(Error::InvalidDirection);
-        }
-        if wager <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-
-        let min_wager: i128 = env.storage().instance().get(&DataKey::MinWager).unwrap();
-        let max_wager: i128 = env.storage().instance().get(&DataKey::MaxWager).unwrap();
-        if wager < min_wager {
-            return Err(Error::WagerTooLow);
-        }
-        if wager > max_wager {
-            return Err(Error::WagerTooHigh);
-        }
-
-        let round_key = DataKey::Round(round_id);
-        let mut round: RoundData = env
-            .storage()
-            .persistent()
-            .get(&round_key)
-            .ok_or(Error::RoundNotFound)?;
-
-        if round.settled {
-            return Err(Error::AlreadySettled);
-        }
-        if env.ledger().timestamp() >= round.close_time {
-            return Err(Error::RoundClosed);
-        }
-
-        let bet_key = DataKey::Bet(BetKey {
-            round_id,
-            player: player.clone(),
-        });
-        if env.storage().persistent().has(&bet_key) {
-            return Err(Error::BetAlreadyPlaced);
-        }
-
-        // Transfer tokens from player to contract
-        let token = get_token(&env);
-        TokenClient::new(&env, &token).transfer(
-            &player,
-            env.current_contract_address(),
-            &wager,
-        );
-
-        // Update round totals
-        if direction == DIRECTION_UP {
-            round.total_up = round.total_up.checked_add(wager).ok_or(Error::Overflow)?;
-        } else {
-            round.total_down = round.total_down.checked_add(wager).ok_or(Error::Overflow)?;
-        }
-        env.storage().persistent().set(&round_key, &round);
-        env.storage()
-            .persistent()
-            .extend_ttl(&round_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
-
-        // Store bet
-        let bet = BetData {
-            direction,
-            wager,
-            claimed: false,
-        };
-        env.storage().persistent().set(&bet_key, &bet);
-        env.storage()
-            .persistent()
-            .extend_ttl(&bet_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+        place_bet(&env, player, round_id, direction, wager, None)
+    }
 
-        PredictionPlaced { round_id, player, direction, wager }.publish(&env);
-        Ok(())
+    /// Place a prediction whose payout goes to `beneficiary`.
+    ///
+    /// Identical to `place_prediction`, except that `claim` transfers the
+    /// winnings or push refund to `beneficiary`. The bettor still authorizes
+    /// the claim.
+    pub fn place_prediction_for(
+        env: Env,
+        player: Address,
+        round_id: u64,
+        direction: u32,
+        wager: i128,
+        beneficiary: Address,
+    ) -> Result<(), Error> {
+        place_bet(&env, player, round_id, direction, wager, Some(beneficiary))
     }
 
     /// Settle a round after `close_time` has passed.
@@ -362,7 +531,7 @@ impl PricePrediction {
             .get(&round_key)
             .ok_or(Error::RoundNotFound)?;
 
-        if round.settled {
+        if round.status == GameStatus::Resolved {
             return Err(Error::AlreadySettled);
         }
         if env.ledger().timestamp() < round.close_time {
@@ -372,51 +541,47 @@ impl PricePrediction {
         // Get closing price from oracle
         let oracle_addr = get_oracle(&env);
         let close_price = OracleClient::new(&env, &oracle_addr).get_price(&round.asset);
-
-        let total_pool = round
-            .total_up
-            .checked_add(round.total_down)
-            .ok_or(Error::Overflow)?;
-
-        // Determine outcome
-        let outcome = if close_price > round.open_price {
-            OUTCOME_UP
-        } else if close_price < round.open_price {
-            OUTCOME_DOWN
-        } else {
-            OUTCOME_FLAT
-        };
-
-        // Push if: flat, no bets, or only one side has bets
-        let is_push = outcome == OUTCOME_FLAT
-            || total_pool == 0
-            || round.total_up == 0
-            || round.total_down == 0;
-
-        let (net_pool, winning_total) = if is_push {
-            (0i128, 0i128)
-        } else {
-            let house_edge_bps: i128 =
-                env.storage().instance().get(&DataKey::HouseEdgeBps).unwrap();
-            let fee = total_pool
-                .checked_mul(house_edge_bps)
-                .and_then(|v| v.checked_div(BASIS_POINTS_DIVISOR))
-                .ok_or(Error::Overflow)?;
-            let net = total_pool.checked_sub(fee).ok_or(Error::Overflow)?;
-            let wt = if outcome == OUTCOME_UP {
-                round.total_up
+        let SettlementPreview {
+            outcome,
+            is_push,
+            fee,
+            net_pool,
+            winning_total,
+            ..
+        } = compute_settlement(&env, &round, close_price)?;
+
+        // Shares only round down to zero when the net pool is smaller than
+        // the winning side. Hold back a floor per winning bet from the fee
+        // before it reaches the bucket, where rebates could spend it.
+        let floor_reserve = if !is_push && net_pool < winning_total {
+            let winning_bets = if outcome == OUTCOME_UP {
+                round.up_bets
             } else {
-                round.total_down
+                round.down_bets
             };
-            (net, wt)
+            let floors = MIN_PAYOUT
+                .checked_mul(winning_bets.into())
+                .ok_or(Error::Overflow)?;
+            fee.min(floors)
+        } else {
+            0
         };
+        let fee = fee - floor_reserve;
+        if fee > 0 {
+            let bucket = get_fee_bucket(&env)
+                .checked_add(fee)
+                .ok_or(Error::Overflow)?;
+            env.storage().instance().set(&DataKey::FeeBucket, &bucket);
+        }
 
         round.close_price = close_price;
-        round.settled = true;
+        set_status(&mut round, GameStatus::Resolved)?;
         round.outcome = outcome;
         round.is_push = is_push;
         round.net_pool = net_pool;
         round.winning_total = winning_total;
+        round.settled_at = env.ledger().timestamp();
+        round.floor_reserve = floor_reserve;
         env.storage().persistent().set(&round_key, &round);
         env.storage()
             .persistent()
@@ -426,23 +591,46 @@ impl PricePrediction {
         Ok(())
     }
 
+    /// Dry-run `settle_round` against the oracle's current price.
+    ///
+    /// Returns the outcome, fee and net pool the round would settle with if
+    /// settled now, without changing any state. Unlike `settle_round` it may
+    /// be called before `close_time`; bets placed later still change the
+    /// result. Fails with `AlreadySettled` once the round is settled.
+    pub fn preview_settlement(env: Env, round_id: u64) -> Result<SettlementPreview, Error> {
+        require_initialized(&env)?;
+
+        let round: RoundData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Round(round_id))
+            .ok_or(Error::RoundNotFound)?;
+        if round.status == GameStatus::Resolved {
+            return Err(Error::AlreadySettled);
+        }
+
+        let close_price = OracleClient::new(&env, &get_oracle(&env)).get_price(&round.asset);
+        compute_settlement(&env, &round, close_price)
+    }
+
     /// Claim winnings for a settled round. Winners receive their
     /// proportional share of the net pool. In a push round, all
     /// players receive a full refund of their wager.
     ///
-    /// Losers cannot claim (returns `NoPayout`).
+    /// Losers cannot claim (returns `NoPayout`), and winners cannot once
+    /// `close_claims` has swept the round (returns `ClaimsAlreadyClosed`).
     pub fn claim(env: Env, player: Address, round_id: u64) -> Result<(), Error> {
         require_initialized(&env)?;
         player.require_auth();
 
         let round_key = DataKey::Round(round_id);
-        let round: RoundData = env
+        let mut round: RoundData = env
             .storage()
             .persistent()
             .get(&round_key)
             .ok_or(Error::RoundNotFound)?;
 
-        if !round.settled {
+        if round.status != GameStatus::Resolved {
             return Err(Error::NotSettled);
         }
 
@@ -460,7 +648,11 @@ impl PricePrediction {
             return Err(Error::AlreadyClaimed);
         }
 
-        let payout = if round.is_push {
+        let is_winner = !round.is_push && bet.direction == round.outcome;
+        if is_winner && round.claims_closed {
+            return Err(Error::ClaimsAlreadyClosed);
+        }
+        let mut payout = if round.is_push {
             // Refund wager
             bet.wager
         } else if bet.direction == round.outcome {
@@ -474,6 +666,26 @@ impl PricePrediction {
             0i128
         };
 
+        // A tiny winning wager can round down to nothing. Pay it the floor
+        // from the round's spare dust, then from its floor reserve, or from
+        // the fee bucket (where dust ends up) when both are used up.
+        let mut from_fee_bucket = false;
+        if payout == 0 && is_winner && round.net_pool > 0 {
+            from_fee_bucket = !has_spare_dust(&round, bet.wager)?;
+            if from_fee_bucket && round.floor_reserve >= MIN_PAYOUT {
+                round.floor_reserve -= MIN_PAYOUT;
+            } else if from_fee_bucket {
+                let bucket = get_fee_bucket(&env);
+                if bucket < MIN_PAYOUT {
+                    return Err(Error::NoPayout);
+                }
+                env.storage()
+                    .instance()
+                    .set(&DataKey::FeeBucket, &(bucket - MIN_PAYOUT));
+            }
+            payout = MIN_PAYOUT;
+        }
+
         if payout == 0 {
             return Err(Error::NoPayout);
         }
@@ -485,17 +697,264 @@ impl PricePrediction {
             .persistent()
             .extend_ttl(&bet_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
 
+        if !round.is_push {
+            if !from_fee_bucket {
+                round.paid_out = round.paid_out.checked_add(payout).ok_or(Error::Overflow)?;
+            }
+            round.claimed_wager = round
+                .claimed_wager
+                .checked_add(bet.wager)
+                .ok_or(Error::Overflow)?;
+            env.storage().persistent().set(&round_key, &round);
+            env.storage()
+                .persistent()
+                .extend_ttl(&round_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+        }
+
+        advance_streak(&env, &player)?;
+        close_open_round(&env, &player, round_id);
+
+        let recipient = bet.beneficiary.unwrap_or(player.clone());
         let token = get_token(&env);
         TokenClient::new(&env, &token).transfer(
             &env.current_contract_address(),
-            &player,
+            &recipient,
             &payout,
         );
 
-        Claimed { round_id, player, payout }.publish(&env);
+        Claimed { round_id, player, recipient, payout }.publish(&env);
+        Ok(())
+    }
+
+    /// Close claims for a settled round and move its rounding dust
+    /// (`net_pool - paid_out`) and unused floor reserve into the fee bucket.
+    /// Anyone can call this once every winning bet has claimed, or
+    /// `CLAIM_WINDOW` after settlement, when the shares still unclaimed are
+    /// swept along with the dust. Push rounds have no dust.
+    pub fn close_claims(env: Env, round_id: u64) -> Result<i128, Error> {
+        require_initialized(&env)?;
+
+        let round_key = DataKey::Round(round_id);
+        let mut round: RoundData = env
+            .storage()
+            .persistent()
+            .get(&round_key)
+            .ok_or(Error::RoundNotFound)?;
+
+        if round.status != GameStatus::Resolved {
+            return Err(Error::NotSettled);
+        }
+        if round.claims_closed {
+            return Err(Error::ClaimsAlreadyClosed);
+        }
+        let window_open = env.ledger().timestamp() < round.settled_at.saturating_add(CLAIM_WINDOW);
+        if !round.is_push && round.claimed_wager < round.winning_total && window_open {
+            return Err(Error::ClaimsOutstanding);
+        }
+
+        let dust = round.net_pool.checked_sub(round.paid_out).ok_or(Error::Overflow)?;
+        let swept = dust
+            .checked_add(round.floor_reserve)
+            .ok_or(Error::Overflow)?;
+        if swept > 0 {
+            let bucket = get_fee_bucket(&env)
+                .checked_add(swept)
+                .ok_or(Error::Overflow)?;
+            env.storage().instance().set(&DataKey::FeeBucket, &bucket);
+        }
+
+        round.dust = dust;
+        round.floor_reserve = 0;
+        round.claims_closed = true;
+        env.storage().persistent().set(&round_key, &round);
+        env.storage()
+            .persistent()
+            .extend_ttl(&round_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+
+        ClaimsClosed { round_id, paid_out: round.paid_out, dust }.publish(&env);
+        Ok(dust)
+    }
+
+    /// Configure the losing-streak rebate program. Admin only.
+    ///
+    /// After `streak` consecutive settled losses a player is credited
+    /// `rebate_bps` of the amount lost over that streak. A `streak` of 0
+    /// disables the program.
+    pub fn set_rebate_config(env: Env, streak: u32, rebate_bps: i128) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env)?;
+
+        if !(0..=BASIS_POINTS_DIVISOR).contains(&rebate_bps) {
+            return Err(Error::InvalidAmount);
+        }
+
+        env.storage().instance().set(&DataKey::RebateStreak, &streak);
+        env.storage().instance().set(&DataKey::RebateBps, &rebate_bps);
         Ok(())
     }
 
+    /// Cap each player's total wagers across unsettled rounds. Admin only.
+    /// A `cap` of 0 removes the cap.
+    pub fn set_exposure_cap(env: Env, cap: i128) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env)?;
+
+        if cap < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        env.storage().instance().set(&DataKey::ExposureCap, &cap);
+        Ok(())
+    }
+
+    /// Mark a settled losing bet as processed and bring the player's losing
+    /// streak up to date.
+    ///
+    /// The streak is advanced in round-id order through every settled round
+    /// the player bet in, stopping at the first unsettled one, so a win or
+    /// push in an earlier round still breaks it even if it was never
+    /// claimed. Each round is counted once. Once the streak reaches the
+    /// configured length, the rebate is credited to the player's pending
+    /// balance and the streak starts over.
+    pub fn settle_loss(env: Env, player: Address, round_id: u64) -> Result<(), Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let round: RoundData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Round(round_id))
+            .ok_or(Error::RoundNotFound)?;
+
+        if round.status != GameStatus::Resolved {
+            return Err(Error::NotSettled);
+        }
+
+        let bet_key = DataKey::Bet(BetKey {
+            round_id,
+            player: player.clone(),
+        });
+        let mut bet: BetData = env
+            .storage()
+            .persistent()
+            .get(&bet_key)
+            .ok_or(Error::BetNotFound)?;
+
+        if bet.claimed {
+            return Err(Error::AlreadyClaimed);
+        }
+        if round.is_push || bet.direction == round.outcome {
+            return Err(Error::NotALoss);
+        }
+
+        bet.claimed = true;
+        env.storage().persistent().set(&bet_key, &bet);
+        env.storage()
+            .persistent()
+            .extend_ttl(&bet_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+        close_open_round(&env, &player, round_id);
+
+        advance_streak(&env, &player)?;
+        Ok(())
+    }
+
+    /// Withdraw all pending losing-streak rebates, funded from the fee bucket.
+    pub fn claim_rebate(env: Env, player: Address) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let mut streak = get_streak(&env, &player);
+        let amount = streak.pending_rebate;
+        if amount <= 0 {
+            return Err(Error::NoRebate);
+        }
+
+        let bucket = get_fee_bucket(&env);
+        if bucket < amount {
+            return Err(Error::InsufficientFees);
+        }
+
+        // State update before transfer (reentrancy-safe)
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeBucket, &(bucket - amount));
+        streak.pending_rebate = 0;
+        set_streak(&env, &player, &streak)?;
+
+        let token = get_token(&env);
+        TokenClient::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &player,
+            &amount,
+        );
+
+        RebateClaimed { player, amount }.publish(&env);
+        Ok(amount)
+    }
+
+    /// View a player's losing streak and pending rebate.
+    pub fn get_streak(env: Env, player: Address) -> StreakData {
+        get_streak(&env, &player)
+    }
+
+    /// View a player's total wagers in rounds that have not settled yet.
+    pub fn get_exposure(env: Env, player: Address) -> Result<i128, Error> {
+        Ok(open_exposure(&env, &player)?.1)
+    }
+
+    /// View the per-player exposure cap (0 = no cap).
+    pub fn get_exposure_cap(env: Env) -> i128 {
+        get_exposure_cap(&env)
+    }
+
+    /// View the fee mode chosen at init.
+    pub fn get_fee_mode(env: Env) -> u32 {
+        get_fee_mode(&env)
+    }
+
+    /// View the house fees accumulated and not yet spent on rebates.
+    pub fn get_fee_bucket(env: Env) -> i128 {
+        get_fee_bucket(&env)
+    }
+
+    /// Attach the operator's detached signature over a settled round's
+    /// result. Admin only. An attestation cannot be replaced once recorded.
+    pub fn attest_round(env: Env, round_id: u64, signature: BytesN<64>) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env)?;
+
+        let message = attestation_message(&env, round_id)?;
+        let key = DataKey::Attestation(round_id);
+        if env.storage().persistent().has(&key) {
+            return Err(Error::AlreadyAttested);
+        }
+
+        let attestation = RoundAttestation {
+            message,
+            signature: signature.clone(),
+            ledger: env.ledger().sequence(),
+        };
+        env.storage().persistent().set(&key, &attestation);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+        count_new_entry(&env, FAMILY_ATTESTATION);
+
+        RoundAttested { round_id, signature }.publish(&env);
+        Ok(())
+    }
+
+    /// View the attestation recorded for a round, if any.
+    pub fn get_attestation(env: Env, round_id: u64) -> Option<RoundAttestation> {
+        env.storage().persistent().get(&DataKey::Attestation(round_id))
+    }
+
+    /// The exact bytes an operator signs for a settled round: the XDR
+    /// encoding of its `AttestationMessage`.
+    pub fn get_attestation_message(env: Env, round_id: u64) -> Result<Bytes, Error> {
+        Ok(attestation_message(&env, round_id)?.to_xdr(&env))
+    }
+
     /// View a round's state.
     pub fn get_round(env: Env, round_id: u64) -> Result<RoundData, Error> {
         env.storage()
@@ -517,6 +976,177 @@ impl PricePrediction {
 // Internal helpers
 // ---------------------------------------------------------------------------
 
+/// Shared body of `place_prediction` and `place_prediction_for`.
+fn place_bet(
+    env: &Env,
+    player: Address,
+    round_id: u64,
+    direction: u32,
+    wager: i128,
+    beneficiary: Option<Address>,
+) -> Result<(), Error> {
+    require_initialized(env)?;
+    player.require_auth();
+
+    if direction != DIRECTION_UP && direction != DIRECTION_DOWN {
+        return Err(Error::InvalidDirection);
+    }
+    if wager <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+
+    let min_wager: i128 = env.storage().instance().get(&DataKey::MinWager).unwrap();
+    let max_wager: i128 = env.storage().instance().get(&DataKey::MaxWager).unwrap();
+    if wager < min_wager {
+        return Err(Error::WagerTooLow);
+    }
+    if wager > max_wager {
+        return Err(Error::WagerTooHigh);
+    }
+
+    let round_key = DataKey::Round(round_id);
+    let mut round: RoundData = env
+        .storage()
+        .persistent()
+        .get(&round_key)
+        .ok_or(Error::RoundNotFound)?;
+
+    if round.status == GameStatus::Resolved {
+        return Err(Error::AlreadySettled);
+    }
+    if env.ledger().timestamp() >= round.close_time {
+        return Err(Error::RoundClosed);
+    }
+
+    let bet_key = DataKey::Bet(BetKey {
+        round_id,
+        player: player.clone(),
+    });
+    if env.storage().persistent().has(&bet_key) {
+        return Err(Error::BetAlreadyPlaced);
+    }
+
+    let (mut open_rounds, exposure) = open_exposure(env, &player)?;
+    let exposure = exposure.checked_add(wager).ok_or(Error::Overflow)?;
+    let cap = get_exposure_cap(env);
+    if cap > 0 && exposure > cap {
+        return Err(Error::ExposureCapExceeded);
+    }
+
+    // Transfer tokens from player to contract
+    let token = get_token(env);
+    TokenClient::new(env, &token).transfer(
+        &player,
+        env.current_contract_address(),
+        &wager,
+    );
+
+    // Update round totals
+    if direction == DIRECTION_UP {
+        round.total_up = round.total_up.checked_add(wager).ok_or(Error::Overflow)?;
+        round.up_bets = round.up_bets.checked_add(1).ok_or(Error::Overflow)?;
+    } else {
+        round.total_down = round.total_down.checked_add(wager).ok_or(Error::Overflow)?;
+        round.down_bets = round.down_bets.checked_add(1).ok_or(Error::Overflow)?;
+    }
+    env.storage().persistent().set(&round_key, &round);
+    env.storage()
+        .persistent()
+        .extend_ttl(&round_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+
+    // Store bet
+    let bet = BetData {
+        direction,
+        wager,
+        claimed: false,
+        beneficiary,
+    };
+    env.storage().persistent().set(&bet_key, &bet);
+    env.storage()
+        .persistent()
+        .extend_ttl(&bet_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+    count_new_entry(env, FAMILY_BET);
+
+    open_rounds.push_back(round_id);
+    set_open_rounds(env, &player, &open_rounds);
+    track_streak_round(env, &player, round_id);
+
+    PredictionPlaced { round_id, player, direction, wager }.publish(env);
+    Ok(())
+}
+
+/// Outcome, fee and net pool of `round` closing at `close_price`. Shared body
+/// of `settle_round` and `preview_settlement`.
+fn compute_settlement(
+    env: &Env,
+    round: &RoundData,
+    close_price: i128,
+) -> Result<SettlementPreview, Error> {
+    let total_pool = round
+        .total_up
+        .checked_add(round.total_down)
+        .ok_or(Error::Overflow)?;
+
+    // Determine outcome
+    let outcome = if close_price > round.open_price {
+        OUTCOME_UP
+    } else if close_price < round.open_price {
+        OUTCOME_DOWN
+    } else {
+        OUTCOME_FLAT
+    };
+
+    // Push if: flat, no bets, or only one side has bets
+    let is_push =
+        outcome == OUTCOME_FLAT || total_pool == 0 || round.total_up == 0 || round.total_down == 0;
+
+    let (net_pool, winning_total, fee) = if is_push {
+        (0i128, 0i128, 0i128)
+    } else {
+        let house_edge_bps: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::HouseEdgeBps)
+            .unwrap();
+        let wt = if outcome == OUTCOME_UP {
+            round.total_up
+        } else {
+            round.total_down
+        };
+        // In winnings mode only the losing side is charged, so winners
+        // always get their own stake back in full.
+        let fee_base = if get_fee_mode(env) == FEE_MODE_WINNINGS {
+            total_pool.checked_sub(wt).ok_or(Error::Overflow)?
+        } else {
+            total_pool
+        };
+        let fee = fee_base
+            .checked_mul(house_edge_bps)
+            .and_then(|v| v.checked_div(BASIS_POINTS_DIVISOR))
+            .ok_or(Error::Overflow)?;
+        let net = total_pool.checked_sub(fee).ok_or(Error::Overflow)?;
+        (net, wt, fee)
+    };
+
+    Ok(SettlementPreview {
+        close_price,
+        outcome,
+        is_push,
+        fee,
+        net_pool,
+        winning_total,
+    })
+}
+
+/// Move `round` along the shared lifecycle, rejecting transitions it does
+/// not allow.
+fn set_status(round: &mut RoundData, next: GameStatus) -> Result<(), Error> {
+    round
+        .status
+        .transition(next)
+        .map_err(|_| Error::InvalidTransition)
+}
+
 fn require_initialized(env: &Env) -> Result<(), Error> {
     if !env.storage().instance().has(&DataKey::Admin) {
         return Err(Error::NotInitialized);
@@ -548,6 +1178,262 @@ fn get_oracle(env: &Env) -> Address {
         .expect("PricePrediction: oracle not set")
 }
 
+fn get_fee_bucket(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::FeeBucket)
+        .unwrap_or(0)
+}
+
+/// Whether the round's dust can fund `MIN_PAYOUT` for a claim of `wager`
+/// while still covering the shares of the winners yet to claim.
+fn has_spare_dust(round: &RoundData, wager: i128) -> Result<bool, Error> {
+    let outstanding = round
+        .winning_total
+        .checked_sub(round.claimed_wager)
+        .and_then(|v| v.checked_sub(wager))
+        .ok_or(Error::Overflow)?;
+    let reserved = round
+        .net_pool
+        .checked_mul(outstanding)
+        .and_then(|v| v.checked_div(round.winning_total))
+        .ok_or(Error::Overflow)?;
+    let spare = round
+        .net_pool
+        .checked_sub(round.paid_out)
+        .and_then(|v| v.checked_sub(reserved))
+        .ok_or(Error::Overflow)?;
+    Ok(spare >= MIN_PAYOUT)
+}
+
+fn get_fee_mode(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::FeeMode)
+        .unwrap_or(FEE_MODE_POOL)
+}
+
+fn get_exposure_cap(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ExposureCap)
+        .unwrap_or(0)
+}
+
+/// The player's still-unsettled rounds and the sum of their wagers in them.
+/// Rounds that have settled since the list was stored are dropped.
+fn open_exposure(env: &Env, player: &Address) -> Result<(Vec<u64>, i128), Error> {
+    let stored: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::OpenRounds(player.clone()))
+        .unwrap_or(Vec::new(env));
+
+    let mut open_rounds = Vec::new(env);
+    let mut exposure: i128 = 0;
+    for round_id in stored.iter() {
+        let round: Option<RoundData> = env.storage().persistent().get(&DataKey::Round(round_id));
+        let bet: Option<BetData> = env.storage().persistent().get(&DataKey::Bet(BetKey {
+            round_id,
+            player: player.clone(),
+        }));
+        if let (Some(round), Some(bet)) = (round, bet) {
+            if round.status != GameStatus::Resolved {
+                exposure = exposure.checked_add(bet.wager).ok_or(Error::Overflow)?;
+                open_rounds.push_back(round_id);
+            }
+        }
+    }
+    Ok((open_rounds, exposure))
+}
+
+fn set_open_rounds(env: &Env, player: &Address, open_rounds: &Vec<u64>) {
+    let key = DataKey::OpenRounds(player.clone());
+    if open_rounds.is_empty() {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, open_rounds);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+}
+
+/// Drop a round the player has claimed or counted as a loss from their
+/// open list.
+fn close_open_round(env: &Env, player: &Address, round_id: u64) {
+    let key = DataKey::OpenRounds(player.clone());
+    let Some(mut open_rounds) = env.storage().persistent().get::<_, Vec<u64>>(&key) else {
+        return;
+    };
+    if let Some(index) = open_rounds.first_index_of(round_id) {
+        open_rounds.remove(index);
+        set_open_rounds(env, player, &open_rounds);
+    }
+}
+
+/// Queue a round for the player's streak, keeping the queue in round-id
+/// order.
+fn track_streak_round(env: &Env, player: &Address, round_id: u64) {
+    let key = DataKey::StreakRounds(player.clone());
+    let mut rounds: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    let index = match rounds.binary_search(round_id) {
+        Ok(index) | Err(index) => index,
+    };
+    rounds.insert(index, round_id);
+    env.storage().persistent().set(&key, &rounds);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+}
+
+/// Count the player's queued rounds into their streak, lowest round id
+/// first, up to the first round that has not settled. A loss extends the
+/// streak and credits a rebate once it reaches the configured length; a win
+/// or push resets it.
+fn advance_streak(env: &Env, player: &Address) -> Result<(), Error> {
+    let key = DataKey::StreakRounds(player.clone());
+    let Some(mut rounds) = env.storage().persistent().get::<_, Vec<u64>>(&key) else {
+        return Ok(());
+    };
+
+    let threshold: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::RebateStreak)
+        .unwrap_or(0);
+    let rebate_bps: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKey::RebateBps)
+        .unwrap_or(0);
+
+    let before = get_streak(env, player);
+    let mut streak = before.clone();
+    while let Some(round_id) = rounds.first() {
+        let Some(round) = env
+            .storage()
+            .persistent()
+            .get::<_, RoundData>(&DataKey::Round(round_id))
+        else {
+            break;
+        };
+        if round.status != GameStatus::Resolved {
+            break;
+        }
+        rounds.pop_front();
+
+        let Some(bet) = env
+            .storage()
+            .persistent()
+            .get::<_, BetData>(&DataKey::Bet(BetKey {
+                round_id,
+                player: player.clone(),
+            }))
+        else {
+            continue;
+        };
+        if round.is_push || bet.direction == round.outcome {
+            streak.losses = 0;
+            streak.lost_amount = 0;
+            continue;
+        }
+
+        streak.losses = streak.losses.checked_add(1).ok_or(Error::Overflow)?;
+        streak.lost_amount = streak
+            .lost_amount
+            .checked_add(bet.wager)
+            .ok_or(Error::Overflow)?;
+        if threshold > 0 && streak.losses >= threshold {
+            let rebate = streak
+                .lost_amount
+                .checked_mul(rebate_bps)
+                .and_then(|v| v.checked_div(BASIS_POINTS_DIVISOR))
+                .ok_or(Error::Overflow)?;
+
+            RebateCredited {
+                player: player.clone(),
+                losses: streak.losses,
+                lost_amount: streak.lost_amount,
+                rebate,
+            }
+            .publish(env);
+
+            streak.pending_rebate = streak
+                .pending_rebate
+                .checked_add(rebate)
+                .ok_or(Error::Overflow)?;
+            streak.losses = 0;
+            streak.lost_amount = 0;
+        }
+    }
+
+    if rounds.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &rounds);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_BUMP_LEDGERS,
+            PERSISTENT_BUMP_LEDGERS,
+        );
+    }
+    if streak != before {
+        set_streak(env, player, &streak)?;
+    }
+    Ok(())
+}
+
+fn get_streak(env: &Env, player: &Address) -> StreakData {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Streak(player.clone()))
+        .unwrap_or(StreakData {
+            losses: 0,
+            lost_amount: 0,
+            pending_rebate: 0,
+        })
+}
+
+fn set_streak(env: &Env, player: &Address, streak: &StreakData) -> Result<(), Error> {
+    let key = DataKey::Streak(player.clone());
+    let is_new = !env.storage().persistent().has(&key);
+    env.storage().persistent().set(&key, streak);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+    if is_new {
+        count_new_entry(env, FAMILY_STREAK);
+    }
+    Ok(())
+}
+
+const FAMILY_ROUND: Symbol = symbol_short!("round");
+const FAMILY_BET: Symbol = symbol_short!("bet");
+const FAMILY_STREAK: Symbol = symbol_short!("streak");
+const FAMILY_ATTESTATION: Symbol = symbol_short!("attest");
+
+fn attestation_message(env: &Env, round_id: u64) -> Result<AttestationMessage, Error> {
+    let round: RoundData = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Round(round_id))
+        .ok_or(Error::RoundNotFound)?;
+    if round.status != GameStatus::Resolved {
+        return Err(Error::NotSettled);
+    }
+    Ok(AttestationMessage {
+        round_id,
+        open_price: round.open_price,
+        close_price: round.close_price,
+        outcome: round.outcome,
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -58,6 +58,10 @@ struct Setup<'a> {
 }
 
 fn setup(env: &Env) -> Setup<'_> {
+    setup_with_fee_mode(env, FEE_MODE_POOL)
+}
+
+fn setup_with_fee_mode(env: &Env, fee_mode: u32) -> Setup<'_> {
     let admin = Address::generate(env);
     let token_admin = Address::generate(env);
 
@@ -77,7 +81,15 @@ fn setup(env: &Env) -> Setup<'_> {
     oracle_client.set_price(&btc(env), &50_000);
 
     // Init: min=10, max=10000, house edge 500 bps (5%)
-    client.init(&admin, &oracle_id, &token_addr, &10i128, &10_000i128, &500i128);
+    client.init(
+        &admin,
+        &oracle_id,
+        &token_addr,
+        &10i128,
+        &10_000i128,
+        &500i128,
+        &fee_mode,
+    );
 
     // Fund contract for payouts
     token_sac.mint(&contract_id, &1_000_000i128);
@@ -111,7 +123,15 @@ fn test_init_rejects_reinit() {
 
     let oracle = Address::generate(&env);
     let tok = Address::generate(&env);
-    let result = s.client.try_init(&Address::generate(&env), &oracle, &tok, &10, &10000, &500);
+    let result = s.client.try_init(
+        &Address::generate(&env),
+        &oracle,
+        &tok,
+        &10,
+        &10000,
+        &500,
+        &0,
+    );
     assert!(result.is_err());
 }
 
@@ -130,7 +150,7 @@ fn test_open_market_happy_path() {
     let round = s.client.get_round(&1u64);
     assert_eq!(round.open_price, 50_000);
     assert_eq!(round.close_time, 2000);
-    assert!(!round.settled);
+    assert_eq!(round.status, GameStatus::Open);
     assert_eq!(round.total_up, 0);
     assert_eq!(round.total_down, 0);
 }
@@ -197,7 +217,8 @@ fn test_place_prediction_up() {
     s.token_sac.mint(&player, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player, &1u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&player, &1u64, &DIRECTION_UP, &100);
 
     let round = s.client.get_round(&1u64);
     assert_eq!(round.total_up, 100);
@@ -226,7 +247,8 @@ fn test_place_prediction_down() {
     s.token_sac.mint(&player, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player, &1u64, &DIRECTION_DOWN, &200);
+    s.client
+        .place_prediction(&player, &1u64, &DIRECTION_DOWN, &200);
 
     let round = s.client.get_round(&1u64);
     assert_eq!(round.total_up, 0);
@@ -265,7 +287,9 @@ fn test_place_prediction_wager_too_low() {
     s.token_sac.mint(&player, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    let result = s.client.try_place_prediction(&player, &1u64, &DIRECTION_UP, &5i128); // min=10
+    let result = s
+        .client
+        .try_place_prediction(&player, &1u64, &DIRECTION_UP, &5i128); // min=10
     assert!(result.is_err());
 }
 
@@ -279,7 +303,9 @@ fn test_place_prediction_wager_too_high() {
     s.token_sac.mint(&player, &50_000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    let result = s.client.try_place_prediction(&player, &1u64, &DIRECTION_UP, &10_001i128); // max=10000
+    let result = s
+        .client
+        .try_place_prediction(&player, &1u64, &DIRECTION_UP, &10_001i128); // max=10000
     assert!(result.is_err());
 }
 
@@ -291,7 +317,9 @@ fn test_place_prediction_zero_wager() {
 
     let player = Address::generate(&env);
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    let result = s.client.try_place_prediction(&player, &1u64, &DIRECTION_UP, &0i128);
+    let result = s
+        .client
+        .try_place_prediction(&player, &1u64, &DIRECTION_UP, &0i128);
     assert!(result.is_err());
 }
 
@@ -315,7 +343,9 @@ fn test_place_prediction_after_close_time_rejected() {
         li.timestamp = 3000;
     });
 
-    let result = s.client.try_place_prediction(&player, &1u64, &DIRECTION_UP, &100);
+    let result = s
+        .client
+        .try_place_prediction(&player, &1u64, &DIRECTION_UP, &100);
     assert!(result.is_err());
 }
 
@@ -333,9 +363,12 @@ fn test_place_prediction_duplicate_rejected() {
     s.token_sac.mint(&player, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player, &1u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&player, &1u64, &DIRECTION_UP, &100);
 
-    let result = s.client.try_place_prediction(&player, &1u64, &DIRECTION_DOWN, &200);
+    let result = s
+        .client
+        .try_place_prediction(&player, &1u64, &DIRECTION_DOWN, &200);
     assert!(result.is_err());
 }
 
@@ -352,7 +385,9 @@ fn test_place_prediction_round_not_found() {
     let player = Address::generate(&env);
     s.token_sac.mint(&player, &5000);
 
-    let result = s.client.try_place_prediction(&player, &99u64, &DIRECTION_UP, &100);
+    let result = s
+        .client
+        .try_place_prediction(&player, &99u64, &DIRECTION_UP, &100);
     assert!(result.is_err());
 }
 
@@ -372,8 +407,10 @@ fn test_settle_round_up_wins() {
     s.token_sac.mint(&player_b, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player_a, &1u64, &DIRECTION_UP, &300);
-    s.client.place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &500);
+    s.client
+        .place_prediction(&player_a, &1u64, &DIRECTION_UP, &300);
+    s.client
+        .place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &500);
 
     // Advance time and set higher price
     env.ledger().with_mut(|li| {
@@ -384,7 +421,7 @@ fn test_settle_round_up_wins() {
     s.client.settle_round(&1u64);
 
     let round = s.client.get_round(&1u64);
-    assert!(round.settled);
+    assert_eq!(round.status, GameStatus::Resolved);
     assert_eq!(round.outcome, OUTCOME_UP);
     assert!(!round.is_push);
     // Total pool = 800, fee = 800 * 500 / 10000 = 40, net = 760
@@ -408,8 +445,10 @@ fn test_settle_round_down_wins() {
     s.token_sac.mint(&player_b, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player_a, &1u64, &DIRECTION_UP, &400);
-    s.client.place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &600);
+    s.client
+        .place_prediction(&player_a, &1u64, &DIRECTION_UP, &400);
+    s.client
+        .place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &600);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -419,7 +458,7 @@ fn test_settle_round_down_wins() {
     s.client.settle_round(&1u64);
 
     let round = s.client.get_round(&1u64);
-    assert!(round.settled);
+    assert_eq!(round.status, GameStatus::Resolved);
     assert_eq!(round.outcome, OUTCOME_DOWN);
     assert!(!round.is_push);
     // Total pool = 1000, fee = 50, net = 950
@@ -443,8 +482,10 @@ fn test_settle_round_flat_push() {
     s.token_sac.mint(&player_b, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player_a, &1u64, &DIRECTION_UP, &100);
-    s.client.place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &200);
+    s.client
+        .place_prediction(&player_a, &1u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &200);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -455,7 +496,7 @@ fn test_settle_round_flat_push() {
     s.client.settle_round(&1u64);
 
     let round = s.client.get_round(&1u64);
-    assert!(round.settled);
+    assert_eq!(round.status, GameStatus::Resolved);
     assert_eq!(round.outcome, OUTCOME_FLAT);
     assert!(round.is_push);
     assert_eq!(round.net_pool, 0);
@@ -514,7 +555,8 @@ fn test_settle_round_one_side_only_push() {
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
     // Only UP bets, no DOWN bets
-    s.client.place_prediction(&player, &1u64, &DIRECTION_UP, &500);
+    s.client
+        .place_prediction(&player, &1u64, &DIRECTION_UP, &500);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -524,7 +566,7 @@ fn test_settle_round_one_side_only_push() {
     s.client.settle_round(&1u64);
 
     let round = s.client.get_round(&1u64);
-    assert!(round.settled);
+    assert_eq!(round.status, GameStatus::Resolved);
     assert!(round.is_push); // Push because only one side
 }
 
@@ -548,7 +590,7 @@ fn test_settle_round_no_bets_push() {
     s.client.settle_round(&1u64);
 
     let round = s.client.get_round(&1u64);
-    assert!(round.settled);
+    assert_eq!(round.status, GameStatus::Resolved);
     assert!(round.is_push);
 }
 
@@ -568,8 +610,10 @@ fn test_claim_winner() {
     s.token_sac.mint(&loser, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&winner, &1u64, &DIRECTION_UP, &300);
-    s.client.place_prediction(&loser, &1u64, &DIRECTION_DOWN, &700);
+    s.client
+        .place_prediction(&winner, &1u64, &DIRECTION_UP, &300);
+    s.client
+        .place_prediction(&loser, &1u64, &DIRECTION_DOWN, &700);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -600,7 +644,8 @@ fn test_claim_push_refund() {
     s.token_sac.mint(&player, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player, &1u64, &DIRECTION_UP, &400);
+    s.client
+        .place_prediction(&player, &1u64, &DIRECTION_UP, &400);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -630,8 +675,10 @@ fn test_claim_loser_rejected() {
     s.token_sac.mint(&loser, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&winner, &1u64, &DIRECTION_UP, &300);
-    s.client.place_prediction(&loser, &1u64, &DIRECTION_DOWN, &700);
+    s.client
+        .place_prediction(&winner, &1u64, &DIRECTION_UP, &300);
+    s.client
+        .place_prediction(&loser, &1u64, &DIRECTION_DOWN, &700);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -660,8 +707,10 @@ fn test_claim_double_claim_rejected() {
     s.token_sac.mint(&player_b, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player_a, &1u64, &DIRECTION_UP, &500);
-    s.client.place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &500);
+    s.client
+        .place_prediction(&player_a, &1u64, &DIRECTION_UP, &500);
+    s.client
+        .place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &500);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -689,7 +738,8 @@ fn test_claim_not_settled_rejected() {
     s.token_sac.mint(&player, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player, &1u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&player, &1u64, &DIRECTION_UP, &100);
 
     let result = s.client.try_claim(&player, &1u64);
     assert!(result.is_err()); // NotSettled
@@ -709,7 +759,8 @@ fn test_claim_bet_not_found() {
     s.token_sac.mint(&player, &5000);
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player, &1u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&player, &1u64, &DIRECTION_UP, &100);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -743,9 +794,12 @@ fn test_multiple_players_proportional_payout() {
     s.client.open_market(&1u64, &btc(&env), &2000u64);
 
     // Two UP bettors, one DOWN bettor
-    s.client.place_prediction(&player_a, &1u64, &DIRECTION_UP, &300);   // UP
-    s.client.place_prediction(&player_b, &1u64, &DIRECTION_UP, &200);   // UP
-    s.client.place_prediction(&player_c, &1u64, &DIRECTION_DOWN, &500); // DOWN
+    s.client
+        .place_prediction(&player_a, &1u64, &DIRECTION_UP, &300); // UP
+    s.client
+        .place_prediction(&player_b, &1u64, &DIRECTION_UP, &200); // UP
+    s.client
+        .place_prediction(&player_c, &1u64, &DIRECTION_DOWN, &500); // DOWN
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -762,8 +816,14 @@ fn test_multiple_players_proportional_payout() {
     s.client.claim(&player_a, &1u64);
     s.client.claim(&player_b, &1u64);
 
-    assert_eq!(tc(&env, &s.token_addr).balance(&player_a), 10_000 - 300 + 570);
-    assert_eq!(tc(&env, &s.token_addr).balance(&player_b), 10_000 - 200 + 380);
+    assert_eq!(
+        tc(&env, &s.token_addr).balance(&player_a),
+        10_000 - 300 + 570
+    );
+    assert_eq!(
+        tc(&env, &s.token_addr).balance(&player_b),
+        10_000 - 200 + 380
+    );
     // Player C lost, no claim — balance stays at 10_000 - 500
     assert_eq!(tc(&env, &s.token_addr).balance(&player_c), 9_500);
 }
@@ -783,12 +843,14 @@ fn test_full_lifecycle_two_rounds() {
 
     // Round 1: player bets UP, price goes up → wins
     s.client.open_market(&1u64, &btc(&env), &2000u64);
-    s.client.place_prediction(&player, &1u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&player, &1u64, &DIRECTION_UP, &100);
 
     // Need a second player on the other side for non-push
     let opponent = Address::generate(&env);
     s.token_sac.mint(&opponent, &10_000);
-    s.client.place_prediction(&opponent, &1u64, &DIRECTION_DOWN, &100);
+    s.client
+        .place_prediction(&opponent, &1u64, &DIRECTION_DOWN, &100);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -798,14 +860,16 @@ fn test_full_lifecycle_two_rounds() {
     s.client.claim(&player, &1u64);
 
     let r1 = s.client.get_round(&1u64);
-    assert!(r1.settled);
+    assert_eq!(r1.status, GameStatus::Resolved);
     assert_eq!(r1.outcome, OUTCOME_UP);
 
     // Round 2: player bets DOWN, price goes down → wins
     s.oracle_client.set_price(&btc(&env), &60_000); // new open price
     s.client.open_market(&2u64, &btc(&env), &5000u64);
-    s.client.place_prediction(&player, &2u64, &DIRECTION_DOWN, &100);
-    s.client.place_prediction(&opponent, &2u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&player, &2u64, &DIRECTION_DOWN, &100);
+    s.client
+        .place_prediction(&opponent, &2u64, &DIRECTION_UP, &100);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 6000;
@@ -815,7 +879,7 @@ fn test_full_lifecycle_two_rounds() {
     s.client.claim(&player, &2u64);
 
     let r2 = s.client.get_round(&2u64);
-    assert!(r2.settled);
+    assert_eq!(r2.status, GameStatus::Resolved);
     assert_eq!(r2.outcome, OUTCOME_DOWN);
 }
 
@@ -836,8 +900,10 @@ fn test_push_one_side_refund() {
 
     s.client.open_market(&1u64, &btc(&env), &2000u64);
     // Both bet DOWN, no UP bets
-    s.client.place_prediction(&player_a, &1u64, &DIRECTION_DOWN, &300);
-    s.client.place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &200);
+    s.client
+        .place_prediction(&player_a, &1u64, &DIRECTION_DOWN, &300);
+    s.client
+        .place_prediction(&player_b, &1u64, &DIRECTION_DOWN, &200);
 
     env.ledger().with_mut(|li| {
         li.timestamp = 3000;
@@ -891,6 +957,753 @@ fn test_place_prediction_on_settled_round_rejected() {
     s.oracle_client.set_price(&btc(&env), &55_000);
     s.client.settle_round(&1u64);
 
-    let result = s.client.try_place_prediction(&player, &1u64, &DIRECTION_UP, &100);
+    let result = s
+        .client
+        .try_place_prediction(&player, &1u64, &DIRECTION_UP, &100);
     assert!(result.is_err());
 }
+
+// -------------------------------------------------------------------
+// 31. Losing streak - rebate credited and claimed from fee bucket
+// -------------------------------------------------------------------
+
+/// Open `round_id`, have `player` bet `player_dir` and `opponent` bet the
+/// other side (100 each), then settle with the price moving UP.
+fn play_round_up(
+    env: &Env,
+    s: &Setup,
+    round_id: u64,
+    player: &Address,
+    player_dir: u32,
+    opponent: &Address,
+) {
+    let now = env.ledger().timestamp();
+    s.oracle_client.set_price(&btc(env), &50_000);
+    s.client.open_market(&round_id, &btc(env), &(now + 1000));
+    s.client
+        .place_prediction(player, &round_id, &player_dir, &100);
+    s.client
+        .place_prediction(opponent, &round_id, &(1 - player_dir), &100);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = now + 2000;
+    });
+    s.oracle_client.set_price(&btc(env), &55_000);
+    s.client.settle_round(&round_id);
+}
+
+#[test]
+fn test_losing_streak_rebate() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let player = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    s.token_sac.mint(&player, &5000);
+    s.token_sac.mint(&opponent, &5000);
+
+    // Rebate 10% of losses after 2 consecutive losses
+    s.client.set_rebate_config(&2, &1000);
+
+    play_round_up(&env, &s, 1, &player, DIRECTION_DOWN, &opponent);
+    s.client.settle_loss(&player, &1u64);
+    assert_eq!(s.client.get_streak(&player).losses, 1);
+    assert_eq!(s.client.get_streak(&player).pending_rebate, 0);
+
+    play_round_up(&env, &s, 2, &player, DIRECTION_DOWN, &opponent);
+    s.client.settle_loss(&player, &2u64);
+
+    // 10% of 200 lost; streak restarts
+    let streak = s.client.get_streak(&player);
+    assert_eq!(streak.losses, 0);
+    assert_eq!(streak.lost_amount, 0);
+    assert_eq!(streak.pending_rebate, 20);
+
+    // Two rounds × 5% of 200 = 20 in fees
+    assert_eq!(s.client.get_fee_bucket(), 20);
+    let claimed = s.client.claim_rebate(&player);
+    assert_eq!(claimed, 20);
+    assert_eq!(s.client.get_fee_bucket(), 0);
+    assert_eq!(tc(&env, &s.token_addr).balance(&player), 5000 - 200 + 20);
+
+    let result = s.client.try_claim_rebate(&player);
+    assert_eq!(result, Err(Ok(Error::NoRebate)));
+}
+
+// -------------------------------------------------------------------
+// 32. Losing streak - a win resets the streak
+// -------------------------------------------------------------------
+
+#[test]
+fn test_losing_streak_reset_on_win() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let player = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    s.token_sac.mint(&player, &5000);
+    s.token_sac.mint(&opponent, &5000);
+    s.client.set_rebate_config(&2, &1000);
+
+    play_round_up(&env, &s, 1, &player, DIRECTION_DOWN, &opponent);
+    s.client.settle_loss(&player, &1u64);
+
+    play_round_up(&env, &s, 2, &player, DIRECTION_UP, &opponent);
+    s.client.claim(&player, &2u64);
+    assert_eq!(s.client.get_streak(&player).losses, 0);
+
+    play_round_up(&env, &s, 3, &player, DIRECTION_DOWN, &opponent);
+    s.client.settle_loss(&player, &3u64);
+
+    let streak = s.client.get_streak(&player);
+    assert_eq!(streak.losses, 1);
+    assert_eq!(streak.pending_rebate, 0);
+}
+
+// -------------------------------------------------------------------
+// 33. Losing streak - winners and double counting rejected
+// -------------------------------------------------------------------
+
+#[test]
+fn test_settle_loss_rejections() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let player = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    s.token_sac.mint(&player, &5000);
+    s.token_sac.mint(&opponent, &5000);
+
+    play_round_up(&env, &s, 1, &player, DIRECTION_DOWN, &opponent);
+
+    let result = s.client.try_settle_loss(&opponent, &1u64);
+    assert_eq!(result, Err(Ok(Error::NotALoss)));
+
+    s.client.settle_loss(&player, &1u64);
+    let result = s.client.try_settle_loss(&player, &1u64);
+    assert_eq!(result, Err(Ok(Error::AlreadyClaimed)));
+}
+
+// -------------------------------------------------------------------
+// 34. Storage health - entry counts and instance bump
+// -------------------------------------------------------------------
+
+#[test]
+fn test_storage_health_and_bump() {
+    let env = Env::default();
+    env.ledger().set_sequence_number(50);
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let player = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    s.token_sac.mint(&player, &5000);
+    s.token_sac.mint(&opponent, &5000);
+
+    play_round_up(&env, &s, 1, &player, DIRECTION_DOWN, &opponent);
+    s.client.settle_loss(&player, &1u64);
+
+    let health = s.client.get_storage_health();
+    assert_eq!(health.last_bump_ledger, 50);
+    assert_eq!(health.families.get(0).unwrap().count, 1); // round
+    assert_eq!(health.families.get(1).unwrap().count, 2); // bets
+    assert_eq!(health.families.get(2).unwrap().count, 1); // streak
+
+    env.ledger().set_sequence_number(550);
+    assert_eq!(
+        s.client.get_storage_health().instance_ttl,
+        INSTANCE_BUMP_LEDGERS - 500
+    );
+
+    s.client.bump_all_config();
+    let health = s.client.get_storage_health();
+    assert_eq!(health.last_bump_ledger, 550);
+    assert_eq!(health.instance_ttl, INSTANCE_BUMP_LEDGERS);
+}
+
+// -------------------------------------------------------------------
+// 35. Attestation - stored after settlement, not replaceable
+// -------------------------------------------------------------------
+
+#[test]
+fn test_round_attestation() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let player = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    s.token_sac.mint(&player, &5000);
+    s.token_sac.mint(&opponent, &5000);
+
+    let signature = BytesN::from_array(&env, &[7u8; 64]);
+
+    s.oracle_client.set_price(&btc(&env), &50_000);
+    s.client.open_market(&1u64, &btc(&env), &2000u64);
+    let result = s.client.try_attest_round(&1u64, &signature);
+    assert_eq!(result, Err(Ok(Error::NotSettled)));
+
+    s.client
+        .place_prediction(&player, &1u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&opponent, &1u64, &DIRECTION_DOWN, &100);
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    s.oracle_client.set_price(&btc(&env), &55_000);
+    s.client.settle_round(&1u64);
+
+    assert_eq!(s.client.get_attestation(&1u64), None);
+    s.client.attest_round(&1u64, &signature);
+
+    let attestation = s.client.get_attestation(&1u64).unwrap();
+    assert_eq!(attestation.signature, signature);
+    assert_eq!(attestation.message.open_price, 50_000);
+    assert_eq!(attestation.message.close_price, 55_000);
+    assert_eq!(attestation.message.outcome, OUTCOME_UP);
+    assert_eq!(
+        s.client.get_attestation_message(&1u64),
+        attestation.message.to_xdr(&env)
+    );
+
+    let result = s.client.try_attest_round(&1u64, &signature);
+    assert_eq!(result, Err(Ok(Error::AlreadyAttested)));
+}
+
+// -------------------------------------------------------------------
+// 36. Rounding dust - routed to the fee bucket at close_claims
+// -------------------------------------------------------------------
+
+#[test]
+fn test_close_claims_routes_rounding_dust() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let winners = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let loser = Address::generate(&env);
+
+    s.oracle_client.set_price(&btc(&env), &50_000);
+    s.client.open_market(&1u64, &btc(&env), &2000u64);
+    for winner in winners.iter() {
+        s.token_sac.mint(winner, &5000);
+        s.client
+            .place_prediction(winner, &1u64, &DIRECTION_UP, &100);
+    }
+    s.token_sac.mint(&loser, &5000);
+    s.client
+        .place_prediction(&loser, &1u64, &DIRECTION_DOWN, &100);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    s.oracle_client.set_price(&btc(&env), &55_000);
+    s.client.settle_round(&1u64);
+
+    // Pool 400, fee 20, net 380; each winner gets 380 * 100 / 300 = 126.
+    assert_eq!(s.client.get_fee_bucket(), 20);
+    s.client.claim(&winners[0], &1u64);
+    s.client.claim(&winners[1], &1u64);
+    let result = s.client.try_close_claims(&1u64);
+    assert_eq!(result, Err(Ok(Error::ClaimsOutstanding)));
+
+    s.client.claim(&winners[2], &1u64);
+    assert_eq!(s.client.close_claims(&1u64), 2);
+
+    let round = s.client.get_round(&1u64);
+    assert_eq!(round.paid_out, 378);
+    assert_eq!(round.dust, 2);
+    assert!(round.claims_closed);
+    assert_eq!(s.client.get_fee_bucket(), 22);
+
+    let result = s.client.try_close_claims(&1u64);
+    assert_eq!(result, Err(Ok(Error::ClaimsAlreadyClosed)));
+}
+
+// -------------------------------------------------------------------
+// 37. Fee modes - whole pool vs. winning side only
+// -------------------------------------------------------------------
+
+#[test]
+fn test_fee_modes_settlement() {
+    // 300 UP vs 700 DOWN at 5%, price goes UP.
+    // Pool mode: fee = 1000 * 5% = 50, winner gets 950.
+    // Winnings mode: fee = 700 * 5% = 35, winner gets 965.
+    for (fee_mode, fee, payout) in [(FEE_MODE_POOL, 50, 950), (FEE_MODE_WINNINGS, 35, 965)] {
+        let env = Env::default();
+        let s = setup_with_fee_mode(&env, fee_mode);
+        env.mock_all_auths();
+        assert_eq!(s.client.get_fee_mode(), fee_mode);
+
+        let winner = Address::generate(&env);
+        let loser = Address::generate(&env);
+        s.token_sac.mint(&winner, &5000);
+        s.token_sac.mint(&loser, &5000);
+
+        let client = &s.client;
+        client.open_market(&1u64, &btc(&env), &2000u64);
+        client.place_prediction(&winner, &1u64, &DIRECTION_UP, &300);
+        client.place_prediction(&loser, &1u64, &DIRECTION_DOWN, &700);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3000;
+        });
+        s.oracle_client.set_price(&btc(&env), &55_000);
+        client.settle_round(&1u64);
+
+        assert_eq!(client.get_fee_bucket(), fee);
+        assert_eq!(client.get_round(&1u64).net_pool, payout);
+        client.claim(&winner, &1u64);
+        let token = tc(&env, &s.token_addr);
+        assert_eq!(token.balance(&winner), 5000 - 300 + payout);
+        assert_eq!(token.balance(&loser), 5000 - 700);
+    }
+}
+
+#[test]
+fn test_winnings_fee_mode_push_refunds_whole() {
+    let env = Env::default();
+    let s = setup_with_fee_mode(&env, FEE_MODE_WINNINGS);
+    env.mock_all_auths();
+
+    let p1 = Address::generate(&env);
+    let p2 = Address::generate(&env);
+    s.token_sac.mint(&p1, &5000);
+    s.token_sac.mint(&p2, &5000);
+
+    s.client.open_market(&1u64, &btc(&env), &2000u64);
+    s.client.place_prediction(&p1, &1u64, &DIRECTION_UP, &400);
+    s.client.place_prediction(&p2, &1u64, &DIRECTION_DOWN, &600);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    s.client.settle_round(&1u64);
+
+    assert!(s.client.get_round(&1u64).is_push);
+    assert_eq!(s.client.get_fee_bucket(), 0);
+    s.client.claim(&p1, &1u64);
+    s.client.claim(&p2, &1u64);
+    assert_eq!(tc(&env, &s.token_addr).balance(&p1), 5000);
+    assert_eq!(tc(&env, &s.token_addr).balance(&p2), 5000);
+}
+
+#[test]
+fn test_init_rejects_unknown_fee_mode() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = PricePredictionClient::new(&env, &env.register(PricePrediction, ()));
+
+    let result = client.try_init(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &10,
+        &10000,
+        &500,
+        &2,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidFeeMode)));
+}
+
+// -------------------------------------------------------------------
+// 38. Beneficiary - payout goes to the named address
+// -------------------------------------------------------------------
+
+#[test]
+fn test_claim_pays_beneficiary() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let bettor = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let loser = Address::generate(&env);
+    s.token_sac.mint(&bettor, &5000);
+    s.token_sac.mint(&loser, &5000);
+
+    s.oracle_client.set_price(&btc(&env), &50_000);
+    s.client.open_market(&1u64, &btc(&env), &2000u64);
+    s.client
+        .place_prediction_for(&bettor, &1u64, &DIRECTION_UP, &300, &treasury);
+    s.client
+        .place_prediction(&loser, &1u64, &DIRECTION_DOWN, &700);
+    assert_eq!(
+        s.client.get_bet(&1u64, &bettor).beneficiary,
+        Some(treasury.clone())
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    s.oracle_client.set_price(&btc(&env), &55_000);
+    s.client.settle_round(&1u64);
+
+    // The bettor authorizes the claim; the treasury receives the 950 payout.
+    s.client.claim(&bettor, &1u64);
+    assert_eq!(env.auths()[0].0, bettor);
+    let token = tc(&env, &s.token_addr);
+    assert_eq!(token.balance(&bettor), 5000 - 300);
+    assert_eq!(token.balance(&treasury), 950);
+    assert!(s.client.get_bet(&1u64, &bettor).claimed);
+}
+
+// -------------------------------------------------------------------
+// 39. Exposure cap - unsettled wagers across rounds are capped
+// -------------------------------------------------------------------
+
+#[test]
+fn test_exposure_cap_across_open_rounds() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let player = Address::generate(&env);
+    s.token_sac.mint(&player, &5000);
+
+    assert_eq!(s.client.try_set_exposure_cap(&-1), Err(Ok(Error::InvalidAmount)));
+    s.client.set_exposure_cap(&1000);
+    assert_eq!(s.client.get_exposure_cap(), 1000);
+
+    s.client.open_market(&1u64, &btc(&env), &2000u64);
+    s.client.open_market(&2u64, &btc(&env), &5000u64);
+    s.client.open_market(&3u64, &btc(&env), &5000u64);
+    s.client.place_prediction(&player, &1u64, &DIRECTION_UP, &600);
+    s.client.place_prediction(&player, &2u64, &DIRECTION_UP, &400);
+    assert_eq!(s.client.get_exposure(&player), 1000);
+
+    let result = s.client.try_place_prediction(&player, &3u64, &DIRECTION_UP, &10);
+    assert_eq!(result, Err(Ok(Error::ExposureCapExceeded)));
+
+    // Settling round 1 releases its wager, even before the refund is claimed.
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    s.client.settle_round(&1u64);
+    assert_eq!(s.client.get_exposure(&player), 400);
+    s.client.place_prediction(&player, &3u64, &DIRECTION_UP, &600);
+    assert_eq!(s.client.get_exposure(&player), 1000);
+
+    s.client.claim(&player, &1u64);
+    assert_eq!(s.client.get_exposure(&player), 1000);
+
+    // Removing the cap lifts the limit.
+    s.client.set_exposure_cap(&0);
+    s.client.open_market(&4u64, &btc(&env), &5000u64);
+    s.client.place_prediction(&player, &4u64, &DIRECTION_DOWN, &500);
+    assert_eq!(s.client.get_exposure(&player), 1500);
+}
+
+// -------------------------------------------------------------------
+// 40. Settlement preview - live outcome without state changes
+// -------------------------------------------------------------------
+
+#[test]
+fn test_preview_settlement_matches_settle() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let up = Address::generate(&env);
+    let down = Address::generate(&env);
+    s.token_sac.mint(&up, &5000);
+    s.token_sac.mint(&down, &5000);
+
+    s.client.open_market(&1u64, &btc(&env), &2000u64);
+    s.client.place_prediction(&up, &1u64, &DIRECTION_UP, &600);
+    s.client
+        .place_prediction(&down, &1u64, &DIRECTION_DOWN, &400);
+
+    // Before close, with the price below open, DOWN is currently winning.
+    s.oracle_client.set_price(&btc(&env), &49_000);
+    let preview = s.client.preview_settlement(&1u64);
+    assert_eq!(preview.outcome, OUTCOME_DOWN);
+    assert!(!preview.is_push);
+    assert_eq!(preview.fee, 50);
+    assert_eq!(preview.net_pool, 950);
+    assert_eq!(preview.winning_total, 400);
+    assert_eq!(s.client.get_round(&1u64).status, GameStatus::Open);
+
+    // The preview tracks the oracle and matches the eventual settlement.
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    s.oracle_client.set_price(&btc(&env), &51_000);
+    let preview = s.client.preview_settlement(&1u64);
+    assert_eq!(preview.outcome, OUTCOME_UP);
+    assert_eq!(preview.winning_total, 600);
+
+    s.client.settle_round(&1u64);
+    let round = s.client.get_round(&1u64);
+    assert_eq!(round.outcome, preview.outcome);
+    assert_eq!(round.net_pool, preview.net_pool);
+    assert_eq!(round.close_price, preview.close_price);
+    assert_eq!(s.client.get_fee_bucket(), preview.fee);
+    assert_eq!(
+        s.client.try_preview_settlement(&1u64),
+        Err(Ok(Error::AlreadySettled))
+    );
+}
+
+// -------------------------------------------------------------------
+// 41. Payout floor - winners whose share rounds to zero still get paid
+// -------------------------------------------------------------------
+
+#[test]
+fn test_min_payout_floor_for_tiny_winning_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token_addr, token_sac) = create_token(&env, &token_admin);
+    let oracle_id = env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&env, &oracle_id);
+    let contract_id = env.register(PricePrediction, ());
+    let client = PricePredictionClient::new(&env, &contract_id);
+
+    oracle_client.set_price(&btc(&env), &50_000);
+    // 99.9% edge and a 1-unit minimum make zero shares reachable.
+    client.init(
+        &Address::generate(&env),
+        &oracle_id,
+        &token_addr,
+        &1i128,
+        &100_000i128,
+        &9_990i128,
+        &FEE_MODE_POOL,
+    );
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let whale = Address::generate(&env);
+    let minnow_a = Address::generate(&env);
+    let minnow_b = Address::generate(&env);
+    let down = Address::generate(&env);
+    for (player, wager) in [
+        (&whale, 9_999i128),
+        (&minnow_a, 1),
+        (&minnow_b, 1),
+        (&down, 10_000),
+    ] {
+        token_sac.mint(player, &wager);
+    }
+
+    client.open_market(&1u64, &btc(&env), &2000u64);
+    client.place_prediction(&whale, &1u64, &DIRECTION_UP, &9_999);
+    client.place_prediction(&minnow_a, &1u64, &DIRECTION_UP, &1);
+    client.place_prediction(&minnow_b, &1u64, &DIRECTION_UP, &1);
+    client.place_prediction(&down, &1u64, &DIRECTION_DOWN, &10_000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    oracle_client.set_price(&btc(&env), &55_000);
+    client.settle_round(&1u64);
+
+    // Pool 20_001, fee 19_980, net 21 over 10_001 winning: the whale's share
+    // is 20, each minnow's rounds to 0, and 1 unit is left as dust.
+    // A floor per winning bet is held back from the fee.
+    let round = client.get_round(&1u64);
+    assert_eq!(round.net_pool, 21);
+    assert_eq!(round.floor_reserve, 3);
+    assert_eq!(client.get_fee_bucket(), 19_977);
+
+    // The first minnow's floor comes out of the round's dust.
+    client.claim(&minnow_a, &1u64);
+    assert_eq!(client.get_round(&1u64).paid_out, 1);
+
+    // The dust left is owed to the whale, so the next floor is taken from
+    // the floor reserve instead.
+    client.claim(&minnow_b, &1u64);
+    let round = client.get_round(&1u64);
+    assert_eq!(round.paid_out, 1);
+    assert_eq!(round.floor_reserve, 2);
+    assert_eq!(client.get_fee_bucket(), 19_977);
+
+    // The whale still receives its full share.
+    client.claim(&whale, &1u64);
+    let token = tc(&env, &token_addr);
+    assert_eq!(token.balance(&minnow_a), 1);
+    assert_eq!(token.balance(&minnow_b), 1);
+    assert_eq!(token.balance(&whale), 20);
+    assert_eq!(client.close_claims(&1u64), 0);
+    assert_eq!(client.get_fee_bucket(), 19_979);
+    assert_eq!(client.get_round(&1u64).floor_reserve, 0);
+    assert_eq!(client.try_claim(&down, &1u64), Err(Ok(Error::NoPayout)));
+}
+
+// -------------------------------------------------------------------
+// 42. Losing streak - counted in round order, not claim order
+// -------------------------------------------------------------------
+
+#[test]
+fn test_losing_streak_follows_round_order() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let player = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    s.token_sac.mint(&player, &5000);
+    s.token_sac.mint(&opponent, &5000);
+    s.client.set_rebate_config(&2, &1000);
+
+    // Loss, unclaimed win, loss.
+    play_round_up(&env, &s, 1, &player, DIRECTION_DOWN, &opponent);
+    play_round_up(&env, &s, 2, &player, DIRECTION_UP, &opponent);
+    play_round_up(&env, &s, 3, &player, DIRECTION_DOWN, &opponent);
+
+    // Settling the losses without claiming the win between them still
+    // breaks the streak at round 2.
+    s.client.settle_loss(&player, &3u64);
+    s.client.settle_loss(&player, &1u64);
+    let streak = s.client.get_streak(&player);
+    assert_eq!(streak.losses, 1);
+    assert_eq!(streak.lost_amount, 100);
+    assert_eq!(streak.pending_rebate, 0);
+
+    // Rounds 3 and 4 make the streak; claiming the win late changes nothing.
+    play_round_up(&env, &s, 4, &player, DIRECTION_DOWN, &opponent);
+    s.client.settle_loss(&player, &4u64);
+    s.client.claim(&player, &2u64);
+    let streak = s.client.get_streak(&player);
+    assert_eq!(streak.losses, 0);
+    assert_eq!(streak.pending_rebate, 20);
+}
+
+// -------------------------------------------------------------------
+// 43. Claim window - unclaimed shares are swept once it has passed
+// -------------------------------------------------------------------
+
+#[test]
+fn test_close_claims_sweeps_unclaimed_shares_after_claim_window() {
+    let env = Env::default();
+    let s = setup(&env);
+    env.mock_all_auths();
+
+    let winner = Address::generate(&env);
+    let absent = Address::generate(&env);
+    let loser = Address::generate(&env);
+    for player in [&winner, &absent, &loser] {
+        s.token_sac.mint(player, &5000);
+    }
+
+    s.oracle_client.set_price(&btc(&env), &50_000);
+    s.client.open_market(&1u64, &btc(&env), &2000u64);
+    s.client
+        .place_prediction(&winner, &1u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&absent, &1u64, &DIRECTION_UP, &100);
+    s.client
+        .place_prediction(&loser, &1u64, &DIRECTION_DOWN, &100);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    s.oracle_client.set_price(&btc(&env), &55_000);
+    s.client.settle_round(&1u64);
+
+    // Pool 300, fee 15, net 285; each winner's share is 142.
+    s.client.claim(&winner, &1u64);
+    let result = s.client.try_close_claims(&1u64);
+    assert_eq!(result, Err(Ok(Error::ClaimsOutstanding)));
+
+    // One winner never claims; once the window has passed their share is
+    // swept along with the rounding dust.
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000 + CLAIM_WINDOW;
+    });
+    assert_eq!(s.client.close_claims(&1u64), 143);
+
+    let round = s.client.get_round(&1u64);
+    assert_eq!(round.paid_out, 142);
+    assert_eq!(round.paid_out + round.dust, round.net_pool);
+    assert_eq!(s.client.get_fee_bucket(), 158);
+
+    let result = s.client.try_claim(&absent, &1u64);
+    assert_eq!(result, Err(Ok(Error::ClaimsAlreadyClosed)));
+}
+
+// -------------------------------------------------------------------
+// 44. Floor reserve - floors are paid even with an empty fee bucket
+// -------------------------------------------------------------------
+
+#[test]
+fn test_floor_reserve_pays_floors_with_empty_fee_bucket() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let (token_addr, token_sac) = create_token(&env, &token_admin);
+    let oracle_id = env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&env, &oracle_id);
+    let contract_id = env.register(PricePrediction, ());
+    let client = PricePredictionClient::new(&env, &contract_id);
+
+    oracle_client.set_price(&btc(&env), &50_000);
+    // A 50% edge on 1-unit wagers leaves a net pool smaller than the
+    // winning side, so every winning share rounds to zero.
+    client.init(
+        &Address::generate(&env),
+        &oracle_id,
+        &token_addr,
+        &1i128,
+        &100_000i128,
+        &5_000i128,
+        &FEE_MODE_POOL,
+    );
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let winners = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let down = Address::generate(&env);
+    client.open_market(&1u64, &btc(&env), &2000u64);
+    for player in winners.iter() {
+        token_sac.mint(player, &1);
+        client.place_prediction(player, &1u64, &DIRECTION_UP, &1);
+    }
+    token_sac.mint(&down, &1);
+    client.place_prediction(&down, &1u64, &DIRECTION_DOWN, &1);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 3000;
+    });
+    oracle_client.set_price(&btc(&env), &55_000);
+    client.settle_round(&1u64);
+
+    // Pool 4, fee 2, net 2 over 3 winning. The whole fee is held back as
+    // floors, leaving the bucket empty.
+    let round = client.get_round(&1u64);
+    assert_eq!(round.net_pool, 2);
+    assert_eq!(round.floor_reserve, 2);
+    assert_eq!(client.get_fee_bucket(), 0);
+
+    // Two floors come out of the dust, the third out of the reserve.
+    for player in winners.iter() {
+        client.claim(player, &1u64);
+    }
+    let token = tc(&env, &token_addr);
+    for player in winners.iter() {
+        assert_eq!(token.balance(player), 1);
+    }
+    let round = client.get_round(&1u64);
+    assert_eq!(round.paid_out, 2);
+    assert_eq!(round.floor_reserve, 1);
+
+    assert_eq!(client.close_claims(&1u64), 0);
+    assert_eq!(client.get_fee_bucket(), 1);
+    assert_eq!(token.balance(&contract_id), 1);
+}
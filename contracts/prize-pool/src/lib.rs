@@ -11,10 +11,21 @@
 //!   Each is a separate ledger entry with its own TTL, bumped on every write,
 //!   so cost does not scale with total contract state.
 //!
+//! ## Insurance Reserve
+//! Each `reserve` call moves an extra `insurance_bps` of the reserved amount
+//! from `available` into a segregated insurance reserve. When a game contract
+//! on the reporter allowlist reports a shortfall with `report_loss`, the
+//! admin either covers it from the reserve with `approve_loss` or rejects it.
+//! The admin can also move funds between `available` and the reserve with
+//! `top_up_insurance` and `withdraw_insurance`.
+//! Every loss event is stored with its outcome, and the reserve keeps
+//! running totals of premiums collected and losses covered.
+//!
 //! ## Invariant
-//! `available + total_reserved == token.balance(contract_address)` at all
-//! times, assuming all token inflows go through `fund`. Any direct transfer
-//! to the contract address bypassing `fund` breaks this invariant.
+//! `available + total_reserved + insurance_reserve ==
+//! token.balance(contract_address)` at all times, assuming all token inflows
+//! go through `fund`. Any direct transfer to the contract address bypassing
+//! `fund` breaks this invariant.
 #![no_std]
 #![allow(unexpected_cfgs)]
 
@@ -31,6 +42,9 @@ use soroban_sdk::{
 /// Bumped on every write so active game data never expires mid-round.
 pub const PERSISTENT_BUMP_LEDGERS: u32 = 518_400;
 
+/// Basis-points divisor (10 000 = 100%).
+const BASIS_POINTS: i128 = 10_000;
+
 // ---------------------------------------------------------------------------
 // Error Types
 // ---------------------------------------------------------------------------
@@ -48,6 +62,8 @@ pub enum Error {
     ReservationNotFound     = 7,
     PayoutExceedsReservation = 8,
     Overflow                = 9,
+    LossNotFound            = 10,
+    LossAlreadySettled      = 11,
 }
 
 // ---------------------------------------------------------------------------
@@ -71,6 +87,20 @@ pub enum DataKey {
     TotalReserved,
     /// Per-game reservation keyed by game_id.
     Reservation(u64),
+    /// Share of each reservation paid into the insurance reserve, in bps.
+    InsuranceBps,
+    /// Running insurance reserve, segregated from `Available`.
+    InsuranceReserve,
+    /// Lifetime insurance premiums collected.
+    PremiumsCollected,
+    /// Lifetime losses covered from the insurance reserve.
+    LossesCovered,
+    /// Number of loss events reported; the next loss id.
+    LossCount,
+    /// Loss event keyed by loss_id.
+    Loss(u32),
+    /// Whether an address may report losses.
+    LossReporter(Address),
 }
 
 /// Per-game reservation record.
@@ -84,6 +114,40 @@ pub struct ReservationData {
     pub remaining: i128,
 }
 
+/// Lifecycle of a reported loss.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LossStatus {
+    Pending = 0,
+    Covered = 1,
+    Rejected = 2,
+}
+
+/// A shortfall reported by a game contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LossEvent {
+    pub reporter: Address,
+    pub game_id: u64,
+    /// Shortfall claimed by the reporter.
+    pub amount: i128,
+    pub status: LossStatus,
+    /// Recipient of the covered amount; the reporter until approved.
+    pub recipient: Address,
+    pub reported_at: u64,
+}
+
+/// Snapshot of the insurance reserve returned by `get_insurance_state`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceState {
+    pub reserve: i128,
+    pub bps: u32,
+    pub premiums_collected: i128,
+    pub losses_covered: i128,
+    pub loss_count: u32,
+}
+
 /// Snapshot of the pool's accounting state returned by `get_pool_state`.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -119,6 +183,49 @@ pub struct Released {
     pub amount: i128,
 }
 
+#[contractevent]
+pub struct InsuranceFunded {
+    #[topic]
+    pub game_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct InsuranceToppedUp {
+    pub amount: i128,
+    pub reserve: i128,
+}
+
+#[contractevent]
+pub struct InsuranceWithdrawn {
+    pub amount: i128,
+    pub reserve: i128,
+}
+
+#[contractevent]
+pub struct LossReported {
+    #[topic]
+    pub loss_id: u32,
+    #[topic]
+    pub game_id: u64,
+    pub reporter: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct LossCovered {
+    #[topic]
+    pub loss_id: u32,
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct LossRejected {
+    #[topic]
+    pub loss_id: u32,
+}
+
 #[contractevent]
 pub struct PaidOut {
     #[topic]
@@ -204,6 +311,10 @@ impl PrizePool {
     /// Calling reserve with a `game_id` that already has a reservation returns
     /// `GameAlreadyReserved` — this is the idempotency guard preventing a
     /// buggy game contract from double-drawing from the pool.
+    ///
+    /// An insurance premium of `amount * insurance_bps / 10_000` is also
+    /// moved from `available` into the insurance reserve, so `available`
+    /// must cover both.
     pub fn reserve(
         env: Env,
         admin: Address,
@@ -222,14 +333,33 @@ impl PrizePool {
             return Err(Error::GameAlreadyReserved);
         }
 
+        let premium = amount
+            .checked_mul(get_insurance_bps(&env) as i128)
+            .ok_or(Error::Overflow)?
+            / BASIS_POINTS;
+        let required = amount.checked_add(premium).ok_or(Error::Overflow)?;
+
         let available = get_available(&env);
-        if amount > available {
+        if required > available {
             return Err(Error::InsufficientFunds);
         }
 
-        let new_available = available.checked_sub(amount).ok_or(Error::Overflow)?;
+        let new_available = available.checked_sub(required).ok_or(Error::Overflow)?;
         set_persistent_i128(&env, DataKey::Available, new_available);
 
+        if premium > 0 {
+            let reserve = get_i128(&env, DataKey::InsuranceReserve)
+                .checked_add(premium)
+                .ok_or(Error::Overflow)?;
+            set_persistent_i128(&env, DataKey::InsuranceReserve, reserve);
+            let collected = get_i128(&env, DataKey::PremiumsCollected)
+                .checked_add(premium)
+                .ok_or(Error::Overflow)?;
+            set_persistent_i128(&env, DataKey::PremiumsCollected, collected);
+
+            InsuranceFunded { game_id, amount: premium }.publish(&env);
+        }
+
         let new_total_reserved = get_total_reserved(&env)
             .checked_add(amount)
             .ok_or(Error::Overflow)?;
@@ -376,6 +506,210 @@ impl PrizePool {
         Ok(())
     }
 
+    // -----------------------------------------------------------------------
+    // Insurance reserve
+    // -----------------------------------------------------------------------
+
+    /// Set the share of each reservation paid into the insurance reserve.
+    /// Admin only. `bps` may not exceed 10 000; `0` stops new premiums.
+    pub fn set_insurance_bps(env: Env, admin: Address, bps: u32) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+
+        if bps as i128 > BASIS_POINTS {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().instance().set(&DataKey::InsuranceBps, &bps);
+        Ok(())
+    }
+
+    /// Move `amount` from `available` into the insurance reserve. Admin only.
+    ///
+    /// Returns `InsufficientFunds` if `available` cannot cover the amount.
+    pub fn top_up_insurance(env: Env, admin: Address, amount: i128) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let available = get_available(&env);
+        if amount > available {
+            return Err(Error::InsufficientFunds);
+        }
+        let reserve = get_i128(&env, DataKey::InsuranceReserve)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        set_persistent_i128(&env, DataKey::Available, available - amount);
+        set_persistent_i128(&env, DataKey::InsuranceReserve, reserve);
+
+        InsuranceToppedUp { amount, reserve }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Move `amount` from the insurance reserve back to `available`. Admin
+    /// only.
+    ///
+    /// Returns `InsufficientFunds` if the reserve cannot cover the amount.
+    pub fn withdraw_insurance(env: Env, admin: Address, amount: i128) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let reserve = get_i128(&env, DataKey::InsuranceReserve);
+        if amount > reserve {
+            return Err(Error::InsufficientFunds);
+        }
+        let available = get_available(&env)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        let reserve = reserve - amount;
+        set_persistent_i128(&env, DataKey::InsuranceReserve, reserve);
+        set_persistent_i128(&env, DataKey::Available, available);
+
+        InsuranceWithdrawn { amount, reserve }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Allow or disallow `reporter` (normally a game contract) to report
+    /// losses. Admin only.
+    pub fn set_loss_reporter(
+        env: Env,
+        admin: Address,
+        reporter: Address,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+
+        let key = DataKey::LossReporter(reporter);
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
+
+    /// Report a shortfall of `amount` for `game_id` (exploit or settlement
+    /// error). The reporter must be on the loss-reporter allowlist. The
+    /// loss is recorded as `Pending` until the admin approves or rejects it.
+    /// Returns the new loss id.
+    pub fn report_loss(
+        env: Env,
+        reporter: Address,
+        game_id: u64,
+        amount: i128,
+    ) -> Result<u32, Error> {
+        require_initialized(&env)?;
+        reporter.require_auth();
+
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::LossReporter(reporter.clone()))
+        {
+            return Err(Error::NotAuthorized);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let loss_id = get_loss_count(&env);
+        set_loss(
+            &env,
+            loss_id,
+            &LossEvent {
+                reporter: reporter.clone(),
+                game_id,
+                amount,
+                status: LossStatus::Pending,
+                recipient: reporter.clone(),
+                reported_at: env.ledger().timestamp(),
+            },
+        );
+        env.storage().instance().set(
+            &DataKey::LossCount,
+            &loss_id.checked_add(1).ok_or(Error::Overflow)?,
+        );
+
+        LossReported { loss_id, game_id, reporter, amount }.publish(&env);
+
+        Ok(loss_id)
+    }
+
+    /// Cover a pending loss from the insurance reserve by transferring its
+    /// full amount to `to`. Admin only.
+    ///
+    /// Returns `InsufficientFunds` if the reserve cannot cover the loss.
+    /// Accounting is updated before the token transfer.
+    pub fn approve_loss(env: Env, admin: Address, loss_id: u32, to: Address) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+
+        let mut loss = get_pending_loss(&env, loss_id)?;
+
+        let reserve = get_i128(&env, DataKey::InsuranceReserve);
+        if loss.amount > reserve {
+            return Err(Error::InsufficientFunds);
+        }
+        let new_reserve = reserve.checked_sub(loss.amount).ok_or(Error::Overflow)?;
+        set_persistent_i128(&env, DataKey::InsuranceReserve, new_reserve);
+        let covered = get_i128(&env, DataKey::LossesCovered)
+            .checked_add(loss.amount)
+            .ok_or(Error::Overflow)?;
+        set_persistent_i128(&env, DataKey::LossesCovered, covered);
+
+        loss.status = LossStatus::Covered;
+        loss.recipient = to.clone();
+        set_loss(&env, loss_id, &loss);
+
+        let token = get_token(&env);
+        TokenClient::new(&env, &token).transfer(&env.current_contract_address(), &to, &loss.amount);
+
+        LossCovered { loss_id, to, amount: loss.amount }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Reject a pending loss without paying it. Admin only.
+    pub fn reject_loss(env: Env, admin: Address, loss_id: u32) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+
+        let mut loss = get_pending_loss(&env, loss_id)?;
+        loss.status = LossStatus::Rejected;
+        set_loss(&env, loss_id, &loss);
+
+        LossRejected { loss_id }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Return a loss event by id, or `None` if it was never reported.
+    pub fn get_loss(env: Env, loss_id: u32) -> Option<LossEvent> {
+        env.storage().persistent().get(&DataKey::Loss(loss_id))
+    }
+
+    /// Returns a snapshot of the insurance reserve and its lifetime totals.
+    pub fn get_insurance_state(env: Env) -> Result<InsuranceState, Error> {
+        require_initialized(&env)?;
+        Ok(InsuranceState {
+            reserve: get_i128(&env, DataKey::InsuranceReserve),
+            bps: get_insurance_bps(&env),
+            premiums_collected: get_i128(&env, DataKey::PremiumsCollected),
+            losses_covered: get_i128(&env, DataKey::LossesCovered),
+            loss_count: get_loss_count(&env),
+        })
+    }
+
     // -----------------------------------------------------------------------
     // get_pool_state
     // -----------------------------------------------------------------------
@@ -388,6 +722,13 @@ impl PrizePool {
             reserved: get_total_reserved(&env),
         })
     }
+
+    /// Returns the tokens free to be reserved, so games can check a prize is
+    /// covered before committing to it.
+    pub fn available(env: Env) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        Ok(get_available(&env))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -436,6 +777,41 @@ fn get_total_reserved(env: &Env) -> i128 {
         .unwrap_or(0)
 }
 
+fn get_i128(env: &Env, key: DataKey) -> i128 {
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
+fn get_insurance_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::InsuranceBps)
+        .unwrap_or(0)
+}
+
+fn get_loss_count(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::LossCount).unwrap_or(0)
+}
+
+fn get_pending_loss(env: &Env, loss_id: u32) -> Result<LossEvent, Error> {
+    let loss: LossEvent = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Loss(loss_id))
+        .ok_or(Error::LossNotFound)?;
+    if loss.status != LossStatus::Pending {
+        return Err(Error::LossAlreadySettled);
+    }
+    Ok(loss)
+}
+
+fn set_loss(env: &Env, loss_id: u32, loss: &LossEvent) {
+    let key = DataKey::Loss(loss_id);
+    env.storage().persistent().set(&key, loss);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+}
+
 /// Write an i128 to persistent storage and extend its TTL in one step.
 fn set_persistent_i128(env: &Env, key: DataKey, value: i128) {
     env.storage().persistent().set(&key, &value);
@@ -452,9 +828,9 @@ fn set_persistent_i128(env: &Env, key: DataKey, value: i128) {
 mod test {
     use super::*;
     use soroban_sdk::{
-        testutils::Address as _,
+        testutils::{Address as _, Events as _},
         token::{StellarAssetClient, TokenClient},
-        Address, Env,
+        Address, Env, Event as _,
     };
 
     // ------------------------------------------------------------------
@@ -570,6 +946,7 @@ mod test {
         let state = client.get_pool_state();
         assert_eq!(state.available, 400);
         assert_eq!(state.reserved, 600);
+        assert_eq!(client.available(), 400);
     }
 
     #[test]
@@ -824,4 +1201,129 @@ mod test {
         assert_eq!(state.available, 1_000);
         assert_eq!(state.reserved, 0);
     }
+
+    // ------------------------------------------------------------------
+    // 11. Insurance reserve covers approved loss events
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_insurance_reserve_and_loss_events() {
+        let env = Env::default();
+        let (client, admin, funder, token_addr) = setup(&env);
+        env.mock_all_auths();
+
+        let game = Address::generate(&env);
+        let victim = Address::generate(&env);
+        let tc = token_client(&env, &token_addr);
+
+        client.fund(&funder, &2_000i128);
+        client.set_insurance_bps(&admin, &500u32); // 5%
+
+        // Reserving 1_000 also moves a 50 premium into the reserve.
+        client.reserve(&admin, &1u64, &1_000i128);
+        let state = client.get_pool_state();
+        assert_eq!(state.available, 950);
+        assert_eq!(state.reserved, 1_000);
+        assert_eq!(client.get_insurance_state().reserve, 50);
+
+        // Only allowlisted reporters may report losses.
+        let result = client.try_report_loss(&game, &1u64, &40i128);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+        client.set_loss_reporter(&admin, &game, &true);
+
+        let covered = client.report_loss(&game, &1u64, &40i128);
+        let too_big = client.report_loss(&game, &1u64, &100i128);
+        assert_eq!(client.get_loss(&covered).unwrap().status, LossStatus::Pending);
+
+        client.approve_loss(&admin, &covered, &victim);
+        assert_eq!(tc.balance(&victim), 40);
+        let loss = client.get_loss(&covered).unwrap();
+        assert_eq!(loss.status, LossStatus::Covered);
+        assert_eq!(loss.recipient, victim);
+        let result = client.try_approve_loss(&admin, &covered, &victim);
+        assert_eq!(result, Err(Ok(Error::LossAlreadySettled)));
+
+        // The reserve alone backs losses; it cannot cover 100 with 10 left.
+        let result = client.try_approve_loss(&admin, &too_big, &victim);
+        assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+        client.reject_loss(&admin, &too_big);
+        assert_eq!(client.get_loss(&too_big).unwrap().status, LossStatus::Rejected);
+
+        assert_eq!(
+            client.get_insurance_state(),
+            InsuranceState {
+                reserve: 10,
+                bps: 500,
+                premiums_collected: 50,
+                losses_covered: 40,
+                loss_count: 2,
+            }
+        );
+
+        // available + reserved + insurance reserve matches the token balance.
+        assert_eq!(tc.balance(&client.address), 950 + 1_000 + 10);
+    }
+
+    // ------------------------------------------------------------------
+    // 12. Admin moves funds into and out of the insurance reserve
+    // ------------------------------------------------------------------
+
+    #[test]
+    fn test_insurance_top_up_and_withdraw() {
+        let env = Env::default();
+        let (client, admin, funder, token_addr) = setup(&env);
+        env.mock_all_auths();
+
+        let tc = token_client(&env, &token_addr);
+        client.fund(&funder, &1_000i128);
+
+        client.top_up_insurance(&admin, &300i128);
+        let event = InsuranceToppedUp {
+            amount: 300,
+            reserve: 300,
+        };
+        assert_eq!(
+            env.events()
+                .all()
+                .filter_by_contract(&client.address)
+                .events()
+                .last()
+                .unwrap(),
+            &event.to_xdr(&env, &client.address)
+        );
+        assert_eq!(client.available(), 700);
+        assert_eq!(client.get_insurance_state().reserve, 300);
+
+        let result = client.try_top_up_insurance(&admin, &701i128);
+        assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+        let result = client.try_top_up_insurance(&admin, &0i128);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        let stranger = Address::generate(&env);
+        let result = client.try_top_up_insurance(&stranger, &10i128);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+        client.withdraw_insurance(&admin, &120i128);
+        let event = InsuranceWithdrawn {
+            amount: 120,
+            reserve: 180,
+        };
+        assert_eq!(
+            env.events()
+                .all()
+                .filter_by_contract(&client.address)
+                .events()
+                .last()
+                .unwrap(),
+            &event.to_xdr(&env, &client.address)
+        );
+        assert_eq!(client.available(), 820);
+        assert_eq!(client.get_insurance_state().reserve, 180);
+
+        let result = client.try_withdraw_insurance(&admin, &181i128);
+        assert_eq!(result, Err(Ok(Error::InsufficientFunds)));
+
+        // Top-ups are not premiums.
+        assert_eq!(client.get_insurance_state().premiums_collected, 0);
+        assert_eq!(tc.balance(&client.address), 820 + 180);
+    }
 }
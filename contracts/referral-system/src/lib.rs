@@ -2,12 +2,44 @@
 #![allow(unexpected_cfgs)]
 
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Vec,
+    contract, contracterror, contractevent, contractimpl, contracttype, token::TokenClient,
+    Address, Env, Symbol, Vec,
 };
 
 /// TTL bump for persistent storage entries (~30 days at 5s/ledger).
 pub const PERSISTENT_BUMP_LEDGERS: u32 = 518_400;
 
+/// Deepest referral level credited by an event (the direct referrer is 1).
+pub const MAX_REFERRAL_LEVELS: u32 = 3;
+
+/// Number of referrers kept on the `total_earned` leaderboard.
+pub const LEADERBOARD_SIZE: u32 = 10;
+
+/// Length of a registration cohort (one week).
+pub const COHORT_SECONDS: u64 = 604_800;
+
+/// Most cohorts returned by one `get_cohorts` call.
+pub const MAX_COHORT_QUERY: u32 = 52;
+
+/// Most rate tiers above the base rate.
+pub const MAX_RATE_TIERS: u32 = 5;
+
+/// Storage layout written by this version of the contract. Deployments
+/// that have not completed a migration report version 1.
+pub const STORAGE_VERSION: u32 = 2;
+
+/// Most users imported by one `migrate_states` call.
+pub const MAX_MIGRATION_BATCH: u32 = 25;
+
+/// Most extra reward tokens accrued alongside the main reward.
+pub const MAX_EXTRA_TOKENS: u32 = 3;
+
+/// Most milestone bonuses configured at once.
+pub const MAX_MILESTONES: u32 = 10;
+
+/// Most referrers sharing a season's bonus pool.
+pub const MAX_SEASON_WINNERS: u32 = 10;
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -26,6 +58,28 @@ pub enum Error {
     NoPendingRewards = 8,
     AlreadyClaimed = 9,
     InvalidEventType = 10,
+    RewardsVesting = 11,
+    VestingNotActive = 12,
+    SubProgramNotFound = 13,
+    UserNotInProgram = 14,
+    BudgetExceeded = 15,
+    PointsRateNotSet = 16,
+    TooManyLevels = 17,
+    CodeTaken = 18,
+    CodeNotFound = 19,
+    AlreadyHasCode = 20,
+    DisputeExists = 21,
+    DisputeNotFound = 22,
+    DisputeNotOpen = 23,
+    ExpiryNotSet = 24,
+    RewardsNotExpired = 25,
+    ReferrerBanned = 26,
+    InvalidTiers = 27,
+    MigrationClosed = 28,
+    TokenNotConfigured = 29,
+    SeasonNotFound = 30,
+    SeasonActive = 31,
+    SeasonEnded = 32,
     Overflow = 99,
 }
 
@@ -42,6 +96,30 @@ pub enum EventType {
     PrizeClaimed = 2,
 }
 
+/// Lifecycle of a referral dispute: `Open` until the admin resolves it as
+/// `Upheld` (the attribution is wrong) or `Dismissed`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DisputeStatus {
+    Open = 0,
+    Upheld = 1,
+    Dismissed = 2,
+}
+
+/// A contested referral attribution, kept per referee.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    /// Referrer credited for the referee when the dispute was raised.
+    pub referrer: Address,
+    pub flagged_by: Address,
+    pub reason_code: u32,
+    pub status: DisputeStatus,
+    pub opened_at: u64,
+    /// Time of the admin's ruling (0 while open).
+    pub resolved_at: u64,
+}
+
 /// Per-user referral state persisted on-chain.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -58,6 +136,206 @@ pub struct ReferralState {
     pub event_count: u64,
 }
 
+/// Vesting schedule applied to pending rewards.
+///
+/// Vesting is active when `duration > 0`. Pending rewards vest linearly over
+/// `duration` seconds from their vesting start, with nothing vested before
+/// `cliff` seconds have elapsed. Each accrual moves the start to the
+/// amount-weighted average of the existing balance's start and the accrual
+/// time, so new rewards never inherit the vesting progress of older ones.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingConfig {
+    pub cliff: u64,
+    pub duration: u64,
+    /// Share of the unvested amount forfeited on an early claim.
+    pub early_claim_penalty_bps: u32,
+}
+
+/// Cap on what a single referrer can earn from referral events per epoch.
+///
+/// The cap is active when `cap > 0`. Epochs are aligned windows of
+/// `epoch_seconds` (e.g. 604_800 for weekly), numbered
+/// `timestamp / epoch_seconds`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochCapConfig {
+    pub cap: i128,
+    pub epoch_seconds: u64,
+}
+
+/// Rewards a referrer has been credited in one epoch.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochEarnings {
+    pub epoch: u64,
+    pub earned: i128,
+}
+
+/// Record of a single early claim.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EarlyClaimRecord {
+    pub timestamp: u64,
+    /// Amount paid out to the referrer.
+    pub claimed: i128,
+    /// Unvested amount returned to the reward pool.
+    pub forfeited: i128,
+}
+
+/// Running totals for a referral program.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProgramTotals {
+    /// Number of referral events recorded.
+    pub event_count: u64,
+    /// Sum of event amounts.
+    pub volume: i128,
+    /// Sum of rewards credited to referrers.
+    pub rewards: i128,
+}
+
+/// Per-user referral state as exported from a v1 deployment, before the
+/// multi-level, epoch and cohort layouts.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralStateV1 {
+    pub referrer: Address,
+    pub referees: Vec<Address>,
+    pub total_earned: i128,
+    pub pending_reward: i128,
+    pub event_count: u64,
+}
+
+/// Extra token a direct referrer accrues at `bps` of each event amount, on
+/// top of the main reward.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenRate {
+    pub token: Address,
+    pub bps: u32,
+}
+
+/// What a referrer milestone counts.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MilestoneKind {
+    /// Referees who have become active (see `RateTier`).
+    ActiveReferees = 0,
+    /// Cumulative amount of the referees' qualifying events.
+    Volume = 1,
+}
+
+/// One-time `bonus` credited to a referrer once their `kind` count reaches
+/// `threshold`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Milestone {
+    pub kind: MilestoneKind,
+    pub threshold: i128,
+    pub bonus: i128,
+}
+
+/// Referral contest: rewards accrued between `start_time` and `end_time`
+/// count towards each referrer's seasonal score, and once the season is
+/// ended the `top_n` highest scores split `pool` pro rata.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Season {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub top_n: u32,
+    pub pool: i128,
+    /// Sum of the winners' scores, set when the season is ended.
+    pub winning_score: i128,
+    pub ended: bool,
+}
+
+/// Platform-wide counters for dashboards.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GlobalStats {
+    /// Users registered under a referrer.
+    pub registered_users: u64,
+    /// Referral events recorded, sub-program events included.
+    pub events: u64,
+    /// Rewards credited, upline rewards and referee bonuses included.
+    pub rewards_accrued: i128,
+    /// Rewards paid out by claims.
+    pub rewards_claimed: i128,
+}
+
+/// A referrer's position on the leaderboard.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaderboardEntry {
+    pub referrer: Address,
+    pub total_earned: i128,
+}
+
+/// Counters for referees who registered in the same week.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CohortStats {
+    /// Referees who registered during the week.
+    pub registrations: u32,
+    /// Of those, referees with at least one recorded event.
+    pub active_referees: u32,
+    /// Rewards their events generated, upline rewards included.
+    pub rewards: i128,
+}
+
+/// A referee's registration cohort and whether they have been active.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CohortMembership {
+    pub cohort: u64,
+    pub active: bool,
+}
+
+/// Reward rate a referrer is upgraded to once `min_active` of their
+/// referees have produced an event.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateTier {
+    pub min_active: u32,
+    pub bps: u32,
+}
+
+/// A referrer's current tier: `0` is the base `reward_bps`, `n` the n-th
+/// configured `RateTier`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferrerTier {
+    pub tier: u32,
+    pub bps: u32,
+    pub active_referees: u32,
+}
+
+/// Rewards an event would credit, returned by `estimate_reward`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RewardEstimate {
+    /// Credited to the referrer.
+    pub reward: i128,
+    /// Credited to the referrers above them, in total.
+    pub upline_reward: i128,
+}
+
+/// Partner sub-program run by its own operator.
+///
+/// Events for users tagged to the sub-program are recorded by `operator` at
+/// `reward_bps`, and the rewards credited may not exceed `budget`. Every
+/// event also rolls up into the global totals.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubProgram {
+    pub operator: Address,
+    pub reward_bps: u32,
+    pub budget: i128,
+    pub totals: ProgramTotals,
+}
+
 /// Storage key layout.
 #[contracttype]
 pub enum DataKey {
@@ -71,6 +349,113 @@ pub enum DataKey {
     State(Address),
     /// Mapping: referee → referrer — persistent storage.
     ReferredBy(Address),
+    /// Tombstone left by `forget_me`; blocks re-registration — persistent
+    /// storage.
+    Forgotten(Address),
+    /// Vesting schedule — instance storage.
+    Vesting,
+    /// Total rewards forfeited back to the reward pool — instance storage.
+    ForfeitedTotal,
+    /// Timestamp the referrer's current pending balance started vesting — persistent storage.
+    VestStart(Address),
+    /// Timestamp vesting was last enabled — instance storage.
+    VestingSince,
+    /// Number of early claims made by a referrer — persistent storage.
+    EarlyClaimCount(Address),
+    /// Early claim record keyed by (referrer, index) — persistent storage.
+    EarlyClaim(Address, u32),
+    /// Totals across every event, including sub-program events — instance storage.
+    GlobalTotals,
+    /// Number of sub-programs created; the next id — instance storage.
+    SubProgramCount,
+    /// Partner sub-program by id — persistent storage.
+    SubProgram(u32),
+    /// Sub-program a user is tagged to — persistent storage.
+    UserProgram(Address),
+    /// Game contract allowed to record referral events — persistent storage.
+    GameReporter(Address),
+    /// Operator allowed to record referral events — persistent storage.
+    Operator(Address),
+    /// Whether events accrue points instead of tokens — instance storage.
+    PointsMode,
+    /// Tokens paid per `POINTS_RATE_SCALE` points — instance storage.
+    PointsRate,
+    /// Unconverted points held by a referrer — persistent storage.
+    Points(Address),
+    /// Vec<u32> of reward bps for levels 2 and up — instance storage.
+    UplineBps,
+    /// Referral code → referrer who owns it — persistent storage.
+    CodeOwner(Symbol),
+    /// Referrer → their referral code — persistent storage.
+    ReferralCode(Address),
+    /// EpochCapConfig — instance storage.
+    EpochCap,
+    /// EpochEarnings per referrer — persistent storage.
+    EpochEarned(Address),
+    /// Dispute over a referee's attribution — persistent storage.
+    Dispute(Address),
+    /// Ledgers after the last accrual before pending rewards expire — instance storage.
+    RewardExpiry,
+    /// Ledger sequence of the referrer's latest reward accrual — persistent storage.
+    LastAccrual(Address),
+    /// Total expired rewards swept back to the reward contract — instance storage.
+    ExpiredTotal,
+    /// Vec<LeaderboardEntry> of the top referrers by `total_earned` — instance storage.
+    Leaderboard,
+    /// Token paid out on claims; unset means accounting-only — instance storage.
+    RewardToken,
+    /// Referrer banned from accruing and claiming rewards — persistent storage.
+    Banned(Address),
+    /// Bonus paid to the referee of an event, in basis points — instance storage.
+    RefereeBps,
+    /// CohortStats by registration week — persistent storage.
+    Cohort(u64),
+    /// CohortMembership of a referee — persistent storage.
+    RefereeCohort(Address),
+    /// Vec<RateTier> in ascending `min_active` order — instance storage.
+    RateTiers,
+    /// Number of a referrer's referees that produced an event — persistent storage.
+    ActiveReferees(Address),
+    /// Referee that has produced an event — persistent storage.
+    ActiveReferee(Address),
+    /// Cumulative event amount a referee needs before rewards accrue — instance storage.
+    ActivityThreshold,
+    /// Cumulative event amount of a referee still below the threshold — persistent storage.
+    RefereeVolume(Address),
+    /// Storage layout version; unset means 1 — instance storage.
+    StorageVersion,
+    /// User whose v1 state has been imported — persistent storage.
+    Migrated(Address),
+    /// Number of users registered under a referrer — instance storage.
+    RegisteredUsers,
+    /// Total rewards paid out by claims — instance storage.
+    ClaimedTotal,
+    /// Address a user's claims are paid to instead of their own — persistent storage.
+    PayoutAddress(Address),
+    /// Vec<TokenRate> of extra reward tokens — instance storage.
+    ExtraTokens,
+    /// Pending reward of a referrer in an extra token — persistent storage.
+    TokenPending(Address, Address),
+    /// instance: Vec<Milestone>
+    Milestones,
+    /// persistent: i128 — qualifying volume of a referrer's referees
+    ReferredVolume(Address),
+    /// persistent: bool — milestone (kind, threshold) already paid to a referrer
+    MilestonePaid(Address, MilestoneKind, i128),
+    /// instance: i128 — rewards removed by clawbacks
+    ClawedBackTotal,
+    /// instance: u32 — number of seasons started
+    SeasonCount,
+    /// instance: u32 — season currently running
+    CurrentSeason,
+    /// persistent: Season
+    Season(u32),
+    /// persistent: i128 — referrer's score in a season
+    SeasonScore(u32, Address),
+    /// persistent: Vec<LeaderboardEntry> — top `top_n` scores of a season
+    SeasonBoard(u32),
+    /// persistent: bool — season bonus already claimed by a referrer
+    SeasonClaimed(u32, Address),
 }
 
 // ---------------------------------------------------------------------------
@@ -93,6 +478,13 @@ pub struct ReferrerRegistered {
     pub referrer: Address,
 }
 
+#[contractevent]
+pub struct ReferralCodeCreated {
+    #[topic]
+    pub referrer: Address,
+    pub code: Symbol,
+}
+
 #[contractevent]
 pub struct ReferralEventRecorded {
     #[topic]
@@ -102,13 +494,224 @@ pub struct ReferralEventRecorded {
     pub event_type: EventType,
     pub amount: i128,
     pub reward: i128,
+    /// Bonus credited to `user` at `referee_bps`.
+    pub referee_bonus: i128,
+}
+
+#[contractevent]
+pub struct UplineRewardCredited {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub referrer: Address,
+    pub level: u32,
+    pub reward: i128,
+}
+
+#[contractevent]
+pub struct CapReached {
+    #[topic]
+    pub referrer: Address,
+    pub epoch: u64,
+    pub cap: i128,
+    /// Reward not credited because of the cap.
+    pub withheld: i128,
+}
+
+#[contractevent]
+pub struct ReferralFlagged {
+    #[topic]
+    pub referee: Address,
+    #[topic]
+    pub referrer: Address,
+    pub flagged_by: Address,
+    pub reason_code: u32,
+}
+
+#[contractevent]
+pub struct DisputeResolved {
+    #[topic]
+    pub referee: Address,
+    #[topic]
+    pub referrer: Address,
+    pub status: DisputeStatus,
+}
+
+#[contractevent]
+pub struct RewardsExpired {
+    #[topic]
+    pub referrer: Address,
+    pub amount: i128,
+    pub reward_contract: Address,
+}
+
+#[contractevent]
+pub struct SeasonStarted {
+    #[topic]
+    pub season_id: u32,
+    pub end_time: u64,
+    pub top_n: u32,
+}
+
+#[contractevent]
+pub struct SeasonFunded {
+    #[topic]
+    pub season_id: u32,
+    pub amount: i128,
+    pub pool: i128,
+}
+
+#[contractevent]
+pub struct SeasonEnded {
+    #[topic]
+    pub season_id: u32,
+    pub winners: u32,
+    pub pool: i128,
+}
+
+#[contractevent]
+pub struct SeasonRewardClaimed {
+    #[topic]
+    pub season_id: u32,
+    #[topic]
+    pub referrer: Address,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct RewardsClawedBack {
+    #[topic]
+    pub referrer: Address,
+    pub amount: i128,
+    pub reason: u32,
+    pub reduced_earned: bool,
+    pub pending_reward: i128,
 }
 
 #[contractevent]
 pub struct RewardClaimed {
+    #[topic]
+    pub user: Address,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct TokenRewardClaimed {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub token: Address,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct PayoutAddressSet {
+    #[topic]
+    pub user: Address,
+    pub payout: Option<Address>,
+}
+
+#[contractevent]
+pub struct StatesMigrated {
+    pub migrated: u32,
+    pub skipped: u32,
+}
+
+#[contractevent]
+pub struct MigrationCompleted {
+    pub version: u32,
+}
+
+#[contractevent]
+pub struct UserForgotten {
+    #[topic]
+    pub user: Address,
+}
+
+#[contractevent]
+pub struct GameReporterSet {
+    #[topic]
+    pub game: Address,
+    pub allowed: bool,
+}
+
+#[contractevent]
+pub struct OperatorSet {
+    #[topic]
+    pub operator: Address,
+    pub allowed: bool,
+}
+
+#[contractevent]
+pub struct MilestoneReached {
+    #[topic]
+    pub referrer: Address,
+    pub kind: MilestoneKind,
+    pub threshold: i128,
+    pub bonus: i128,
+}
+
+#[contractevent]
+pub struct TierUpgraded {
+    #[topic]
+    pub referrer: Address,
+    pub tier: u32,
+    pub bps: u32,
+}
+
+#[contractevent]
+pub struct BanSet {
+    #[topic]
+    pub referrer: Address,
+    pub banned: bool,
+}
+
+#[contractevent]
+pub struct RewardTokenSet {
+    pub token: Option<Address>,
+}
+
+#[contractevent]
+pub struct SubProgramCreated {
+    #[topic]
+    pub program_id: u32,
+    #[topic]
+    pub operator: Address,
+    pub reward_bps: u32,
+    pub budget: i128,
+}
+
+#[contractevent]
+pub struct UserTagged {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub program_id: u32,
+}
+
+#[contractevent]
+pub struct PointsRateSet {
+    pub rate: i128,
+}
+
+#[contractevent]
+pub struct PointsClaimed {
+    #[topic]
+    pub user: Address,
+    pub points: i128,
+    pub rate: i128,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct EarlyRewardClaimed {
     #[topic]
     pub user: Address,
     pub amount: i128,
+    pub forfeited: i128,
 }
 
 // ---------------------------------------------------------------------------
@@ -131,6 +734,21 @@ fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
     Ok(())
 }
 
+/// Require `caller` to be the admin, an operator, or an allowed game
+/// contract.
+fn require_reporter(env: &Env, caller: &Address) -> Result<(), Error> {
+    let admin = get_admin(env)?;
+    caller.require_auth();
+    let storage = env.storage().persistent();
+    if *caller != admin
+        && !storage.has(&DataKey::Operator(caller.clone()))
+        && !storage.has(&DataKey::GameReporter(caller.clone()))
+    {
+        return Err(Error::NotAuthorized);
+    }
+    Ok(())
+}
+
 fn get_reward_bps(env: &Env) -> Result<u32, Error> {
     env.storage()
         .instance()
@@ -163,706 +781,4350 @@ fn bump_referred_by(env: &Env, user: &Address) {
     }
 }
 
-/// Basis-points divisor (10 000 = 100%).
-const BASIS_POINTS: i128 = 10_000;
+fn get_vesting(env: &Env) -> Option<VestingConfig> {
+    env.storage()
+        .instance()
+        .get::<DataKey, VestingConfig>(&DataKey::Vesting)
+        .filter(|v| v.duration > 0)
+}
 
-fn calculate_reward(amount: i128, bps: u32) -> Result<i128, Error> {
-    amount
-        .checked_mul(bps as i128)
-        .and_then(|v| v.checked_div(BASIS_POINTS))
-        .ok_or(Error::Overflow)
+fn set_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(
+    env: &Env,
+    key: &DataKey,
+    val: &V,
+) {
+    env.storage().persistent().set(key, val);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
 }
 
-// ---------------------------------------------------------------------------
-// Contract
-// ---------------------------------------------------------------------------
-
-#[contract]
-pub struct ReferralSystem;
+/// When `user`'s pending balance started vesting. Balances without a
+/// recorded start (e.g. migrated ones) vest from when vesting was enabled.
+fn vest_start(env: &Env, user: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::VestStart(user.clone()))
+        .or_else(|| env.storage().instance().get(&DataKey::VestingSince))
+        .unwrap_or(env.ledger().timestamp())
+}
 
-#[contractimpl]
-impl ReferralSystem {
-    // -----------------------------------------------------------------------
-    // Initialization
-    // -----------------------------------------------------------------------
+/// Move `user`'s vesting start to the average of the current start and now,
+/// weighted by the `pending` balance and the `added` reward.
+fn weight_vest_start(env: &Env, user: &Address, pending: i128, added: i128) -> Result<(), Error> {
+    let now = env.ledger().timestamp();
+    let start = if pending <= 0 {
+        now
+    } else {
+        let weighted = (vest_start(env, user) as i128)
+            .checked_mul(pending)
+            .and_then(|v| v.checked_add((now as i128).checked_mul(added)?))
+            .and_then(|v| v.checked_div(pending.checked_add(added)?))
+            .ok_or(Error::Overflow)?;
+        weighted as u64
+    };
+    set_persistent(env, &DataKey::VestStart(user.clone()), &start);
+    Ok(())
+}
 
-    /// Initializes the referral system contract.
-    ///
-    /// * `admin`           – address that can call privileged methods.
-    /// * `reward_contract` – address of the contract/account that funds rewards.
-    pub fn init(env: Env, admin: Address, reward_contract: Address) -> Result<(), Error> {
-        if env.storage().instance().has(&DataKey::Admin) {
-            return Err(Error::AlreadyInitialized);
-        }
+/// Portion of `pending` that has vested under `vesting` at the current time.
+fn vested_portion(env: &Env, user: &Address, pending: i128, vesting: &VestingConfig) -> i128 {
+    let elapsed = env
+        .ledger()
+        .timestamp()
+        .saturating_sub(vest_start(env, user));
+    if elapsed < vesting.cliff {
+        return 0;
+    }
+    if elapsed >= vesting.duration {
+        return pending;
+    }
+    // elapsed < duration, so this never exceeds `pending`.
+    pending
+        .checked_mul(elapsed as i128)
+        .and_then(|v| v.checked_div(vesting.duration as i128))
+        .unwrap_or(0)
+}
 
-        admin.require_auth();
+/// Basis-points divisor (10 000 = 100%).
+const BASIS_POINTS: i128 = 10_000;
 
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage()
-            .instance()
-            .set(&DataKey::RewardContract, &reward_contract);
-        // Default reward: 5% (500 basis points).
-        let default_bps: u32 = 500;
-        env.storage()
-            .instance()
-            .set(&DataKey::RewardBps, &default_bps);
+/// Number of points the points rate is quoted against.
+pub const POINTS_RATE_SCALE: i128 = 10_000;
 
-        Initialized {
-            admin,
-            reward_contract,
-            reward_bps: default_bps,
-        }
-        .publish(&env);
+fn calculate_reward(amount: i128, bps: u32) -> Result<i128, Error> {
+    amount
+        .checked_mul(bps as i128)
+        .and_then(|v| v.checked_div(BASIS_POINTS))
+        .ok_or(Error::Overflow)
+}
 
-        Ok(())
+/// Compute the reward earned by `referrer` for a referee event of `amount`,
+/// before the epoch cap. Partner events pay the sub-program's rate, other
+/// events the referrer's tier rate; banned referrers earn nothing. Shared by
+/// `record_referral_event`, `record_partner_event` and `estimate_reward`.
+fn compute_reward(
+    env: &Env,
+    referrer: &Address,
+    program: Option<&SubProgram>,
+    amount: i128,
+) -> Result<i128, Error> {
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if is_banned(env, referrer) {
+        return Ok(0);
     }
+    let bps = match program {
+        Some(program) => program.reward_bps,
+        None => referrer_tier(env, referrer)?.bps,
+    };
+    calculate_reward(amount, bps)
+}
 
-    // -----------------------------------------------------------------------
-    // Admin
-    // -----------------------------------------------------------------------
+fn get_rate_tiers(env: &Env) -> Vec<RateTier> {
+    env.storage()
+        .instance()
+        .get(&DataKey::RateTiers)
+        .unwrap_or_else(|| Vec::new(env))
+}
 
-    /// Update the reward percentage (in basis points). Admin only.
-    pub fn set_reward_bps(env: Env, admin: Address, bps: u32) -> Result<(), Error> {
-        require_admin(&env, &admin)?;
-        if bps > 10_000 {
-            return Err(Error::InvalidAmount);
+fn get_active_referees(env: &Env, referrer: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ActiveReferees(referrer.clone()))
+        .unwrap_or(0)
+}
+
+/// The highest tier `referrer`'s active referee count reaches, falling back
+/// to the base reward rate.
+fn referrer_tier(env: &Env, referrer: &Address) -> Result<ReferrerTier, Error> {
+    let active_referees = get_active_referees(env, referrer);
+    let mut current = ReferrerTier {
+        tier: 0,
+        bps: get_reward_bps(env)?,
+        active_referees,
+    };
+    for (i, tier) in get_rate_tiers(env).iter().enumerate() {
+        if active_referees < tier.min_active {
+            break;
         }
-        env.storage().instance().set(&DataKey::RewardBps, &bps);
-        Ok(())
+        current.tier = i as u32 + 1;
+        current.bps = tier.bps;
     }
+    Ok(current)
+}
 
-    /// Update the reward contract address. Admin only.
-    pub fn set_reward_contract(
-        env: Env,
-        admin: Address,
-        reward_contract: Address,
-    ) -> Result<(), Error> {
-        require_admin(&env, &admin)?;
-        env.storage()
-            .instance()
-            .set(&DataKey::RewardContract, &reward_contract);
-        Ok(())
-    }
+fn storage_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::StorageVersion)
+        .unwrap_or(1)
+}
 
-    // -----------------------------------------------------------------------
-    // Referral registration
-    // -----------------------------------------------------------------------
+fn get_activity_threshold(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ActivityThreshold)
+        .unwrap_or(0)
+}
 
-    /// Register `referrer` as the referrer of `user`.
-    ///
-    /// * Both `user` and `referrer` must authorize the call.
-    /// * A user cannot refer themselves.
-    /// * A user can only be referred once.
-    pub fn register_referrer(env: Env, user: Address, referrer: Address) -> Result<(), Error> {
-        get_admin(&env)?; // ensure initialized
+fn get_referee_volume(env: &Env, user: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RefereeVolume(user.clone()))
+        .unwrap_or(0)
+}
 
-        user.require_auth();
+/// Add `amount` to `user`'s cumulative event volume and return whether it
+/// has reached the activity threshold. Volume stops being tracked once the
+/// referee qualifies.
+fn track_referee_volume(env: &Env, user: &Address, amount: i128) -> Result<bool, Error> {
+    let threshold = get_activity_threshold(env);
+    if threshold == 0 {
+        return Ok(true);
+    }
+    let volume = get_referee_volume(env, user);
+    if volume >= threshold {
+        return Ok(true);
+    }
+    let volume = volume.checked_add(amount).ok_or(Error::Overflow)?;
+    set_persistent(env, &DataKey::RefereeVolume(user.clone()), &volume);
+    Ok(volume >= threshold)
+}
 
-        // Guard: self-referral
-        if user == referrer {
-            return Err(Error::SelfReferral);
-        }
+/// Whether an event of `amount` by `user` (a new referee when `None`) would
+/// reach the activity threshold, without tracking it. Mirrors
+/// `track_referee_volume` for `estimate_reward`.
+fn referee_would_qualify(env: &Env, user: Option<&Address>, amount: i128) -> bool {
+    let threshold = get_activity_threshold(env);
+    let volume = user.map_or(0, |user| get_referee_volume(env, user));
+    threshold == 0 || volume >= threshold || volume.saturating_add(amount) >= threshold
+}
 
-        // Guard: already referred
-        let referred_key = DataKey::ReferredBy(user.clone());
-        if env.storage().persistent().has(&referred_key) {
-            return Err(Error::AlreadyReferred);
-        }
+/// Count `user`'s first qualifying event towards `referrer`'s active referees, and
+/// announce any tier the new count reaches. A referee only ever counts
+/// once, for the referrer they had at their first event.
+fn mark_referee_active(env: &Env, user: &Address, referrer: &Address) -> Result<(), Error> {
+    let key = DataKey::ActiveReferee(user.clone());
+    if env.storage().persistent().has(&key) {
+        return Ok(());
+    }
+    set_persistent(env, &key, &true);
 
-        // Store referee → referrer mapping
-        env.storage().persistent().set(&referred_key, &referrer);
-        env.storage().persistent().extend_ttl(
-            &referred_key,
-            PERSISTENT_BUMP_LEDGERS,
-            PERSISTENT_BUMP_LEDGERS,
-        );
+    let before = referrer_tier(env, referrer)?.tier;
+    let count = get_active_referees(env, referrer)
+        .checked_add(1)
+        .ok_or(Error::Overflow)?;
+    set_persistent(env, &DataKey::ActiveReferees(referrer.clone()), &count);
 
-        // Initialize user state if first interaction
-        let user_state = get_state(&env, &user).unwrap_or(ReferralState {
+    let tier = referrer_tier(env, referrer)?;
+    if tier.tier > before {
+        TierUpgraded {
             referrer: referrer.clone(),
-            referees: Vec::new(&env),
-            total_earned: 0,
-            pending_reward: 0,
-            event_count: 0,
-        });
-        let user_state = ReferralState {
-            referrer: referrer.clone(),
-            ..user_state
-        };
-        set_state(&env, &user, &user_state);
-
-        // Update referrer's referee list
-        let mut referrer_state = get_state(&env, &referrer).unwrap_or(ReferralState {
-            referrer: referrer.clone(), // placeholder, referrer may not have a referrer
-            referees: Vec::new(&env),
-            total_earned: 0,
-            pending_reward: 0,
-            event_count: 0,
-        });
-        referrer_state.referees.push_back(user.clone());
-        set_state(&env, &referrer, &referrer_state);
+            tier: tier.tier,
+            bps: tier.bps,
+        }
+        .publish(env);
+    }
+    Ok(())
+}
 
-        ReferrerRegistered { user, referrer }.publish(&env);
+fn is_banned(env: &Env, referrer: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Banned(referrer.clone()))
+}
 
-        Ok(())
+fn require_not_banned(env: &Env, user: &Address) -> Result<(), Error> {
+    if is_banned(env, user) {
+        return Err(Error::ReferrerBanned);
     }
+    Ok(())
+}
 
-    // -----------------------------------------------------------------------
-    // Referral events
-    // -----------------------------------------------------------------------
+fn points_mode(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::PointsMode)
+        .unwrap_or(false)
+}
 
-    /// Record a referral event for `user`.
-    ///
-    /// Called by an admin/operator when a qualifying action occurs
-    /// (e.g. game played, deposit made). The `amount` is the transaction value
-    /// and the reward is computed as `amount * reward_bps / 10_000`.
-    ///
-    /// The reward is credited to the **referrer** of `user`.
-    pub fn record_referral_event(
-        env: Env,
-        admin: Address,
-        user: Address,
-        event_type: EventType,
-        amount: i128,
-    ) -> Result<(), Error> {
-        require_admin(&env, &admin)?;
+fn get_points(env: &Env, user: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Points(user.clone()))
+        .unwrap_or(0)
+}
 
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
+/// Add `reward` to `user`'s pending balance and lifetime totals, returning
+/// the updated (unsaved) state. Shared by `credit_referrer` and
+/// `credit_referee`.
+///
+/// In points mode the reward is credited as points instead; those only turn
+/// into tokens (and count towards `total_earned`) when claimed.
+fn accrue_reward(env: &Env, user: &Address, reward: i128) -> Result<ReferralState, Error> {
+    if points_mode(env) {
+        let points = get_points(env, user)
+            .checked_add(reward)
+            .ok_or(Error::Overflow)?;
+        set_persistent(env, &DataKey::Points(user.clone()), &points);
+    }
+    let mut state = get_state(env, user).unwrap_or(ReferralState {
+        referrer: user.clone(),
+        referees: Vec::new(env),
+        total_earned: 0,
+        pending_reward: 0,
+        event_count: 0,
+    });
+    if !points_mode(env) {
+        if reward > 0 {
+            weight_vest_start(env, user, state.pending_reward, reward)?;
+            set_persistent(
+                env,
+                &DataKey::LastAccrual(user.clone()),
+                &env.ledger().sequence(),
+            );
         }
-
-        // Lookup user's referrer
-        let referred_key = DataKey::ReferredBy(user.clone());
-        let referrer: Address = env
-            .storage()
-            .persistent()
-            .get(&referred_key)
-            .ok_or(Error::ReferrerNotRegistered)?;
-        bump_referred_by(&env, &user);
-
-        // Calculate reward
-        let bps = get_reward_bps(&env)?;
-        let reward = calculate_reward(amount, bps)?;
-
-        // Credit referrer
-        let mut referrer_state = get_state(&env, &referrer).unwrap_or(ReferralState {
-            referrer: referrer.clone(),
-            referees: Vec::new(&env),
-            total_earned: 0,
-            pending_reward: 0,
-            event_count: 0,
-        });
-        referrer_state.pending_reward = referrer_state
+        state.pending_reward = state
             .pending_reward
             .checked_add(reward)
             .ok_or(Error::Overflow)?;
-        referrer_state.total_earned = referrer_state
+        state.total_earned = state
             .total_earned
             .checked_add(reward)
             .ok_or(Error::Overflow)?;
-        referrer_state.event_count = referrer_state
-            .event_count
-            .checked_add(1)
-            .ok_or(Error::Overflow)?;
-        set_state(&env, &referrer, &referrer_state);
+    }
+    Ok(state)
+}
 
-        ReferralEventRecorded {
-            user,
-            referrer,
-            event_type,
-            amount,
-            reward,
+/// Credit `reward` to `referrer` for one of their referees' events.
+fn credit_referrer(env: &Env, referrer: &Address, reward: i128) -> Result<(), Error> {
+    let mut referrer_state = accrue_reward(env, referrer, reward)?;
+    referrer_state.event_count = referrer_state
+        .event_count
+        .checked_add(1)
+        .ok_or(Error::Overflow)?;
+    set_state(env, referrer, &referrer_state);
+    if reward > 0 {
+        if !points_mode(env) {
+            update_leaderboard(env, referrer, referrer_state.total_earned);
         }
-        .publish(&env);
-
-        Ok(())
+        add_season_score(env, referrer, reward)?;
     }
+    Ok(())
+}
 
-    // -----------------------------------------------------------------------
-    // Claiming rewards
-    // -----------------------------------------------------------------------
+/// Credit the referee of an event their `referee_bps` bonus on `amount` and
+/// return it. Banned users earn nothing, and the bonus counts towards the
+/// referee's own epoch cap. Referees do not enter the referrer leaderboard.
+fn credit_referee(env: &Env, user: &Address, amount: i128) -> Result<i128, Error> {
+    let bps: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::RefereeBps)
+        .unwrap_or(0);
+    if bps == 0 || is_banned(env, user) {
+        return Ok(0);
+    }
+    let bonus = apply_epoch_cap(env, user, calculate_reward(amount, bps)?)?;
+    if bonus > 0 {
+        let state = accrue_reward(env, user, bonus)?;
+        set_state(env, user, &state);
+    }
+    Ok(bonus)
+}
 
-    /// Claim all pending referral rewards for `user`.
-    ///
-    /// Marks the pending balance as claimed. The actual token transfer is
-    /// expected to be handled by the reward contract integration; this method
-    /// records the accounting and emits an event for off-chain settlement or
-    /// cross-contract calls.
-    pub fn claim_referral_reward(env: Env, user: Address) -> Result<i128, Error> {
-        get_admin(&env)?; // ensure initialized
-        user.require_auth();
+fn get_extra_tokens(env: &Env) -> Vec<TokenRate> {
+    env.storage()
+        .instance()
+        .get(&DataKey::ExtraTokens)
+        .unwrap_or_else(|| Vec::new(env))
+}
 
-        let mut state = get_state(&env, &user).ok_or(Error::ReferrerNotRegistered)?;
+fn get_token_pending(env: &Env, user: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TokenPending(user.clone(), token.clone()))
+        .unwrap_or(0)
+}
 
-        if state.pending_reward <= 0 {
-            return Err(Error::NoPendingRewards);
+/// Accrue each extra token's reward on `amount` to `referrer`. Vesting,
+/// epoch caps, expiry and points mode only govern the main reward.
+fn credit_extra_tokens(env: &Env, referrer: &Address, amount: i128) -> Result<(), Error> {
+    for rate in get_extra_tokens(env).iter() {
+        let reward = calculate_reward(amount, rate.bps)?;
+        if reward == 0 {
+            continue;
         }
+        let pending = get_token_pending(env, referrer, &rate.token)
+            .checked_add(reward)
+            .ok_or(Error::Overflow)?;
+        set_persistent(
+            env,
+            &DataKey::TokenPending(referrer.clone(), rate.token),
+            &pending,
+        );
+    }
+    Ok(())
+}
 
-        let amount = state.pending_reward;
+fn get_milestones(env: &Env) -> Vec<Milestone> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Milestones)
+        .unwrap_or_else(|| Vec::new(env))
+}
 
-        // Set pending to zero BEFORE any potential external call (reentrancy guard)
-        state.pending_reward = 0;
-        set_state(&env, &user, &state);
+fn get_referred_volume(env: &Env, referrer: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ReferredVolume(referrer.clone()))
+        .unwrap_or(0)
+}
 
-        RewardClaimed { user, amount }.publish(&env);
+/// Add a qualifying event's `amount` to `referrer`'s referred volume and
+/// credit the bonus of every milestone this crosses, returning their sum.
+/// Each milestone is paid at most once per referrer, keyed by its kind and
+/// threshold so reconfiguring the list does not pay it again.
+fn credit_milestones(env: &Env, referrer: &Address, amount: i128) -> Result<i128, Error> {
+    let volume = get_referred_volume(env, referrer)
+        .checked_add(amount)
+        .ok_or(Error::Overflow)?;
+    set_persistent(env, &DataKey::ReferredVolume(referrer.clone()), &volume);
+
+    let active_referees = i128::from(get_active_referees(env, referrer));
+    let mut total = 0i128;
+    for milestone in get_milestones(env).iter() {
+        let reached = match milestone.kind {
+            MilestoneKind::ActiveReferees => active_referees,
+            MilestoneKind::Volume => volume,
+        };
+        let key = DataKey::MilestonePaid(referrer.clone(), milestone.kind, milestone.threshold);
+        if reached < milestone.threshold || env.storage().persistent().has(&key) {
+            continue;
+        }
+        set_persistent(env, &key, &true);
+        let state = accrue_reward(env, referrer, milestone.bonus)?;
+        set_state(env, referrer, &state);
+        if !points_mode(env) {
+            update_leaderboard(env, referrer, state.total_earned);
+        }
+        total = total.checked_add(milestone.bonus).ok_or(Error::Overflow)?;
 
-        Ok(amount)
+        MilestoneReached {
+            referrer: referrer.clone(),
+            kind: milestone.kind,
+            threshold: milestone.threshold,
+            bonus: milestone.bonus,
+        }
+        .publish(env);
     }
+    Ok(total)
+}
 
-    // -----------------------------------------------------------------------
-    // View / query functions
-    // -----------------------------------------------------------------------
+fn payout_address(env: &Env, user: &Address) -> Address {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PayoutAddress(user.clone()))
+        .unwrap_or_else(|| user.clone())
+}
 
-    /// Return the full referral state for a user.
-    pub fn referral_state(env: Env, user: Address) -> Result<ReferralState, Error> {
-        get_admin(&env)?; // ensure initialized
-        get_state(&env, &user).ok_or(Error::ReferrerNotRegistered)
+/// Transfer a claimed `amount` to `user`'s payout address when a reward
+/// token is configured, drawing on the reward contract's allowance to this
+/// contract, and return the recipient. Without a token the claim is
+/// accounting-only and settled off-chain.
+fn pay_reward(env: &Env, user: &Address, amount: i128) -> Result<Address, Error> {
+    let recipient = payout_address(env, user);
+    let claimed = get_i128(env, &DataKey::ClaimedTotal)
+        .checked_add(amount)
+        .ok_or(Error::Overflow)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::ClaimedTotal, &claimed);
+
+    let token: Option<Address> = env.storage().instance().get(&DataKey::RewardToken);
+    if let Some(token) = token {
+        if amount > 0 {
+            let reward_contract: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::RewardContract)
+                .ok_or(Error::NotInitialized)?;
+            TokenClient::new(env, &token).transfer_from(
+                &env.current_contract_address(),
+                &reward_contract,
+                &recipient,
+                &amount,
+            );
+        }
     }
+    Ok(recipient)
+}
 
-    /// Return the referrer of a user, if any.
-    pub fn get_referrer(env: Env, user: Address) -> Option<Address> {
-        env.storage().persistent().get(&DataKey::ReferredBy(user))
+fn get_leaderboard(env: &Env) -> Vec<LeaderboardEntry> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Leaderboard)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Move `referrer` to its rank for `total`, keeping `board` sorted in
+/// descending order and at most `size` long, and return whether the board
+/// changed. On a tie the referrer who reached the amount first ranks higher.
+fn rank_entry(
+    board: &mut Vec<LeaderboardEntry>,
+    referrer: &Address,
+    total: i128,
+    size: u32,
+) -> bool {
+    let listed = board.iter().position(|e| e.referrer == *referrer);
+    if let Some(index) = listed {
+        board.remove(index as u32);
+    }
+    let rank = board
+        .iter()
+        .position(|e| e.total_earned < total)
+        .map_or(board.len(), |i| i as u32);
+    if rank >= size {
+        // A referrer whose total dropped off the board is removed.
+        return listed.is_some();
     }
+    board.insert(
+        rank,
+        LeaderboardEntry {
+            referrer: referrer.clone(),
+            total_earned: total,
+        },
+    );
+    while board.len() > size {
+        board.pop_back();
+    }
+    true
+}
 
-    /// Return the reward contract address.
-    pub fn get_reward_contract(env: Env) -> Result<Address, Error> {
+fn update_leaderboard(env: &Env, referrer: &Address, total_earned: i128) {
+    let mut board = get_leaderboard(env);
+    if rank_entry(&mut board, referrer, total_earned, LEADERBOARD_SIZE) {
+        env.storage().instance().set(&DataKey::Leaderboard, &board);
+    }
+}
+
+fn get_season(env: &Env, season_id: u32) -> Result<Season, Error> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Season(season_id))
+        .ok_or(Error::SeasonNotFound)
+}
+
+fn get_season_score(env: &Env, season_id: u32, referrer: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SeasonScore(season_id, referrer.clone()))
+        .unwrap_or(0)
+}
+
+fn get_season_board(env: &Env, season_id: u32) -> Vec<LeaderboardEntry> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SeasonBoard(season_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Add `reward` to `referrer`'s score in the running season, if any.
+fn add_season_score(env: &Env, referrer: &Address, reward: i128) -> Result<(), Error> {
+    let season_id: Option<u32> = env.storage().instance().get(&DataKey::CurrentSeason);
+    let Some(season_id) = season_id else {
+        return Ok(());
+    };
+    let season = get_season(env, season_id)?;
+    if env.ledger().timestamp() >= season.end_time {
+        return Ok(());
+    }
+    let score = get_season_score(env, season_id, referrer)
+        .checked_add(reward)
+        .ok_or(Error::Overflow)?;
+    set_persistent(
+        env,
+        &DataKey::SeasonScore(season_id, referrer.clone()),
+        &score,
+    );
+    let mut board = get_season_board(env, season_id);
+    if rank_entry(&mut board, referrer, score, season.top_n) {
+        set_persistent(env, &DataKey::SeasonBoard(season_id), &board);
+    }
+    Ok(())
+}
+
+fn get_epoch_cap(env: &Env) -> Option<EpochCapConfig> {
+    env.storage()
+        .instance()
+        .get::<DataKey, EpochCapConfig>(&DataKey::EpochCap)
+        .filter(|c| c.cap > 0)
+}
+
+/// `referrer`'s earnings in the current epoch, or `None` without a cap.
+fn current_epoch_earnings(
+    env: &Env,
+    referrer: &Address,
+) -> Option<(EpochCapConfig, EpochEarnings)> {
+    let config = get_epoch_cap(env)?;
+    let epoch = env.ledger().timestamp() / config.epoch_seconds;
+    let earnings = env
+        .storage()
+        .persistent()
+        .get::<DataKey, EpochEarnings>(&DataKey::EpochEarned(referrer.clone()))
+        .filter(|e| e.epoch == epoch)
+        .unwrap_or(EpochEarnings { epoch, earned: 0 });
+    Some((config, earnings))
+}
+
+/// Part of `reward` that fits under `config`'s cap given `earnings`.
+fn capped_reward(config: &EpochCapConfig, earnings: &EpochEarnings, reward: i128) -> i128 {
+    reward.min(config.cap.saturating_sub(earnings.earned).max(0))
+}
+
+/// Clamp `reward` to what `referrer` may still earn this epoch without
+/// recording anything. Used by `estimate_reward`.
+fn preview_epoch_cap(env: &Env, referrer: &Address, reward: i128) -> i128 {
+    match current_epoch_earnings(env, referrer) {
+        Some((config, earnings)) => capped_reward(&config, &earnings, reward),
+        None => reward,
+    }
+}
+
+/// Clamp `reward` to what `referrer` may still earn this epoch and add it
+/// to their epoch accumulator. Emits `CapReached` when part of the reward
+/// is withheld. Returns the amount to credit.
+fn apply_epoch_cap(env: &Env, referrer: &Address, reward: i128) -> Result<i128, Error> {
+    let Some((config, mut earnings)) = current_epoch_earnings(env, referrer) else {
+        return Ok(reward);
+    };
+    let credited = capped_reward(&config, &earnings, reward);
+    if credited < reward {
+        CapReached {
+            referrer: referrer.clone(),
+            epoch: earnings.epoch,
+            cap: config.cap,
+            withheld: reward - credited,
+        }
+        .publish(env);
+    }
+    earnings.earned = earnings
+        .earned
+        .checked_add(credited)
+        .ok_or(Error::Overflow)?;
+    set_persistent(env, &DataKey::EpochEarned(referrer.clone()), &earnings);
+    Ok(credited)
+}
+
+fn get_reward_expiry(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::RewardExpiry)
+        .unwrap_or(0)
+}
+
+/// Ledger at which `referrer`'s pending rewards expire, or `None` when no
+/// expiry is set, nothing is pending, or no accrual has been recorded.
+fn expiry_ledger(env: &Env, referrer: &Address) -> Option<u32> {
+    let expiry = get_reward_expiry(env);
+    if expiry == 0 {
+        return None;
+    }
+    let pending = get_state(env, referrer).map_or(0, |s| s.pending_reward);
+    if pending <= 0 {
+        return None;
+    }
+    let last_accrual: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::LastAccrual(referrer.clone()))?;
+    Some(last_accrual.saturating_add(expiry))
+}
+
+fn get_upline_bps(env: &Env) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get(&DataKey::UplineBps)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// The referrers above `referrer` in `user`'s chain, one level per
+/// configured upline rate, with what each earns on an event of `amount`
+/// before the epoch cap (nothing while banned). The walk stops at the first
+/// account without a referrer, and at an account already in the chain so a
+/// referral cycle cannot pay anyone twice. Shared by `credit_upline` and
+/// `estimate_reward`.
+fn upline_rewards(
+    env: &Env,
+    user: Option<&Address>,
+    referrer: &Address,
+    amount: i128,
+) -> Result<Vec<(Address, i128)>, Error> {
+    let mut chain = Vec::from_array(env, [referrer.clone()]);
+    if let Some(user) = user {
+        chain.push_back(user.clone());
+    }
+    let mut current = referrer.clone();
+    let mut rewards = Vec::new(env);
+    for bps in get_upline_bps(env).iter() {
+        let upline: Address = match env
+            .storage()
+            .persistent()
+            .get(&DataKey::ReferredBy(current.clone()))
+        {
+            Some(upline) => upline,
+            None => break,
+        };
+        if chain.contains(&upline) {
+            break;
+        }
+        let reward = if is_banned(env, &upline) {
+            0
+        } else {
+            calculate_reward(amount, bps)?
+        };
+        rewards.push_back((upline.clone(), reward));
+        chain.push_back(upline.clone());
+        current = upline;
+    }
+    Ok(rewards)
+}
+
+/// Credit the referrers above `referrer` in `user`'s chain at the upline
+/// rates (see `upline_rewards`). Returns the total credited.
+fn credit_upline(
+    env: &Env,
+    user: &Address,
+    referrer: &Address,
+    amount: i128,
+) -> Result<i128, Error> {
+    let mut total: i128 = 0;
+    for (i, (upline, reward)) in upline_rewards(env, Some(user), referrer, amount)?
+        .iter()
+        .enumerate()
+    {
+        let reward = if reward > 0 {
+            apply_epoch_cap(env, &upline, reward)?
+        } else {
+            0
+        };
+        credit_referrer(env, &upline, reward)?;
+        total = total.checked_add(reward).ok_or(Error::Overflow)?;
+
+        UplineRewardCredited {
+            user: user.clone(),
+            referrer: upline.clone(),
+            level: i as u32 + 2,
+            reward,
+        }
+        .publish(env);
+    }
+    Ok(total)
+}
+
+fn add_to_totals(totals: &mut ProgramTotals, amount: i128, reward: i128) -> Result<(), Error> {
+    totals.event_count = totals.event_count.checked_add(1).ok_or(Error::Overflow)?;
+    totals.volume = totals.volume.checked_add(amount).ok_or(Error::Overflow)?;
+    totals.rewards = totals.rewards.checked_add(reward).ok_or(Error::Overflow)?;
+    Ok(())
+}
+
+fn get_i128(env: &Env, key: &DataKey) -> i128 {
+    env.storage().instance().get(key).unwrap_or(0)
+}
+
+fn count_registration(env: &Env) -> Result<(), Error> {
+    let count: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::RegisteredUsers)
+        .unwrap_or(0);
+    let count = count.checked_add(1).ok_or(Error::Overflow)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::RegisteredUsers, &count);
+    Ok(())
+}
+
+fn get_global_totals(env: &Env) -> ProgramTotals {
+    env.storage()
+        .instance()
+        .get(&DataKey::GlobalTotals)
+        .unwrap_or_default()
+}
+
+fn add_to_global_totals(env: &Env, amount: i128, reward: i128) -> Result<(), Error> {
+    let mut totals = get_global_totals(env);
+    add_to_totals(&mut totals, amount, reward)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::GlobalTotals, &totals);
+    Ok(())
+}
+
+fn get_cohort(env: &Env, cohort: u64) -> CohortStats {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Cohort(cohort))
+        .unwrap_or_default()
+}
+
+/// Count `user`'s registration in the current week's cohort.
+fn join_cohort(env: &Env, user: &Address) -> Result<(), Error> {
+    let cohort = env.ledger().timestamp() / COHORT_SECONDS;
+    let mut stats = get_cohort(env, cohort);
+    stats.registrations = stats.registrations.checked_add(1).ok_or(Error::Overflow)?;
+    set_persistent(env, &DataKey::Cohort(cohort), &stats);
+    set_persistent(
+        env,
+        &DataKey::RefereeCohort(user.clone()),
+        &CohortMembership {
+            cohort,
+            active: false,
+        },
+    );
+    Ok(())
+}
+
+/// Roll an event for `user` into their cohort. Referees registered before
+/// cohorts were tracked have no membership and are skipped.
+fn record_cohort_activity(env: &Env, user: &Address, reward: i128) -> Result<(), Error> {
+    let key = DataKey::RefereeCohort(user.clone());
+    let mut membership: CohortMembership = match env.storage().persistent().get(&key) {
+        Some(membership) => membership,
+        None => return Ok(()),
+    };
+    let mut stats = get_cohort(env, membership.cohort);
+    if !membership.active {
+        membership.active = true;
+        stats.active_referees = stats
+            .active_referees
+            .checked_add(1)
+            .ok_or(Error::Overflow)?;
+        set_persistent(env, &key, &membership);
+    }
+    stats.rewards = stats.rewards.checked_add(reward).ok_or(Error::Overflow)?;
+    set_persistent(env, &DataKey::Cohort(membership.cohort), &stats);
+    Ok(())
+}
+
+fn get_sub_program(env: &Env, program_id: u32) -> Result<SubProgram, Error> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SubProgram(program_id))
+        .ok_or(Error::SubProgramNotFound)
+}
+
+fn get_referrer_of(env: &Env, user: &Address) -> Result<Address, Error> {
+    let referrer: Address = env
+        .storage()
+        .persistent()
+        .get(&DataKey::ReferredBy(user.clone()))
+        .ok_or(Error::ReferrerNotRegistered)?;
+    bump_referred_by(env, user);
+    Ok(referrer)
+}
+
+/// Shared body of `register_referrer` and `register_with_code`; the caller
+/// checks initialization and `user`'s auth.
+fn link_referrer(env: &Env, user: Address, referrer: Address) -> Result<(), Error> {
+    // Guard: self-referral
+    if user == referrer {
+        return Err(Error::SelfReferral);
+    }
+
+    // Guard: already referred, now or before a `forget_me`
+    let referred_key = DataKey::ReferredBy(user.clone());
+    if env.storage().persistent().has(&referred_key)
+        || env
+            .storage()
+            .persistent()
+            .has(&DataKey::Forgotten(user.clone()))
+    {
+        return Err(Error::AlreadyReferred);
+    }
+
+    // Store referee → referrer mapping
+    env.storage().persistent().set(&referred_key, &referrer);
+    env.storage().persistent().extend_ttl(
+        &referred_key,
+        PERSISTENT_BUMP_LEDGERS,
+        PERSISTENT_BUMP_LEDGERS,
+    );
+
+    // Initialize user state if first interaction
+    let user_state = get_state(env, &user).unwrap_or(ReferralState {
+        referrer: referrer.clone(),
+        referees: Vec::new(env),
+        total_earned: 0,
+        pending_reward: 0,
+        event_count: 0,
+    });
+    let user_state = ReferralState {
+        referrer: referrer.clone(),
+        ..user_state
+    };
+    set_state(env, &user, &user_state);
+
+    // Update referrer's referee list
+    let mut referrer_state = get_state(env, &referrer).unwrap_or(ReferralState {
+        referrer: referrer.clone(), // placeholder, referrer may not have a referrer
+        referees: Vec::new(env),
+        total_earned: 0,
+        pending_reward: 0,
+        event_count: 0,
+    });
+    referrer_state.referees.push_back(user.clone());
+    set_state(env, &referrer, &referrer_state);
+    join_cohort(env, &user)?;
+    count_registration(env)?;
+
+    ReferrerRegistered { user, referrer }.publish(env);
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Contract
+// ---------------------------------------------------------------------------
+
+#[contract]
+pub struct ReferralSystem;
+
+#[contractimpl]
+impl ReferralSystem {
+    // -----------------------------------------------------------------------
+    // Initialization
+    // -----------------------------------------------------------------------
+
+    /// Initializes the referral system contract.
+    ///
+    /// * `admin`           – address that can call privileged methods.
+    /// * `reward_contract` – address of the contract/account that funds rewards.
+    pub fn init(env: Env, admin: Address, reward_contract: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage()
             .instance()
-            .get(&DataKey::RewardContract)
-            .ok_or(Error::NotInitialized)
+            .set(&DataKey::RewardContract, &reward_contract);
+        // Default reward: 5% (500 basis points).
+        let default_bps: u32 = 500;
+        env.storage()
+            .instance()
+            .set(&DataKey::RewardBps, &default_bps);
+
+        Initialized {
+            admin,
+            reward_contract,
+            reward_bps: default_bps,
+        }
+        .publish(&env);
+
+        Ok(())
     }
 
-    /// Return the current reward basis points.
-    pub fn get_reward_bps(env: Env) -> Result<u32, Error> {
-        get_reward_bps(&env)
+    // -----------------------------------------------------------------------
+    // Admin
+    // -----------------------------------------------------------------------
+
+    /// Update the reward percentage (in basis points). Admin only.
+    pub fn set_reward_bps(env: Env, admin: Address, bps: u32) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if bps > 10_000 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().instance().set(&DataKey::RewardBps, &bps);
+        Ok(())
+    }
+
+    /// Set the bonus credited to the referee of each `record_referral_event`,
+    /// in basis points of the event amount, on top of the referrer's reward.
+    /// `0` (the default) turns it off. Admin only.
+    pub fn set_referee_bps(env: Env, admin: Address, bps: u32) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if bps > 10_000 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().instance().set(&DataKey::RefereeBps, &bps);
+        Ok(())
+    }
+
+    /// Require each referee to reach a cumulative event `amount` of
+    /// `threshold` before their events accrue any reward, to make farming
+    /// with throwaway accounts costlier. The event that reaches it is
+    /// rewarded in full. `0` (the default) disables the threshold. Admin
+    /// only.
+    pub fn set_activity_threshold(env: Env, admin: Address, threshold: i128) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if threshold < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::ActivityThreshold, &threshold);
+        Ok(())
+    }
+
+    /// Update the reward contract address. Admin only.
+    pub fn set_reward_contract(
+        env: Env,
+        admin: Address,
+        reward_contract: Address,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::RewardContract, &reward_contract);
+        Ok(())
+    }
+
+    /// Set the token that claims are paid in, or `None` for accounting-only
+    /// claims settled off-chain (the default). Admin only.
+    ///
+    /// With a token set, `claim_referral_reward`, `claim_early` and
+    /// `claim_points` transfer the claimed amount from the reward contract to
+    /// the user with `transfer_from` in the same call, so the reward contract
+    /// must keep an allowance for this contract.
+    pub fn set_reward_token(env: Env, admin: Address, token: Option<Address>) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        match &token {
+            Some(token) => env.storage().instance().set(&DataKey::RewardToken, token),
+            None => env.storage().instance().remove(&DataKey::RewardToken),
+        }
+
+        RewardTokenSet { token }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Set the rate tiers direct referrers are upgraded to as their referees
+    /// become active (produce their first event past any activity
+    /// threshold). Admin only.
+    ///
+    /// Tiers must be in strictly ascending `min_active` order with non-zero
+    /// thresholds, at most `MAX_RATE_TIERS` of them; e.g. `[(10, 700),
+    /// (50, 1000)]` over the default 500 bps pays 5%, then 7% from the 10th
+    /// active referee and 10% from the 50th. An empty list leaves everyone on
+    /// `reward_bps`.
+    pub fn set_rate_tiers(env: Env, admin: Address, tiers: Vec<RateTier>) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if tiers.len() > MAX_RATE_TIERS {
+            return Err(Error::InvalidTiers);
+        }
+        let mut last = 0;
+        for tier in tiers.iter() {
+            if tier.min_active <= last || tier.bps > 10_000 {
+                return Err(Error::InvalidTiers);
+            }
+            last = tier.min_active;
+        }
+        env.storage().instance().set(&DataKey::RateTiers, &tiers);
+        Ok(())
+    }
+
+    /// Add an extra reward token, or update its rate, so direct referrers
+    /// accrue `bps` of each `record_referral_event` amount in `token` next
+    /// to the main reward, e.g. a campaign paid in the platform token. At
+    /// most `MAX_EXTRA_TOKENS` (`InvalidAmount` beyond that, or above
+    /// 10_000 bps). Admin only.
+    ///
+    /// Balances accrued in a token stay claimable after it is removed.
+    pub fn set_extra_token(
+        env: Env,
+        admin: Address,
+        token: Address,
+        bps: u32,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if bps > 10_000 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut tokens = get_extra_tokens(&env);
+        let rate = TokenRate {
+            token: token.clone(),
+            bps,
+        };
+        match tokens.iter().position(|r| r.token == token) {
+            Some(index) => tokens.set(index as u32, rate),
+            None if tokens.len() < MAX_EXTRA_TOKENS => tokens.push_back(rate),
+            None => return Err(Error::InvalidAmount),
+        }
+        env.storage().instance().set(&DataKey::ExtraTokens, &tokens);
+        Ok(())
+    }
+
+    /// Set the one-time bonuses referrers earn as they cross milestones,
+    /// e.g. `[(ActiveReferees, 1, b1), (ActiveReferees, 10, b2), (Volume,
+    /// 1_000_000, b3)]`. Admin only.
+    ///
+    /// Bonuses are credited to the referrer's pending balance by the
+    /// `record_referral_event` that crosses the threshold and are not
+    /// subject to the epoch cap. Only qualifying events of non-banned
+    /// referrers count. At most `MAX_MILESTONES`, each with a positive
+    /// threshold and bonus (`InvalidAmount` otherwise). An empty list turns
+    /// milestone bonuses off.
+    pub fn set_milestones(
+        env: Env,
+        admin: Address,
+        milestones: Vec<Milestone>,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if milestones.len() > MAX_MILESTONES
+            || milestones.iter().any(|m| m.threshold <= 0 || m.bonus <= 0)
+        {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Milestones, &milestones);
+        Ok(())
+    }
+
+    /// Stop accruing `token`. Admin only.
+    pub fn remove_extra_token(env: Env, admin: Address, token: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        let mut tokens = get_extra_tokens(&env);
+        let index = tokens
+            .iter()
+            .position(|r| r.token == token)
+            .ok_or(Error::TokenNotConfigured)?;
+        tokens.remove(index as u32);
+        env.storage().instance().set(&DataKey::ExtraTokens, &tokens);
+        Ok(())
+    }
+
+    /// Set the reward rates for referrers above the direct referrer. Admin
+    /// only.
+    ///
+    /// `bps[0]` is paid to the referrer's referrer (level 2), `bps[1]` to
+    /// the level above that, up to `MAX_REFERRAL_LEVELS` in total; e.g.
+    /// `[200, 50]` with the default 500 bps gives 500/200/50. An empty list
+    /// turns multi-level rewards off.
+    pub fn set_upline_bps(env: Env, admin: Address, bps: Vec<u32>) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if bps.len() >= MAX_REFERRAL_LEVELS {
+            return Err(Error::TooManyLevels);
+        }
+        if bps.iter().any(|level_bps| level_bps > 10_000) {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().instance().set(&DataKey::UplineBps, &bps);
+        Ok(())
+    }
+
+    /// Allow or disallow a game contract to call `record_referral_event`
+    /// directly, so gameplay credits referrers without an off-chain bridge.
+    /// Admin only.
+    pub fn set_game_reporter(
+        env: Env,
+        admin: Address,
+        game: Address,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        let key = DataKey::GameReporter(game.clone());
+        if allowed {
+            set_persistent(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        GameReporterSet { game, allowed }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Grant `operator` the right to call `record_referral_event`, e.g. an
+    /// off-chain game server. Operators hold no other privileges: rates,
+    /// the reward contract and every other setting stay admin only.
+    pub fn add_operator(env: Env, admin: Address, operator: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        set_persistent(&env, &DataKey::Operator(operator.clone()), &true);
+
+        OperatorSet {
+            operator,
+            allowed: true,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Revoke an operator granted with `add_operator`. Admin only.
+    pub fn remove_operator(env: Env, admin: Address, operator: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Operator(operator.clone()));
+
+        OperatorSet {
+            operator,
+            allowed: false,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Ban `referrer` after fraud or sybil abuse is discovered. Admin only.
+    ///
+    /// Their pending rewards and points are frozen: every claim fails with
+    /// `ReferrerBanned` until `unban_referrer`. Events from their referees
+    /// are still recorded but credit them nothing, at any level.
+    pub fn ban_referrer(env: Env, admin: Address, referrer: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        set_persistent(&env, &DataKey::Banned(referrer.clone()), &true);
+
+        BanSet {
+            referrer,
+            banned: true,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Lift a ban set with `ban_referrer`. Admin only. Frozen rewards become
+    /// claimable again; rewards withheld while banned are not restored.
+    pub fn unban_referrer(env: Env, admin: Address, referrer: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Banned(referrer.clone()));
+
+        BanSet {
+            referrer,
+            banned: false,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Switch between token accrual and points accrual. Admin only.
+    ///
+    /// While points mode is on, events credit points at the usual rates
+    /// instead of pending tokens; `claim_points` converts them at whatever
+    /// rate is set at claim time. Points already accrued are kept when the
+    /// mode is switched off.
+    pub fn set_points_mode(env: Env, admin: Address, enabled: bool) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::PointsMode, &enabled);
+        Ok(())
+    }
+
+    /// Set the points conversion rate, in tokens per `POINTS_RATE_SCALE`
+    /// points. Admin only; meant to be updated periodically to keep the
+    /// reward budget steady as the token price moves.
+    pub fn set_points_rate(env: Env, admin: Address, rate: i128) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if rate <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().instance().set(&DataKey::PointsRate, &rate);
+
+        PointsRateSet { rate }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Configure reward vesting. Admin only.
+    ///
+    /// A `duration` of 0 disables vesting. While vesting is active,
+    /// `claim_referral_reward` only succeeds once pending rewards are fully
+    /// vested; `claim_early` pays out immediately but forfeits
+    /// `early_claim_penalty_bps` of the unvested part to the reward pool.
+    pub fn set_vesting(
+        env: Env,
+        admin: Address,
+        cliff: u64,
+        duration: u64,
+        early_claim_penalty_bps: u32,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if early_claim_penalty_bps > 10_000 || cliff > duration {
+            return Err(Error::InvalidAmount);
+        }
+        if duration == 0 {
+            env.storage().instance().remove(&DataKey::VestingSince);
+        } else if get_vesting(&env).is_none() {
+            env.storage()
+                .instance()
+                .set(&DataKey::VestingSince, &env.ledger().timestamp());
+        }
+        env.storage().instance().set(
+            &DataKey::Vesting,
+            &VestingConfig {
+                cliff,
+                duration,
+                early_claim_penalty_bps,
+            },
+        );
+        Ok(())
+    }
+
+    /// Cap what each referrer can earn from `record_referral_event` per
+    /// epoch of `epoch_seconds`. Admin only.
+    ///
+    /// A `cap` of 0 disables the cap. Rewards beyond it are withheld, not
+    /// deferred; the accumulator starts over each epoch.
+    pub fn set_epoch_cap(
+        env: Env,
+        admin: Address,
+        cap: i128,
+        epoch_seconds: u64,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if cap < 0 || (cap > 0 && epoch_seconds == 0) {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::EpochCap, &EpochCapConfig { cap, epoch_seconds });
+        Ok(())
+    }
+
+    /// Let pending rewards expire `ledgers` after the referrer's latest
+    /// accrual if they have not been claimed by then. Admin only.
+    ///
+    /// A value of 0 disables expiry. Points do not expire.
+    pub fn set_reward_expiry(env: Env, admin: Address, ledgers: u32) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::RewardExpiry, &ledgers);
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Referral registration
+    // -----------------------------------------------------------------------
+
+    /// Register `referrer` as the referrer of `user`.
+    ///
+    /// * Both `user` and `referrer` must authorize the call.
+    /// * A user cannot refer themselves.
+    /// * A user can only be referred once.
+    pub fn register_referrer(env: Env, user: Address, referrer: Address) -> Result<(), Error> {
+        get_admin(&env)?; // ensure initialized
+
+        user.require_auth();
+
+        link_referrer(&env, user, referrer)
+    }
+
+    /// Claim `code` as `referrer`'s referral code, so new users can sign up
+    /// with `register_with_code` instead of the referrer's address.
+    ///
+    /// * `referrer` must authorize the call.
+    /// * Codes are unique, and each referrer may hold one code.
+    pub fn create_referral_code(env: Env, referrer: Address, code: Symbol) -> Result<(), Error> {
+        get_admin(&env)?; // ensure initialized
+
+        referrer.require_auth();
+
+        let owner_key = DataKey::CodeOwner(code.clone());
+        if env.storage().persistent().has(&owner_key) {
+            return Err(Error::CodeTaken);
+        }
+        let code_key = DataKey::ReferralCode(referrer.clone());
+        if env.storage().persistent().has(&code_key) {
+            return Err(Error::AlreadyHasCode);
+        }
+
+        set_persistent(&env, &owner_key, &referrer);
+        set_persistent(&env, &code_key, &code);
+
+        ReferralCodeCreated { referrer, code }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Register the owner of `code` as the referrer of `user`.
+    ///
+    /// Same rules as `register_referrer`; fails with `CodeNotFound` if no
+    /// referrer holds `code`.
+    pub fn register_with_code(env: Env, user: Address, code: Symbol) -> Result<(), Error> {
+        get_admin(&env)?; // ensure initialized
+
+        user.require_auth();
+
+        let referrer: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CodeOwner(code))
+            .ok_or(Error::CodeNotFound)?;
+        link_referrer(&env, user, referrer)
+    }
+
+    /// Unlink `user` from their referrer at the user's request.
+    ///
+    /// Removes the `ReferredBy` mapping, strips `user` from the referrer's
+    /// referee list, and clears the referrer from the user's own state.
+    /// Aggregate totals (`total_earned`, `pending_reward`, `event_count`) on
+    /// both sides are preserved so reward accounting stays intact. Future
+    /// events for `user` no longer credit the former referrer. A tombstone
+    /// keeps `user` from registering again, so forgetting cannot be used to
+    /// move a referee to a new referrer.
+    pub fn forget_me(env: Env, user: Address) -> Result<(), Error> {
+        get_admin(&env)?; // ensure initialized
+        user.require_auth();
+
+        let referred_key = DataKey::ReferredBy(user.clone());
+        let referrer: Address = env
+            .storage()
+            .persistent()
+            .get(&referred_key)
+            .ok_or(Error::ReferrerNotRegistered)?;
+        env.storage().persistent().remove(&referred_key);
+        set_persistent(&env, &DataKey::Forgotten(user.clone()), &true);
+
+        if let Some(mut referrer_state) = get_state(&env, &referrer) {
+            if let Some(index) = referrer_state.referees.first_index_of(&user) {
+                referrer_state.referees.remove(index);
+                set_state(&env, &referrer, &referrer_state);
+            }
+        }
+
+        if let Some(mut user_state) = get_state(&env, &user) {
+            // Self-reference is the "no referrer" placeholder.
+            user_state.referrer = user.clone();
+            set_state(&env, &user, &user_state);
+        }
+
+        UserForgotten { user }.publish(&env);
+
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Referral events
+    // -----------------------------------------------------------------------
+
+    /// Record a referral event for `user`.
+    ///
+    /// Called by the admin, an operator, or a game contract allowed with
+    /// `set_game_reporter`, when a qualifying action occurs (e.g. game
+    /// played, deposit made). The `amount` is the transaction value and the
+    /// reward is computed as `amount * reward_bps / 10_000`.
+    ///
+    /// The reward is credited to the **referrer** of `user`. When upline
+    /// rates are set, the referrers above them in the chain are credited at
+    /// those rates too (see `set_upline_bps`), and with a `referee_bps` set
+    /// `user` earns a bonus of their own (see `set_referee_bps`).
+    pub fn record_referral_event(
+        env: Env,
+        caller: Address,
+        user: Address,
+        event_type: EventType,
+        amount: i128,
+    ) -> Result<(), Error> {
+        require_reporter(&env, &caller)?;
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // Lookup user's referrer
+        let referrer = get_referrer_of(&env, &user)?;
+        let qualified = track_referee_volume(&env, &user, amount)?;
+        if qualified {
+            mark_referee_active(&env, &user, &referrer)?;
+        }
+
+        // Calculate reward; nothing accrues until the referee qualifies.
+        let reward = if qualified {
+            compute_reward(&env, &referrer, None, amount)?
+        } else {
+            0
+        };
+        let reward = apply_epoch_cap(&env, &referrer, reward)?;
+
+        credit_referrer(&env, &referrer, reward)?;
+        let milestone_bonus = if qualified && !is_banned(&env, &referrer) {
+            credit_extra_tokens(&env, &referrer, amount)?;
+            credit_milestones(&env, &referrer, amount)?
+        } else {
+            0
+        };
+        let (upline_reward, referee_bonus) = if qualified {
+            (
+                credit_upline(&env, &user, &referrer, amount)?,
+                credit_referee(&env, &user, amount)?,
+            )
+        } else {
+            (0, 0)
+        };
+        let total_reward = reward
+            .checked_add(upline_reward)
+            .and_then(|total| total.checked_add(referee_bonus))
+            .and_then(|total| total.checked_add(milestone_bonus))
+            .ok_or(Error::Overflow)?;
+        add_to_global_totals(&env, amount, total_reward)?;
+        record_cohort_activity(&env, &user, total_reward)?;
+
+        ReferralEventRecorded {
+            user,
+            referrer,
+            event_type,
+            amount,
+            reward,
+            referee_bonus,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Partner sub-programs
+    // -----------------------------------------------------------------------
+
+    /// Create a partner sub-program. Admin only.
+    ///
+    /// `operator` may record events for users tagged to the sub-program;
+    /// rewards are computed at `reward_bps` and capped in total by `budget`.
+    /// Returns the new sub-program id.
+    pub fn create_sub_program(
+        env: Env,
+        admin: Address,
+        operator: Address,
+        reward_bps: u32,
+        budget: i128,
+    ) -> Result<u32, Error> {
+        require_admin(&env, &admin)?;
+        if reward_bps > 10_000 || budget < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let program_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SubProgramCount)
+            .unwrap_or(0);
+        set_persistent(
+            &env,
+            &DataKey::SubProgram(program_id),
+            &SubProgram {
+                operator: operator.clone(),
+                reward_bps,
+                budget,
+                totals: ProgramTotals::default(),
+            },
+        );
+        env.storage().instance().set(
+            &DataKey::SubProgramCount,
+            &program_id.checked_add(1).ok_or(Error::Overflow)?,
+        );
+
+        SubProgramCreated {
+            program_id,
+            operator,
+            reward_bps,
+            budget,
+        }
+        .publish(&env);
+
+        Ok(program_id)
+    }
+
+    /// Tag `user` to a sub-program so its operator can record their events.
+    /// Admin only. Re-tagging moves the user to the new sub-program.
+    pub fn tag_user(env: Env, admin: Address, user: Address, program_id: u32) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        get_sub_program(&env, program_id)?;
+        set_persistent(&env, &DataKey::UserProgram(user.clone()), &program_id);
+
+        UserTagged { user, program_id }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Record a referral event for a user tagged to `program_id`.
+    ///
+    /// `operator` must be the sub-program's operator. The reward is
+    /// `amount * reward_bps / 10_000` at the sub-program's rate, is credited
+    /// to the user's referrer, and fails with `BudgetExceeded` if it would
+    /// take the sub-program past its budget. The event counts towards both
+    /// the sub-program's and the global totals.
+    pub fn record_partner_event(
+        env: Env,
+        operator: Address,
+        program_id: u32,
+        user: Address,
+        event_type: EventType,
+        amount: i128,
+    ) -> Result<(), Error> {
+        get_admin(&env)?; // ensure initialized
+        operator.require_auth();
+
+        let mut program = get_sub_program(&env, program_id)?;
+        if operator != program.operator {
+            return Err(Error::NotAuthorized);
+        }
+        let tagged: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::UserProgram(user.clone()));
+        if tagged != Some(program_id) {
+            return Err(Error::UserNotInProgram);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let referrer = get_referrer_of(&env, &user)?;
+        let qualified = track_referee_volume(&env, &user, amount)?;
+        if qualified {
+            mark_referee_active(&env, &user, &referrer)?;
+        }
+        let reward = if qualified {
+            compute_reward(&env, &referrer, Some(&program), amount)?
+        } else {
+            0
+        };
+        let spent = program
+            .totals
+            .rewards
+            .checked_add(reward)
+            .ok_or(Error::Overflow)?;
+        if spent > program.budget {
+            return Err(Error::BudgetExceeded);
+        }
+
+        credit_referrer(&env, &referrer, reward)?;
+        add_to_totals(&mut program.totals, amount, reward)?;
+        set_persistent(&env, &DataKey::SubProgram(program_id), &program);
+        add_to_global_totals(&env, amount, reward)?;
+        record_cohort_activity(&env, &user, reward)?;
+
+        ReferralEventRecorded {
+            user,
+            referrer,
+            event_type,
+            amount,
+            reward,
+            referee_bonus: 0,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Disputes
+    // -----------------------------------------------------------------------
+
+    /// Flag `referee`'s referral attribution as contested.
+    ///
+    /// Any user may flag a relationship; `caller` must authorize and is
+    /// recorded with the off-chain `reason_code`. A relationship can be
+    /// flagged again once an earlier dispute was dismissed, but not while
+    /// one is open or after one was upheld.
+    pub fn flag_referral(
+        env: Env,
+        caller: Address,
+        referee: Address,
+        reason_code: u32,
+    ) -> Result<(), Error> {
+        get_admin(&env)?; // ensure initialized
+
+        caller.require_auth();
+        let referrer = get_referrer_of(&env, &referee)?;
+
+        let key = DataKey::Dispute(referee.clone());
+        let existing: Option<Dispute> = env.storage().persistent().get(&key);
+        if existing.is_some_and(|d| d.status != DisputeStatus::Dismissed) {
+            return Err(Error::DisputeExists);
+        }
+
+        let dispute = Dispute {
+            referrer: referrer.clone(),
+            flagged_by: caller.clone(),
+            reason_code,
+            status: DisputeStatus::Open,
+            opened_at: env.ledger().timestamp(),
+            resolved_at: 0,
+        };
+        set_persistent(&env, &key, &dispute);
+
+        ReferralFlagged {
+            referee,
+            referrer,
+            flagged_by: caller,
+            reason_code,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Rule on an open dispute. Admin only.
+    ///
+    /// `upheld` marks the attribution as wrong, leaving the record as the
+    /// audit trail for any clawback; otherwise the dispute is dismissed.
+    pub fn resolve_dispute(
+        env: Env,
+        admin: Address,
+        referee: Address,
+        upheld: bool,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+
+        let key = DataKey::Dispute(referee.clone());
+        let mut dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::DisputeNotFound)?;
+        if dispute.status != DisputeStatus::Open {
+            return Err(Error::DisputeNotOpen);
+        }
+
+        dispute.status = if upheld {
+            DisputeStatus::Upheld
+        } else {
+            DisputeStatus::Dismissed
+        };
+        dispute.resolved_at = env.ledger().timestamp();
+        set_persistent(&env, &key, &dispute);
+
+        DisputeResolved {
+            referee,
+            referrer: dispute.referrer,
+            status: dispute.status,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Claiming rewards
+    // -----------------------------------------------------------------------
+
+    /// Route `user`'s claims to `payout` instead of their own address, or
+    /// back to themselves with `None`. Lets a referrer keep a cold wallet as
+    /// their referral identity and receive rewards elsewhere. Applies to
+    /// `claim_referral_reward`, `claim_early` and `claim_points`.
+    pub fn set_payout_address(
+        env: Env,
+        user: Address,
+        payout: Option<Address>,
+    ) -> Result<(), Error> {
+        get_admin(&env)?; // ensure initialized
+        user.require_auth();
+
+        let key = DataKey::PayoutAddress(user.clone());
+        match &payout {
+            Some(payout) if *payout != user => set_persistent(&env, &key, payout),
+            _ => env.storage().persistent().remove(&key),
+        }
+
+        PayoutAddressSet { user, payout }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Claim `user`'s pending reward in the extra `token`, transferred from
+    /// the reward contract to their payout address with `transfer_from`, so
+    /// the reward contract must keep an allowance in `token` for this
+    /// contract. Returns the amount paid.
+    pub fn claim_token_reward(env: Env, user: Address, token: Address) -> Result<i128, Error> {
+        let reward_contract: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::RewardContract)
+            .ok_or(Error::NotInitialized)?;
+        user.require_auth();
+        require_not_banned(&env, &user)?;
+
+        let amount = get_token_pending(&env, &user, &token);
+        if amount <= 0 {
+            return Err(Error::NoPendingRewards);
+        }
+        // Zero the balance BEFORE the external call (reentrancy guard)
+        env.storage()
+            .persistent()
+            .remove(&DataKey::TokenPending(user.clone(), token.clone()));
+
+        let recipient = payout_address(&env, &user);
+        TokenClient::new(&env, &token).transfer_from(
+            &env.current_contract_address(),
+            &reward_contract,
+            &recipient,
+            &amount,
+        );
+
+        TokenRewardClaimed {
+            user,
+            token,
+            recipient,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    /// Claim all pending referral rewards for `user`.
+    ///
+    /// Marks the pending balance as claimed. The actual token transfer is
+    /// expected to be handled by the reward contract integration; this method
+    /// records the accounting and emits an event for off-chain settlement or
+    /// cross-contract calls.
+    ///
+    /// When vesting is active the pending balance must be fully vested;
+    /// otherwise `RewardsVesting` is returned (see `claim_early`).
+    pub fn claim_referral_reward(env: Env, user: Address) -> Result<i128, Error> {
+        get_admin(&env)?; // ensure initialized
+        user.require_auth();
+        require_not_banned(&env, &user)?;
+
+        let mut state = get_state(&env, &user).ok_or(Error::ReferrerNotRegistered)?;
+
+        if state.pending_reward <= 0 {
+            return Err(Error::NoPendingRewards);
+        }
+
+        let amount = state.pending_reward;
+
+        if let Some(vesting) = get_vesting(&env) {
+            if vested_portion(&env, &user, amount, &vesting) < amount {
+                return Err(Error::RewardsVesting);
+            }
+        }
+
+        // Set pending to zero BEFORE any potential external call (reentrancy guard)
+        state.pending_reward = 0;
+        set_state(&env, &user, &state);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::VestStart(user.clone()));
+        let recipient = pay_reward(&env, &user, amount)?;
+
+        RewardClaimed {
+            user,
+            recipient,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    /// Reclaim `referrer`'s expired pending rewards to the reward contract.
+    /// Admin only.
+    ///
+    /// Fails with `RewardsNotExpired` until the expiry ledger has been
+    /// reached. The pending balance is zeroed and added to the expired total;
+    /// `total_earned` keeps its history. Returns the amount swept.
+    pub fn sweep_expired(env: Env, admin: Address, referrer: Address) -> Result<i128, Error> {
+        require_admin(&env, &admin)?;
+        if get_reward_expiry(&env) == 0 {
+            return Err(Error::ExpiryNotSet);
+        }
+
+        let mut state = get_state(&env, &referrer).ok_or(Error::ReferrerNotRegistered)?;
+        if state.pending_reward <= 0 {
+            return Err(Error::NoPendingRewards);
+        }
+        match expiry_ledger(&env, &referrer) {
+            Some(ledger) if env.ledger().sequence() >= ledger => {}
+            _ => return Err(Error::RewardsNotExpired),
+        }
+
+        let amount = state.pending_reward;
+        state.pending_reward = 0;
+        set_state(&env, &referrer, &state);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::VestStart(referrer.clone()));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::LastAccrual(referrer.clone()));
+
+        let expired_total: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ExpiredTotal)
+            .unwrap_or(0);
+        env.storage().instance().set(
+            &DataKey::ExpiredTotal,
+            &expired_total.checked_add(amount).ok_or(Error::Overflow)?,
+        );
+
+        let reward_contract: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::RewardContract)
+            .ok_or(Error::NotInitialized)?;
+        RewardsExpired {
+            referrer,
+            amount,
+            reward_contract,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    /// Remove `amount` from `referrer`'s pending rewards, e.g. after a
+    /// chargeback or detected abuse. Admin only.
+    ///
+    /// `reason` is an off-chain reason code recorded in the audit event.
+    /// With `reduce_earned` the amount is also taken off `total_earned` (and
+    /// the leaderboard), otherwise the lifetime history is kept. Fails with
+    /// `InvalidAmount` unless `0 < amount <= pending_reward`. Returns the
+    /// remaining pending balance.
+    pub fn clawback(
+        env: Env,
+        admin: Address,
+        referrer: Address,
+        amount: i128,
+        reason: u32,
+        reduce_earned: bool,
+    ) -> Result<i128, Error> {
+        require_admin(&env, &admin)?;
+        let mut state = get_state(&env, &referrer).ok_or(Error::ReferrerNotRegistered)?;
+        if amount <= 0 || amount > state.pending_reward {
+            return Err(Error::InvalidAmount);
+        }
+
+        state.pending_reward -= amount;
+        if reduce_earned {
+            state.total_earned = state
+                .total_earned
+                .checked_sub(amount)
+                .ok_or(Error::Overflow)?
+                .max(0);
+            update_leaderboard(&env, &referrer, state.total_earned);
+        }
+        set_state(&env, &referrer, &state);
+        if state.pending_reward == 0 {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::VestStart(referrer.clone()));
+        }
+
+        let clawed_back = get_i128(&env, &DataKey::ClawedBackTotal)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::ClawedBackTotal, &clawed_back);
+
+        RewardsClawedBack {
+            referrer,
+            amount,
+            reason,
+            reduced_earned: reduce_earned,
+            pending_reward: state.pending_reward,
+        }
+        .publish(&env);
+
+        Ok(state.pending_reward)
+    }
+
+    /// Start a referral season running until `end_time`, whose `top_n`
+    /// scorers will split its bonus pool. Admin only.
+    ///
+    /// Every reward credited to a referrer (direct, upline or partner)
+    /// while the season runs adds to their seasonal score. Only one season
+    /// runs at a time (`SeasonActive` until the current one is ended), and
+    /// `top_n` must be between 1 and `MAX_SEASON_WINNERS`. Returns the
+    /// season id.
+    pub fn start_season(env: Env, admin: Address, end_time: u64, top_n: u32) -> Result<u32, Error> {
+        require_admin(&env, &admin)?;
+        if env.storage().instance().has(&DataKey::CurrentSeason) {
+            return Err(Error::SeasonActive);
+        }
+        let now = env.ledger().timestamp();
+        if end_time <= now || top_n == 0 || top_n > MAX_SEASON_WINNERS {
+            return Err(Error::InvalidAmount);
+        }
+
+        let season_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SeasonCount)
+            .unwrap_or(0);
+        set_persistent(
+            &env,
+            &DataKey::Season(season_id),
+            &Season {
+                start_time: now,
+                end_time,
+                top_n,
+                pool: 0,
+                winning_score: 0,
+                ended: false,
+            },
+        );
+        env.storage().instance().set(
+            &DataKey::SeasonCount,
+            &season_id.checked_add(1).ok_or(Error::Overflow)?,
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::CurrentSeason, &season_id);
+
+        SeasonStarted {
+            season_id,
+            end_time,
+            top_n,
+        }
+        .publish(&env);
+
+        Ok(season_id)
+    }
+
+    /// Add `amount` to a season's bonus pool until it is ended
+    /// (`SeasonEnded` after). Admin only.
+    ///
+    /// With a reward token configured the amount is transferred from the
+    /// admin to this contract; otherwise the pool is accounting-only and
+    /// settled off-chain, like the main rewards.
+    pub fn fund_season(
+        env: Env,
+        admin: Address,
+        season_id: u32,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        require_admin(&env, &admin)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut season = get_season(&env, season_id)?;
+        if season.ended {
+            return Err(Error::SeasonEnded);
+        }
+        season.pool = season.pool.checked_add(amount).ok_or(Error::Overflow)?;
+        set_persistent(&env, &DataKey::Season(season_id), &season);
+
+        let token: Option<Address> = env.storage().instance().get(&DataKey::RewardToken);
+        if let Some(token) = token {
+            TokenClient::new(&env, &token).transfer(
+                &admin,
+                env.current_contract_address(),
+                &amount,
+            );
+        }
+
+        SeasonFunded {
+            season_id,
+            amount,
+            pool: season.pool,
+        }
+        .publish(&env);
+
+        Ok(season.pool)
+    }
+
+    /// End the running season once its `end_time` has passed, fixing its
+    /// winners (`SeasonActive` before then, `SeasonEnded` if already
+    /// ended). Anyone may call. Returns the
+    /// number of winners.
+    ///
+    /// If nobody scored, the pool stays unclaimed in this contract.
+    pub fn end_season(env: Env, season_id: u32) -> Result<u32, Error> {
+        let mut season = get_season(&env, season_id)?;
+        if season.ended {
+            return Err(Error::SeasonEnded);
+        }
+        if env.ledger().timestamp() < season.end_time {
+            return Err(Error::SeasonActive);
+        }
+
+        let board = get_season_board(&env, season_id);
+        let mut winning_score: i128 = 0;
+        for entry in board.iter() {
+            winning_score = winning_score
+                .checked_add(entry.total_earned)
+                .ok_or(Error::Overflow)?;
+        }
+        season.winning_score = winning_score;
+        season.ended = true;
+        set_persistent(&env, &DataKey::Season(season_id), &season);
+        env.storage().instance().remove(&DataKey::CurrentSeason);
+
+        SeasonEnded {
+            season_id,
+            winners: board.len(),
+            pool: season.pool,
+        }
+        .publish(&env);
+
+        Ok(board.len())
+    }
+
+    /// Claim a season winner's share of the bonus pool,
+    /// `pool * score / winning_score`, paid to their payout address. The
+    /// winner must authorize.
+    ///
+    /// Fails with `SeasonActive` before `end_season`, `NoPendingRewards`
+    /// for referrers outside the top `top_n`, and `AlreadyClaimed` on a
+    /// second claim.
+    pub fn claim_season_reward(env: Env, user: Address, season_id: u32) -> Result<i128, Error> {
+        user.require_auth();
+        require_not_banned(&env, &user)?;
+        let season = get_season(&env, season_id)?;
+        if !season.ended {
+            return Err(Error::SeasonActive);
+        }
+        let claimed_key = DataKey::SeasonClaimed(season_id, user.clone());
+        if env.storage().persistent().has(&claimed_key) {
+            return Err(Error::AlreadyClaimed);
+        }
+        let score = get_season_board(&env, season_id)
+            .iter()
+            .find(|e| e.referrer == user)
+            .map(|e| e.total_earned)
+            .ok_or(Error::NoPendingRewards)?;
+        let amount = season.pool.checked_mul(score).ok_or(Error::Overflow)? / season.winning_score;
+        set_persistent(&env, &claimed_key, &true);
+
+        let recipient = payout_address(&env, &user);
+        let token: Option<Address> = env.storage().instance().get(&DataKey::RewardToken);
+        if let Some(token) = token {
+            if amount > 0 {
+                TokenClient::new(&env, &token).transfer(
+                    &env.current_contract_address(),
+                    &recipient,
+                    &amount,
+                );
+            }
+        }
+
+        SeasonRewardClaimed {
+            season_id,
+            referrer: user,
+            recipient,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    /// Convert all of `user`'s points to tokens at the current points rate.
+    ///
+    /// Points are zeroed before the payout is recorded, and the converted
+    /// amount is added to `total_earned`. Vesting does not apply to points.
+    /// Returns the token amount.
+    pub fn claim_points(env: Env, user: Address) -> Result<i128, Error> {
+        get_admin(&env)?; // ensure initialized
+        user.require_auth();
+        require_not_banned(&env, &user)?;
+
+        let points = get_points(&env, &user);
+        if points <= 0 {
+            return Err(Error::NoPendingRewards);
+        }
+        let rate: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PointsRate)
+            .ok_or(Error::PointsRateNotSet)?;
+        let amount = points
+            .checked_mul(rate)
+            .and_then(|v| v.checked_div(POINTS_RATE_SCALE))
+            .ok_or(Error::Overflow)?;
+
+        // Zero the points BEFORE any potential external call (reentrancy guard)
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Points(user.clone()));
+        let mut state = get_state(&env, &user).ok_or(Error::ReferrerNotRegistered)?;
+        state.total_earned = state
+            .total_earned
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        set_state(&env, &user, &state);
+        update_leaderboard(&env, &user, state.total_earned);
+        pay_reward(&env, &user, amount)?;
+
+        PointsClaimed {
+            user,
+            points,
+            rate,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    /// Claim all pending rewards before they have fully vested.
+    ///
+    /// Only available while vesting is active. The vested part is paid in
+    /// full; `early_claim_penalty_bps` of the unvested part is forfeited back
+    /// to the reward pool. Each early claim is recorded and can be read back
+    /// with `get_early_claim`. Returns the amount paid out.
+    pub fn claim_early(env: Env, user: Address) -> Result<i128, Error> {
+        get_admin(&env)?; // ensure initialized
+        user.require_auth();
+        require_not_banned(&env, &user)?;
+
+        let vesting = get_vesting(&env).ok_or(Error::VestingNotActive)?;
+        let mut state = get_state(&env, &user).ok_or(Error::ReferrerNotRegistered)?;
+
+        if state.pending_reward <= 0 {
+            return Err(Error::NoPendingRewards);
+        }
+
+        let pending = state.pending_reward;
+        let vested = vested_portion(&env, &user, pending, &vesting);
+        let unvested = pending.checked_sub(vested).ok_or(Error::Overflow)?;
+        let forfeited = calculate_reward(unvested, vesting.early_claim_penalty_bps)?;
+        let amount = pending.checked_sub(forfeited).ok_or(Error::Overflow)?;
+
+        // Set pending to zero BEFORE any potential external call (reentrancy guard)
+        state.pending_reward = 0;
+        set_state(&env, &user, &state);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::VestStart(user.clone()));
+
+        let forfeited_total: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ForfeitedTotal)
+            .unwrap_or(0);
+        env.storage().instance().set(
+            &DataKey::ForfeitedTotal,
+            &forfeited_total
+                .checked_add(forfeited)
+                .ok_or(Error::Overflow)?,
+        );
+
+        let count_key = DataKey::EarlyClaimCount(user.clone());
+        let index: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        set_persistent(
+            &env,
+            &DataKey::EarlyClaim(user.clone(), index),
+            &EarlyClaimRecord {
+                timestamp: env.ledger().timestamp(),
+                claimed: amount,
+                forfeited,
+            },
+        );
+        set_persistent(
+            &env,
+            &count_key,
+            &index.checked_add(1).ok_or(Error::Overflow)?,
+        );
+        pay_reward(&env, &user, amount)?;
+
+        EarlyRewardClaimed {
+            user,
+            amount,
+            forfeited,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    // -----------------------------------------------------------------------
+    // Migration
+    // -----------------------------------------------------------------------
+
+    /// Import referral states exported from a v1 deployment. Admin only,
+    /// until `complete_migration` (`MigrationClosed` afterwards), and at most
+    /// `MAX_MIGRATION_BATCH` users per call.
+    ///
+    /// Each user is imported once: users already migrated are skipped, so a
+    /// failed or repeated batch can simply be resubmitted. A v1 balance is
+    /// added to any state the user already has here, their referrer link is
+    /// restored unless they already have one (v1 marks "no referrer" with the
+    /// user's own address), and earning referrers are placed on the
+    /// leaderboard. Returns the number of users imported.
+    ///
+    /// Emits `StatesMigrated`.
+    pub fn migrate_states(
+        env: Env,
+        admin: Address,
+        states: Vec<(Address, ReferralStateV1)>,
+    ) -> Result<u32, Error> {
+        require_admin(&env, &admin)?;
+        if storage_version(&env) >= STORAGE_VERSION {
+            return Err(Error::MigrationClosed);
+        }
+        if states.len() > MAX_MIGRATION_BATCH {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut migrated: u32 = 0;
+        for (user, v1) in states.iter() {
+            let marker = DataKey::Migrated(user.clone());
+            if env.storage().persistent().has(&marker) {
+                continue;
+            }
+            if v1.total_earned < 0 || v1.pending_reward < 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            let mut state = get_state(&env, &user).unwrap_or(ReferralState {
+                referrer: user.clone(),
+                referees: Vec::new(&env),
+                total_earned: 0,
+                pending_reward: 0,
+                event_count: 0,
+            });
+            if v1.referrer != user
+                && !env
+                    .storage()
+                    .persistent()
+                    .has(&DataKey::ReferredBy(user.clone()))
+            {
+                set_persistent(&env, &DataKey::ReferredBy(user.clone()), &v1.referrer);
+                state.referrer = v1.referrer.clone();
+                count_registration(&env)?;
+            }
+            for referee in v1.referees.iter() {
+                if !state.referees.contains(&referee) {
+                    state.referees.push_back(referee);
+                }
+            }
+            state.total_earned = state
+                .total_earned
+                .checked_add(v1.total_earned)
+                .ok_or(Error::Overflow)?;
+            state.pending_reward = state
+                .pending_reward
+                .checked_add(v1.pending_reward)
+                .ok_or(Error::Overflow)?;
+            state.event_count = state
+                .event_count
+                .checked_add(v1.event_count)
+                .ok_or(Error::Overflow)?;
+            set_state(&env, &user, &state);
+            if state.total_earned > 0 {
+                update_leaderboard(&env, &user, state.total_earned);
+            }
+
+            set_persistent(&env, &marker, &true);
+            migrated += 1;
+        }
+
+        StatesMigrated {
+            migrated,
+            skipped: states.len() - migrated,
+        }
+        .publish(&env);
+
+        Ok(migrated)
+    }
+
+    /// Stamp the storage as `STORAGE_VERSION` and close `migrate_states`.
+    /// Admin only. Fresh deployments with nothing to import call this once.
+    ///
+    /// Emits `MigrationCompleted`.
+    pub fn complete_migration(env: Env, admin: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if storage_version(&env) >= STORAGE_VERSION {
+            return Err(Error::MigrationClosed);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::StorageVersion, &STORAGE_VERSION);
+
+        MigrationCompleted {
+            version: STORAGE_VERSION,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Return the storage layout version.
+    pub fn get_storage_version(env: Env) -> u32 {
+        storage_version(&env)
+    }
+
+    /// Return whether `user`'s v1 state has been imported.
+    pub fn is_migrated(env: Env, user: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Migrated(user))
+    }
+
+    // -----------------------------------------------------------------------
+    // View / query functions
+    // -----------------------------------------------------------------------
+
+    /// Return the full referral state for a user.
+    pub fn referral_state(env: Env, user: Address) -> Result<ReferralState, Error> {
+        get_admin(&env)?; // ensure initialized
+        get_state(&env, &user).ok_or(Error::ReferrerNotRegistered)
+    }
+
+    /// Return the referrer of a user, if any.
+    pub fn get_referrer(env: Env, user: Address) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::ReferredBy(user))
+    }
+
+    /// Return the reward contract address.
+    pub fn get_reward_contract(env: Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::RewardContract)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Return the current reward basis points.
+    pub fn get_reward_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::RewardToken)
+    }
+
+    pub fn get_reward_bps(env: Env) -> Result<u32, Error> {
+        get_reward_bps(&env)
+    }
+
+    /// Return the referee bonus rate in basis points (`0` when disabled).
+    pub fn get_referee_bps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RefereeBps)
+            .unwrap_or(0)
+    }
+
+    /// Return the cumulative event amount referees need before rewards
+    /// accrue.
+    pub fn get_activity_threshold(env: Env) -> i128 {
+        get_activity_threshold(&env)
+    }
+
+    /// Return `user`'s cumulative event amount towards the activity
+    /// threshold. Tracking stops once they qualify.
+    pub fn get_referee_volume(env: Env, user: Address) -> i128 {
+        get_referee_volume(&env, &user)
+    }
+
+    /// Return what `referrer` and the referrers above them would be
+    /// credited if one of their referees produced an event of `amount` right
+    /// now. The threshold is checked against `referee`'s tracked volume, or
+    /// against a new referee with no volume when `None`. A `referee` tagged
+    /// to a partner sub-program is quoted as `record_partner_event` credits
+    /// it: at the sub-program's rate, with no upline rewards or epoch cap,
+    /// failing with `BudgetExceeded` past the budget. Otherwise the rates and
+    /// epoch caps of `record_referral_event` apply. Every event type earns
+    /// the same rates. Nothing is recorded.
+    pub fn estimate_reward(
+        env: Env,
+        _event_type: EventType,
+        amount: i128,
+        referrer: Address,
+        referee: Option<Address>,
+    ) -> Result<RewardEstimate, Error> {
+        get_admin(&env)?; // ensure initialized
+        let program = match &referee {
+            Some(referee) => env
+                .storage()
+                .persistent()
+                .get::<_, u32>(&DataKey::UserProgram(referee.clone()))
+                .map(|program_id| get_sub_program(&env, program_id))
+                .transpose()?,
+            None => None,
+        };
+        let reward = compute_reward(&env, &referrer, program.as_ref(), amount)?;
+        if !referee_would_qualify(&env, referee.as_ref(), amount) {
+            return Ok(RewardEstimate::default());
+        }
+
+        if let Some(program) = program {
+            let spent = program
+                .totals
+                .rewards
+                .checked_add(reward)
+                .ok_or(Error::Overflow)?;
+            if spent > program.budget {
+                return Err(Error::BudgetExceeded);
+            }
+            return Ok(RewardEstimate {
+                reward,
+                upline_reward: 0,
+            });
+        }
+
+        let mut upline_reward: i128 = 0;
+        for (upline, reward) in upline_rewards(&env, referee.as_ref(), &referrer, amount)?.iter() {
+            upline_reward = upline_reward
+                .checked_add(preview_epoch_cap(&env, &upline, reward))
+                .ok_or(Error::Overflow)?;
+        }
+        Ok(RewardEstimate {
+            reward: preview_epoch_cap(&env, &referrer, reward),
+            upline_reward,
+        })
+    }
+
+    /// Return the referrer who owns `code`, if any.
+    pub fn get_code_owner(env: Env, code: Symbol) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::CodeOwner(code))
+    }
+
+    /// Return `referrer`'s referral code, if they have created one.
+    pub fn get_referral_code(env: Env, referrer: Address) -> Option<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReferralCode(referrer))
+    }
+
+    /// Return the reward rates for levels 2 and up.
+    pub fn get_upline_bps(env: Env) -> Vec<u32> {
+        get_upline_bps(&env)
+    }
+
+    /// Return the vesting schedule, or `None` if vesting is not active.
+    pub fn get_vesting(env: Env) -> Option<VestingConfig> {
+        get_vesting(&env)
+    }
+
+    /// Return the per-epoch reward cap, or `None` when no cap is set.
+    pub fn get_epoch_cap(env: Env) -> Option<EpochCapConfig> {
+        get_epoch_cap(&env)
+    }
+
+    /// Return what `referrer` has earned from referral events in the
+    /// current epoch (0 when no cap is set).
+    pub fn get_epoch_earned(env: Env, referrer: Address) -> i128 {
+        current_epoch_earnings(&env, &referrer)
+            .map(|(_, earnings)| earnings.earned)
+            .unwrap_or(0)
+    }
+
+    /// Return the vested part of a user's pending rewards right now.
+    pub fn vested_reward(env: Env, user: Address) -> i128 {
+        let pending = get_state(&env, &user)
+            .map(|s| s.pending_reward)
+            .unwrap_or(0);
+        match get_vesting(&env) {
+            Some(vesting) => vested_portion(&env, &user, pending, &vesting),
+            None => pending,
+        }
+    }
+
+    /// Return the number of early claims made by a user.
+    pub fn get_early_claim_count(env: Env, user: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EarlyClaimCount(user))
+            .unwrap_or(0)
+    }
+
+    /// Return a user's early claim record by index.
+    pub fn get_early_claim(env: Env, user: Address, index: u32) -> Option<EarlyClaimRecord> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EarlyClaim(user, index))
+    }
+
+    /// Return the dispute over `referee`'s attribution, if any.
+    pub fn get_dispute(env: Env, referee: Address) -> Option<Dispute> {
+        env.storage().persistent().get(&DataKey::Dispute(referee))
+    }
+
+    /// Return a partner sub-program, or `None` if it does not exist.
+    pub fn get_sub_program(env: Env, program_id: u32) -> Option<SubProgram> {
+        get_sub_program(&env, program_id).ok()
+    }
+
+    /// Return the sub-program a user is tagged to, if any.
+    pub fn get_user_program(env: Env, user: Address) -> Option<u32> {
+        env.storage().persistent().get(&DataKey::UserProgram(user))
+    }
+
+    /// Return the totals across every recorded event, sub-programs included.
+    pub fn get_global_totals(env: Env) -> ProgramTotals {
+        get_global_totals(&env)
+    }
+
+    /// Return platform-wide registration, event and reward counters.
+    pub fn get_global_stats(env: Env) -> GlobalStats {
+        let totals = get_global_totals(&env);
+        GlobalStats {
+            registered_users: env
+                .storage()
+                .instance()
+                .get(&DataKey::RegisteredUsers)
+                .unwrap_or(0),
+            events: totals.event_count,
+            rewards_accrued: totals.rewards,
+            rewards_claimed: get_i128(&env, &DataKey::ClaimedTotal),
+        }
+    }
+
+    /// Week index (`timestamp / COHORT_SECONDS`) of the cohort registrations
+    /// currently join.
+    pub fn current_cohort(env: Env) -> u64 {
+        env.ledger().timestamp() / COHORT_SECONDS
+    }
+
+    /// Counters for the referees who registered in week `cohort`.
+    pub fn get_cohort(env: Env, cohort: u64) -> CohortStats {
+        get_cohort(&env, cohort)
+    }
+
+    /// Counters for `count` consecutive cohorts starting at `from`, capped
+    /// at `MAX_COHORT_QUERY`.
+    pub fn get_cohorts(env: Env, from: u64, count: u32) -> Vec<CohortStats> {
+        let mut cohorts = Vec::new(&env);
+        for offset in 0..count.min(MAX_COHORT_QUERY) {
+            match from.checked_add(offset as u64) {
+                Some(cohort) => cohorts.push_back(get_cohort(&env, cohort)),
+                None => break,
+            }
+        }
+        cohorts
+    }
+
+    /// Registration cohort of `user`, if they registered while cohorts were
+    /// tracked.
+    pub fn get_user_cohort(env: Env, user: Address) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get::<DataKey, CohortMembership>(&DataKey::RefereeCohort(user))
+            .map(|membership| membership.cohort)
+    }
+
+    /// Top referrers by `total_earned`, highest first.
+    pub fn get_leaderboard(env: Env) -> Vec<LeaderboardEntry> {
+        get_leaderboard(&env)
+    }
+
+    /// Return whether `game` may record referral events.
+    pub fn is_game_reporter(env: Env, game: Address) -> bool {
+        env.storage().persistent().has(&DataKey::GameReporter(game))
+    }
+
+    /// Return the configured rate tiers.
+    pub fn get_rate_tiers(env: Env) -> Vec<RateTier> {
+        get_rate_tiers(&env)
+    }
+
+    /// Return `referrer`'s current tier, its rate and their active referee
+    /// count.
+    pub fn get_tier(env: Env, referrer: Address) -> Result<ReferrerTier, Error> {
+        referrer_tier(&env, &referrer)
+    }
+
+    /// Return the configured milestone bonuses.
+    pub fn get_milestones(env: Env) -> Vec<Milestone> {
+        get_milestones(&env)
+    }
+
+    /// Return the cumulative qualifying volume of `referrer`'s referees.
+    pub fn get_referred_volume(env: Env, referrer: Address) -> i128 {
+        get_referred_volume(&env, &referrer)
+    }
+
+    /// Whether `referrer` has been paid the milestone `(kind, threshold)`.
+    pub fn is_milestone_reached(
+        env: Env,
+        referrer: Address,
+        kind: MilestoneKind,
+        threshold: i128,
+    ) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::MilestonePaid(referrer, kind, threshold))
+    }
+
+    /// Return the extra reward tokens and their rates.
+    pub fn get_extra_tokens(env: Env) -> Vec<TokenRate> {
+        get_extra_tokens(&env)
+    }
+
+    /// Return `user`'s pending reward in the extra `token`.
+    pub fn get_token_pending(env: Env, user: Address, token: Address) -> i128 {
+        get_token_pending(&env, &user, &token)
+    }
+
+    /// Return the address `user`'s claims are paid to.
+    pub fn get_payout_address(env: Env, user: Address) -> Address {
+        payout_address(&env, &user)
+    }
+
+    /// Return whether `referrer` is banned.
+    pub fn is_banned(env: Env, referrer: Address) -> bool {
+        is_banned(&env, &referrer)
+    }
+
+    /// Return whether events currently accrue points.
+    pub fn is_operator(env: Env, operator: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Operator(operator))
+    }
+
+    pub fn is_points_mode(env: Env) -> bool {
+        points_mode(&env)
+    }
+
+    /// Return the current points conversion rate, if one has been set.
+    pub fn get_points_rate(env: Env) -> Option<i128> {
+        env.storage().instance().get(&DataKey::PointsRate)
+    }
+
+    /// Return the unconverted points held by a user.
+    pub fn get_points(env: Env, user: Address) -> i128 {
+        get_points(&env, &user)
+    }
+
+    /// Return the number of ledgers after which unclaimed rewards expire
+    /// (0 = never).
+    pub fn get_reward_expiry(env: Env) -> u32 {
+        get_reward_expiry(&env)
+    }
+
+    /// Return the ledger at which `referrer`'s pending rewards expire, or
+    /// `None` if they do not.
+    pub fn get_expiry_ledger(env: Env, referrer: Address) -> Option<u32> {
+        expiry_ledger(&env, &referrer)
+    }
+
+    /// Return the total expired rewards swept back to the reward contract.
+    pub fn get_expired_total(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ExpiredTotal)
+            .unwrap_or(0)
+    }
+
+    /// Return a season's configuration and pool.
+    pub fn get_season(env: Env, season_id: u32) -> Option<Season> {
+        get_season(&env, season_id).ok()
+    }
+
+    /// Return the id of the running season, if any.
+    pub fn get_current_season(env: Env) -> Option<u32> {
+        env.storage().instance().get(&DataKey::CurrentSeason)
+    }
+
+    /// Return `referrer`'s score in a season.
+    pub fn get_season_score(env: Env, season_id: u32, referrer: Address) -> i128 {
+        get_season_score(&env, season_id, &referrer)
+    }
+
+    /// Top scorers of a season, highest first; `total_earned` holds the
+    /// seasonal score.
+    pub fn get_season_standings(env: Env, season_id: u32) -> Vec<LeaderboardEntry> {
+        get_season_board(&env, season_id)
+    }
+
+    /// Return the total rewards removed by `clawback`.
+    pub fn get_clawed_back_total(env: Env) -> i128 {
+        get_i128(&env, &DataKey::ClawedBackTotal)
+    }
+
+    /// Return the total rewards forfeited back to the reward pool.
+    pub fn get_forfeited_total(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ForfeitedTotal)
+            .unwrap_or(0)
+    }
+}
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        token::StellarAssetClient,
+        vec, Env,
+    };
+
+    // -----------------------------------------------------------------------
+    // Test helpers
+    // -----------------------------------------------------------------------
+
+    /// Game contract that reports its own plays, authorizing as itself.
+    #[contract]
+    pub struct MockGame;
+
+    #[contractimpl]
+    impl MockGame {
+        pub fn play(env: Env, referral: Address, player: Address, wager: i128) {
+            ReferralSystemClient::new(&env, &referral).record_referral_event(
+                &env.current_contract_address(),
+                &player,
+                &EventType::GamePlayed,
+                &wager,
+            );
+        }
+    }
+
+    fn setup(env: &Env) -> (ReferralSystemClient<'_>, Address, Address) {
+        let admin = Address::generate(env);
+        let reward_contract = Address::generate(env);
+
+        let contract_id = env.register(ReferralSystem, ());
+        let client = ReferralSystemClient::new(env, &contract_id);
+
+        env.mock_all_auths();
+        client.init(&admin, &reward_contract);
+
+        (client, admin, reward_contract)
+    }
+
+    // -----------------------------------------------------------------------
+    // Initialization tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_init_success() {
+        let env = Env::default();
+        let (client, _admin, reward_contract) = setup(&env);
+
+        assert_eq!(client.get_reward_contract(), reward_contract);
+        assert_eq!(client.get_reward_bps(), 500); // default 5%
+    }
+
+    #[test]
+    fn test_init_already_initialized() {
+        let env = Env::default();
+        let (client, admin, reward_contract) = setup(&env);
+
+        let result = client.try_init(&admin, &reward_contract);
+        assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
+    }
+
+    // -----------------------------------------------------------------------
+    // Admin configuration tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_set_reward_bps() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        client.set_reward_bps(&admin, &1000); // 10%
+        assert_eq!(client.get_reward_bps(), 1000);
+    }
+
+    #[test]
+    fn test_set_reward_bps_invalid() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let result = client.try_set_reward_bps(&admin, &10_001);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+    }
+
+    #[test]
+    fn test_set_reward_bps_not_admin() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let attacker = Address::generate(&env);
+        let result = client.try_set_reward_bps(&attacker, &1000);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    }
+
+    #[test]
+    fn test_set_reward_contract() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let new_reward = Address::generate(&env);
+        client.set_reward_contract(&admin, &new_reward);
+        assert_eq!(client.get_reward_contract(), new_reward);
+    }
+
+    // -----------------------------------------------------------------------
+    // Referral registration tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_register_referrer_success() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+
+        client.register_referrer(&user, &referrer);
+
+        // User's referrer is set
+        assert_eq!(client.get_referrer(&user), Some(referrer.clone()));
+
+        // User state shows the referrer
+        let user_state = client.referral_state(&user);
+        assert_eq!(user_state.referrer, referrer);
+        assert_eq!(user_state.pending_reward, 0);
+
+        // Referrer state shows the referee
+        let referrer_state = client.referral_state(&referrer);
+        assert_eq!(referrer_state.referees.len(), 1);
+        assert_eq!(referrer_state.referees.get(0).unwrap(), user);
+    }
+
+    #[test]
+    fn test_register_referrer_self_referral() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let result = client.try_register_referrer(&user, &user);
+        assert_eq!(result, Err(Ok(Error::SelfReferral)));
+    }
+
+    #[test]
+    fn test_register_referrer_already_referred() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer1 = Address::generate(&env);
+        let referrer2 = Address::generate(&env);
+
+        client.register_referrer(&user, &referrer1);
+
+        let result = client.try_register_referrer(&user, &referrer2);
+        assert_eq!(result, Err(Ok(Error::AlreadyReferred)));
+    }
+
+    #[test]
+    fn test_register_multiple_referees() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let referrer = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+
+        client.register_referrer(&user1, &referrer);
+        client.register_referrer(&user2, &referrer);
+        client.register_referrer(&user3, &referrer);
+
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.referees.len(), 3);
+    }
+
+    // -----------------------------------------------------------------------
+    // Record referral event tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_record_referral_event_success() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        // Record a game-played event with amount 10_000
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+
+        // Referrer should have 5% of 10_000 = 500 pending
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.pending_reward, 500);
+        assert_eq!(state.total_earned, 500);
+        assert_eq!(state.event_count, 1);
+    }
+
+    #[test]
+    fn test_estimate_reward_matches_credited_reward() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+        client.set_reward_bps(&admin, &750);
+
+        let estimate = client
+            .estimate_reward(&EventType::Deposit, &12_345, &referrer, &None)
+            .reward;
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &12_345);
+        assert_eq!(client.referral_state(&referrer).pending_reward, estimate);
+        assert_eq!(estimate, 925);
+
+        assert_eq!(
+            client.try_estimate_reward(&EventType::GamePlayed, &0, &referrer, &None),
+            Err(Ok(Error::InvalidAmount))
+        );
+    }
+
+    #[test]
+    fn test_record_multiple_events() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &20_000);
+        client.record_referral_event(&admin, &user, &EventType::PrizeClaimed, &5_000);
+
+        // Total reward: 500 + 1000 + 250 = 1750
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.pending_reward, 1750);
+        assert_eq!(state.total_earned, 1750);
+        assert_eq!(state.event_count, 3);
+    }
+
+    #[test]
+    fn test_record_event_no_referrer() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+
+        let result =
+            client.try_record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(result, Err(Ok(Error::ReferrerNotRegistered)));
+    }
+
+    #[test]
+    fn test_record_event_invalid_amount() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        let result = client.try_record_referral_event(&admin, &user, &EventType::GamePlayed, &0);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+        let result = client.try_record_referral_event(&admin, &user, &EventType::GamePlayed, &-100);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+    }
+
+    #[test]
+    fn test_record_event_not_admin() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        let attacker = Address::generate(&env);
+        let result =
+            client.try_record_referral_event(&attacker, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    }
+
+    #[test]
+    fn test_record_events_from_multiple_referees() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let referrer = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+
+        client.register_referrer(&user1, &referrer);
+        client.register_referrer(&user2, &referrer);
+
+        client.record_referral_event(&admin, &user1, &EventType::GamePlayed, &10_000);
+        client.record_referral_event(&admin, &user2, &EventType::Deposit, &20_000);
+
+        // Referrer earns from both: 500 + 1000 = 1500
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.pending_reward, 1500);
+        assert_eq!(state.total_earned, 1500);
+        assert_eq!(state.event_count, 2);
+    }
+
+    // -----------------------------------------------------------------------
+    // Claim reward tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_claim_referral_reward_success() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+
+        // Referrer claims
+        let claimed = client.claim_referral_reward(&referrer);
+        assert_eq!(claimed, 500);
+
+        // Pending is now 0, but total_earned remains
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.pending_reward, 0);
+        assert_eq!(state.total_earned, 500);
+    }
+
+    #[test]
+    fn test_claim_no_pending_rewards() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        // Referrer has 0 pending
+        let result = client.try_claim_referral_reward(&referrer);
+        assert_eq!(result, Err(Ok(Error::NoPendingRewards)));
+    }
+
+    #[test]
+    fn test_claim_then_accumulate_then_claim_again() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        // First batch of events
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        let claimed1 = client.claim_referral_reward(&referrer);
+        assert_eq!(claimed1, 500);
+
+        // Second batch
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &20_000);
+        let claimed2 = client.claim_referral_reward(&referrer);
+        assert_eq!(claimed2, 1000);
+
+        // Total earned reflects both
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.pending_reward, 0);
+        assert_eq!(state.total_earned, 1500);
+        assert_eq!(state.event_count, 2);
     }
-}
 
-// ===========================================================================
-// Tests
-// ===========================================================================
+    #[test]
+    fn test_claim_unknown_user() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        env.mock_all_auths();
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Env};
+        let unknown = Address::generate(&env);
+        let result = client.try_claim_referral_reward(&unknown);
+        assert_eq!(result, Err(Ok(Error::ReferrerNotRegistered)));
+    }
 
     // -----------------------------------------------------------------------
-    // Test helpers
+    // View function tests
     // -----------------------------------------------------------------------
 
-    fn setup(env: &Env) -> (ReferralSystemClient<'_>, Address, Address) {
-        let admin = Address::generate(env);
-        let reward_contract = Address::generate(env);
+    #[test]
+    fn test_referral_state_not_found() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
 
-        let contract_id = env.register(ReferralSystem, ());
-        let client = ReferralSystemClient::new(env, &contract_id);
+        let unknown = Address::generate(&env);
+        let result = client.try_referral_state(&unknown);
+        assert_eq!(result, Err(Ok(Error::ReferrerNotRegistered)));
+    }
 
-        env.mock_all_auths();
-        client.init(&admin, &reward_contract);
+    #[test]
+    fn test_get_referrer_none() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
 
-        (client, admin, reward_contract)
+        let user = Address::generate(&env);
+        assert_eq!(client.get_referrer(&user), None);
     }
 
     // -----------------------------------------------------------------------
-    // Initialization tests
+    // Custom reward BPS tests
     // -----------------------------------------------------------------------
 
     #[test]
-    fn test_init_success() {
+    fn test_custom_reward_bps() {
         let env = Env::default();
-        let (client, _admin, reward_contract) = setup(&env);
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
 
-        assert_eq!(client.get_reward_contract(), reward_contract);
-        assert_eq!(client.get_reward_bps(), 500); // default 5%
+        // Set to 10% (1000 bps)
+        client.set_reward_bps(&admin, &1000);
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.pending_reward, 1000); // 10% of 10_000
     }
 
     #[test]
-    fn test_init_already_initialized() {
+    fn test_zero_reward_bps() {
         let env = Env::default();
-        let (client, admin, reward_contract) = setup(&env);
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
 
-        let result = client.try_init(&admin, &reward_contract);
-        assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
+        // Set to 0%
+        client.set_reward_bps(&admin, &0);
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.pending_reward, 0);
+        assert_eq!(state.event_count, 1);
     }
 
     // -----------------------------------------------------------------------
-    // Admin configuration tests
+    // Vesting tests
     // -----------------------------------------------------------------------
 
     #[test]
-    fn test_set_reward_bps() {
+    fn test_claim_blocked_until_vested() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
 
-        client.set_reward_bps(&admin, &1000); // 10%
-        assert_eq!(client.get_reward_bps(), 1000);
+        // 100s cliff, 1000s linear vesting, 50% penalty on unvested.
+        client.set_vesting(&admin, &100, &1_000, &5_000);
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &20_000);
+
+        let result = client.try_claim_referral_reward(&referrer);
+        assert_eq!(result, Err(Ok(Error::RewardsVesting)));
+        assert_eq!(client.vested_reward(&referrer), 0);
+
+        env.ledger().set_timestamp(2_000);
+        assert_eq!(client.vested_reward(&referrer), 1_000);
+        assert_eq!(client.claim_referral_reward(&referrer), 1_000);
     }
 
     #[test]
-    fn test_set_reward_bps_invalid() {
+    fn test_early_claim_forfeits_unvested_share() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
 
-        let result = client.try_set_reward_bps(&admin, &10_001);
-        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        client.set_vesting(&admin, &100, &1_000, &5_000);
+
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &20_000);
+
+        // 40% vested: 400 vested, 600 unvested, half of it forfeited.
+        env.ledger().set_timestamp(1_400);
+        let paid = client.claim_early(&referrer);
+        assert_eq!(paid, 700);
+        assert_eq!(client.get_forfeited_total(), 300);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 0);
+
+        assert_eq!(client.get_early_claim_count(&referrer), 1);
+        let record = client.get_early_claim(&referrer, &0).unwrap();
+        assert_eq!(record.claimed, 700);
+        assert_eq!(record.forfeited, 300);
+        assert_eq!(record.timestamp, 1_400);
     }
 
     #[test]
-    fn test_set_reward_bps_not_admin() {
+    fn test_vesting_start_weights_later_accruals_and_old_balances() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
+        let (client, admin, _) = setup(&env);
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+
+        // A balance migrated before vesting existed has no start of its own.
+        let holder = Address::generate(&env);
+        let batch = vec![
+            &env,
+            (
+                holder.clone(),
+                ReferralStateV1 {
+                    referrer: holder.clone(),
+                    referees: Vec::new(&env),
+                    total_earned: 400,
+                    pending_reward: 400,
+                    event_count: 1,
+                },
+            ),
+        ];
+        client.migrate_states(&admin, &batch);
+        env.ledger().set_timestamp(5_000);
+        client.set_vesting(&admin, &0, &1_000, &5_000);
+        assert_eq!(client.vested_reward(&holder), 0);
+        env.ledger().set_timestamp(6_000);
+        assert_eq!(client.claim_referral_reward(&holder), 400);
 
-        let attacker = Address::generate(&env);
-        let result = client.try_set_reward_bps(&attacker, &1000);
-        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &20_000);
+        env.ledger().set_timestamp(7_000);
+        assert_eq!(client.vested_reward(&referrer), 1_000);
+
+        // The new 1_000 accrues at 7_000, moving the start to 6_500.
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &20_000);
+        assert_eq!(client.vested_reward(&referrer), 1_000);
+        let result = client.try_claim_referral_reward(&referrer);
+        assert_eq!(result, Err(Ok(Error::RewardsVesting)));
+        env.ledger().set_timestamp(7_500);
+        assert_eq!(client.claim_referral_reward(&referrer), 2_000);
     }
 
     #[test]
-    fn test_set_reward_contract() {
+    fn test_early_claim_requires_vesting() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
         env.mock_all_auths();
 
-        let new_reward = Address::generate(&env);
-        client.set_reward_contract(&admin, &new_reward);
-        assert_eq!(client.get_reward_contract(), new_reward);
+        let user = Address::generate(&env);
+        let referrer = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+
+        let result = client.try_claim_early(&referrer);
+        assert_eq!(result, Err(Ok(Error::VestingNotActive)));
+        assert_eq!(client.claim_referral_reward(&referrer), 500);
     }
 
     // -----------------------------------------------------------------------
-    // Referral registration tests
+    // Data unlink tests
     // -----------------------------------------------------------------------
 
     #[test]
-    fn test_register_referrer_success() {
+    fn test_forget_me_unlinks_and_preserves_totals() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
+        let (client, admin, _) = setup(&env);
+        env.mock_all_auths();
+
+        let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
+        let other = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+        client.register_referrer(&other, &referrer);
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+
+        client.forget_me(&user);
+
+        assert_eq!(client.get_referrer(&user), None);
+        assert_eq!(client.referral_state(&user).referrer, user);
+
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.referees.len(), 1);
+        assert_eq!(state.referees.get(0).unwrap(), other);
+        assert_eq!(state.pending_reward, 500);
+        assert_eq!(state.total_earned, 500);
+        assert_eq!(state.event_count, 1);
+
+        // No further rewards flow to the former referrer.
+        let result =
+            client.try_record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(result, Err(Ok(Error::ReferrerNotRegistered)));
+        assert_eq!(client.claim_referral_reward(&referrer), 500);
+
+        let again = client.try_forget_me(&user);
+        assert_eq!(again, Err(Ok(Error::ReferrerNotRegistered)));
+
+        // The tombstone keeps the user from signing up under a new referrer.
+        let fresh = Address::generate(&env);
+        let result = client.try_register_referrer(&user, &fresh);
+        assert_eq!(result, Err(Ok(Error::AlreadyReferred)));
+        client.create_referral_code(&fresh, &Symbol::new(&env, "FRESH"));
+        let result = client.try_register_with_code(&user, &Symbol::new(&env, "FRESH"));
+        assert_eq!(result, Err(Ok(Error::AlreadyReferred)));
+    }
+
+    // -----------------------------------------------------------------------
+    // Partner sub-program tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_partner_sub_program_records_within_budget() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
         env.mock_all_auths();
 
+        let operator = Address::generate(&env);
+        let program_id = client.create_sub_program(&admin, &operator, &1_000, &1_500);
+        assert_eq!(program_id, 0);
+
+        let referrer = Address::generate(&env);
+        let tagged = Address::generate(&env);
+        let untagged = Address::generate(&env);
+        client.register_referrer(&tagged, &referrer);
+        client.register_referrer(&untagged, &referrer);
+        client.tag_user(&admin, &tagged, &program_id);
+        assert_eq!(client.get_user_program(&tagged), Some(program_id));
+
+        // Partner rate applies: 10% of 10 000, and the estimate quotes it.
+        let estimate = client.estimate_reward(
+            &EventType::Deposit,
+            &10_000,
+            &referrer,
+            &Some(tagged.clone()),
+        );
+        assert_eq!(
+            estimate,
+            RewardEstimate {
+                reward: 1_000,
+                upline_reward: 0,
+            }
+        );
+        client.record_partner_event(
+            &operator,
+            &program_id,
+            &tagged,
+            &EventType::Deposit,
+            &10_000,
+        );
+        assert_eq!(client.referral_state(&referrer).pending_reward, 1_000);
+
+        // Operators can only record for their own users.
+        let result = client.try_record_partner_event(
+            &operator,
+            &program_id,
+            &untagged,
+            &EventType::Deposit,
+            &10_000,
+        );
+        assert_eq!(result, Err(Ok(Error::UserNotInProgram)));
+        let stranger = Address::generate(&env);
+        let result = client.try_record_partner_event(
+            &stranger,
+            &program_id,
+            &tagged,
+            &EventType::Deposit,
+            &10_000,
+        );
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+        // A further 1 000 reward would take the program past its budget.
+        let result = client.try_record_partner_event(
+            &operator,
+            &program_id,
+            &tagged,
+            &EventType::Deposit,
+            &10_000,
+        );
+        assert_eq!(result, Err(Ok(Error::BudgetExceeded)));
+        let result = client.try_estimate_reward(
+            &EventType::Deposit,
+            &10_000,
+            &referrer,
+            &Some(tagged.clone()),
+        );
+        assert_eq!(result, Err(Ok(Error::BudgetExceeded)));
+
+        // Global events roll up alongside the partner's.
+        client.record_referral_event(&admin, &untagged, &EventType::GamePlayed, &2_000);
+
+        let program = client.get_sub_program(&program_id).unwrap();
+        assert_eq!(
+            program.totals,
+            ProgramTotals {
+                event_count: 1,
+                volume: 10_000,
+                rewards: 1_000,
+            }
+        );
+        assert_eq!(
+            client.get_global_totals(),
+            ProgramTotals {
+                event_count: 2,
+                volume: 12_000,
+                rewards: 1_100,
+            }
+        );
+        assert_eq!(client.get_sub_program(&1), None);
+    }
+
+    #[test]
+    fn test_game_reporter_records_events() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        let referrer = Address::generate(&env);
         let user = Address::generate(&env);
+        let game = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        let result = client.try_record_referral_event(&game, &user, &EventType::GamePlayed, &1_000);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+        client.set_game_reporter(&admin, &game, &true);
+        assert!(client.is_game_reporter(&game));
+        client.record_referral_event(&game, &user, &EventType::GamePlayed, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 50);
+
+        client.set_game_reporter(&admin, &game, &false);
+        assert!(!client.is_game_reporter(&game));
+        let result = client.try_record_referral_event(&game, &user, &EventType::GamePlayed, &1_000);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    }
+
+    #[test]
+    fn test_operator_records_events_but_cannot_configure() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
         let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
+        let operator = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        client.add_operator(&admin, &operator);
+        assert!(client.is_operator(&operator));
+        client.record_referral_event(&operator, &user, &EventType::Deposit, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 50);
+
+        let result = client.try_set_reward_bps(&operator, &900);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+        let result = client.try_set_reward_contract(&operator, &operator);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+        let result = client.try_add_operator(&operator, &user);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+        client.remove_operator(&admin, &operator);
+        assert!(!client.is_operator(&operator));
+        let result =
+            client.try_record_referral_event(&operator, &user, &EventType::Deposit, &1_000);
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    }
 
+    #[test]
+    fn test_points_convert_at_rate_in_force_when_claimed() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
         client.register_referrer(&user, &referrer);
 
-        // User's referrer is set
-        assert_eq!(client.get_referrer(&user), Some(referrer.clone()));
+        client.set_points_mode(&admin, &true);
+        assert!(client.is_points_mode());
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(client.get_points(&referrer), 500);
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.pending_reward, 0);
+        assert_eq!(state.total_earned, 0);
+        assert_eq!(state.event_count, 1);
 
-        // User state shows the referrer
-        let user_state = client.referral_state(&user);
-        assert_eq!(user_state.referrer, referrer);
-        assert_eq!(user_state.pending_reward, 0);
+        let result = client.try_claim_points(&referrer);
+        assert_eq!(result, Err(Ok(Error::PointsRateNotSet)));
+        let result = client.try_set_points_rate(&admin, &0);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
 
-        // Referrer state shows the referee
-        let referrer_state = client.referral_state(&referrer);
-        assert_eq!(referrer_state.referees.len(), 1);
-        assert_eq!(referrer_state.referees.get(0).unwrap(), user);
+        // Rate set, then cut before the claim: the claim uses the new rate.
+        client.set_points_rate(&admin, &20_000);
+        client.set_points_rate(&admin, &5_000);
+        assert_eq!(client.get_points_rate(), Some(5_000));
+        assert_eq!(client.claim_points(&referrer), 250);
+        assert_eq!(client.get_points(&referrer), 0);
+        assert_eq!(client.referral_state(&referrer).total_earned, 250);
+
+        let result = client.try_claim_points(&referrer);
+        assert_eq!(result, Err(Ok(Error::NoPendingRewards)));
+
+        // Back to token accrual.
+        client.set_points_mode(&admin, &false);
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(client.get_points(&referrer), 0);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 500);
     }
 
     #[test]
-    fn test_register_referrer_self_referral() {
+    fn test_upline_referrers_earn_per_level() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
-        env.mock_all_auths();
+        let (client, admin, _) = setup(&env);
+        let top = Address::generate(&env);
+        let upper = Address::generate(&env);
+        let direct = Address::generate(&env);
+        let user = Address::generate(&env);
+        client.register_referrer(&upper, &top);
+        client.register_referrer(&direct, &upper);
+        client.register_referrer(&user, &direct);
+
+        let result = client.try_set_upline_bps(&admin, &vec![&env, 200, 50, 10]);
+        assert_eq!(result, Err(Ok(Error::TooManyLevels)));
+        let result = client.try_set_upline_bps(&admin, &vec![&env, 10_001]);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+        client.set_upline_bps(&admin, &vec![&env, 200, 50]);
+        assert_eq!(client.get_upline_bps(), vec![&env, 200, 50]);
+        let estimate = client.estimate_reward(
+            &EventType::GamePlayed,
+            &10_000,
+            &direct,
+            &Some(user.clone()),
+        );
+        assert_eq!(
+            estimate,
+            RewardEstimate {
+                reward: 500,
+                upline_reward: 250,
+            }
+        );
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(client.referral_state(&direct).pending_reward, 500);
+        assert_eq!(client.referral_state(&upper).pending_reward, 200);
+        assert_eq!(client.referral_state(&top).pending_reward, 50);
+        assert_eq!(client.get_global_totals().rewards, 750);
+
+        // A shorter chain stops at the last referrer.
+        client.record_referral_event(&admin, &direct, &EventType::GamePlayed, &10_000);
+        assert_eq!(client.referral_state(&upper).pending_reward, 700);
+        assert_eq!(client.referral_state(&top).pending_reward, 250);
+
+        // A referral cycle never credits the same account twice.
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        client.register_referrer(&a, &b);
+        client.register_referrer(&b, &a);
+        client.record_referral_event(&admin, &a, &EventType::GamePlayed, &10_000);
+        assert_eq!(client.referral_state(&b).pending_reward, 500);
+        assert_eq!(client.referral_state(&a).pending_reward, 0);
+    }
 
+    #[test]
+    fn test_register_with_referral_code() {
+        let env = Env::default();
+        let (client, _, _) = setup(&env);
+        let referrer = Address::generate(&env);
+        let other = Address::generate(&env);
         let user = Address::generate(&env);
-        let result = client.try_register_referrer(&user, &user);
+        let code = Symbol::new(&env, "ALICE");
+
+        let result = client.try_register_with_code(&user, &code);
+        assert_eq!(result, Err(Ok(Error::CodeNotFound)));
+
+        client.create_referral_code(&referrer, &code);
+        assert_eq!(client.get_code_owner(&code), Some(referrer.clone()));
+        assert_eq!(client.get_referral_code(&referrer), Some(code.clone()));
+
+        let result = client.try_create_referral_code(&other, &code);
+        assert_eq!(result, Err(Ok(Error::CodeTaken)));
+        let result = client.try_create_referral_code(&referrer, &Symbol::new(&env, "ALICE2"));
+        assert_eq!(result, Err(Ok(Error::AlreadyHasCode)));
+
+        client.register_with_code(&user, &code);
+        assert_eq!(client.get_referrer(&user), Some(referrer.clone()));
+        assert_eq!(client.referral_state(&referrer).referees.len(), 1);
+
+        // The usual registration rules still apply.
+        let result = client.try_register_with_code(&user, &code);
+        assert_eq!(result, Err(Ok(Error::AlreadyReferred)));
+        let result = client.try_register_with_code(&referrer, &code);
         assert_eq!(result, Err(Ok(Error::SelfReferral)));
     }
 
     #[test]
-    fn test_register_referrer_already_referred() {
+    fn test_epoch_cap_withholds_rewards_until_next_epoch() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
-        env.mock_all_auths();
+        let (client, admin, _) = setup(&env);
+        let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
+
+        let result = client.try_set_epoch_cap(&admin, &100, &0);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        client.set_epoch_cap(&admin, &800, &604_800);
+        assert_eq!(
+            client.get_epoch_cap(),
+            Some(EpochCapConfig {
+                cap: 800,
+                epoch_seconds: 604_800
+            })
+        );
 
-        let user = Address::generate(&env);
-        let referrer1 = Address::generate(&env);
-        let referrer2 = Address::generate(&env);
+        // 500 bps of 10_000 is 500: the second event is cut to 300. The
+        // estimate follows the cap without consuming any of it.
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        let estimate = client
+            .estimate_reward(&EventType::GamePlayed, &10_000, &referrer, &None)
+            .reward;
+        assert_eq!(estimate, 300);
+        assert_eq!(client.get_epoch_earned(&referrer), 500);
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(
+            client.referral_state(&referrer).pending_reward,
+            500 + estimate
+        );
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(
+            client
+                .estimate_reward(&EventType::GamePlayed, &10_000, &referrer, &None)
+                .reward,
+            0
+        );
+        assert_eq!(client.referral_state(&referrer).pending_reward, 800);
+        assert_eq!(client.get_epoch_earned(&referrer), 800);
+        assert_eq!(client.get_global_totals().rewards, 800);
 
-        client.register_referrer(&user, &referrer1);
+        // The next epoch starts a fresh accumulator.
+        env.ledger().with_mut(|li| li.timestamp += 604_800);
+        assert_eq!(client.get_epoch_earned(&referrer), 0);
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 1_300);
 
-        let result = client.try_register_referrer(&user, &referrer2);
-        assert_eq!(result, Err(Ok(Error::AlreadyReferred)));
+        client.set_epoch_cap(&admin, &0, &0);
+        assert_eq!(client.get_epoch_cap(), None);
     }
 
     #[test]
-    fn test_register_multiple_referees() {
+    fn test_dispute_lifecycle() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
-        env.mock_all_auths();
-
+        let (client, admin, _) = setup(&env);
         let referrer = Address::generate(&env);
-        let user1 = Address::generate(&env);
-        let user2 = Address::generate(&env);
-        let user3 = Address::generate(&env);
+        let user = Address::generate(&env);
+        let reporter = Address::generate(&env);
 
-        client.register_referrer(&user1, &referrer);
-        client.register_referrer(&user2, &referrer);
-        client.register_referrer(&user3, &referrer);
+        let result = client.try_flag_referral(&reporter, &user, &1);
+        assert_eq!(result, Err(Ok(Error::ReferrerNotRegistered)));
+        let result = client.try_resolve_dispute(&admin, &user, &true);
+        assert_eq!(result, Err(Ok(Error::DisputeNotFound)));
 
-        let state = client.referral_state(&referrer);
-        assert_eq!(state.referees.len(), 3);
+        client.register_referrer(&user, &referrer);
+        client.flag_referral(&reporter, &user, &7);
+        let dispute = client.get_dispute(&user).unwrap();
+        assert_eq!(dispute.referrer, referrer);
+        assert_eq!(dispute.flagged_by, reporter);
+        assert_eq!(dispute.reason_code, 7);
+        assert_eq!(dispute.status, DisputeStatus::Open);
+        let result = client.try_flag_referral(&user, &user, &2);
+        assert_eq!(result, Err(Ok(Error::DisputeExists)));
+
+        // A dismissed dispute can be raised again; an upheld one is final.
+        env.ledger().with_mut(|li| li.timestamp = 500);
+        client.resolve_dispute(&admin, &user, &false);
+        let dispute = client.get_dispute(&user).unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Dismissed);
+        assert_eq!(dispute.resolved_at, 500);
+        let result = client.try_resolve_dispute(&admin, &user, &true);
+        assert_eq!(result, Err(Ok(Error::DisputeNotOpen)));
+
+        client.flag_referral(&user, &user, &2);
+        client.resolve_dispute(&admin, &user, &true);
+        assert_eq!(
+            client.get_dispute(&user).unwrap().status,
+            DisputeStatus::Upheld
+        );
+        let result = client.try_flag_referral(&reporter, &user, &1);
+        assert_eq!(result, Err(Ok(Error::DisputeExists)));
     }
 
-    // -----------------------------------------------------------------------
-    // Record referral event tests
-    // -----------------------------------------------------------------------
-
     #[test]
-    fn test_record_referral_event_success() {
+    fn test_sweep_expired_rewards() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
-        env.mock_all_auths();
-
-        let user = Address::generate(&env);
         let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
         client.register_referrer(&user, &referrer);
+        env.ledger().with_mut(|li| li.sequence_number = 100);
+        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
 
-        // Record a game-played event with amount 10_000
+        let result = client.try_sweep_expired(&admin, &referrer);
+        assert_eq!(result, Err(Ok(Error::ExpiryNotSet)));
+        assert_eq!(client.get_expiry_ledger(&referrer), None);
+
+        client.set_reward_expiry(&admin, &1_000);
+        assert_eq!(client.get_expiry_ledger(&referrer), Some(1_100));
+
+        // A new accrual pushes the expiry back.
+        env.ledger().with_mut(|li| li.sequence_number = 600);
         client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        assert_eq!(client.get_expiry_ledger(&referrer), Some(1_600));
+        env.ledger().with_mut(|li| li.sequence_number = 1_599);
+        let result = client.try_sweep_expired(&admin, &referrer);
+        assert_eq!(result, Err(Ok(Error::RewardsNotExpired)));
 
-        // Referrer should have 5% of 10_000 = 500 pending
+        env.ledger().with_mut(|li| li.sequence_number = 1_600);
+        assert_eq!(client.sweep_expired(&admin, &referrer), 1_000);
         let state = client.referral_state(&referrer);
-        assert_eq!(state.pending_reward, 500);
-        assert_eq!(state.total_earned, 500);
-        assert_eq!(state.event_count, 1);
+        assert_eq!(state.pending_reward, 0);
+        assert_eq!(state.total_earned, 1_000);
+        assert_eq!(client.get_expired_total(), 1_000);
+        assert_eq!(client.get_expiry_ledger(&referrer), None);
+
+        let result = client.try_sweep_expired(&admin, &referrer);
+        assert_eq!(result, Err(Ok(Error::NoPendingRewards)));
     }
 
     #[test]
-    fn test_record_multiple_events() {
+    fn test_leaderboard_ranks_top_referrers() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
-        env.mock_all_auths();
 
-        let user = Address::generate(&env);
+        let mut referrers = Vec::new(&env);
+        for i in 0..(LEADERBOARD_SIZE + 2) {
+            let referrer = Address::generate(&env);
+            let user = Address::generate(&env);
+            client.register_referrer(&user, &referrer);
+            // 5% of (i + 1) * 1_000: 50, 100, 150, ...
+            let amount = (i as i128 + 1) * 1_000;
+            client.record_referral_event(&admin, &user, &EventType::Deposit, &amount);
+            referrers.push_back((referrer, user));
+        }
+
+        let board = client.get_leaderboard();
+        assert_eq!(board.len(), LEADERBOARD_SIZE);
+        let top = board.get(0).unwrap();
+        assert_eq!(top.referrer, referrers.get(LEADERBOARD_SIZE + 1).unwrap().0);
+        assert_eq!(top.total_earned, 600);
+        assert_eq!(board.get(LEADERBOARD_SIZE - 1).unwrap().total_earned, 150);
+
+        // The lowest earner climbs to the top and the board stays bounded.
+        let (first, first_user) = referrers.get(0).unwrap();
+        client.record_referral_event(&admin, &first_user, &EventType::Deposit, &20_000);
+        let board = client.get_leaderboard();
+        assert_eq!(board.len(), LEADERBOARD_SIZE);
+        assert_eq!(
+            board.get(0).unwrap(),
+            LeaderboardEntry {
+                referrer: first,
+                total_earned: 1_050,
+            }
+        );
+        assert_eq!(board.get(1).unwrap().total_earned, 600);
+        assert_eq!(board.get(LEADERBOARD_SIZE - 1).unwrap().total_earned, 200);
+    }
+
+    #[test]
+    fn test_claim_transfers_reward_token() {
+        let env = Env::default();
+        let (client, admin, reward_contract) = setup(&env);
         let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
         client.register_referrer(&user, &referrer);
 
-        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
-        client.record_referral_event(&admin, &user, &EventType::Deposit, &20_000);
-        client.record_referral_event(&admin, &user, &EventType::PrizeClaimed, &5_000);
-
-        // Total reward: 500 + 1000 + 250 = 1750
-        let state = client.referral_state(&referrer);
-        assert_eq!(state.pending_reward, 1750);
-        assert_eq!(state.total_earned, 1750);
-        assert_eq!(state.event_count, 3);
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        let token = TokenClient::new(&env, &sac.address());
+        StellarAssetClient::new(&env, &sac.address()).mint(&reward_contract, &10_000);
+        token.approve(&reward_contract, &client.address, &10_000, &1_000);
+
+        // Accounting-only by default: nothing moves on claim.
+        assert_eq!(client.get_reward_token(), None);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &2_000);
+        assert_eq!(client.claim_referral_reward(&referrer), 100);
+        assert_eq!(token.balance(&referrer), 0);
+
+        client.set_reward_token(&admin, &Some(sac.address()));
+        assert_eq!(client.get_reward_token(), Some(sac.address()));
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &4_000);
+        assert_eq!(client.claim_referral_reward(&referrer), 200);
+        assert_eq!(token.balance(&referrer), 200);
+        assert_eq!(token.balance(&reward_contract), 9_800);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 0);
+
+        client.set_reward_token(&admin, &None);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &2_000);
+        client.claim_referral_reward(&referrer);
+        assert_eq!(token.balance(&referrer), 200);
     }
 
     #[test]
-    fn test_record_event_no_referrer() {
+    fn test_cohorts_track_registrations_activity_and_rewards() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
-        env.mock_all_auths();
+        env.ledger().set_timestamp(COHORT_SECONDS * 3 + 10);
+        assert_eq!(client.current_cohort(), 3);
 
-        let user = Address::generate(&env);
-
-        let result =
-            client.try_record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
-        assert_eq!(result, Err(Ok(Error::ReferrerNotRegistered)));
+        let referrer = Address::generate(&env);
+        let (alice, bob, carol) = (
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        );
+        client.register_referrer(&alice, &referrer);
+        client.register_referrer(&bob, &referrer);
+        client.record_referral_event(&admin, &alice, &EventType::Deposit, &1_000);
+        client.record_referral_event(&admin, &alice, &EventType::GamePlayed, &2_000);
+
+        env.ledger().set_timestamp(COHORT_SECONDS * 4);
+        client.register_referrer(&carol, &referrer);
+        client.record_referral_event(&admin, &bob, &EventType::Deposit, &4_000);
+        client.record_referral_event(&admin, &carol, &EventType::Deposit, &500);
+
+        assert_eq!(client.get_user_cohort(&alice), Some(3));
+        assert_eq!(client.get_user_cohort(&carol), Some(4));
+        assert_eq!(client.get_user_cohort(&referrer), None);
+        // Bob's later activity stays with the week he registered in.
+        assert_eq!(
+            client.get_cohort(&3),
+            CohortStats {
+                registrations: 2,
+                active_referees: 2,
+                rewards: 350,
+            }
+        );
+        assert_eq!(
+            client.get_cohorts(&2, &3),
+            vec![
+                &env,
+                CohortStats::default(),
+                client.get_cohort(&3),
+                CohortStats {
+                    registrations: 1,
+                    active_referees: 1,
+                    rewards: 25,
+                },
+            ]
+        );
+        assert_eq!(client.get_cohorts(&0, &100).len(), MAX_COHORT_QUERY);
     }
 
     #[test]
-    fn test_record_event_invalid_amount() {
+    fn test_banned_referrer_is_frozen_until_unbanned() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
-        env.mock_all_auths();
+        client.set_upline_bps(&admin, &vec![&env, 200]);
 
-        let user = Address::generate(&env);
+        let top = Address::generate(&env);
         let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
+        client.register_referrer(&referrer, &top);
         client.register_referrer(&user, &referrer);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 50);
+
+        client.ban_referrer(&admin, &referrer);
+        assert!(client.is_banned(&referrer));
+        assert_eq!(
+            client
+                .estimate_reward(&EventType::Deposit, &1_000, &referrer, &None)
+                .reward,
+            0
+        );
 
-        let result = client.try_record_referral_event(&admin, &user, &EventType::GamePlayed, &0);
-        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        // Events still record and credit the upline, but not the banned referrer.
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &1_000);
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.pending_reward, 50);
+        assert_eq!(state.event_count, 2);
+        assert_eq!(client.referral_state(&top).pending_reward, 40);
+        assert_eq!(
+            client.try_claim_referral_reward(&referrer),
+            Err(Ok(Error::ReferrerBanned))
+        );
 
-        let result = client.try_record_referral_event(&admin, &user, &EventType::GamePlayed, &-100);
-        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        client.unban_referrer(&admin, &referrer);
+        assert!(!client.is_banned(&referrer));
+        assert_eq!(client.claim_referral_reward(&referrer), 50);
     }
 
     #[test]
-    fn test_record_event_not_admin() {
+    fn test_referee_bonus_credits_both_sides() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
-        env.mock_all_auths();
-
-        let user = Address::generate(&env);
+        let (client, admin, _) = setup(&env);
         let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
         client.register_referrer(&user, &referrer);
 
-        let attacker = Address::generate(&env);
-        let result =
-            client.try_record_referral_event(&attacker, &user, &EventType::GamePlayed, &10_000);
-        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+        assert_eq!(client.get_referee_bps(), 0);
+        let result = client.try_set_referee_bps(&admin, &10_001);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        client.set_referee_bps(&admin, &200);
+        assert_eq!(client.get_referee_bps(), 200);
+
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 50);
+        let referee = client.referral_state(&user);
+        assert_eq!(referee.pending_reward, 20);
+        assert_eq!(referee.total_earned, 20);
+        assert_eq!(referee.event_count, 0);
+        assert_eq!(client.get_global_totals().rewards, 70);
+        assert_eq!(client.get_leaderboard().len(), 1);
+
+        assert_eq!(client.claim_referral_reward(&user), 20);
     }
 
     #[test]
-    fn test_record_events_from_multiple_referees() {
+    fn test_rate_tiers_upgrade_with_active_referees() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
-        env.mock_all_auths();
-
         let referrer = Address::generate(&env);
-        let user1 = Address::generate(&env);
-        let user2 = Address::generate(&env);
-
-        client.register_referrer(&user1, &referrer);
-        client.register_referrer(&user2, &referrer);
-
-        client.record_referral_event(&admin, &user1, &EventType::GamePlayed, &10_000);
-        client.record_referral_event(&admin, &user2, &EventType::Deposit, &20_000);
+        let users = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        for user in users.iter() {
+            client.register_referrer(user, &referrer);
+        }
 
-        // Referrer earns from both: 500 + 1000 = 1500
-        let state = client.referral_state(&referrer);
-        assert_eq!(state.pending_reward, 1500);
-        assert_eq!(state.total_earned, 1500);
-        assert_eq!(state.event_count, 2);
+        let tier = |min_active, bps| RateTier { min_active, bps };
+        let result = client.try_set_rate_tiers(&admin, &vec![&env, tier(3, 700), tier(3, 1_000)]);
+        assert_eq!(result, Err(Ok(Error::InvalidTiers)));
+        let result = client.try_set_rate_tiers(&admin, &vec![&env, tier(0, 700)]);
+        assert_eq!(result, Err(Ok(Error::InvalidTiers)));
+        client.set_rate_tiers(&admin, &vec![&env, tier(2, 700), tier(3, 1_000)]);
+        assert_eq!(client.get_rate_tiers().len(), 2);
+
+        // Base rate until the second referee becomes active.
+        client.record_referral_event(&admin, &users[0], &EventType::Deposit, &1_000);
+        client.record_referral_event(&admin, &users[0], &EventType::Deposit, &1_000);
+        let current = client.get_tier(&referrer);
+        assert_eq!(
+            (current.tier, current.bps, current.active_referees),
+            (0, 500, 1)
+        );
+        assert_eq!(client.referral_state(&referrer).pending_reward, 100);
+
+        // The upgrading event is already paid at the new rate.
+        client.record_referral_event(&admin, &users[1], &EventType::Deposit, &1_000);
+        assert_eq!(client.get_tier(&referrer).tier, 1);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 170);
+
+        client.record_referral_event(&admin, &users[2], &EventType::Deposit, &1_000);
+        let current = client.get_tier(&referrer);
+        assert_eq!(
+            (current.tier, current.bps, current.active_referees),
+            (2, 1_000, 3)
+        );
+        assert_eq!(client.referral_state(&referrer).pending_reward, 270);
+        assert_eq!(
+            client
+                .estimate_reward(&EventType::Deposit, &1_000, &referrer, &None)
+                .reward,
+            100
+        );
     }
 
-    // -----------------------------------------------------------------------
-    // Claim reward tests
-    // -----------------------------------------------------------------------
-
     #[test]
-    fn test_claim_referral_reward_success() {
+    fn test_activity_threshold_delays_accrual() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
-        env.mock_all_auths();
-
-        let user = Address::generate(&env);
         let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
         client.register_referrer(&user, &referrer);
 
-        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
-
-        // Referrer claims
-        let claimed = client.claim_referral_reward(&referrer);
-        assert_eq!(claimed, 500);
-
-        // Pending is now 0, but total_earned remains
+        let result = client.try_set_activity_threshold(&admin, &-1);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        client.set_activity_threshold(&admin, &1_500);
+        client.set_referee_bps(&admin, &100);
+
+        // Below the threshold the event is recorded but pays nobody.
+        let estimate = |referee: Option<Address>| {
+            client
+                .estimate_reward(&EventType::Deposit, &1_000, &referrer, &referee)
+                .reward
+        };
+        assert_eq!(estimate(Some(user.clone())), 0);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &1_000);
         let state = client.referral_state(&referrer);
         assert_eq!(state.pending_reward, 0);
-        assert_eq!(state.total_earned, 500);
+        assert_eq!(state.event_count, 1);
+        assert_eq!(client.referral_state(&user).pending_reward, 0);
+        assert_eq!(client.get_referee_volume(&user), 1_000);
+        assert_eq!(client.get_tier(&referrer).active_referees, 0);
+
+        // The event crossing the threshold and later ones accrue in full.
+        assert_eq!(estimate(Some(user.clone())), 50);
+        assert_eq!(estimate(None), 0);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 50);
+        assert_eq!(client.referral_state(&user).pending_reward, 10);
+        assert_eq!(client.get_tier(&referrer).active_referees, 1);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &200);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 60);
+        assert_eq!(client.get_referee_volume(&user), 2_000);
     }
 
     #[test]
-    fn test_claim_no_pending_rewards() {
+    fn test_migrate_states_imports_v1_balances_once() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
-        env.mock_all_auths();
-
-        let user = Address::generate(&env);
+        let (client, admin, _) = setup(&env);
         let referrer = Address::generate(&env);
-        client.register_referrer(&user, &referrer);
+        let user = Address::generate(&env);
+        assert_eq!(client.get_storage_version(), 1);
 
-        // Referrer has 0 pending
-        let result = client.try_claim_referral_reward(&referrer);
-        assert_eq!(result, Err(Ok(Error::NoPendingRewards)));
+        let v1 = |referrer: &Address, referees: Vec<Address>, earned, pending| ReferralStateV1 {
+            referrer: referrer.clone(),
+            referees,
+            total_earned: earned,
+            pending_reward: pending,
+            event_count: 3,
+        };
+        let batch = vec![
+            &env,
+            (
+                referrer.clone(),
+                v1(&referrer, vec![&env, user.clone()], 300, 120),
+            ),
+            (user.clone(), v1(&referrer, Vec::new(&env), 0, 0)),
+        ];
+        assert_eq!(client.migrate_states(&admin, &batch), 2);
+
+        let state = client.referral_state(&referrer);
+        assert_eq!(state.total_earned, 300);
+        assert_eq!(state.pending_reward, 120);
+        assert_eq!(state.referees, vec![&env, user.clone()]);
+        assert_eq!(client.get_referrer(&user), Some(referrer.clone()));
+        assert_eq!(client.get_leaderboard().get(0).unwrap().total_earned, 300);
+        assert!(client.is_migrated(&referrer));
+
+        // Resubmitting the batch is a no-op.
+        assert_eq!(client.migrate_states(&admin, &batch), 0);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 120);
+
+        // Migrated balances keep accruing and can be claimed.
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &1_000);
+        assert_eq!(client.claim_referral_reward(&referrer), 170);
+
+        client.complete_migration(&admin);
+        assert_eq!(client.get_storage_version(), STORAGE_VERSION);
+        let result = client.try_migrate_states(&admin, &batch);
+        assert_eq!(result, Err(Ok(Error::MigrationClosed)));
+        let result = client.try_complete_migration(&admin);
+        assert_eq!(result, Err(Ok(Error::MigrationClosed)));
     }
 
     #[test]
-    fn test_claim_then_accumulate_then_claim_again() {
+    fn test_allowed_game_contract_reports_as_invoker() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
-        env.mock_all_auths();
-
-        let user = Address::generate(&env);
         let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
         client.register_referrer(&user, &referrer);
 
-        // First batch of events
-        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
-        let claimed1 = client.claim_referral_reward(&referrer);
-        assert_eq!(claimed1, 500);
+        let game_id = env.register(MockGame, ());
+        let game = MockGameClient::new(&env, &game_id);
+        let other = MockGameClient::new(&env, &env.register(MockGame, ()));
+        client.set_game_reporter(&admin, &game_id, &true);
 
-        // Second batch
-        client.record_referral_event(&admin, &user, &EventType::Deposit, &20_000);
-        let claimed2 = client.claim_referral_reward(&referrer);
-        assert_eq!(claimed2, 1000);
+        // No mocked auths: the game is authorized as the direct invoker.
+        env.set_auths(&[]);
+        game.play(&client.address, &user, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 50);
 
-        // Total earned reflects both
-        let state = client.referral_state(&referrer);
-        assert_eq!(state.pending_reward, 0);
-        assert_eq!(state.total_earned, 1500);
-        assert_eq!(state.event_count, 2);
+        assert!(other.try_play(&client.address, &user, &1_000).is_err());
+        assert_eq!(client.referral_state(&referrer).event_count, 1);
     }
 
     #[test]
-    fn test_claim_unknown_user() {
+    fn test_global_stats_track_registrations_events_and_claims() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
-        env.mock_all_auths();
+        let (client, admin, _) = setup(&env);
+        assert_eq!(client.get_global_stats(), GlobalStats::default());
 
-        let unknown = Address::generate(&env);
-        let result = client.try_claim_referral_reward(&unknown);
-        assert_eq!(result, Err(Ok(Error::ReferrerNotRegistered)));
+        let referrer = Address::generate(&env);
+        let users = [Address::generate(&env), Address::generate(&env)];
+        for user in users.iter() {
+            client.register_referrer(user, &referrer);
+        }
+        client.set_upline_bps(&admin, &vec![&env, 200]);
+        client.register_referrer(&referrer, &Address::generate(&env));
+
+        client.record_referral_event(&admin, &users[0], &EventType::Deposit, &1_000);
+        client.record_referral_event(&admin, &users[1], &EventType::Deposit, &2_000);
+        client.claim_referral_reward(&referrer);
+
+        let stats = client.get_global_stats();
+        assert_eq!(stats.registered_users, 3);
+        assert_eq!(stats.events, 2);
+        assert_eq!(stats.rewards_accrued, 150 + 60);
+        assert_eq!(stats.rewards_claimed, 150);
     }
 
-    // -----------------------------------------------------------------------
-    // View function tests
-    // -----------------------------------------------------------------------
-
     #[test]
-    fn test_referral_state_not_found() {
+    fn test_claims_pay_delegated_payout_address() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
+        let (client, admin, reward_contract) = setup(&env);
+        let referrer = Address::generate(&env);
+        let hot_wallet = Address::generate(&env);
+        let user = Address::generate(&env);
+        client.register_referrer(&user, &referrer);
 
-        let unknown = Address::generate(&env);
-        let result = client.try_referral_state(&unknown);
-        assert_eq!(result, Err(Ok(Error::ReferrerNotRegistered)));
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        let token = TokenClient::new(&env, &sac.address());
+        StellarAssetClient::new(&env, &sac.address()).mint(&reward_contract, &10_000);
+        token.approve(&reward_contract, &client.address, &10_000, &1_000);
+        client.set_reward_token(&admin, &Some(sac.address()));
+
+        assert_eq!(client.get_payout_address(&referrer), referrer);
+        client.set_payout_address(&referrer, &Some(hot_wallet.clone()));
+        assert_eq!(client.get_payout_address(&referrer), hot_wallet);
+
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &2_000);
+        assert_eq!(client.claim_referral_reward(&referrer), 100);
+        assert_eq!(token.balance(&hot_wallet), 100);
+        assert_eq!(token.balance(&referrer), 0);
+
+        client.set_payout_address(&referrer, &None);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &2_000);
+        client.claim_referral_reward(&referrer);
+        assert_eq!(token.balance(&referrer), 100);
     }
 
     #[test]
-    fn test_get_referrer_none() {
+    fn test_extra_reward_tokens_accrue_and_claim_separately() {
         let env = Env::default();
-        let (client, _, _) = setup(&env);
-
+        let (client, admin, reward_contract) = setup(&env);
+        let referrer = Address::generate(&env);
         let user = Address::generate(&env);
-        assert_eq!(client.get_referrer(&user), None);
-    }
+        client.register_referrer(&user, &referrer);
 
-    // -----------------------------------------------------------------------
-    // Custom reward BPS tests
-    // -----------------------------------------------------------------------
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        let platform = TokenClient::new(&env, &sac.address());
+        StellarAssetClient::new(&env, &sac.address()).mint(&reward_contract, &10_000);
+        platform.approve(&reward_contract, &client.address, &10_000, &1_000);
+
+        let result = client.try_set_extra_token(&admin, &platform.address, &10_001);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        client.set_extra_token(&admin, &platform.address, &100);
+        client.set_extra_token(&admin, &platform.address, &200);
+        assert_eq!(client.get_extra_tokens().len(), 1);
+
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 50);
+        assert_eq!(client.get_token_pending(&referrer, &platform.address), 20);
+
+        // Removing the token stops accrual but keeps the balance claimable.
+        client.remove_extra_token(&admin, &platform.address);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &1_000);
+        assert_eq!(client.get_token_pending(&referrer, &platform.address), 20);
+        let result = client.try_remove_extra_token(&admin, &platform.address);
+        assert_eq!(result, Err(Ok(Error::TokenNotConfigured)));
+
+        assert_eq!(client.claim_token_reward(&referrer, &platform.address), 20);
+        assert_eq!(platform.balance(&referrer), 20);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 100);
+        let result = client.try_claim_token_reward(&referrer, &platform.address);
+        assert_eq!(result, Err(Ok(Error::NoPendingRewards)));
+    }
 
     #[test]
-    fn test_custom_reward_bps() {
+    fn test_milestone_bonuses_paid_once() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
-        env.mock_all_auths();
+        let referrer = Address::generate(&env);
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        client.register_referrer(&first, &referrer);
+        client.register_referrer(&second, &referrer);
+
+        let milestone = |kind, threshold, bonus| Milestone {
+            kind,
+            threshold,
+            bonus,
+        };
+        let result =
+            client.try_set_milestones(&admin, &vec![&env, milestone(MilestoneKind::Volume, 0, 10)]);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        client.set_milestones(
+            &admin,
+            &vec![
+                &env,
+                milestone(MilestoneKind::ActiveReferees, 1, 10),
+                milestone(MilestoneKind::ActiveReferees, 2, 20),
+                milestone(MilestoneKind::Volume, 3_000, 300),
+            ],
+        );
 
-        // Set to 10% (1000 bps)
-        client.set_reward_bps(&admin, &1000);
+        // First active referee: 5% of 1_000 plus the first-referee bonus.
+        client.record_referral_event(&admin, &first, &EventType::Deposit, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 60);
+        assert!(client.is_milestone_reached(&referrer, &MilestoneKind::ActiveReferees, &1));
+
+        // Repeat events from the same referee do not pay it again.
+        client.record_referral_event(&admin, &first, &EventType::Deposit, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 110);
+
+        // The second referee crosses both the referee and volume milestones.
+        client.record_referral_event(&admin, &second, &EventType::Deposit, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 480);
+        assert_eq!(client.get_referred_volume(&referrer), 3_000);
+        assert!(client.is_milestone_reached(&referrer, &MilestoneKind::Volume, &3_000));
+        assert_eq!(client.get_global_stats().rewards_accrued, 480);
+
+        // Re-setting the same milestones does not pay them a second time.
+        client.set_milestones(&admin, &client.get_milestones());
+        client.record_referral_event(&admin, &second, &EventType::Deposit, &1_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 530);
+    }
 
-        let user = Address::generate(&env);
+    #[test]
+    fn test_clawback_reduces_pending_and_optionally_earned() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
         let referrer = Address::generate(&env);
+        let user = Address::generate(&env);
         client.register_referrer(&user, &referrer);
+        client.record_referral_event(&admin, &user, &EventType::Deposit, &10_000);
+        assert_eq!(client.referral_state(&referrer).pending_reward, 500);
 
-        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        let result = client.try_clawback(&admin, &referrer, &501, &1, &false);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        let stranger = Address::generate(&env);
+        let result = client.try_clawback(&admin, &stranger, &1, &1, &false);
+        assert_eq!(result, Err(Ok(Error::ReferrerNotRegistered)));
 
+        // History is kept unless asked otherwise.
+        assert_eq!(client.clawback(&admin, &referrer, &200, &7, &false), 300);
         let state = client.referral_state(&referrer);
-        assert_eq!(state.pending_reward, 1000); // 10% of 10_000
+        assert_eq!((state.pending_reward, state.total_earned), (300, 500));
+
+        assert_eq!(client.clawback(&admin, &referrer, &300, &7, &true), 0);
+        let state = client.referral_state(&referrer);
+        assert_eq!((state.pending_reward, state.total_earned), (0, 200));
+        assert_eq!(client.get_leaderboard().get(0).unwrap().total_earned, 200);
+        assert_eq!(client.get_clawed_back_total(), 500);
+        assert_eq!(
+            client.try_claim_referral_reward(&referrer),
+            Err(Ok(Error::NoPendingRewards))
+        );
     }
 
     #[test]
-    fn test_zero_reward_bps() {
+    fn test_referral_season_top_referrers_split_pool() {
         let env = Env::default();
         let (client, admin, _) = setup(&env);
-        env.mock_all_auths();
-
-        // Set to 0%
-        client.set_reward_bps(&admin, &0);
+        let referrers = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        let users = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        for (user, referrer) in users.iter().zip(referrers.iter()) {
+            client.register_referrer(user, referrer);
+        }
 
-        let user = Address::generate(&env);
-        let referrer = Address::generate(&env);
-        client.register_referrer(&user, &referrer);
+        // Accruals before the season do not count.
+        client.record_referral_event(&admin, &users[2], &EventType::Deposit, &100_000);
 
-        client.record_referral_event(&admin, &user, &EventType::GamePlayed, &10_000);
+        let result = client.try_start_season(&admin, &1_000, &0);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+        let season_id = client.start_season(&admin, &1_000, &2);
+        assert_eq!(client.get_current_season(), Some(season_id));
+        let result = client.try_start_season(&admin, &2_000, &2);
+        assert_eq!(result, Err(Ok(Error::SeasonActive)));
+        assert_eq!(client.fund_season(&admin, &season_id, &900), 900);
+
+        client.record_referral_event(&admin, &users[0], &EventType::Deposit, &20_000);
+        client.record_referral_event(&admin, &users[1], &EventType::Deposit, &10_000);
+        client.record_referral_event(&admin, &users[2], &EventType::Deposit, &5_000);
+        assert_eq!(client.get_season_score(&season_id, &referrers[0]), 1_000);
+        let standings = client.get_season_standings(&season_id);
+        assert_eq!(standings.len(), 2);
+        assert_eq!(standings.get(1).unwrap().referrer, referrers[1]);
+
+        assert_eq!(
+            client.try_claim_season_reward(&referrers[0], &season_id),
+            Err(Ok(Error::SeasonActive))
+        );
+        assert_eq!(
+            client.try_end_season(&season_id),
+            Err(Ok(Error::SeasonActive))
+        );
 
-        let state = client.referral_state(&referrer);
-        assert_eq!(state.pending_reward, 0);
-        assert_eq!(state.event_count, 1);
+        env.ledger().set_timestamp(1_000);
+        // Accruals after the end time do not count either.
+        client.record_referral_event(&admin, &users[2], &EventType::Deposit, &100_000);
+        assert_eq!(client.end_season(&season_id), 2);
+        assert_eq!(client.get_current_season(), None);
+
+        assert_eq!(client.claim_season_reward(&referrers[0], &season_id), 600);
+        assert_eq!(client.claim_season_reward(&referrers[1], &season_id), 300);
+        assert_eq!(
+            client.try_claim_season_reward(&referrers[0], &season_id),
+            Err(Ok(Error::AlreadyClaimed))
+        );
+        assert_eq!(
+            client.try_claim_season_reward(&referrers[2], &season_id),
+            Err(Ok(Error::NoPendingRewards))
+        );
     }
 }
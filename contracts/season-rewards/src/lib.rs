@@ -0,0 +1,728 @@
+//! Stellarcade Season Rewards Contract
+//!
+//! Pays out the platform-wide season prizes. Anyone may fund a season's
+//! prize pool with the reward token. When the season ends the admin calls
+//! `finalize_season`, which reads the final standings from the leaderboard
+//! contract (the standard `StandingsSource` interface) and allocates the pool
+//! to the top players along the configured prize curve. Each tier of the
+//! curve pays `bps` of the pool to each of its `ranks` players, so
+//! `[(1, 3000), (2, 1500), (7, 500)]` pays 30% to first, 15% each to second
+//! and third, and 5% each to fourth through tenth.
+//!
+//! Winners `claim` their allocation until the season's claim deadline. After
+//! it, the admin sweeps whatever was not allocated or claimed back out with
+//! `sweep_unclaimed`.
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype,
+    token::TokenClient, Address, Env, Vec,
+};
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Maximum number of ranked players paid per season (bounds finalization).
+pub const MAX_PAID_RANKS: u32 = 50;
+
+/// Maximum number of tiers in the prize curve.
+pub const MAX_TIERS: u32 = 10;
+
+/// Persistent storage TTL in ledgers (~30 days at 5 s/ledger).
+pub const PERSISTENT_BUMP_LEDGERS: u32 = 518_400;
+
+const BASIS_POINTS: i128 = 10_000;
+
+// ---------------------------------------------------------------------------
+// External contract clients
+// ---------------------------------------------------------------------------
+
+/// Interface the leaderboard implements so final standings can be read.
+/// `tournament-system` implements it, with tournament ids as season ids.
+#[contractclient(name = "StandingsSourceClient")]
+pub trait StandingsSource {
+    /// Best `limit` players of `season_id`, ranked first to last.
+    fn get_standings(env: Env, season_id: u64, limit: u32) -> Vec<Standing>;
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    NotAuthorized = 3,
+    InvalidAmount = 4,
+    InvalidCurve = 5,
+    SeasonFinalized = 6,
+    SeasonNotFinalized = 7,
+    NothingToClaim = 8,
+    AlreadyClaimed = 9,
+    ClaimWindowClosed = 10,
+    ClaimWindowOpen = 11,
+    Overflow = 12,
+}
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    /// Token prizes are funded and paid in (Address).
+    Token,
+    /// Leaderboard contract read at finalization (Address).
+    Leaderboard,
+    /// Prize curve (Vec<PrizeTier>).
+    PrizeCurve,
+    /// `Season` by id — persistent storage.
+    Season(u64),
+    /// `Allocation` per (season, player) — persistent storage.
+    Allocation(u64, Address),
+}
+
+/// One ranked entry of a leaderboard's standings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Standing {
+    pub player: Address,
+    pub score: i128,
+}
+
+/// `ranks` consecutive places that each receive `bps` of the season pool.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrizeTier {
+    pub ranks: u32,
+    pub bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Season {
+    /// Tokens funded for the season.
+    pub pool: i128,
+    /// Sum of the allocations made at finalization.
+    pub allocated: i128,
+    /// Sum of the allocations claimed so far.
+    pub claimed: i128,
+    /// Number of players allocated a prize.
+    pub winners: u32,
+    pub finalized: bool,
+    /// Ledger timestamp after which claims close; `0` until finalized.
+    pub claim_deadline: u64,
+    /// Set once the unclaimed remainder has been swept.
+    pub swept: bool,
+}
+
+/// A player's prize for one season.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Allocation {
+    pub rank: u32,
+    pub amount: i128,
+    pub claimed: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Events
+// ---------------------------------------------------------------------------
+
+#[contractevent]
+pub struct SeasonFunded {
+    #[topic]
+    pub season_id: u64,
+    pub funder: Address,
+    pub amount: i128,
+    pub pool: i128,
+}
+
+#[contractevent]
+pub struct SeasonFinalized {
+    #[topic]
+    pub season_id: u64,
+    pub pool: i128,
+    pub allocated: i128,
+    pub winners: u32,
+    pub claim_deadline: u64,
+}
+
+#[contractevent]
+pub struct PrizeClaimed {
+    #[topic]
+    pub season_id: u64,
+    #[topic]
+    pub player: Address,
+    pub rank: u32,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct UnclaimedSwept {
+    #[topic]
+    pub season_id: u64,
+    pub to: Address,
+    pub amount: i128,
+}
+
+// ---------------------------------------------------------------------------
+// Contract
+// ---------------------------------------------------------------------------
+
+#[contract]
+pub struct SeasonRewards;
+
+#[contractimpl]
+impl SeasonRewards {
+    pub fn init(
+        env: Env,
+        admin: Address,
+        token: Address,
+        leaderboard: Address,
+    ) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage()
+            .instance()
+            .set(&DataKey::Leaderboard, &leaderboard);
+        env.storage()
+            .instance()
+            .set(&DataKey::PrizeCurve, &Vec::<PrizeTier>::new(&env));
+        Ok(())
+    }
+
+    /// Set the prize curve applied at finalization. Admin only.
+    ///
+    /// Fails with `InvalidCurve` if a tier has no ranks, the curve has more
+    /// than `MAX_TIERS` tiers or pays more than `MAX_PAID_RANKS` places, or
+    /// the tiers together pay out more than the whole pool.
+    pub fn set_prize_curve(env: Env, curve: Vec<PrizeTier>) -> Result<(), Error> {
+        require_admin(&env)?;
+
+        if curve.len() > MAX_TIERS {
+            return Err(Error::InvalidCurve);
+        }
+        let mut ranks: u32 = 0;
+        let mut total_bps: i128 = 0;
+        for tier in curve.iter() {
+            if tier.ranks == 0 {
+                return Err(Error::InvalidCurve);
+            }
+            ranks = ranks.checked_add(tier.ranks).ok_or(Error::Overflow)?;
+            total_bps = total_bps
+                .checked_add(i128::from(tier.ranks) * i128::from(tier.bps))
+                .ok_or(Error::Overflow)?;
+        }
+        if ranks > MAX_PAID_RANKS || total_bps > BASIS_POINTS {
+            return Err(Error::InvalidCurve);
+        }
+
+        env.storage().instance().set(&DataKey::PrizeCurve, &curve);
+        Ok(())
+    }
+
+    /// Add `amount` of the reward token to `season_id`'s prize pool. Anyone
+    /// may fund a season until it is finalized.
+    ///
+    /// Emits `SeasonFunded`.
+    pub fn fund_season(
+        env: Env,
+        funder: Address,
+        season_id: u64,
+        amount: i128,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        funder.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut season = get_season(&env, season_id);
+        if season.finalized {
+            return Err(Error::SeasonFinalized);
+        }
+        season.pool = season.pool.checked_add(amount).ok_or(Error::Overflow)?;
+
+        TokenClient::new(&env, &get_token(&env)).transfer(
+            &funder,
+            env.current_contract_address(),
+            &amount,
+        );
+        set_persistent(&env, &DataKey::Season(season_id), &season);
+
+        SeasonFunded {
+            season_id,
+            funder,
+            amount,
+            pool: season.pool,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Close `season_id`: read its final standings from the leaderboard and
+    /// allocate the pool along the prize curve, then open claims for
+    /// `claim_window` seconds. Admin only. Returns the number of winners.
+    ///
+    /// Players are ranked in the leaderboard's order; an address listed
+    /// twice is only paid for its best rank. Places the leaderboard cannot
+    /// fill leave their share unallocated.
+    ///
+    /// Emits `SeasonFinalized`.
+    pub fn finalize_season(env: Env, season_id: u64, claim_window: u64) -> Result<u32, Error> {
+        require_admin(&env)?;
+        if claim_window == 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut season = get_season(&env, season_id);
+        if season.finalized {
+            return Err(Error::SeasonFinalized);
+        }
+
+        let curve = get_prize_curve(&env);
+        let paid_ranks: u32 = curve.iter().map(|tier| tier.ranks).sum();
+        let leaderboard: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Leaderboard)
+            .ok_or(Error::NotInitialized)?;
+        let standings =
+            StandingsSourceClient::new(&env, &leaderboard).get_standings(&season_id, &paid_ranks);
+
+        let mut paid: Vec<Address> = Vec::new(&env);
+        let mut entries = standings.iter();
+        'tiers: for tier in curve.iter() {
+            let share = season
+                .pool
+                .checked_mul(i128::from(tier.bps))
+                .ok_or(Error::Overflow)?
+                / BASIS_POINTS;
+            for _ in 0..tier.ranks {
+                let player = loop {
+                    match entries.next() {
+                        Some(entry) if paid.contains(&entry.player) => continue,
+                        Some(entry) => break entry.player,
+                        None => break 'tiers,
+                    }
+                };
+                paid.push_back(player.clone());
+                if share > 0 {
+                    set_persistent(
+                        &env,
+                        &DataKey::Allocation(season_id, player),
+                        &Allocation {
+                            rank: paid.len(),
+                            amount: share,
+                            claimed: false,
+                        },
+                    );
+                    season.allocated =
+                        season.allocated.checked_add(share).ok_or(Error::Overflow)?;
+                    season.winners += 1;
+                }
+            }
+        }
+
+        season.finalized = true;
+        season.claim_deadline = env
+            .ledger()
+            .timestamp()
+            .checked_add(claim_window)
+            .ok_or(Error::Overflow)?;
+        set_persistent(&env, &DataKey::Season(season_id), &season);
+
+        SeasonFinalized {
+            season_id,
+            pool: season.pool,
+            allocated: season.allocated,
+            winners: season.winners,
+            claim_deadline: season.claim_deadline,
+        }
+        .publish(&env);
+        Ok(season.winners)
+    }
+
+    /// Pay `player` their prize for `season_id`. Player must authorize, and
+    /// only until the season's claim deadline. Returns the amount paid.
+    ///
+    /// Emits `PrizeClaimed`.
+    pub fn claim(env: Env, player: Address, season_id: u64) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let mut season = get_season(&env, season_id);
+        if !season.finalized {
+            return Err(Error::SeasonNotFinalized);
+        }
+        if env.ledger().timestamp() > season.claim_deadline {
+            return Err(Error::ClaimWindowClosed);
+        }
+
+        let key = DataKey::Allocation(season_id, player.clone());
+        let mut allocation: Allocation = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NothingToClaim)?;
+        if allocation.claimed {
+            return Err(Error::AlreadyClaimed);
+        }
+
+        allocation.claimed = true;
+        set_persistent(&env, &key, &allocation);
+        season.claimed = season
+            .claimed
+            .checked_add(allocation.amount)
+            .ok_or(Error::Overflow)?;
+        set_persistent(&env, &DataKey::Season(season_id), &season);
+
+        TokenClient::new(&env, &get_token(&env)).transfer(
+            &env.current_contract_address(),
+            &player,
+            &allocation.amount,
+        );
+
+        PrizeClaimed {
+            season_id,
+            player,
+            rank: allocation.rank,
+            amount: allocation.amount,
+        }
+        .publish(&env);
+        Ok(allocation.amount)
+    }
+
+    /// Transfer the part of `season_id`'s pool that was never allocated or
+    /// claimed to `to`, once the claim deadline has passed. Admin only, once
+    /// per season. Returns the amount swept.
+    ///
+    /// Emits `UnclaimedSwept`.
+    pub fn sweep_unclaimed(env: Env, season_id: u64, to: Address) -> Result<i128, Error> {
+        require_admin(&env)?;
+
+        let mut season = get_season(&env, season_id);
+        if !season.finalized {
+            return Err(Error::SeasonNotFinalized);
+        }
+        if env.ledger().timestamp() <= season.claim_deadline {
+            return Err(Error::ClaimWindowOpen);
+        }
+        if season.swept {
+            return Err(Error::NothingToClaim);
+        }
+
+        let amount = season
+            .pool
+            .checked_sub(season.claimed)
+            .ok_or(Error::Overflow)?;
+        season.swept = true;
+        set_persistent(&env, &DataKey::Season(season_id), &season);
+
+        if amount > 0 {
+            TokenClient::new(&env, &get_token(&env)).transfer(
+                &env.current_contract_address(),
+                &to,
+                &amount,
+            );
+        }
+
+        UnclaimedSwept {
+            season_id,
+            to,
+            amount,
+        }
+        .publish(&env);
+        Ok(amount)
+    }
+
+    pub fn get_prize_curve(env: Env) -> Vec<PrizeTier> {
+        get_prize_curve(&env)
+    }
+
+    /// Return a season's pool, allocation and claim totals. Seasons never
+    /// funded or finalized read as empty.
+    pub fn get_season(env: Env, season_id: u64) -> Season {
+        get_season(&env, season_id)
+    }
+
+    pub fn get_allocation(env: Env, season_id: u64, player: Address) -> Option<Allocation> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Allocation(season_id, player))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn require_initialized(env: &Env) -> Result<(), Error> {
+    if !env.storage().instance().has(&DataKey::Admin) {
+        return Err(Error::NotInitialized);
+    }
+    Ok(())
+}
+
+fn require_admin(env: &Env) -> Result<(), Error> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)?;
+    admin.require_auth();
+    Ok(())
+}
+
+fn get_token(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Token)
+        .expect("SeasonRewards: token not set")
+}
+
+fn get_prize_curve(env: &Env) -> Vec<PrizeTier> {
+    env.storage()
+        .instance()
+        .get(&DataKey::PrizeCurve)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn get_season(env: &Env, season_id: u64) -> Season {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Season(season_id))
+        .unwrap_or_default()
+}
+
+fn set_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(
+    env: &Env,
+    key: &DataKey,
+    value: &V,
+) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        token::StellarAssetClient,
+        vec, Address, Env,
+    };
+
+    // -----------------------------
+    // Mock leaderboard contract
+    // -----------------------------
+
+    #[contract]
+    pub struct MockLeaderboard;
+
+    #[contracttype]
+    pub enum MockKey {
+        Standings(u64),
+    }
+
+    #[contractimpl]
+    impl MockLeaderboard {
+        pub fn set_standings(env: Env, season_id: u64, standings: Vec<Standing>) {
+            env.storage()
+                .instance()
+                .set(&MockKey::Standings(season_id), &standings);
+        }
+
+        pub fn get_standings(env: Env, season_id: u64, limit: u32) -> Vec<Standing> {
+            let standings: Vec<Standing> = env
+                .storage()
+                .instance()
+                .get(&MockKey::Standings(season_id))
+                .unwrap_or_else(|| Vec::new(&env));
+            standings.slice(0..limit.min(standings.len()))
+        }
+    }
+
+    struct Setup<'a> {
+        client: SeasonRewardsClient<'a>,
+        leaderboard: MockLeaderboardClient<'a>,
+        token: TokenClient<'a>,
+        funder: Address,
+    }
+
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+
+        let admin = Address::generate(env);
+        let token_admin = Address::generate(env);
+        let token_id = env
+            .register_stellar_asset_contract_v2(token_admin)
+            .address();
+        let funder = Address::generate(env);
+        StellarAssetClient::new(env, &token_id).mint(&funder, &100_000);
+
+        let leaderboard = MockLeaderboardClient::new(env, &env.register(MockLeaderboard, ()));
+        let client = SeasonRewardsClient::new(env, &env.register(SeasonRewards, ()));
+        client.init(&admin, &token_id, &leaderboard.address);
+
+        Setup {
+            client,
+            leaderboard,
+            token: TokenClient::new(env, &token_id),
+            funder,
+        }
+    }
+
+    fn tier(ranks: u32, bps: u32) -> PrizeTier {
+        PrizeTier { ranks, bps }
+    }
+
+    fn standings(env: &Env, players: &[&Address]) -> Vec<Standing> {
+        let mut standings = Vec::new(env);
+        for (i, player) in players.iter().enumerate() {
+            standings.push_back(Standing {
+                player: (*player).clone(),
+                score: 1_000 - i as i128,
+            });
+        }
+        standings
+    }
+
+    #[test]
+    fn test_prize_curve_validation() {
+        let env = Env::default();
+        let s = setup(&env);
+
+        let result = s.client.try_set_prize_curve(&vec![&env, tier(0, 1_000)]);
+        assert_eq!(result, Err(Ok(Error::InvalidCurve)));
+        let result = s
+            .client
+            .try_set_prize_curve(&vec![&env, tier(1, 6_000), tier(2, 2_500)]);
+        assert_eq!(result, Err(Ok(Error::InvalidCurve)));
+        let result = s
+            .client
+            .try_set_prize_curve(&vec![&env, tier(MAX_PAID_RANKS + 1, 1)]);
+        assert_eq!(result, Err(Ok(Error::InvalidCurve)));
+
+        let curve = vec![&env, tier(1, 5_000), tier(2, 2_500)];
+        s.client.set_prize_curve(&curve);
+        assert_eq!(s.client.get_prize_curve(), curve);
+    }
+
+    #[test]
+    fn test_finalize_allocates_tiers_from_standings() {
+        let env = Env::default();
+        let s = setup(&env);
+        let players: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+
+        s.client
+            .set_prize_curve(&vec![&env, tier(1, 4_000), tier(3, 1_500)]);
+        s.client.fund_season(&s.funder, &1, &10_000);
+
+        // The first player appears twice; only their best rank is paid.
+        s.leaderboard.set_standings(
+            &1,
+            &standings(&env, &[&players[0], &players[0], &players[1], &players[2]]),
+        );
+        assert_eq!(s.client.finalize_season(&1, &1_000), 3);
+
+        let season = s.client.get_season(&1);
+        assert_eq!(season.pool, 10_000);
+        assert_eq!(season.allocated, 4_000 + 1_500 * 2);
+        let first = s.client.get_allocation(&1, &players[0]).unwrap();
+        assert_eq!((first.rank, first.amount), (1, 4_000));
+        let third = s.client.get_allocation(&1, &players[2]).unwrap();
+        assert_eq!((third.rank, third.amount), (3, 1_500));
+        assert_eq!(s.client.get_allocation(&1, &players[3]), None);
+
+        let result = s.client.try_finalize_season(&1, &1_000);
+        assert_eq!(result, Err(Ok(Error::SeasonFinalized)));
+        let result = s.client.try_fund_season(&s.funder, &1, &100);
+        assert_eq!(result, Err(Ok(Error::SeasonFinalized)));
+    }
+
+    #[test]
+    fn test_claims_close_at_deadline_and_remainder_is_swept() {
+        let env = Env::default();
+        let s = setup(&env);
+        let winner = Address::generate(&env);
+        let late = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        s.client
+            .set_prize_curve(&vec![&env, tier(1, 5_000), tier(1, 3_000)]);
+        s.client.fund_season(&s.funder, &7, &1_000);
+        let result = s.client.try_claim(&winner, &7);
+        assert_eq!(result, Err(Ok(Error::SeasonNotFinalized)));
+
+        s.leaderboard
+            .set_standings(&7, &standings(&env, &[&winner, &late]));
+        s.client.finalize_season(&7, &100);
+
+        assert_eq!(s.client.claim(&winner, &7), 500);
+        assert_eq!(s.token.balance(&winner), 500);
+        let result = s.client.try_claim(&winner, &7);
+        assert_eq!(result, Err(Ok(Error::AlreadyClaimed)));
+        let result = s.client.try_claim(&treasury, &7);
+        assert_eq!(result, Err(Ok(Error::NothingToClaim)));
+        let result = s.client.try_sweep_unclaimed(&7, &treasury);
+        assert_eq!(result, Err(Ok(Error::ClaimWindowOpen)));
+
+        env.ledger().with_mut(|li| li.timestamp += 101);
+        let result = s.client.try_claim(&late, &7);
+        assert_eq!(result, Err(Ok(Error::ClaimWindowClosed)));
+
+        // The late player's 300 and the unallocated 200 are swept.
+        assert_eq!(s.client.sweep_unclaimed(&7, &treasury), 500);
+        assert_eq!(s.token.balance(&treasury), 500);
+        assert_eq!(s.token.balance(&s.client.address), 0);
+        let result = s.client.try_sweep_unclaimed(&7, &treasury);
+        assert_eq!(result, Err(Ok(Error::NothingToClaim)));
+    }
+
+    #[test]
+    fn test_finalize_reads_tournament_standings() {
+        use soroban_sdk::BytesN;
+        use stellarcade_tournament_system::{TournamentSystem, TournamentSystemClient};
+
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        let tournament = TournamentSystemClient::new(&env, &env.register(TournamentSystem, ()));
+        tournament.init(&admin, &Address::generate(&env), &Address::generate(&env));
+        tournament.create_tournament(&admin, &3, &BytesN::from_array(&env, &[0; 32]), &0);
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        for (player, score) in [(&second, 200), (&first, 900)] {
+            tournament.join_tournament(player, &3);
+            tournament.record_result(&admin, &3, player, &score);
+        }
+
+        let client = SeasonRewardsClient::new(&env, &env.register(SeasonRewards, ()));
+        client.init(&admin, &s.token.address, &tournament.address);
+        client.set_prize_curve(&vec![&env, tier(1, 6_000), tier(1, 4_000)]);
+        client.fund_season(&s.funder, &3, &1_000);
+
+        assert_eq!(client.finalize_season(&3, &100), 2);
+        assert_eq!(client.get_allocation(&3, &first).unwrap().amount, 600);
+        assert_eq!(client.get_allocation(&3, &second).unwrap().amount, 400);
+    }
+}
@@ -2,10 +2,15 @@
 #![no_std]
 #![allow(unexpected_cfgs)]
 
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracterror, contracttype, Address, Bytes, Env};
+
+pub mod storage;
 
 /// Common error codes used across all contracts.
-#[contracttype]
+///
+/// Declared with `contracterror`: the SDK does not accept a `contracttype`
+/// named `Error`.
+#[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u32)]
 pub enum Error {
@@ -13,6 +18,61 @@ pub enum Error {
     InsufficientBalance = 2,
     InvalidAmount = 3,
     Overflow = 4,
+    InvalidTransition = 5,
+}
+
+/// Canonical lifecycle of a game or round:
+/// `Created → Open → Locked → Resolved | Cancelled | Expired`.
+///
+/// `Locked` (closed to entries, awaiting a result) is optional: games that
+/// stop taking entries at a deadline may resolve straight from `Open`.
+/// `Resolved`, `Cancelled` and `Expired` are terminal. Discriminants keep the
+/// values color-prediction stored before the enum moved here.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GameStatus {
+    /// Accepting entries.
+    Open = 0,
+    /// Result known and recorded.
+    Resolved = 1,
+    /// Called off; every entry is refundable.
+    Cancelled = 2,
+    /// Configured but not yet accepting entries.
+    Created = 3,
+    /// No longer accepting entries; awaiting a result.
+    Locked = 4,
+    /// Never resolved within its deadline.
+    Expired = 5,
+}
+
+impl GameStatus {
+    /// Whether no further transition is possible.
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            GameStatus::Resolved | GameStatus::Cancelled | GameStatus::Expired
+        )
+    }
+
+    /// Whether the lifecycle allows moving from `self` to `next`.
+    pub fn can_transition_to(self, next: GameStatus) -> bool {
+        match self {
+            GameStatus::Created => matches!(next, GameStatus::Open | GameStatus::Cancelled),
+            GameStatus::Open => next == GameStatus::Locked || next.is_terminal(),
+            GameStatus::Locked => next.is_terminal(),
+            GameStatus::Resolved | GameStatus::Cancelled | GameStatus::Expired => false,
+        }
+    }
+
+    /// Move to `next`, failing with `InvalidTransition` if the lifecycle
+    /// does not allow it.
+    pub fn transition(&mut self, next: GameStatus) -> Result<(), Error> {
+        if !self.can_transition_to(next) {
+            return Err(Error::InvalidTransition);
+        }
+        *self = next;
+        Ok(())
+    }
 }
 
 /// A standard configuration for platform-wide settings.
@@ -39,3 +99,144 @@ pub fn calculate_fee(amount: i128, fee_bps: u32) -> Result<i128, Error> {
         .and_then(|v| v.checked_div(BASIS_POINTS_DIVISOR as i128))
         .ok_or(Error::Overflow)
 }
+
+/// Derive a pseudo-random value in `[0, bound)` for lightweight tie-breaking
+/// (podium order, dust assignment).
+///
+/// The value is the SHA-256 of 32 bytes drawn from `env.prng()`, the caller's
+/// `salt`, and the ledger sequence, reduced modulo `bound`. Distinct salts
+/// give independent draws within one invocation, and the result is
+/// reproducible for the same PRNG state, salt, and ledger. It is not suitable
+/// for outcomes players can profit from predicting; use the RNG contract for
+/// those.
+pub fn random_below(env: &Env, salt: &Bytes, bound: u64) -> Result<u64, Error> {
+    if bound == 0 {
+        return Err(Error::InvalidAmount);
+    }
+    let mut input = Bytes::new(env);
+    input.extend_from_array(&env.prng().gen::<[u8; 32]>());
+    input.append(salt);
+    input.extend_from_array(&env.ledger().sequence().to_be_bytes());
+
+    let digest = env.crypto().sha256(&input).to_array();
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&digest[..8]);
+    Ok(u64::from_be_bytes(head) % bound)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Ledger};
+
+    #[contract]
+    struct Harness;
+
+    #[contractimpl]
+    impl Harness {}
+
+    fn draw(env: &Env, salt: &[u8], bound: u64) -> Result<u64, Error> {
+        let id = env.register(Harness, ());
+        env.as_contract(&id, || {
+            random_below(env, &Bytes::from_slice(env, salt), bound)
+        })
+    }
+
+    #[test]
+    fn test_game_status_transitions() {
+        use GameStatus::*;
+        let all = [Created, Open, Locked, Resolved, Cancelled, Expired];
+        let allowed = [
+            (Created, Open),
+            (Created, Cancelled),
+            (Open, Locked),
+            (Open, Resolved),
+            (Open, Cancelled),
+            (Open, Expired),
+            (Locked, Resolved),
+            (Locked, Cancelled),
+            (Locked, Expired),
+        ];
+        for from in all {
+            for to in all {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    allowed.contains(&(from, to)),
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+            assert_eq!(from.is_terminal(), !allowed.iter().any(|(f, _)| *f == from));
+        }
+
+        let mut status = Created;
+        status.transition(Open).unwrap();
+        status.transition(Locked).unwrap();
+        assert_eq!(status.transition(Open), Err(Error::InvalidTransition));
+        status.transition(Resolved).unwrap();
+        assert_eq!(status.transition(Cancelled), Err(Error::InvalidTransition));
+        assert_eq!(status, Resolved);
+    }
+
+    #[test]
+    fn test_random_below_stays_in_bounds() {
+        let env = Env::default();
+        let id = env.register(Harness, ());
+        env.as_contract(&id, || {
+            for i in 0u8..50 {
+                let salt = Bytes::from_slice(&env, &[i]);
+                assert!(random_below(&env, &salt, 3).unwrap() < 3);
+            }
+            let salt = Bytes::from_slice(&env, b"one");
+            assert_eq!(random_below(&env, &salt, 1), Ok(0));
+        });
+    }
+
+    #[test]
+    fn test_random_below_rejects_zero_bound() {
+        let env = Env::default();
+        assert_eq!(draw(&env, b"podium", 0), Err(Error::InvalidAmount));
+    }
+
+    #[test]
+    fn test_random_below_is_reproducible() {
+        let first = draw(&Env::default(), b"podium", u64::MAX).unwrap();
+        assert_eq!(draw(&Env::default(), b"podium", u64::MAX).unwrap(), first);
+        assert_ne!(draw(&Env::default(), b"dust", u64::MAX).unwrap(), first);
+
+        let env = Env::default();
+        env.ledger().set_sequence_number(99);
+        assert_ne!(draw(&env, b"podium", u64::MAX).unwrap(), first);
+    }
+
+    #[test]
+    fn test_storage_health_tracks_live_entries() {
+        use crate::storage::*;
+        use soroban_sdk::symbol_short;
+
+        let env = Env::default();
+        env.ledger().set_sequence_number(10);
+        let id = env.register(Harness, ());
+        let game = symbol_short!("game");
+        let other = symbol_short!("other");
+        env.as_contract(&id, || {
+            assert_eq!(storage_health(&env, &[]).instance_ttl, 0);
+            count_new_entry(&env, game.clone());
+            count_new_entry(&env, game.clone());
+            count_removed_entry(&env, game.clone());
+            count_removed_entry(&env, other.clone());
+            bump_instance(&env);
+        });
+
+        env.ledger().set_sequence_number(110);
+        env.as_contract(&id, || {
+            let health = storage_health(&env, &[game.clone(), other.clone()]);
+            assert_eq!(health.last_bump_ledger, 10);
+            assert_eq!(health.instance_ttl, INSTANCE_BUMP_LEDGERS - 100);
+            assert_eq!(health.families.get(0).unwrap().family, game);
+            assert_eq!(health.families.get(0).unwrap().count, 1);
+            assert_eq!(health.families.get(1).unwrap().count, 0);
+        });
+    }
+}
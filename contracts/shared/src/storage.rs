@@ -0,0 +1,106 @@
+//! Storage-health bookkeeping for game contracts.
+//!
+//! A contract tags each persistent entry with a key family (a short
+//! `Symbol`), calls `count_new_entry` when it creates an entry and
+//! `count_removed_entry` when it deletes one, and reports the counts with
+//! `storage_health`. Entries that expire through TTL are never seen by the
+//! contract, so they stay counted until it removes them.
+//!
+//! Contracts cannot read their own TTL on-chain. The instance TTL reported
+//! here is derived from the ledger recorded by the last `bump_instance`.
+
+use soroban_sdk::{contracttype, Env, Symbol, Vec};
+
+/// Instance storage TTL in ledgers applied by `bump_instance` (~30 days).
+pub const INSTANCE_BUMP_LEDGERS: u32 = 518_400;
+
+/// Instance keys written by this module. They encode like the
+/// `LastBumpLedger` and `EntryCount` variants contracts kept in their own
+/// `DataKey`, so counters stored before the move are read unchanged.
+#[contracttype]
+#[derive(Clone)]
+pub enum StorageHealthKey {
+    /// Ledger sequence of the last instance TTL extension.
+    LastBumpLedger,
+    /// Persistent entries held for a key family.
+    EntryCount(Symbol),
+}
+
+/// Number of persistent entries a contract holds for one key family.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyFamilyCount {
+    pub family: Symbol,
+    pub count: u32,
+}
+
+/// Maintenance snapshot returned by a contract's `get_storage_health`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageHealth {
+    /// Estimated ledgers until the instance entry expires.
+    pub instance_ttl: u32,
+    /// Ledger sequence of the last instance bump.
+    pub last_bump_ledger: u32,
+    /// Entries held per persistent key family.
+    pub families: Vec<KeyFamilyCount>,
+}
+
+/// Record that an entry of `family` was created.
+pub fn count_new_entry(env: &Env, family: Symbol) {
+    let key = StorageHealthKey::EntryCount(family);
+    let count: u32 = env.storage().instance().get(&key).unwrap_or(0);
+    env.storage().instance().set(&key, &count.saturating_add(1));
+}
+
+/// Record that an entry of `family` was deleted.
+pub fn count_removed_entry(env: &Env, family: Symbol) {
+    let key = StorageHealthKey::EntryCount(family);
+    let count: u32 = env.storage().instance().get(&key).unwrap_or(0);
+    env.storage().instance().set(&key, &count.saturating_sub(1));
+}
+
+/// Extend the instance TTL and record the ledger it was bumped at.
+pub fn bump_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_BUMP_LEDGERS, INSTANCE_BUMP_LEDGERS);
+    env.storage()
+        .instance()
+        .set(&StorageHealthKey::LastBumpLedger, &env.ledger().sequence());
+}
+
+/// Entry counts for `families`, in the order given, and the estimated
+/// instance TTL (0 if the instance was never bumped).
+pub fn storage_health(env: &Env, families: &[Symbol]) -> StorageHealth {
+    let last_bump_ledger: u32 = env
+        .storage()
+        .instance()
+        .get(&StorageHealthKey::LastBumpLedger)
+        .unwrap_or(0);
+    let instance_ttl = if last_bump_ledger == 0 {
+        0
+    } else {
+        last_bump_ledger
+            .saturating_add(INSTANCE_BUMP_LEDGERS)
+            .saturating_sub(env.ledger().sequence())
+    };
+
+    let mut counts = Vec::new(env);
+    for family in families {
+        counts.push_back(KeyFamilyCount {
+            family: family.clone(),
+            count: env
+                .storage()
+                .instance()
+                .get(&StorageHealthKey::EntryCount(family.clone()))
+                .unwrap_or(0),
+        });
+    }
+
+    StorageHealth {
+        instance_ttl,
+        last_bump_ledger,
+        families: counts,
+    }
+}
@@ -2,15 +2,108 @@
 //!
 //! Players compete to answer a question as quickly as possible.
 //! Rewards are shared among correct answers submitted before the deadline.
-//! The speed of submission (captured via timestamp) can be used to rank or reward players.
+//!
+//! The answer is committed at open with a secret salt
+//! (`sha256(answer || salt)`), so players cannot test guesses against the
+//! commitment. Submissions are only marked correct when the admin reveals the
+//! answer and salt with `reveal_answer`, which closes entries and must happen
+//! before the round is finalized.
+//! Each submission records the ledger time it was accepted and its position in
+//! the round's submission order, so speed-based rankings cannot be gamed by a
+//! caller-supplied time. The fastest correct players are kept in a per-round
+//! podium that is announced when the round is finalized.
+//!
+//! Rounds may opt into anti-sniping: the admin commits to a secret offset at
+//! open (`sha256(offset_be_u64 || salt)`), and reveals it with
+//! `reveal_cutoff` after the deadline. Only correct answers submitted at or
+//! before `deadline - offset` are rewarded, so bots that wait until the last
+//! second to copy leaked answers risk missing the real cutoff.
+//!
+//! High-value rounds can require a two-admin quorum: once the admin sets a
+//! reward threshold, rounds whose reward meets it are only finalized when two
+//! distinct admins (the primary admin or registered co-admins) call
+//! `finalize_round`. The first call records an approval, the second executes.
+//! Once a co-admin is registered, adding or removing co-admins needs the same
+//! two-admin approval, so the primary admin cannot stack the quorum alone.
+//!
+//! Rounds can hold seats back for season-pass holders: with a seat
+//! reservation, only the first `total_seats - reserved_seats` entries during
+//! the priority window are open to everyone, and the remaining seats require
+//! a pass, checked against the season-pass contract in `submit_answer`. Once
+//! the window ends, open entry resumes.
+//!
+//! Winners have a claim window (`DEFAULT_CLAIM_WINDOW` unless the admin sets
+//! another) after a round is finalized. Once it has passed, anyone may call
+//! `archive_round`, which deletes the round's per-player submissions and
+//! auxiliary entries and keeps only a compact `RoundSummary` (winner count,
+//! total paid, answer commitment) for auditing.
+//!
+//! Rounds can be chained into elimination ladders with
+//! `open_follow_up_question`: only players whose answer to the previous round
+//! was rewarded (correct, and inside its effective deadline) may enter the
+//! follow-up, which opens for entries once the previous round is finalized.
+//! Each rung carries its own reward, so escalating prizes and a final
+//! survivors' payout are just larger rewards on later rounds.
+//!
+//! Small prizes often go unclaimed, so the admin can switch a round to push
+//! payouts with `set_push_payout` before it is finalized. Finalizing such a
+//! round credits the winners among the first `MAX_PUSH_BATCH` entries
+//! directly, and keepers work through the rest with the permissionless `push_payouts` in bounded
+//! batches. Winners may still claim for themselves in the meantime.
+//!
+//! Operators can inspect storage with `get_storage_health`, which reports how
+//! many Round, Submission, and Podium entries the contract holds (archiving a
+//! round removes its entries from the counts) and an estimate of the instance
+//! TTL, and extend config TTLs with the permissionless `bump_all_config`.
 
 #![no_std]
 #![allow(unexpected_cfgs)]
 
 use soroban_sdk::{
     contract, contractclient, contracterror, contractevent, contractimpl, contracttype,
-    symbol_short, Address, Bytes, BytesN, Env, Symbol,
+    symbol_short, Address, Bytes, BytesN, Env, Symbol, Vec,
 };
+use stellarcade_shared::storage::{
+    bump_instance, count_new_entry, count_removed_entry, storage_health,
+};
+pub use stellarcade_shared::storage::{KeyFamilyCount, StorageHealth, INSTANCE_BUMP_LEDGERS};
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Number of fastest correct players announced when a round is finalized.
+pub const PODIUM_SIZE: u32 = 3;
+
+/// Largest anti-sniping offset (seconds) that may be revealed for a round.
+pub const MAX_CUTOFF_OFFSET: u64 = 300;
+
+/// Seconds after finalization during which winners may claim (7 days).
+pub const DEFAULT_CLAIM_WINDOW: u64 = 604_800;
+
+/// Most submissions scanned by one push-payout batch.
+pub const MAX_PUSH_BATCH: u32 = 10;
+
+/// Most options a multiple-choice round may publish.
+pub const MAX_OPTIONS: u32 = 8;
+
+/// Most players one round accepts, whatever its `max_participants`. Reveal,
+/// finalization and archiving visit every player in a single call, and
+/// reveal writes up to three entries per player against the 50-entry write
+/// limit of a transaction.
+pub const MAX_PARTICIPANTS: u32 = 12;
+
+/// Most submissions returned by one `get_submissions` call.
+pub const MAX_SUBMISSIONS_PAGE: u32 = 50;
+
+/// Most rounds claimed by one `claim_rewards` call.
+pub const MAX_CLAIM_BATCH: u32 = 20;
+
+/// Most won rounds tracked per player for `preview_payout`/`claim_payout`.
+pub const MAX_TRACKED_WINS: u32 = 50;
+
+/// Basis-point denominator for the streak bonus.
+pub const BASIS_POINTS: i128 = 10_000;
 
 // ---------------------------------------------------------------------------
 // External contract clients
@@ -21,6 +114,7 @@ pub trait PrizePoolContract {
     fn reserve(env: Env, admin: Address, game_id: u64, amount: i128);
     fn release(env: Env, admin: Address, game_id: u64, amount: i128);
     fn payout(env: Env, admin: Address, to: Address, game_id: u64, amount: i128);
+    fn available(env: Env) -> i128;
 }
 
 #[contractclient(name = "BalanceClient")]
@@ -30,6 +124,11 @@ pub trait UserBalanceContract {
     fn balance_of(env: Env, user: Address) -> i128;
 }
 
+#[contractclient(name = "SeasonPassClient")]
+pub trait SeasonPassContract {
+    fn has_pass(env: Env, user: Address) -> bool;
+}
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -52,6 +151,28 @@ pub enum Error {
     Overflow = 12,
     InvalidDeadline = 13,
     PastDeadline = 14,
+    NoCutoffCommitted = 15,
+    CutoffNotRevealed = 16,
+    InvalidCutoffReveal = 17,
+    DeadlineNotReached = 18,
+    AlreadyApproved = 19,
+    SeasonPassNotConfigured = 20,
+    InvalidReservation = 21,
+    SeatsFull = 22,
+    PassRequired = 23,
+    ClaimWindowOpen = 24,
+    ClaimWindowClosed = 25,
+    RoundArchived = 26,
+    NotEligible = 27,
+    PreviousRoundOpen = 28,
+    PushNotEnabled = 29,
+    InsufficientPrizeFunds = 30,
+    AnswerNotRevealed = 31,
+    InvalidAnswerReveal = 32,
+    WrongAnswerMode = 33,
+    InvalidOption = 34,
+    AlreadySwept = 35,
+    RoundFull = 36,
 }
 
 // ---------------------------------------------------------------------------
@@ -63,11 +184,24 @@ pub enum Error {
 pub enum RoundStatus {
     Open = 0,
     Finalized = 1,
+    Cancelled = 2,
+}
+
+/// How a round's reward is split among its winners.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoundMode {
+    /// Every correct answer inside the effective deadline shares the reward.
+    Shared = 0,
+    /// Only the earliest correct answer wins the whole reward; ledger-time
+    /// ties go to the lower submission index.
+    FastestFinger = 1,
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub struct RoundData {
+    /// Salted commitment to the answer: `sha256(answer || salt)`.
     pub answer_commitment: BytesN<32>,
     pub reward_amount: i128,
     pub payout_per_winner: i128,
@@ -75,15 +209,121 @@ pub struct RoundData {
     pub status: RoundStatus,
     pub deadline: u64,
     pub opened_at: u64,
+    /// Anti-sniping commitment to a secret cutoff offset, if enabled.
+    pub cutoff_commitment: Option<BytesN<32>>,
+    pub cutoff_revealed: bool,
+    /// Latest ledger time at which a correct answer is rewarded. Equals
+    /// `deadline` until an anti-sniping offset is revealed.
+    pub effective_deadline: u64,
+    /// Ledger time the round was finalized (0 while open).
+    pub finalized_at: u64,
+    /// Whether `reveal_answer` has marked the correct submissions.
+    pub answer_revealed: bool,
+    /// Streak bonus in basis points applied to the round's payouts, fixed
+    /// at finalize when at least one winner qualifies (0 when none).
+    pub streak_bonus_bps: u32,
+    /// Streak a winner needs for `streak_bonus_bps` to apply.
+    pub streak_min: u32,
+    pub mode: RoundMode,
+    /// Submission index of the winner of a `FastestFinger` round, valid
+    /// while `winner_count` is 1.
+    pub winning_index: u32,
+    /// Most players the round accepts; 0 means no cap.
+    pub max_participants: u32,
+    /// Players who have submitted so far.
+    pub participant_count: u32,
+    /// Correct answers, including late ones, counted when the answer is
+    /// revealed.
+    pub correct_count: u32,
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub struct Submission {
+    /// `sha256(answer)` of the submitted answer.
     pub answer_hash: BytesN<32>,
+    /// Set by `reveal_answer`; `false` until the answer is revealed.
     pub correct: bool,
     pub claimed: bool,
-    pub timestamp: u64,
+    /// Ledger time the submission was recorded.
+    pub submitted_at: u64,
+    /// Position in the round's submission order, starting at 0.
+    pub index: u32,
+    /// The player's streak of correct rounds including this one, set by
+    /// `reveal_answer` (0 for wrong answers).
+    pub streak: u32,
+}
+
+/// Lifetime trivia stats of a player. Correctness is counted when a round's
+/// answer is revealed.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayerStats {
+    pub rounds_played: u32,
+    pub correct_answers: u32,
+    /// Consecutive correct rounds up to the latest revealed one.
+    pub current_streak: u32,
+    pub best_streak: u32,
+}
+
+/// Extra weight given to winners on a streak: a winner whose streak is at
+/// least `min_streak` is paid `payout_per_winner * (10_000 + bonus_bps) /
+/// 10_000`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreakBonus {
+    pub min_streak: u32,
+    pub bonus_bps: u32,
+}
+
+/// Compact record left behind by `archive_round`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoundSummary {
+    pub answer_commitment: BytesN<32>,
+    pub reward_amount: i128,
+    pub winner_count: u32,
+    /// Sum of rewards claimed before the round was archived.
+    pub total_paid: i128,
+    pub submission_count: u32,
+    pub finalized_at: u64,
+    pub archived_at: u64,
+}
+
+/// One row of a round's submission listing.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubmissionEntry {
+    pub player: Address,
+    /// `false` for every submission until the answer is revealed.
+    pub correct: bool,
+    pub submitted_at: u64,
+    pub index: u32,
+}
+
+/// A correct submission ranked on a round's podium.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PodiumEntry {
+    pub player: Address,
+    pub submitted_at: u64,
+    /// Position in the round's submission order.
+    pub index: u32,
+}
+
+/// Seats held back for season-pass holders while a round's priority window
+/// is open.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeatReservation {
+    /// Seats available during the priority window.
+    pub total_seats: u32,
+    /// Seats only pass holders may take during the priority window.
+    pub reserved_seats: u32,
+    /// Ledger time at which open entry resumes.
+    pub priority_until: u64,
+    /// Seats taken during the priority window.
+    pub filled: u32,
 }
 
 #[contracttype]
@@ -93,6 +333,46 @@ pub enum DataKey {
     BalanceContract,
     Round(u64),
     Submission(u64, Address),
+    /// Fastest correct submissions for a round, in submission order.
+    Podium(u64),
+    /// Ledger times of correct submissions in anti-sniping rounds.
+    CorrectTimes(u64),
+    /// Additional admins allowed to approve and finalize rounds.
+    CoAdmins,
+    /// Reward at or above which finalization needs two admins (0 = disabled).
+    QuorumThreshold,
+    /// First admin to approve finalizing a quorum round.
+    FinalizeApproval(u64),
+    /// First admin to approve adding (`true`) or removing a co-admin.
+    CoAdminApproval(Address, bool),
+    /// Contract consulted for season-pass ownership.
+    SeasonPassContract,
+    /// Pass-holder seat reservation for a round.
+    SeatReservation(u64),
+    /// Seconds after finalization during which winners may claim.
+    ClaimWindow,
+    /// Players who submitted to a round, in submission order.
+    Players(u64),
+    /// Summary kept after a round is archived.
+    RoundSummary(u64),
+    /// Round whose rewarded players may enter a follow-up round.
+    PreviousRound(u64),
+    /// Whether a round's winners are paid by push instead of claims.
+    PushPayout(u64),
+    /// Index into `Players` of the next submission a push batch visits.
+    PushCursor(u64),
+    /// Published option hashes of a multiple-choice round.
+    Options(u64),
+    /// Submissions per option of a multiple-choice round.
+    OptionCounts(u64),
+    /// Unclaimed reward returned to the prize pool for a round.
+    Swept(u64),
+    /// Lifetime stats of a player.
+    PlayerStats(Address),
+    /// Streak bonus applied at finalize.
+    StreakBonus,
+    /// Rounds a player answered correctly and may still claim (Vec<u64>).
+    Wins(Address),
 }
 
 // ---------------------------------------------------------------------------
@@ -105,6 +385,14 @@ pub struct QuestionOpened {
     pub round_id: u64,
     pub reward_amount: i128,
     pub deadline: u64,
+    pub mode: RoundMode,
+}
+
+#[contractevent]
+pub struct FollowUpLinked {
+    #[topic]
+    pub round_id: u64,
+    pub previous_round_id: u64,
 }
 
 #[contractevent]
@@ -112,8 +400,55 @@ pub struct AnswerSubmitted {
     #[topic]
     pub round_id: u64,
     pub player: Address,
-    pub correct: bool,
-    pub timestamp: u64,
+    pub submitted_at: u64,
+    pub index: u32,
+}
+
+#[contractevent]
+pub struct AnswerRevealed {
+    #[topic]
+    pub round_id: u64,
+    pub winners: u32,
+}
+
+#[contractevent]
+pub struct CutoffRevealed {
+    #[topic]
+    pub round_id: u64,
+    pub offset: u64,
+    pub effective_deadline: u64,
+    pub winners: u32,
+}
+
+#[contractevent]
+pub struct ParticipantCapSet {
+    #[topic]
+    pub round_id: u64,
+    pub max_participants: u32,
+}
+
+#[contractevent]
+pub struct SeatsReserved {
+    #[topic]
+    pub round_id: u64,
+    pub total_seats: u32,
+    pub reserved_seats: u32,
+    pub priority_until: u64,
+}
+
+#[contractevent]
+pub struct FinalizeApproved {
+    #[topic]
+    pub round_id: u64,
+    pub approver: Address,
+}
+
+#[contractevent]
+pub struct CoAdminChangeApproved {
+    #[topic]
+    pub co_admin: Address,
+    pub add: bool,
+    pub approver: Address,
 }
 
 #[contractevent]
@@ -122,6 +457,22 @@ pub struct RoundFinalized {
     pub round_id: u64,
     pub winners: u32,
     pub payout_per_winner: i128,
+    /// Whether the admin forced finalization before the deadline.
+    pub forced: bool,
+}
+
+#[contractevent]
+pub struct WinnersAnnounced {
+    #[topic]
+    pub round_id: u64,
+    pub podium: Vec<PodiumEntry>,
+}
+
+#[contractevent]
+pub struct RoundCancelled {
+    #[topic]
+    pub round_id: u64,
+    pub released: i128,
 }
 
 #[contractevent]
@@ -132,6 +483,31 @@ pub struct RewardClaimed {
     pub amount: i128,
 }
 
+#[contractevent]
+pub struct PayoutsPushed {
+    #[topic]
+    pub round_id: u64,
+    pub paid: u32,
+    pub next_index: u32,
+    pub done: bool,
+}
+
+#[contractevent]
+pub struct UnclaimedSwept {
+    #[topic]
+    pub round_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct RoundArchived {
+    #[topic]
+    pub round_id: u64,
+    pub winner_count: u32,
+    pub total_paid: i128,
+    pub submissions_removed: u32,
+}
+
 // ---------------------------------------------------------------------------
 // Contract
 // ---------------------------------------------------------------------------
@@ -159,17 +535,259 @@ impl SpeedTrivia {
         env.storage()
             .instance()
             .set(&DataKey::BalanceContract, &balance_contract);
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Extend the instance (config) TTL. Permissionless.
+    pub fn bump_all_config(env: Env) -> Result<(), Error> {
+        require_initialized(&env)?;
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Report how many Round, Submission and Podium entries the contract
+    /// holds, with the estimated instance TTL.
+    pub fn get_storage_health(env: Env) -> StorageHealth {
+        storage_health(&env, &[FAMILY_ROUND, FAMILY_SUBMISSION, FAMILY_PODIUM])
+    }
+
+    /// Register an additional admin for finalization quorums.
+    ///
+    /// The primary admin adds the first co-admin alone. After that `caller`
+    /// must be the admin or a co-admin, and the change needs two distinct
+    /// approvals: the first call records `caller`'s approval and returns
+    /// `false`, a second call by a different admin applies it and returns
+    /// `true`.
+    pub fn add_co_admin(env: Env, caller: Address, co_admin: Address) -> Result<bool, Error> {
+        if !approve_co_admin_change(&env, &caller, &co_admin, true)? {
+            return Ok(false);
+        }
+        let mut co_admins = get_co_admins(&env);
+        if !co_admins.contains(&co_admin) {
+            co_admins.push_back(co_admin);
+            env.storage().instance().set(&DataKey::CoAdmins, &co_admins);
+        }
+        Ok(true)
+    }
+
+    /// Remove a co-admin. Needs two distinct admin approvals, like
+    /// `add_co_admin`. Returns `true` once the co-admin is removed.
+    pub fn remove_co_admin(env: Env, caller: Address, co_admin: Address) -> Result<bool, Error> {
+        if !approve_co_admin_change(&env, &caller, &co_admin, false)? {
+            return Ok(false);
+        }
+        let mut co_admins = get_co_admins(&env);
+        if let Some(index) = co_admins.first_index_of(&co_admin) {
+            co_admins.remove(index);
+            env.storage().instance().set(&DataKey::CoAdmins, &co_admins);
+        }
+        Ok(true)
+    }
+
+    /// Set the reward at or above which finalizing a round needs two distinct
+    /// admins. `0` disables the quorum. Admin only.
+    pub fn set_quorum_threshold(env: Env, threshold: i128) -> Result<(), Error> {
+        require_admin(&env)?;
+        if threshold < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::QuorumThreshold, &threshold);
+        Ok(())
+    }
+
+    /// Get the registered co-admins.
+    pub fn get_co_admins(env: Env) -> Vec<Address> {
+        get_co_admins(&env)
+    }
+
+    /// Get the quorum reward threshold (0 = disabled).
+    pub fn get_quorum_threshold(env: Env) -> i128 {
+        get_quorum_threshold(&env)
+    }
+
+    /// Set the season-pass contract used for reserved seats. Admin only.
+    pub fn set_season_pass_contract(env: Env, season_pass: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::SeasonPassContract, &season_pass);
+        Ok(())
+    }
+
+    /// Cap an open round at `max_participants` players; later submissions
+    /// fail with `RoundFull`. 0 falls back to `MAX_PARTICIPANTS`; a cap above
+    /// it or below the current participant count is rejected. Admin only.
+    pub fn set_max_participants(
+        env: Env,
+        round_id: u64,
+        max_participants: u32,
+    ) -> Result<(), Error> {
+        require_admin(&env)?;
+        let key = DataKey::Round(round_id);
+        let mut round = load_round(&env, round_id)?;
+        if round.status != RoundStatus::Open {
+            return Err(Error::RoundClosed);
+        }
+        if max_participants > MAX_PARTICIPANTS
+            || (max_participants != 0 && max_participants < round.participant_count)
+        {
+            return Err(Error::InvalidAmount);
+        }
+        round.max_participants = max_participants;
+        env.storage().persistent().set(&key, &round);
+
+        ParticipantCapSet {
+            round_id,
+            max_participants,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Reserve `reserved_seats` of `total_seats` for pass holders during the
+    /// first `priority_window` seconds after the round opened. Admin only.
+    pub fn set_seat_reservation(
+        env: Env,
+        round_id: u64,
+        total_seats: u32,
+        reserved_seats: u32,
+        priority_window: u64,
+    ) -> Result<(), Error> {
+        require_admin(&env)?;
+        get_season_pass(&env)?;
+        if total_seats == 0 || reserved_seats > total_seats || priority_window == 0 {
+            return Err(Error::InvalidReservation);
+        }
+
+        let round: RoundData = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Round(round_id))
+            .ok_or(Error::RoundNotFound)?;
+        if round.status != RoundStatus::Open {
+            return Err(Error::RoundClosed);
+        }
+
+        let key = DataKey::SeatReservation(round_id);
+        let filled = env
+            .storage()
+            .persistent()
+            .get::<_, SeatReservation>(&key)
+            .map(|seats| seats.filled)
+            .unwrap_or(0);
+        let priority_until = round
+            .opened_at
+            .checked_add(priority_window)
+            .ok_or(Error::Overflow)?;
+        let seats = SeatReservation {
+            total_seats,
+            reserved_seats,
+            priority_until,
+            filled,
+        };
+        env.storage().persistent().set(&key, &seats);
+
+        SeatsReserved {
+            round_id,
+            total_seats,
+            reserved_seats,
+            priority_until,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Set the claim window applied after finalization. Admin only.
+    pub fn set_claim_window(env: Env, window: u64) -> Result<(), Error> {
+        require_admin(&env)?;
+        env.storage().instance().set(&DataKey::ClaimWindow, &window);
         Ok(())
     }
 
+    /// Set the streak bonus applied when rounds are finalized: winners whose
+    /// streak of correct rounds is at least `min_streak` weigh
+    /// `10_000 + bonus_bps` in the payout split against 10_000 for the
+    /// others. `bonus_bps` of 0 disables it. Admin only.
+    pub fn set_streak_bonus(env: Env, min_streak: u32, bonus_bps: u32) -> Result<(), Error> {
+        require_admin(&env)?;
+        if min_streak == 0 || bonus_bps > 10_000 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().instance().set(
+            &DataKey::StreakBonus,
+            &StreakBonus {
+                min_streak,
+                bonus_bps,
+            },
+        );
+        Ok(())
+    }
+
+    /// Get the streak bonus, if enabled.
+    pub fn get_streak_bonus(env: Env) -> Option<StreakBonus> {
+        get_streak_bonus(&env)
+    }
+
+    /// Get a player's lifetime stats.
+    pub fn get_player_stats(env: Env, player: Address) -> PlayerStats {
+        get_player_stats(&env, &player)
+    }
+
+    /// Get the claim window in seconds.
+    pub fn get_claim_window(env: Env) -> u64 {
+        get_claim_window(&env)
+    }
+
+    /// Pay a round's winners by push instead of individual claims. Admin
+    /// only, while the round is open.
+    pub fn set_push_payout(env: Env, round_id: u64, enabled: bool) -> Result<(), Error> {
+        require_admin(&env)?;
+        let round = load_round(&env, round_id)?;
+        if round.status != RoundStatus::Open {
+            return Err(Error::RoundClosed);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::PushPayout(round_id), &enabled);
+        Ok(())
+    }
+
+    /// Whether a round pays its winners by push.
+    pub fn is_push_payout(env: Env, round_id: u64) -> bool {
+        is_push_payout(&env, round_id)
+    }
+
+    /// Index of the next submission `push_payouts` will visit for a round.
+    pub fn get_push_cursor(env: Env, round_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PushCursor(round_id))
+            .unwrap_or(0)
+    }
+
+    /// Get a round's seat reservation, if any.
+    pub fn get_seat_reservation(env: Env, round_id: u64) -> Option<SeatReservation> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SeatReservation(round_id))
+    }
+
     /// Open a new trivia question.
     /// Added `reward_amount` to facilitate prize pool reservation.
+    /// Pass `cutoff_commitment` to enable the anti-sniping cutoff.
+    /// `mode` selects whether winners share the reward or the fastest
+    /// correct answer takes it all.
     pub fn open_question(
         env: Env,
         round_id: u64,
         answer_commitment: BytesN<32>,
         deadline: u64,
         reward_amount: i128,
+        cutoff_commitment: Option<BytesN<32>>,
+        mode: RoundMode,
     ) -> Result<(), Error> {
         let admin = require_admin(&env)?;
         require_positive(reward_amount)?;
@@ -180,12 +798,22 @@ impl SpeedTrivia {
         }
 
         let key = DataKey::Round(round_id);
-        if env.storage().persistent().has(&key) {
+        if env.storage().persistent().has(&key)
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::RoundSummary(round_id))
+        {
             return Err(Error::RoundAlreadyExists);
         }
 
+        // Check the pool can cover the prize up front rather than leaving
+        // the shortfall to surface when winners claim.
         let prize_pool = get_prize_pool(&env)?;
         let pool_client = PrizePoolClient::new(&env, &prize_pool);
+        if pool_client.available() < reward_amount {
+            return Err(Error::InsufficientPrizeFunds);
+        }
         pool_client.reserve(&admin, &round_id, &reward_amount);
 
         let round = RoundData {
@@ -196,89 +824,362 @@ impl SpeedTrivia {
             status: RoundStatus::Open,
             deadline,
             opened_at: now,
+            cutoff_commitment,
+            cutoff_revealed: false,
+            effective_deadline: deadline,
+            finalized_at: 0,
+            answer_revealed: false,
+            streak_bonus_bps: 0,
+            streak_min: 0,
+            mode,
+            winning_index: 0,
+            max_participants: 0,
+            participant_count: 0,
+            correct_count: 0,
         };
         env.storage().persistent().set(&key, &round);
+        count_new_entry(&env, FAMILY_ROUND);
 
         QuestionOpened {
             round_id,
             reward_amount,
             deadline,
+            mode,
         }
         .publish(&env);
         Ok(())
     }
 
-    /// Submit an answer for a specific round.
-    /// `timestamp` is provide by the caller, verified to be within ledger bounds.
-    pub fn submit_answer(
+    /// Open a question that only players rewarded in `previous_round_id`
+    /// may enter. Same parameters as `open_question` otherwise; the reward is
+    /// shared.
+    pub fn open_follow_up_question(
         env: Env,
-        player: Address,
         round_id: u64,
-        answer: Bytes,
-        timestamp: u64,
+        previous_round_id: u64,
+        answer_commitment: BytesN<32>,
+        deadline: u64,
+        reward_amount: i128,
+        cutoff_commitment: Option<BytesN<32>>,
     ) -> Result<(), Error> {
-        require_initialized(&env)?;
-        player.require_auth();
-
-        let key = DataKey::Round(round_id);
-        let mut round: RoundData = env
-            .storage()
+        load_round(&env, previous_round_id)?;
+        Self::open_question(
+            env.clone(),
+            round_id,
+            answer_commitment,
+            deadline,
+            reward_amount,
+            cutoff_commitment,
+            RoundMode::Shared,
+        )?;
+        env.storage()
             .persistent()
-            .get(&key)
-            .ok_or(Error::RoundNotFound)?;
+            .set(&DataKey::PreviousRound(round_id), &previous_round_id);
 
-        if round.status != RoundStatus::Open {
-            return Err(Error::RoundClosed);
+        FollowUpLinked {
+            round_id,
+            previous_round_id,
         }
+        .publish(&env);
+        Ok(())
+    }
 
-        let now = env.ledger().timestamp();
-        if now > round.deadline {
-            return Err(Error::PastDeadline);
+    /// Open a multiple-choice question with `options` (hashes of the option
+    /// texts, 2 to `MAX_OPTIONS` of them). Players submit an option index
+    /// with `submit_choice`, and `answer_commitment` commits to the correct
+    /// index: `sha256(index_be_u32 || salt)`, revealed with the same bytes
+    /// through `reveal_answer`. Same parameters as `open_question` otherwise;
+    /// the reward is shared.
+    pub fn open_multiple_choice_question(
+        env: Env,
+        round_id: u64,
+        options: Vec<BytesN<32>>,
+        answer_commitment: BytesN<32>,
+        deadline: u64,
+        reward_amount: i128,
+        cutoff_commitment: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        if options.len() < 2 || options.len() > MAX_OPTIONS {
+            return Err(Error::InvalidOption);
         }
+        Self::open_question(
+            env.clone(),
+            round_id,
+            answer_commitment,
+            deadline,
+            reward_amount,
+            cutoff_commitment,
+            RoundMode::Shared,
+        )?;
+        let mut counts = Vec::new(&env);
+        for _ in options.iter() {
+            counts.push_back(0u32);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::OptionCounts(round_id), &counts);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Options(round_id), &options);
+        Ok(())
+    }
+
+    /// Get a multiple-choice round's option hashes, if it is one.
+    pub fn get_options(env: Env, round_id: u64) -> Option<Vec<BytesN<32>>> {
+        get_options(&env, round_id)
+    }
+
+    /// Get the number of submissions per option of a multiple-choice round.
+    pub fn get_option_counts(env: Env, round_id: u64) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OptionCounts(round_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get the round a follow-up round draws its players from, if any.
+    pub fn get_previous_round(env: Env, round_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PreviousRound(round_id))
+    }
+
+    /// Submit an answer for a specific free-text round (`WrongAnswerMode`
+    /// for multiple-choice rounds). The submission is stamped with the ledger time and the next index in
+    /// the round's submission order. Its correctness is only known once the
+    /// answer is revealed, after which no more answers are accepted.
+    pub fn submit_answer(
+        env: Env,
+        player: Address,
+        round_id: u64,
+        answer: Bytes,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+        if env.storage().persistent().has(&DataKey::Options(round_id)) {
+            return Err(Error::WrongAnswerMode);
+        }
+        record_submission(&env, player, round_id, &answer)
+    }
 
-        // Validate timestamp: shouldn't be too far in the future or drastically in the past
-        // For simplicity, we ensure it's not beyond current ledger time.
-        if timestamp > now {
-            return Err(Error::InvalidAmount); // Or a more specific error
+    /// Submit option `option` (0-based) for a multiple-choice round. Same
+    /// rules as `submit_answer`; the submission also counts towards the
+    /// option's on-chain tally.
+    pub fn submit_choice(
+        env: Env,
+        player: Address,
+        round_id: u64,
+        option: u32,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+        load_round(&env, round_id)?;
+        let options = get_options(&env, round_id).ok_or(Error::WrongAnswerMode)?;
+        if option >= options.len() {
+            return Err(Error::InvalidOption);
         }
+        record_submission(&env, player, round_id, &choice_bytes(&env, option))?;
 
-        let submission_key = DataKey::Submission(round_id, player.clone());
-        if env.storage().persistent().has(&submission_key) {
-            return Err(Error::AlreadySubmitted);
+        let counts_key = DataKey::OptionCounts(round_id);
+        let mut counts: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&counts_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        let count = counts.get(option).unwrap_or(0);
+        counts.set(option, count.checked_add(1).ok_or(Error::Overflow)?);
+        env.storage().persistent().set(&counts_key, &counts);
+        Ok(())
+    }
+
+    /// Reveal a round's answer and salt, closing it to new answers and
+    /// marking the correct submissions. Admin only, once, before
+    /// `finalize_round`.
+    ///
+    /// Verifies `sha256(answer || salt)` against the commitment made at open,
+    /// then walks the submissions in order: those matching the answer are
+    /// marked correct, and the ones inside the effective deadline count as
+    /// winners and fill the podium. Every submitter's stats and streak are
+    /// updated. Returns the winner count.
+    ///
+    /// Answers are accepted until the deadline, so the reveal fails with
+    /// `DeadlineNotReached` until it has passed, unless the admin ends
+    /// entries early by passing `force`.
+    pub fn reveal_answer(
+        env: Env,
+        round_id: u64,
+        answer: Bytes,
+        salt: BytesN<32>,
+        force: bool,
+    ) -> Result<u32, Error> {
+        require_admin(&env)?;
+        let key = DataKey::Round(round_id);
+        let mut round = load_round(&env, round_id)?;
+        if round.status != RoundStatus::Open {
+            return Err(Error::RoundClosed);
+        }
+        if env.ledger().timestamp() <= round.deadline && !force {
+            return Err(Error::DeadlineNotReached);
+        }
+        if round.answer_revealed || answer_hash(&env, &answer, &salt) != round.answer_commitment {
+            return Err(Error::InvalidAnswerReveal);
         }
 
         let answer_hash: BytesN<32> = env.crypto().sha256(&answer).into();
-        let correct = answer_hash == round.answer_commitment;
+        let players: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Players(round_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        let mut times: Vec<u64> = Vec::new(&env);
+        let mut winners: u32 = 0;
+        let mut correct_count: u32 = 0;
+        for player in players.iter() {
+            let submission_key = DataKey::Submission(round_id, player.clone());
+            let mut submission: Submission = match env.storage().persistent().get(&submission_key) {
+                Some(submission) => submission,
+                None => continue,
+            };
+            let correct = submission.answer_hash == answer_hash;
+            let stats = record_result(&env, &player, correct)?;
+            if !correct {
+                continue;
+            }
+            correct_count = correct_count.checked_add(1).ok_or(Error::Overflow)?;
+            submission.correct = true;
+            submission.streak = stats.current_streak;
+            env.storage().persistent().set(&submission_key, &submission);
+            track_win(&env, &player, round_id);
+            times.push_back(submission.submitted_at);
+            if submission.submitted_at <= round.effective_deadline {
+                winners = winners.checked_add(1).ok_or(Error::Overflow)?;
+                record_podium(
+                    &env,
+                    round_id,
+                    &player,
+                    submission.submitted_at,
+                    submission.index,
+                )?;
+            }
+        }
+        if round.cutoff_commitment.is_some() {
+            env.storage()
+                .persistent()
+                .set(&DataKey::CorrectTimes(round_id), &times);
+        }
 
-        if correct {
-            round.winner_count = round
-                .winner_count
-                .checked_add(1)
-                .ok_or(Error::Overflow)?;
-            env.storage().persistent().set(&key, &round);
+        round.answer_revealed = true;
+        round.correct_count = correct_count;
+        let winners = settle_winners(&env, round_id, &mut round, winners);
+        env.storage().persistent().set(&key, &round);
+
+        AnswerRevealed { round_id, winners }.publish(&env);
+        Ok(winners)
+    }
+
+    /// Reveal an anti-sniping round's cutoff offset after its deadline.
+    ///
+    /// Verifies `sha256(offset_be_u64 || salt)` against the commitment made at
+    /// open, then moves the effective deadline to `deadline - offset`. Correct
+    /// answers submitted after it no longer count as winners and are removed
+    /// from the podium. Must be called before `finalize_round`, either before
+    /// or after `reveal_answer`.
+    pub fn reveal_cutoff(
+        env: Env,
+        round_id: u64,
+        offset: u64,
+        salt: BytesN<32>,
+    ) -> Result<(), Error> {
+        require_admin(&env)?;
+        let key = DataKey::Round(round_id);
+        let mut round: RoundData = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::RoundNotFound)?;
+
+        if round.status != RoundStatus::Open {
+            return Err(Error::RoundClosed);
+        }
+        let commitment = round
+            .cutoff_commitment
+            .clone()
+            .ok_or(Error::NoCutoffCommitted)?;
+        if round.cutoff_revealed {
+            return Err(Error::InvalidCutoffReveal);
+        }
+        if env.ledger().timestamp() <= round.deadline {
+            return Err(Error::DeadlineNotReached);
+        }
+        if offset > MAX_CUTOFF_OFFSET || cutoff_hash(&env, offset, &salt) != commitment {
+            return Err(Error::InvalidCutoffReveal);
         }
 
-        let submission = Submission {
-            answer_hash,
-            correct,
-            claimed: false,
-            timestamp,
-        };
-        env.storage().persistent().set(&submission_key, &submission);
+        let effective_deadline = round.deadline.saturating_sub(offset);
+        let times: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CorrectTimes(round_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        let mut winners: u32 = 0;
+        for t in times.iter() {
+            if t <= effective_deadline {
+                winners = winners.checked_add(1).ok_or(Error::Overflow)?;
+            }
+        }
 
-        AnswerSubmitted {
+        let mut podium = Vec::new(&env);
+        for entry in get_podium(&env, round_id).iter() {
+            let submission: Option<Submission> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Submission(round_id, entry.player.clone()));
+            if submission.is_some_and(|s| s.submitted_at <= effective_deadline) {
+                podium.push_back(entry);
+            }
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Podium(round_id), &podium);
+
+        round.cutoff_revealed = true;
+        round.effective_deadline = effective_deadline;
+        let winners = settle_winners(&env, round_id, &mut round, winners);
+        env.storage().persistent().set(&key, &round);
+
+        CutoffRevealed {
             round_id,
-            player,
-            correct,
-            timestamp,
+            offset,
+            effective_deadline,
+            winners,
         }
         .publish(&env);
         Ok(())
     }
 
     /// Finalize the round, closing it and calculating the payout per winner.
-    pub fn finalize_round(env: Env, round_id: u64) -> Result<(), Error> {
-        let admin = require_admin(&env)?;
+    ///
+    /// `caller` must be the admin or a co-admin. For rounds whose reward meets
+    /// the quorum threshold, the first call only records `caller`'s approval
+    /// and returns `false`; a second call by a different admin finalizes.
+    /// Returns `true` once the round is finalized.
+    ///
+    /// Rounds finalize once their deadline has passed (`DeadlineNotReached`
+    /// before), so players are not cut off mid-round. Only the primary admin
+    /// may close a round early, by passing `force`.
+    pub fn finalize_round(
+        env: Env,
+        caller: Address,
+        round_id: u64,
+        force: bool,
+    ) -> Result<bool, Error> {
+        let admin = get_admin(&env)?;
+        caller.require_auth();
+        let co_admins = get_co_admins(&env);
+        if !is_any_admin(&admin, &co_admins, &caller) {
+            return Err(Error::NotAuthorized);
+        }
+
         let key = DataKey::Round(round_id);
         let mut round: RoundData = env
             .storage()
@@ -289,18 +1190,70 @@ impl SpeedTrivia {
         if round.status != RoundStatus::Open {
             return Err(Error::RoundClosed);
         }
+        if !round.answer_revealed {
+            return Err(Error::AnswerNotRevealed);
+        }
+        if round.cutoff_commitment.is_some() && !round.cutoff_revealed {
+            return Err(Error::CutoffNotRevealed);
+        }
+        let now = env.ledger().timestamp();
+        let forced = now < round.deadline;
+        if forced && !force {
+            return Err(Error::DeadlineNotReached);
+        }
+        if forced && caller != admin {
+            return Err(Error::NotAuthorized);
+        }
 
-        // Allow finalize even before deadline if admin chooses, or wait until after.
-        // Usually finalize happens after deadline.
+        let threshold = get_quorum_threshold(&env);
+        if threshold > 0 && round.reward_amount >= threshold {
+            let approval_key = DataKey::FinalizeApproval(round_id);
+            let approver: Option<Address> = env.storage().persistent().get(&approval_key);
+            match approver {
+                Some(approver) if approver == caller => {
+                    return Err(Error::AlreadyApproved);
+                }
+                // An approval only counts while its approver is still an admin.
+                Some(approver) if is_any_admin(&admin, &co_admins, &approver) => {
+                    env.storage().persistent().remove(&approval_key)
+                }
+                _ => {
+                    env.storage().persistent().set(&approval_key, &caller);
+                    FinalizeApproved {
+                        round_id,
+                        approver: caller,
+                    }
+                    .publish(&env);
+                    return Ok(false);
+                }
+            }
+        }
 
+        // Winners on a streak weigh `10_000 + bonus_bps` against 10_000 for
+        // the others, so the bonus is funded from the round's reservation.
+        let bonus = get_streak_bonus(&env);
+        let bonus_winners = match &bonus {
+            Some(bonus) => count_streak_winners(&env, round_id, &round, bonus.min_streak),
+            None => 0,
+        };
         let payout_per_winner = if round.winner_count == 0 {
             0
         } else {
+            let bonus_bps = bonus.as_ref().map_or(0, |b| b.bonus_bps as i128);
+            let weight = (round.winner_count as i128)
+                .checked_mul(BASIS_POINTS)
+                .and_then(|w| w.checked_add((bonus_winners as i128).checked_mul(bonus_bps)?))
+                .ok_or(Error::Overflow)?;
             round
                 .reward_amount
-                .checked_div(round.winner_count as i128)
+                .checked_mul(BASIS_POINTS)
+                .and_then(|r| r.checked_div(weight))
                 .ok_or(Error::Overflow)?
         };
+        if let Some(bonus) = bonus.filter(|_| bonus_winners > 0) {
+            round.streak_bonus_bps = bonus.bonus_bps;
+            round.streak_min = bonus.min_streak;
+        }
 
         if round.winner_count == 0 {
             let prize_pool = get_prize_pool(&env)?;
@@ -310,86 +1263,325 @@ impl SpeedTrivia {
 
         round.status = RoundStatus::Finalized;
         round.payout_per_winner = payout_per_winner;
+        round.finalized_at = now;
         env.storage().persistent().set(&key, &round);
 
         RoundFinalized {
             round_id,
             winners: round.winner_count,
             payout_per_winner,
+            forced,
+        }
+        .publish(&env);
+
+        WinnersAnnounced {
+            round_id,
+            podium: get_podium(&env, round_id),
+        }
+        .publish(&env);
+
+        if payout_per_winner > 0 && is_push_payout(&env, round_id) {
+            push_batch(&env, round_id, &round, MAX_PUSH_BATCH)?;
+        }
+        Ok(true)
+    }
+
+    /// Cancel an open round, e.g. one opened with a wrong commitment, and
+    /// release its prize-pool reservation. Admin only.
+    ///
+    /// The round is marked `Cancelled`: it takes no more answers, cannot be
+    /// finalized or claimed, and follow-up rounds linked to it admit nobody.
+    /// Rounds charge no entry fee, so there is nothing to refund to players.
+    pub fn cancel_round(env: Env, round_id: u64) -> Result<(), Error> {
+        let admin = require_admin(&env)?;
+        let mut round = load_round(&env, round_id)?;
+        if round.status != RoundStatus::Open {
+            return Err(Error::RoundClosed);
+        }
+
+        let prize_pool = get_prize_pool(&env)?;
+        PrizePoolClient::new(&env, &prize_pool).release(&admin, &round_id, &round.reward_amount);
+
+        round.status = RoundStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Round(round_id), &round);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::FinalizeApproval(round_id));
+
+        RoundCancelled {
+            round_id,
+            released: round.reward_amount,
         }
         .publish(&env);
         Ok(())
     }
 
-    /// Claim reward for a correct answer.
+    /// Credit the next batch of unpaid winners of a finalized push-payout
+    /// round. Permissionless, so keepers can complete the payouts.
+    ///
+    /// Visits at most `limit` (capped at `MAX_PUSH_BATCH`) submissions after
+    /// the last one visited and pays each rewarded, unclaimed one. Returns
+    /// the number of winners paid; `0` once every submission was visited.
+    pub fn push_payouts(env: Env, round_id: u64, limit: u32) -> Result<u32, Error> {
+        require_initialized(&env)?;
+        let round = load_round(&env, round_id)?;
+        if round.status != RoundStatus::Finalized {
+            return Err(Error::RoundNotOpen);
+        }
+        if !is_push_payout(&env, round_id) {
+            return Err(Error::PushNotEnabled);
+        }
+        if env.ledger().timestamp() >= claim_deadline(&env, &round) {
+            return Err(Error::ClaimWindowClosed);
+        }
+        if limit == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        push_batch(&env, round_id, &round, limit.min(MAX_PUSH_BATCH))
+    }
+
+    /// Claim reward for a correct answer within the claim window.
     pub fn claim_reward(env: Env, player: Address, round_id: u64) -> Result<i128, Error> {
         require_initialized(&env)?;
         player.require_auth();
 
-        let round: RoundData = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Round(round_id))
-            .ok_or(Error::RoundNotFound)?;
+        let amount = claim_from_pool(&env, &player, round_id)?;
+        credit_winnings(&env, &player, amount)?;
+        Ok(amount)
+    }
 
-        if round.status != RoundStatus::Finalized {
-            return Err(Error::RoundNotOpen);
+    /// Claim the rewards of several finalized rounds at once (1 to
+    /// `MAX_CLAIM_BATCH`, else `InvalidAmount`). Each round is checked as in
+    /// `claim_reward` and any failure reverts the whole batch; the prize
+    /// pool pays out per round, but the player's balance is credited once
+    /// with the total. Returns the total claimed.
+    pub fn claim_rewards(env: Env, player: Address, round_ids: Vec<u64>) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+        if round_ids.is_empty() || round_ids.len() > MAX_CLAIM_BATCH {
+            return Err(Error::InvalidAmount);
         }
 
-        let submission_key = DataKey::Submission(round_id, player.clone());
-        let mut submission: Submission = env
-            .storage()
-            .persistent()
-            .get(&submission_key)
-            .ok_or(Error::NoRewardAvailable)?;
+        let mut total: i128 = 0;
+        for round_id in round_ids.iter() {
+            let amount = claim_from_pool(&env, &player, round_id)?;
+            total = total.checked_add(amount).ok_or(Error::Overflow)?;
+        }
+        credit_winnings(&env, &player, total)?;
+        Ok(total)
+    }
 
-        if submission.claimed {
-            return Err(Error::AlreadyClaimed);
+    /// Total `player` could claim right now across the rounds they answered
+    /// correctly. Part of the claims router's `ClaimableGame` interface.
+    pub fn preview_payout(env: Env, player: Address) -> i128 {
+        let mut total: i128 = 0;
+        for round_id in get_wins(&env, &player).iter() {
+            if let Ok((_, amount)) = check_claim(&env, &player, round_id) {
+                total = total.saturating_add(amount);
+            }
         }
+        total
+    }
 
-        if !submission.correct || round.payout_per_winner <= 0 {
-            return Err(Error::NoRewardAvailable);
+    /// Claim everything `player` can claim right now, up to
+    /// `MAX_CLAIM_BATCH` rounds, crediting the balance once. Part of the
+    /// claims router's `ClaimableGame` interface.
+    ///
+    /// Rounds that can no longer pay (claimed, expired, cancelled, archived
+    /// or not rewarded) are dropped from the player's tracked wins; rounds
+    /// still open stay. Returns the amount paid, 0 if nothing was claimable.
+    pub fn claim_payout(env: Env, player: Address) -> Result<i128, Error> {
+        require_initialized(&env)?;
+        player.require_auth();
+
+        let mut kept = Vec::new(&env);
+        let mut total: i128 = 0;
+        let mut claimed: u32 = 0;
+        for round_id in get_wins(&env, &player).iter() {
+            if claimed >= MAX_CLAIM_BATCH {
+                kept.push_back(round_id);
+            } else if check_claim(&env, &player, round_id).is_ok() {
+                let amount = claim_from_pool(&env, &player, round_id)?;
+                total = total.checked_add(amount).ok_or(Error::Overflow)?;
+                claimed += 1;
+            } else if load_round(&env, round_id).is_ok_and(|r| r.status == RoundStatus::Open) {
+                kept.push_back(round_id);
+            }
+        }
+        let key = DataKey::Wins(player.clone());
+        if kept.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &kept);
+        }
+        if total > 0 {
+            credit_winnings(&env, &player, total)?;
         }
+        Ok(total)
+    }
 
-        let prize_pool = get_prize_pool(&env)?;
-        let pool_client = PrizePoolClient::new(&env, &prize_pool);
-        let admin = get_admin(&env)?;
-        pool_client.payout(&admin, &player, &round_id, &round.payout_per_winner);
+    /// Return a finalized round's unclaimed reward to the prize pool once
+    /// its claim window has passed (`ClaimWindowOpen` before). Admin only,
+    /// once per round (`AlreadySwept` after).
+    ///
+    /// Releases `reward_amount` minus the rewards paid, which includes the
+    /// division remainder of `payout_per_winner`. Rounds without winners
+    /// were already released at finalize and sweep nothing. Returns the
+    /// amount released.
+    pub fn sweep_unclaimed(env: Env, round_id: u64) -> Result<i128, Error> {
+        require_admin(&env)?;
+        let round = load_round(&env, round_id)?;
+        if round.status != RoundStatus::Finalized {
+            return Err(Error::RoundNotOpen);
+        }
+        if env.ledger().timestamp() < claim_deadline(&env, &round) {
+            return Err(Error::ClaimWindowOpen);
+        }
+        if env.storage().persistent().has(&DataKey::Swept(round_id)) {
+            return Err(Error::AlreadySwept);
+        }
 
-        let balance_contract = get_balance_contract(&env)?;
-        let balance_client = BalanceClient::new(&env, &balance_contract);
-        let contract_addr = env.current_contract_address();
+        let paid = total_paid(&env, round_id, &round)?;
+        release_unclaimed(&env, round_id, &round, paid)
+    }
 
-        // Adjust internal balance tracking
-        balance_client.debit(
-            &contract_addr,
-            &contract_addr,
-            &round.payout_per_winner,
-            &symbol_short!("payout"),
-        );
-        balance_client.credit(
-            &contract_addr,
-            &player,
-            &round.payout_per_winner,
-            &symbol_short!("win"),
-        );
+    /// Get the unclaimed reward swept from a round, if it was swept.
+    pub fn get_swept(env: Env, round_id: u64) -> Option<i128> {
+        env.storage().persistent().get(&DataKey::Swept(round_id))
+    }
 
-        submission.claimed = true;
-        env.storage().persistent().set(&submission_key, &submission);
+    /// Archive a finalized round once its claim window has passed.
+    /// Permissionless, so keepers can reclaim rent.
+    ///
+    /// Deletes the round, its submissions, player list, podium and other
+    /// per-round entries, and stores a `RoundSummary` in their place. Unclaimed
+    /// rewards can no longer be claimed; if the round was not swept yet, they
+    /// are released back to the prize pool as `sweep_unclaimed` would.
+    /// Returns the summary.
+    pub fn archive_round(env: Env, round_id: u64) -> Result<RoundSummary, Error> {
+        require_initialized(&env)?;
+        let round = load_round(&env, round_id)?;
+        if round.status != RoundStatus::Finalized {
+            return Err(Error::RoundNotOpen);
+        }
+        let now = env.ledger().timestamp();
+        if now < claim_deadline(&env, &round) {
+            return Err(Error::ClaimWindowOpen);
+        }
 
-        RewardClaimed {
+        let storage = env.storage().persistent();
+        let players: Vec<Address> = storage
+            .get(&DataKey::Players(round_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        let total_paid = total_paid(&env, round_id, &round)?;
+        if !storage.has(&DataKey::Swept(round_id)) {
+            release_unclaimed(&env, round_id, &round, total_paid)?;
+        }
+        for player in players.iter() {
+            storage.remove(&DataKey::Submission(round_id, player));
+            count_removed_entry(&env, FAMILY_SUBMISSION);
+        }
+        storage.remove(&DataKey::Players(round_id));
+        if storage.has(&DataKey::Podium(round_id)) {
+            storage.remove(&DataKey::Podium(round_id));
+            count_removed_entry(&env, FAMILY_PODIUM);
+        }
+        storage.remove(&DataKey::CorrectTimes(round_id));
+        storage.remove(&DataKey::SeatReservation(round_id));
+        storage.remove(&DataKey::FinalizeApproval(round_id));
+        storage.remove(&DataKey::PreviousRound(round_id));
+        storage.remove(&DataKey::PushPayout(round_id));
+        storage.remove(&DataKey::PushCursor(round_id));
+        storage.remove(&DataKey::Options(round_id));
+        storage.remove(&DataKey::OptionCounts(round_id));
+        storage.remove(&DataKey::Round(round_id));
+        count_removed_entry(&env, FAMILY_ROUND);
+
+        let summary = RoundSummary {
+            answer_commitment: round.answer_commitment,
+            reward_amount: round.reward_amount,
+            winner_count: round.winner_count,
+            total_paid,
+            submission_count: players.len(),
+            finalized_at: round.finalized_at,
+            archived_at: now,
+        };
+        storage.set(&DataKey::RoundSummary(round_id), &summary);
+
+        RoundArchived {
             round_id,
-            player,
-            amount: round.payout_per_winner,
+            winner_count: round.winner_count,
+            total_paid,
+            submissions_removed: players.len(),
         }
         .publish(&env);
-        Ok(round.payout_per_winner)
+        Ok(summary)
+    }
+
+    /// Get the summary of an archived round.
+    pub fn get_round_summary(env: Env, round_id: u64) -> Option<RoundSummary> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RoundSummary(round_id))
     }
 
     /// Get round data.
     pub fn get_round(env: Env, round_id: u64) -> Option<RoundData> {
         env.storage().persistent().get(&DataKey::Round(round_id))
     }
+
+    /// List a round's submissions in submission order, skipping the first
+    /// `start` and returning at most `limit` (capped at
+    /// `MAX_SUBMISSIONS_PAGE`), so leaderboards can be built from on-chain
+    /// data alone.
+    pub fn get_submissions(
+        env: Env,
+        round_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<SubmissionEntry>, Error> {
+        load_round(&env, round_id)?;
+        let players: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Players(round_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        let end = start
+            .saturating_add(limit.min(MAX_SUBMISSIONS_PAGE))
+            .min(players.len());
+        let mut entries = Vec::new(&env);
+        for index in start..end {
+            let player = players.get_unchecked(index);
+            let submission: Option<Submission> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Submission(round_id, player.clone()));
+            if let Some(submission) = submission {
+                entries.push_back(SubmissionEntry {
+                    player,
+                    correct: submission.correct,
+                    submitted_at: submission.submitted_at,
+                    index: submission.index,
+                });
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Get the fastest correct submissions for a round (at most `PODIUM_SIZE`).
+    pub fn get_podium(env: Env, round_id: u64) -> Vec<PodiumEntry> {
+        get_podium(&env, round_id)
+    }
+
+    /// Get `player`'s submission to a round, including its ledger time and
+    /// submission index.
+    pub fn get_submission(env: Env, round_id: u64, player: Address) -> Option<Submission> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Submission(round_id, player))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -423,20 +1615,613 @@ fn get_admin(env: &Env) -> Result<Address, Error> {
         .ok_or(Error::NotInitialized)
 }
 
-fn get_prize_pool(env: &Env) -> Result<Address, Error> {
-    env.storage()
-        .instance()
-        .get(&DataKey::PrizePoolContract)
-        .ok_or(Error::NotInitialized)
+fn get_co_admins(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::CoAdmins)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Whether `address` is the primary admin or a registered co-admin.
+fn is_any_admin(admin: &Address, co_admins: &Vec<Address>, address: &Address) -> bool {
+    address == admin || co_admins.contains(address)
+}
+
+/// Record `caller`'s approval of adding (`add`) or removing `co_admin` and
+/// return whether the change may be applied. With no co-admin registered the
+/// primary admin decides alone; otherwise a second admin must approve, and an
+/// approval whose approver has since lost admin rights is replaced.
+fn approve_co_admin_change(
+    env: &Env,
+    caller: &Address,
+    co_admin: &Address,
+    add: bool,
+) -> Result<bool, Error> {
+    let admin = get_admin(env)?;
+    caller.require_auth();
+    let co_admins = get_co_admins(env);
+    if !is_any_admin(&admin, &co_admins, caller) {
+        return Err(Error::NotAuthorized);
+    }
+    if co_admins.is_empty() {
+        return Ok(true);
+    }
+
+    let key = DataKey::CoAdminApproval(co_admin.clone(), add);
+    let approver: Option<Address> = env.storage().persistent().get(&key);
+    match approver {
+        Some(approver) if approver == *caller => Err(Error::AlreadyApproved),
+        Some(approver) if is_any_admin(&admin, &co_admins, &approver) => {
+            env.storage().persistent().remove(&key);
+            Ok(true)
+        }
+        _ => {
+            env.storage().persistent().set(&key, caller);
+            CoAdminChangeApproved {
+                co_admin: co_admin.clone(),
+                add,
+                approver: caller.clone(),
+            }
+            .publish(env);
+            Ok(false)
+        }
+    }
+}
+
+fn get_quorum_threshold(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::QuorumThreshold)
+        .unwrap_or(0)
+}
+
+fn get_prize_pool(env: &Env) -> Result<Address, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::PrizePoolContract)
+        .ok_or(Error::NotInitialized)
+}
+
+fn get_balance_contract(env: &Env) -> Result<Address, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::BalanceContract)
+        .ok_or(Error::NotInitialized)
+}
+
+fn get_claim_window(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ClaimWindow)
+        .unwrap_or(DEFAULT_CLAIM_WINDOW)
+}
+
+fn claim_deadline(env: &Env, round: &RoundData) -> u64 {
+    round.finalized_at.saturating_add(get_claim_window(env))
+}
+
+/// Load a round, reporting `RoundArchived` for rounds that were archived.
+fn load_round(env: &Env, round_id: u64) -> Result<RoundData, Error> {
+    match env.storage().persistent().get(&DataKey::Round(round_id)) {
+        Some(round) => Ok(round),
+        None if env
+            .storage()
+            .persistent()
+            .has(&DataKey::RoundSummary(round_id)) =>
+        {
+            Err(Error::RoundArchived)
+        }
+        None => Err(Error::RoundNotFound),
+    }
+}
+
+/// Check that `player` has an unclaimed reward in a finalized round inside
+/// its claim window. Returns the submission and the amount it pays.
+fn check_claim(env: &Env, player: &Address, round_id: u64) -> Result<(Submission, i128), Error> {
+    let round = load_round(env, round_id)?;
+
+    if round.status != RoundStatus::Finalized {
+        return Err(Error::RoundNotOpen);
+    }
+    if env.ledger().timestamp() >= claim_deadline(env, &round) {
+        return Err(Error::ClaimWindowClosed);
+    }
+
+    let submission: Submission = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Submission(round_id, player.clone()))
+        .ok_or(Error::NoRewardAvailable)?;
+
+    if submission.claimed {
+        return Err(Error::AlreadyClaimed);
+    }
+
+    if !is_rewarded(&round, &submission) || round.payout_per_winner <= 0 {
+        return Err(Error::NoRewardAvailable);
+    }
+
+    let amount = winner_payout(&round, &submission)?;
+    Ok((submission, amount))
+}
+
+/// Mark `player`'s reward in a round claimed and pay it out of the round's
+/// prize pool reservation, after the checks of `check_claim`. The caller
+/// credits the player's balance. Returns the amount.
+fn claim_from_pool(env: &Env, player: &Address, round_id: u64) -> Result<i128, Error> {
+    let (mut submission, amount) = check_claim(env, player, round_id)?;
+    submission.claimed = true;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Submission(round_id, player.clone()), &submission);
+    pool_payout(env, round_id, player, amount)?;
+    Ok(amount)
+}
+
+fn get_wins(env: &Env, player: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Wins(player.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Remember that `player` answered `round_id` correctly so `claim_payout`
+/// can find it. Players with `MAX_TRACKED_WINS` tracked rounds claim the
+/// rest with `claim_reward`.
+fn track_win(env: &Env, player: &Address, round_id: u64) {
+    let mut wins = get_wins(env, player);
+    if wins.len() < MAX_TRACKED_WINS {
+        wins.push_back(round_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Wins(player.clone()), &wins);
+    }
+}
+
+/// Pay `amount` of a round's prize to `player`. Used by push batches; the
+/// caller has already marked the submission claimed.
+fn pay_winner(env: &Env, round_id: u64, player: &Address, amount: i128) -> Result<(), Error> {
+    pool_payout(env, round_id, player, amount)?;
+    credit_winnings(env, player, amount)
+}
+
+/// Pay `amount` out of a round's prize pool reservation to `player`.
+fn pool_payout(env: &Env, round_id: u64, player: &Address, amount: i128) -> Result<(), Error> {
+    let prize_pool = get_prize_pool(env)?;
+    let pool_client = PrizePoolClient::new(env, &prize_pool);
+    let admin = get_admin(env)?;
+    pool_client.payout(&admin, player, &round_id, &amount);
+
+    RewardClaimed {
+        round_id,
+        player: player.clone(),
+        amount,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Move `amount` of winnings from the contract to `player` in the balance
+/// contract.
+fn credit_winnings(env: &Env, player: &Address, amount: i128) -> Result<(), Error> {
+    let balance_contract = get_balance_contract(env)?;
+    let balance_client = BalanceClient::new(env, &balance_contract);
+    let contract_addr = env.current_contract_address();
+
+    // Adjust internal balance tracking
+    balance_client.debit(
+        &contract_addr,
+        &contract_addr,
+        &amount,
+        &symbol_short!("payout"),
+    );
+    balance_client.credit(&contract_addr, player, &amount, &symbol_short!("win"));
+    Ok(())
+}
+
+/// Release a finalized round's unclaimed reward, `reward_amount` minus
+/// `paid`, back to the prize pool and record it as swept. Rounds without
+/// winners were released at finalize and sweep nothing. Shared by
+/// `sweep_unclaimed` and `archive_round`. Returns the amount released.
+fn release_unclaimed(
+    env: &Env,
+    round_id: u64,
+    round: &RoundData,
+    paid: i128,
+) -> Result<i128, Error> {
+    let amount = if round.winner_count == 0 {
+        0
+    } else {
+        round
+            .reward_amount
+            .checked_sub(paid)
+            .ok_or(Error::Overflow)?
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::Swept(round_id), &amount);
+    if amount > 0 {
+        let prize_pool = get_prize_pool(env)?;
+        let admin = get_admin(env)?;
+        PrizePoolClient::new(env, &prize_pool).release(&admin, &round_id, &amount);
+    }
+
+    UnclaimedSwept { round_id, amount }.publish(env);
+    Ok(amount)
+}
+
+/// Sum of the rewards paid to a round's winners, by claim or push.
+fn total_paid(env: &Env, round_id: u64, round: &RoundData) -> Result<i128, Error> {
+    let players: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Players(round_id))
+        .unwrap_or_else(|| Vec::new(env));
+    let mut paid: i128 = 0;
+    for player in players.iter() {
+        let submission: Option<Submission> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Submission(round_id, player));
+        if let Some(submission) = submission.filter(|s| s.claimed) {
+            paid = paid
+                .checked_add(winner_payout(round, &submission)?)
+                .ok_or(Error::Overflow)?;
+        }
+    }
+    Ok(paid)
+}
+
+/// Whether `submission` is one of the round's winners: correct, inside the
+/// effective deadline and, in `FastestFinger` rounds, the earliest such.
+fn is_rewarded(round: &RoundData, submission: &Submission) -> bool {
+    submission.correct
+        && submission.submitted_at <= round.effective_deadline
+        && (round.mode == RoundMode::Shared || submission.index == round.winning_index)
+}
+
+/// Store `winners` correct in-time answers on the round. A `FastestFinger`
+/// round keeps only the first podium entry, which is the earliest correct
+/// answer by submission index. Returns the resulting winner count.
+fn settle_winners(env: &Env, round_id: u64, round: &mut RoundData, winners: u32) -> u32 {
+    round.winner_count = winners;
+    if round.mode == RoundMode::FastestFinger {
+        if let Some(first) = get_podium(env, round_id).first() {
+            round.winner_count = 1;
+            round.winning_index = first.index;
+        } else {
+            round.winner_count = 0;
+        }
+    }
+    round.winner_count
+}
+
+/// What a rewarded `submission` is paid: `payout_per_winner`, raised by the
+/// round's streak bonus if the player's streak qualifies.
+fn winner_payout(round: &RoundData, submission: &Submission) -> Result<i128, Error> {
+    if round.streak_bonus_bps == 0 || submission.streak < round.streak_min {
+        return Ok(round.payout_per_winner);
+    }
+    round
+        .payout_per_winner
+        .checked_mul(BASIS_POINTS + round.streak_bonus_bps as i128)
+        .map(|v| v / BASIS_POINTS)
+        .ok_or(Error::Overflow)
+}
+
+fn get_streak_bonus(env: &Env) -> Option<StreakBonus> {
+    env.storage()
+        .instance()
+        .get::<_, StreakBonus>(&DataKey::StreakBonus)
+        .filter(|b| b.bonus_bps > 0)
+}
+
+/// Number of a round's winners whose streak is at least `min_streak`.
+fn count_streak_winners(env: &Env, round_id: u64, round: &RoundData, min_streak: u32) -> u32 {
+    let players: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Players(round_id))
+        .unwrap_or_else(|| Vec::new(env));
+    let mut count: u32 = 0;
+    for player in players.iter() {
+        let submission: Option<Submission> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Submission(round_id, player));
+        if submission.is_some_and(|s| is_rewarded(round, &s) && s.streak >= min_streak) {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn get_player_stats(env: &Env, player: &Address) -> PlayerStats {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlayerStats(player.clone()))
+        .unwrap_or_default()
+}
+
+/// Record a revealed answer in `player`'s stats: a correct one extends the
+/// streak, a wrong one ends it. Returns the updated stats.
+fn record_result(env: &Env, player: &Address, correct: bool) -> Result<PlayerStats, Error> {
+    let mut stats = get_player_stats(env, player);
+    if correct {
+        stats.correct_answers = stats
+            .correct_answers
+            .checked_add(1)
+            .ok_or(Error::Overflow)?;
+        stats.current_streak = stats.current_streak.checked_add(1).ok_or(Error::Overflow)?;
+        stats.best_streak = stats.best_streak.max(stats.current_streak);
+    } else {
+        stats.current_streak = 0;
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlayerStats(player.clone()), &stats);
+    Ok(stats)
+}
+
+fn is_push_payout(env: &Env, round_id: u64) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PushPayout(round_id))
+        .unwrap_or(false)
+}
+
+/// Visit up to `limit` submissions from the push cursor, paying each
+/// rewarded, unclaimed one. Returns the number of winners paid.
+fn push_batch(env: &Env, round_id: u64, round: &RoundData, limit: u32) -> Result<u32, Error> {
+    let players: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Players(round_id))
+        .unwrap_or_else(|| Vec::new(env));
+    let cursor_key = DataKey::PushCursor(round_id);
+    let start: u32 = env.storage().persistent().get(&cursor_key).unwrap_or(0);
+    let end = start.saturating_add(limit).min(players.len());
+
+    let mut paid: u32 = 0;
+    for index in start..end {
+        let player = players.get_unchecked(index);
+        let key = DataKey::Submission(round_id, player.clone());
+        let mut submission: Submission = match env.storage().persistent().get(&key) {
+            Some(submission) => submission,
+            None => continue,
+        };
+        if submission.claimed || !is_rewarded(round, &submission) {
+            continue;
+        }
+        let amount = winner_payout(round, &submission)?;
+        submission.claimed = true;
+        env.storage().persistent().set(&key, &submission);
+        pay_winner(env, round_id, &player, amount)?;
+        paid = paid.checked_add(1).ok_or(Error::Overflow)?;
+    }
+    env.storage().persistent().set(&cursor_key, &end);
+
+    if end > start {
+        PayoutsPushed {
+            round_id,
+            paid,
+            next_index: end,
+            done: end == players.len(),
+        }
+        .publish(env);
+    }
+    Ok(paid)
+}
+
+fn get_season_pass(env: &Env) -> Result<Address, Error> {
+    env.storage()
+        .instance()
+        .get(&DataKey::SeasonPassContract)
+        .ok_or(Error::SeasonPassNotConfigured)
+}
+
+/// Admit `player` under the round's seat reservation while its priority
+/// window is open. The first `total_seats - reserved_seats` entries are open
+/// to everyone; the rest need a season pass, so the pass contract is only
+/// called once the open seats are gone.
+fn take_seat(env: &Env, round_id: u64, player: &Address, now: u64) -> Result<(), Error> {
+    let key = DataKey::SeatReservation(round_id);
+    let mut seats: SeatReservation = match env.storage().persistent().get(&key) {
+        Some(seats) => seats,
+        None => return Ok(()),
+    };
+    if now >= seats.priority_until {
+        return Ok(());
+    }
+    if seats.filled >= seats.total_seats {
+        return Err(Error::SeatsFull);
+    }
+    if seats.filled >= seats.total_seats - seats.reserved_seats {
+        let season_pass = get_season_pass(env)?;
+        if !SeasonPassClient::new(env, &season_pass).has_pass(player) {
+            return Err(Error::PassRequired);
+        }
+    }
+    seats.filled += 1;
+    env.storage().persistent().set(&key, &seats);
+    Ok(())
+}
+
+/// For a follow-up round, require that `player`'s answer to the previous
+/// round was rewarded. The previous round must be finalized, so a revealed
+/// anti-sniping cutoff is already reflected in its effective deadline.
+fn require_carry_over(env: &Env, round_id: u64, player: &Address) -> Result<(), Error> {
+    let previous_round_id: u64 = match env
+        .storage()
+        .persistent()
+        .get(&DataKey::PreviousRound(round_id))
+    {
+        Some(previous_round_id) => previous_round_id,
+        None => return Ok(()),
+    };
+    let previous = load_round(env, previous_round_id)?;
+    match previous.status {
+        RoundStatus::Open => return Err(Error::PreviousRoundOpen),
+        RoundStatus::Cancelled => return Err(Error::NotEligible),
+        RoundStatus::Finalized => {}
+    }
+    let submission: Option<Submission> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Submission(previous_round_id, player.clone()));
+    match submission {
+        Some(s) if is_rewarded(&previous, &s) => Ok(()),
+        _ => Err(Error::NotEligible),
+    }
+}
+
+/// Record `player`'s answer to an open round, stamped with the ledger time
+/// and the next submission index. Shared by `submit_answer` and
+/// `submit_choice`.
+fn record_submission(
+    env: &Env,
+    player: Address,
+    round_id: u64,
+    answer: &Bytes,
+) -> Result<(), Error> {
+    let round_key = DataKey::Round(round_id);
+    let mut round: RoundData = env
+        .storage()
+        .persistent()
+        .get(&round_key)
+        .ok_or(Error::RoundNotFound)?;
+
+    if round.status != RoundStatus::Open || round.answer_revealed {
+        return Err(Error::RoundClosed);
+    }
+
+    let now = env.ledger().timestamp();
+    if now > round.deadline {
+        return Err(Error::PastDeadline);
+    }
+
+    let submission_key = DataKey::Submission(round_id, player.clone());
+    if env.storage().persistent().has(&submission_key) {
+        return Err(Error::AlreadySubmitted);
+    }
+    let cap = if round.max_participants == 0 {
+        MAX_PARTICIPANTS
+    } else {
+        round.max_participants
+    };
+    if round.participant_count >= cap {
+        return Err(Error::RoundFull);
+    }
+    require_carry_over(env, round_id, &player)?;
+    take_seat(env, round_id, &player, now)?;
+    round.participant_count = round
+        .participant_count
+        .checked_add(1)
+        .ok_or(Error::Overflow)?;
+    env.storage().persistent().set(&round_key, &round);
+
+    let players_key = DataKey::Players(round_id);
+    let mut players: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&players_key)
+        .unwrap_or_else(|| Vec::new(env));
+    let index = players.len();
+
+    let answer_hash: BytesN<32> = env.crypto().sha256(answer).into();
+    let submission = Submission {
+        answer_hash,
+        correct: false,
+        claimed: false,
+        submitted_at: now,
+        index,
+        streak: 0,
+    };
+    env.storage().persistent().set(&submission_key, &submission);
+    count_new_entry(env, FAMILY_SUBMISSION);
+
+    let mut stats = get_player_stats(env, &player);
+    stats.rounds_played = stats.rounds_played.checked_add(1).ok_or(Error::Overflow)?;
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlayerStats(player.clone()), &stats);
+
+    players.push_back(player.clone());
+    env.storage().persistent().set(&players_key, &players);
+
+    AnswerSubmitted {
+        round_id,
+        player,
+        submitted_at: now,
+        index,
+    }
+    .publish(env);
+    Ok(())
+}
+
+fn get_options(env: &Env, round_id: u64) -> Option<Vec<BytesN<32>>> {
+    env.storage().persistent().get(&DataKey::Options(round_id))
+}
+
+/// Answer bytes for a multiple-choice option: its index as a big-endian
+/// `u32`.
+fn choice_bytes(env: &Env, option: u32) -> Bytes {
+    Bytes::from_array(env, &option.to_be_bytes())
+}
+
+/// Commitment to a round's answer: `sha256(answer || salt)`.
+fn answer_hash(env: &Env, answer: &Bytes, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = answer.clone();
+    preimage.append(&Bytes::from(salt));
+    env.crypto().sha256(&preimage).into()
 }
 
-fn get_balance_contract(env: &Env) -> Result<Address, Error> {
+/// Commitment to an anti-sniping offset: `sha256(offset_be_u64 || salt)`.
+fn cutoff_hash(env: &Env, offset: u64, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &offset.to_be_bytes());
+    preimage.append(&Bytes::from(salt));
+    env.crypto().sha256(&preimage).into()
+}
+
+fn get_podium(env: &Env, round_id: u64) -> Vec<PodiumEntry> {
     env.storage()
-        .instance()
-        .get(&DataKey::BalanceContract)
-        .ok_or(Error::NotInitialized)
+        .persistent()
+        .get(&DataKey::Podium(round_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Add a correct submission to the round's podium while it has fewer than
+/// `PODIUM_SIZE` entries. Submissions arrive in order, so the podium is the
+/// first correct answers; ledger-time ties are broken by submission index.
+fn record_podium(
+    env: &Env,
+    round_id: u64,
+    player: &Address,
+    submitted_at: u64,
+    index: u32,
+) -> Result<(), Error> {
+    let mut podium = get_podium(env, round_id);
+    if podium.len() >= PODIUM_SIZE {
+        return Ok(());
+    }
+    podium.push_back(PodiumEntry {
+        player: player.clone(),
+        submitted_at,
+        index,
+    });
+    let key = DataKey::Podium(round_id);
+    let is_new = !env.storage().persistent().has(&key);
+    env.storage().persistent().set(&key, &podium);
+    if is_new {
+        count_new_entry(env, FAMILY_PODIUM);
+    }
+    Ok(())
 }
 
+const FAMILY_ROUND: Symbol = symbol_short!("round");
+const FAMILY_SUBMISSION: Symbol = symbol_short!("submit");
+const FAMILY_PODIUM: Symbol = symbol_short!("podium");
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -456,6 +2241,7 @@ mod test {
         Reserved(u64),
         Paid(u64),
         Released(u64),
+        Available,
     }
 
     #[contractimpl]
@@ -471,6 +2257,17 @@ mod test {
         pub fn payout(env: Env, _admin: Address, _to: Address, game_id: u64, amount: i128) {
             env.storage().persistent().set(&PoolKey::Paid(game_id), &amount);
         }
+
+        pub fn set_available(env: Env, amount: i128) {
+            env.storage().persistent().set(&PoolKey::Available, &amount);
+        }
+
+        pub fn available(env: Env) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&PoolKey::Available)
+                .unwrap_or(i128::MAX)
+        }
     }
 
     #[contract]
@@ -511,6 +2308,30 @@ mod test {
         }
     }
 
+    #[contract]
+    pub struct MockSeasonPass;
+
+    #[contracttype]
+    pub enum PassKey {
+        Holder(Address),
+    }
+
+    #[contractimpl]
+    impl MockSeasonPass {
+        pub fn grant(env: Env, user: Address) {
+            env.storage()
+                .persistent()
+                .set(&PassKey::Holder(user), &true);
+        }
+
+        pub fn has_pass(env: Env, user: Address) -> bool {
+            env.storage()
+                .persistent()
+                .get(&PassKey::Holder(user))
+                .unwrap_or(false)
+        }
+    }
+
     fn setup(
         env: &Env,
     ) -> (
@@ -539,24 +2360,29 @@ mod test {
         (trivia_client, admin, player, trivia_id, balance_client)
     }
 
+    fn answer_salt(env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &[5u8; 32])
+    }
+
     fn hash_answer(env: &Env, payload: &Bytes) -> BytesN<32> {
-        env.crypto().sha256(payload).into()
+        answer_hash(env, payload, &answer_salt(env))
     }
 
     #[test]
     fn test_lifecycle() {
         let env = Env::default();
-        let (client, _admin, player, _trivia_id, balance) = setup(&env);
+        let (client, admin, player, _trivia_id, balance) = setup(&env);
 
         let deadline = env.ledger().timestamp() + 1000;
         let payload = Bytes::from_array(&env, &[1, 2, 3]);
         let commitment = hash_answer(&env, &payload);
 
-        client.open_question(&1, &commitment, &deadline, &1000);
+        client.open_question(&1, &commitment, &deadline, &1000, &None, &RoundMode::Shared);
         
-        client.submit_answer(&player, &1, &payload, &env.ledger().timestamp());
+        client.submit_answer(&player, &1, &payload);
         
-        client.finalize_round(&1);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
         
         let reward = client.claim_reward(&player, &1);
         assert_eq!(reward, 1000);
@@ -572,11 +2398,11 @@ mod test {
         let payload = Bytes::from_array(&env, &[1, 2, 3]);
         let commitment = hash_answer(&env, &payload);
 
-        client.open_question(&1, &commitment, &deadline, &1000);
+        client.open_question(&1, &commitment, &deadline, &1000, &None, &RoundMode::Shared);
         
         env.ledger().set_timestamp(deadline + 1);
         
-        let result = client.try_submit_answer(&player, &1, &payload, &env.ledger().timestamp());
+        let result = client.try_submit_answer(&player, &1, &payload);
         assert!(result.is_err());
     }
 
@@ -594,11 +2420,19 @@ mod test {
             invoke: &soroban_sdk::testutils::MockAuthInvoke {
                 contract: &client.address,
                 fn_name: "open_question",
-                args: (1u64, commitment.clone(), 1000u64, 100i128).into_val(&env),
+                args: (
+                    1u64,
+                    commitment.clone(),
+                    1000u64,
+                    100i128,
+                    None::<BytesN<32>>,
+                )
+                    .into_val(&env),
                 sub_invokes: &[],
             },
         }]);
-        let result = client.try_open_question(&1, &commitment, &1000, &100);
+        let result =
+            client.try_open_question(&1, &commitment, &1000, &100, &None, &RoundMode::Shared);
         assert!(result.is_err());
     }
 
@@ -609,13 +2443,53 @@ mod test {
 
         let payload = Bytes::from_array(&env, &[1]);
         let commitment = hash_answer(&env, &payload);
-        client.open_question(&1, &commitment, &(env.ledger().timestamp() + 100), &100);
+        let deadline = env.ledger().timestamp() + 100;
+        client.open_question(&1, &commitment, &deadline, &100, &None, &RoundMode::Shared);
 
-        client.submit_answer(&player, &1, &payload, &env.ledger().timestamp());
-        let result = client.try_submit_answer(&player, &1, &payload, &env.ledger().timestamp());
+        client.submit_answer(&player, &1, &payload);
+        let result = client.try_submit_answer(&player, &1, &payload);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_podium_orders_fastest_correct_players() {
+        let env = Env::default();
+        let (client, admin, _player, _trivia_id, _balance) = setup(&env);
+        env.ledger().set_timestamp(100);
+
+        let payload = Bytes::from_array(&env, &[7]);
+        let wrong = Bytes::from_array(&env, &[8]);
+        let commitment = hash_answer(&env, &payload);
+        client.open_question(&1, &commitment, &1000, &400, &None, &RoundMode::Shared);
+
+        let slow = Address::generate(&env);
+        let fast = Address::generate(&env);
+        let mid = Address::generate(&env);
+        let late = Address::generate(&env);
+        let incorrect = Address::generate(&env);
+
+        env.ledger().set_timestamp(110);
+        client.submit_answer(&fast, &1, &payload);
+        client.submit_answer(&incorrect, &1, &wrong);
+        client.submit_answer(&mid, &1, &payload);
+        env.ledger().set_timestamp(150);
+        client.submit_answer(&slow, &1, &payload);
+        client.submit_answer(&late, &1, &payload);
+
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
+
+        let podium = client.get_podium(&1);
+        assert_eq!(podium.len(), PODIUM_SIZE);
+        assert_eq!(podium.get(0).unwrap().player, fast);
+        // Same ledger time: the submission index breaks the tie.
+        assert_eq!(podium.get(1).unwrap().player, mid);
+        assert_eq!(podium.get(1).unwrap().index, 2);
+        assert_eq!(podium.get(2).unwrap().player, slow);
+        assert_eq!(podium.get(2).unwrap().submitted_at, 150);
+        assert_eq!(client.get_submission(&1, &late).unwrap().index, 4);
+    }
+
     #[test]
     fn test_claim_before_finalize_rejected() {
         let env = Env::default();
@@ -623,10 +2497,990 @@ mod test {
 
         let payload = Bytes::from_array(&env, &[1]);
         let commitment = hash_answer(&env, &payload);
-        client.open_question(&1, &commitment, &(env.ledger().timestamp() + 100), &100);
-        client.submit_answer(&player, &1, &payload, &env.ledger().timestamp());
+        let deadline = env.ledger().timestamp() + 100;
+        client.open_question(&1, &commitment, &deadline, &100, &None, &RoundMode::Shared);
+        client.submit_answer(&player, &1, &payload);
 
         let result = client.try_claim_reward(&player, &1);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_storage_health_and_bump() {
+        let env = Env::default();
+        env.ledger().set_sequence_number(10);
+        let (client, _admin, player, _trivia_id, _balance) = setup(&env);
+        let other = Address::generate(&env);
+
+        let payload = Bytes::from_array(&env, &[1]);
+        let wrong = Bytes::from_array(&env, &[2]);
+        let commitment = hash_answer(&env, &payload);
+        let deadline = env.ledger().timestamp() + 100;
+        client.open_question(&1, &commitment, &deadline, &100, &None, &RoundMode::Shared);
+        client.submit_answer(&player, &1, &payload);
+        client.submit_answer(&other, &1, &wrong);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+
+        let health = client.get_storage_health();
+        assert_eq!(health.last_bump_ledger, 10);
+        assert_eq!(health.instance_ttl, INSTANCE_BUMP_LEDGERS);
+        assert_eq!(health.families.get(0).unwrap().count, 1);
+        assert_eq!(health.families.get(1).unwrap().count, 2);
+        assert_eq!(health.families.get(2).unwrap().count, 1);
+
+        env.ledger().set_sequence_number(20);
+        assert_eq!(
+            client.get_storage_health().instance_ttl,
+            INSTANCE_BUMP_LEDGERS - 10
+        );
+        client.bump_all_config();
+        assert_eq!(client.get_storage_health().last_bump_ledger, 20);
+    }
+
+    #[test]
+    fn test_anti_snipe_cutoff_excludes_late_answers() {
+        let env = Env::default();
+        let (client, admin, early, _trivia_id, _balance) = setup(&env);
+        env.ledger().set_timestamp(100);
+        let sniper = Address::generate(&env);
+
+        let payload = Bytes::from_array(&env, &[9]);
+        let commitment = hash_answer(&env, &payload);
+        let salt = BytesN::from_array(&env, &[3u8; 32]);
+        let offset: u64 = 60;
+        let cutoff = cutoff_hash(&env, offset, &salt);
+        client.open_question(
+            &1,
+            &commitment,
+            &1000,
+            &600,
+            &Some(cutoff),
+            &RoundMode::Shared,
+        );
+
+        env.ledger().set_timestamp(500);
+        client.submit_answer(&early, &1, &payload);
+        env.ledger().set_timestamp(995);
+        client.submit_answer(&sniper, &1, &payload);
+
+        env.ledger().set_timestamp(1001);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &false);
+        assert_eq!(client.get_round(&1).unwrap().winner_count, 2);
+
+        let not_revealed = client.try_finalize_round(&admin, &1, &false);
+        assert_eq!(not_revealed, Err(Ok(Error::CutoffNotRevealed)));
+        let wrong = client.try_reveal_cutoff(&1, &30, &salt);
+        assert_eq!(wrong, Err(Ok(Error::InvalidCutoffReveal)));
+        client.reveal_cutoff(&1, &offset, &salt);
+
+        let round = client.get_round(&1).unwrap();
+        assert_eq!(round.effective_deadline, 940);
+        assert_eq!(round.winner_count, 1);
+        assert_eq!(client.get_podium(&1).len(), 1);
+
+        client.finalize_round(&admin, &1, &false);
+        assert_eq!(client.claim_reward(&early, &1), 600);
+        let late = client.try_claim_reward(&sniper, &1);
+        assert_eq!(late, Err(Ok(Error::NoRewardAvailable)));
+    }
+
+    #[test]
+    fn test_quorum_required_for_high_value_rounds() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let co_admin = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        client.add_co_admin(&admin, &co_admin);
+        client.set_quorum_threshold(&1_000);
+
+        let payload = Bytes::from_array(&env, &[4]);
+        let commitment = hash_answer(&env, &payload);
+        let deadline = env.ledger().timestamp() + 100;
+        client.open_question(
+            &1,
+            &commitment,
+            &deadline,
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.open_question(&2, &commitment, &deadline, &999, &None, &RoundMode::Shared);
+        client.submit_answer(&player, &1, &payload);
+        env.ledger().set_timestamp(deadline + 1);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &false);
+        client.reveal_answer(&2, &payload, &answer_salt(&env), &false);
+
+        // Below the threshold a single admin finalizes.
+        assert!(client.finalize_round(&co_admin, &2, &false));
+
+        let unauthorized = client.try_finalize_round(&outsider, &1, &false);
+        assert_eq!(unauthorized, Err(Ok(Error::NotAuthorized)));
+
+        assert!(!client.finalize_round(&admin, &1, &false));
+        assert_eq!(client.get_round(&1).unwrap().status, RoundStatus::Open);
+        let same = client.try_finalize_round(&admin, &1, &false);
+        assert_eq!(same, Err(Ok(Error::AlreadyApproved)));
+
+        assert!(client.finalize_round(&co_admin, &1, &false));
+        assert_eq!(client.get_round(&1).unwrap().status, RoundStatus::Finalized);
+        assert_eq!(client.claim_reward(&player, &1), 1_000);
+    }
+
+    #[test]
+    fn test_co_admin_changes_need_quorum() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        let outsider = Address::generate(&env);
+
+        // The first co-admin is added by the primary admin alone.
+        assert!(client.add_co_admin(&admin, &first));
+        let unauthorized = client.try_add_co_admin(&outsider, &outsider);
+        assert_eq!(unauthorized, Err(Ok(Error::NotAuthorized)));
+
+        // After that the admin cannot add another co-admin by itself.
+        assert!(!client.add_co_admin(&admin, &second));
+        let same = client.try_add_co_admin(&admin, &second);
+        assert_eq!(same, Err(Ok(Error::AlreadyApproved)));
+        assert_eq!(
+            client.get_co_admins(),
+            Vec::from_array(&env, [first.clone()])
+        );
+        assert!(client.add_co_admin(&first, &second));
+        assert_eq!(
+            client.get_co_admins(),
+            Vec::from_array(&env, [first.clone(), second.clone()])
+        );
+
+        client.set_quorum_threshold(&1_000);
+        let payload = Bytes::from_array(&env, &[7]);
+        let commitment = hash_answer(&env, &payload);
+        let deadline = env.ledger().timestamp() + 100;
+        client.open_question(
+            &1,
+            &commitment,
+            &deadline,
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.submit_answer(&player, &1, &payload);
+        env.ledger().set_timestamp(deadline + 1);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &false);
+        assert!(!client.finalize_round(&second, &1, &false));
+
+        assert!(!client.remove_co_admin(&admin, &second));
+        assert!(client.remove_co_admin(&first, &second));
+        assert_eq!(
+            client.get_co_admins(),
+            Vec::from_array(&env, [first.clone()])
+        );
+
+        // The removed co-admin's approval no longer counts.
+        assert!(!client.finalize_round(&first, &1, &false));
+        assert_eq!(client.get_round(&1).unwrap().status, RoundStatus::Open);
+        assert!(client.finalize_round(&admin, &1, &false));
+        assert_eq!(client.get_round(&1).unwrap().status, RoundStatus::Finalized);
+    }
+
+    #[test]
+    fn test_reserved_seats_for_pass_holders() {
+        let env = Env::default();
+        let (client, _admin, player, _trivia_id, _balance) = setup(&env);
+        let pass_id = env.register(MockSeasonPass, ());
+        let pass = MockSeasonPassClient::new(&env, &pass_id);
+
+        let payload = Bytes::from_array(&env, &[5]);
+        let commitment = hash_answer(&env, &payload);
+        let opened_at = env.ledger().timestamp();
+        client.open_question(
+            &1,
+            &commitment,
+            &(opened_at + 1_000),
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+
+        let unconfigured = client.try_set_seat_reservation(&1, &3, &1, &60);
+        assert_eq!(unconfigured, Err(Ok(Error::SeasonPassNotConfigured)));
+        client.set_season_pass_contract(&pass_id);
+        let invalid = client.try_set_seat_reservation(&1, &3, &4, &60);
+        assert_eq!(invalid, Err(Ok(Error::InvalidReservation)));
+
+        // Three seats in the first 60s, one of them held for pass holders.
+        client.set_seat_reservation(&1, &3, &1, &60);
+        let holder = Address::generate(&env);
+        let late = Address::generate(&env);
+        pass.grant(&holder);
+
+        client.submit_answer(&player, &1, &payload);
+        client.submit_answer(&Address::generate(&env), &1, &payload);
+        let blocked = client.try_submit_answer(&late, &1, &payload);
+        assert_eq!(blocked, Err(Ok(Error::PassRequired)));
+        client.submit_answer(&holder, &1, &payload);
+        let full = client.try_submit_answer(&Address::generate(&env), &1, &payload);
+        assert_eq!(full, Err(Ok(Error::SeatsFull)));
+        assert_eq!(client.get_seat_reservation(&1).unwrap().filled, 3);
+
+        // After the priority window, open entry resumes.
+        env.ledger().set_timestamp(opened_at + 60);
+        client.submit_answer(&late, &1, &payload);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        assert_eq!(client.get_round(&1).unwrap().winner_count, 4);
+    }
+
+    #[test]
+    fn test_archive_round_after_claim_window() {
+        let env = Env::default();
+        let (client, admin, player, trivia_id, _balance) = setup(&env);
+        client.set_claim_window(&100);
+
+        let payload = Bytes::from_array(&env, &[7]);
+        let commitment = hash_answer(&env, &payload);
+        let now = env.ledger().timestamp();
+        client.open_question(
+            &1,
+            &commitment,
+            &(now + 1_000),
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+
+        let slow = Address::generate(&env);
+        let wrong = Address::generate(&env);
+        client.submit_answer(&player, &1, &payload);
+        client.submit_answer(&slow, &1, &payload);
+        client.submit_answer(&wrong, &1, &Bytes::from_array(&env, &[8]));
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
+        assert_eq!(client.claim_reward(&player, &1), 500);
+
+        let early = client.try_archive_round(&1);
+        assert_eq!(early, Err(Ok(Error::ClaimWindowOpen)));
+
+        env.ledger().set_timestamp(now + 100);
+        let late = client.try_claim_reward(&slow, &1);
+        assert_eq!(late, Err(Ok(Error::ClaimWindowClosed)));
+
+        let summary = client.archive_round(&1);
+        assert_eq!(summary.winner_count, 2);
+        assert_eq!(summary.total_paid, 500);
+        assert_eq!(summary.submission_count, 3);
+        assert_eq!(summary.answer_commitment, commitment);
+        assert_eq!(client.get_round_summary(&1), Some(summary));
+
+        assert!(client.get_round(&1).is_none());
+        assert!(client.get_podium(&1).is_empty());
+        let health = client.get_storage_health();
+        assert_eq!(health.families.get(0).unwrap().count, 0);
+        assert_eq!(health.families.get(1).unwrap().count, 0);
+        assert_eq!(health.families.get(2).unwrap().count, 0);
+        env.as_contract(&trivia_id, || {
+            let storage = env.storage().persistent();
+            assert!(!storage.has(&DataKey::Submission(1, player.clone())));
+            assert!(!storage.has(&DataKey::Players(1)));
+        });
+
+        let again = client.try_archive_round(&1);
+        assert_eq!(again, Err(Ok(Error::RoundArchived)));
+        let claim = client.try_claim_reward(&slow, &1);
+        assert_eq!(claim, Err(Ok(Error::RoundArchived)));
+        let reopen = client.try_open_question(
+            &1,
+            &commitment,
+            &(now + 1_000),
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+        assert_eq!(reopen, Err(Ok(Error::RoundAlreadyExists)));
+    }
+
+    #[test]
+    fn test_follow_up_round_admits_only_survivors() {
+        let env = Env::default();
+        let (client, admin, survivor, _trivia_id, _balance) = setup(&env);
+        let eliminated = Address::generate(&env);
+        let outsider = Address::generate(&env);
+
+        let first = Bytes::from_array(&env, &[1]);
+        let second = Bytes::from_array(&env, &[2]);
+        let deadline = env.ledger().timestamp() + 100;
+        client.open_question(
+            &1,
+            &hash_answer(&env, &first),
+            &deadline,
+            &100,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.submit_answer(&survivor, &1, &first);
+        client.submit_answer(&eliminated, &1, &second);
+
+        let missing = client.try_open_follow_up_question(
+            &2,
+            &9,
+            &hash_answer(&env, &second),
+            &deadline,
+            &500,
+            &None,
+        );
+        assert_eq!(missing, Err(Ok(Error::RoundNotFound)));
+        client.open_follow_up_question(&2, &1, &hash_answer(&env, &second), &deadline, &500, &None);
+        assert_eq!(client.get_previous_round(&2), Some(1));
+
+        let early = client.try_submit_answer(&survivor, &2, &second);
+        assert_eq!(early, Err(Ok(Error::PreviousRoundOpen)));
+        client.reveal_answer(&1, &first, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
+
+        let wrong = client.try_submit_answer(&eliminated, &2, &second);
+        assert_eq!(wrong, Err(Ok(Error::NotEligible)));
+        let absent = client.try_submit_answer(&outsider, &2, &second);
+        assert_eq!(absent, Err(Ok(Error::NotEligible)));
+
+        // The last survivor takes the escalated prize.
+        client.submit_answer(&survivor, &2, &second);
+        client.reveal_answer(&2, &second, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &2, &true);
+        assert_eq!(client.claim_reward(&survivor, &2), 500);
+    }
+
+    #[test]
+    fn test_push_payouts_credit_winners_in_batches() {
+        let env = Env::default();
+        let (client, admin, wrong, _trivia_id, balance) = setup(&env);
+        balance.set_balance(&client.address, &100_000);
+
+        let payload = Bytes::from_array(&env, &[4]);
+        let commitment = hash_answer(&env, &payload);
+        let now = env.ledger().timestamp();
+        client.open_question(
+            &1,
+            &commitment,
+            &(now + 100),
+            &1_100,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.set_push_payout(&1, &true);
+        assert!(client.is_push_payout(&1));
+
+        client.submit_answer(&wrong, &1, &Bytes::from_array(&env, &[5]));
+        let mut winners = Vec::new(&env);
+        for _ in 0..11 {
+            let player = Address::generate(&env);
+            client.submit_answer(&player, &1, &payload);
+            winners.push_back(player);
+        }
+        let early = client.try_push_payouts(&1, &5);
+        assert_eq!(early, Err(Ok(Error::RoundNotOpen)));
+
+        // Finalizing pays the first batch: 9 winners among the first 10 entries.
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
+        assert_eq!(client.get_push_cursor(&1), MAX_PUSH_BATCH);
+        assert_eq!(balance.balance_of(&winners.get(8).unwrap()), 100);
+        assert_eq!(balance.balance_of(&winners.get(9).unwrap()), 0);
+        assert_eq!(balance.balance_of(&wrong), 0);
+
+        // A winner may still claim before the keeper reaches them.
+        let late = winners.get(10).unwrap();
+        assert_eq!(client.claim_reward(&late, &1), 100);
+
+        assert_eq!(client.push_payouts(&1, &2), 1);
+        assert_eq!(client.push_payouts(&1, &50), 0);
+        for player in winners.iter() {
+            assert_eq!(balance.balance_of(&player), 100);
+        }
+
+        client.open_question(
+            &2,
+            &commitment,
+            &(now + 100),
+            &100,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.reveal_answer(&2, &payload, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &2, &true);
+        let pull = client.try_push_payouts(&2, &5);
+        assert_eq!(pull, Err(Ok(Error::PushNotEnabled)));
+    }
+
+    #[test]
+    fn test_open_question_requires_unreserved_prize_funds() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let balance_id = env.register(MockBalance, ());
+        let pool_id = env.register(MockPrizePool, ());
+        let pool = MockPrizePoolClient::new(&env, &pool_id);
+        let trivia_id = env.register(SpeedTrivia, ());
+        let client = SpeedTriviaClient::new(&env, &trivia_id);
+        client.init(&admin, &pool_id, &balance_id);
+
+        pool.set_available(&50);
+        let deadline = env.ledger().timestamp() + 1000;
+        let commitment = hash_answer(&env, &Bytes::from_array(&env, &[1]));
+        let result =
+            client.try_open_question(&1, &commitment, &deadline, &100, &None, &RoundMode::Shared);
+        assert_eq!(result, Err(Ok(Error::InsufficientPrizeFunds)));
+        assert!(client.get_round(&1).is_none());
+
+        pool.set_available(&100);
+        client.open_question(&1, &commitment, &deadline, &100, &None, &RoundMode::Shared);
+        let reserved: i128 = env.as_contract(&pool_id, || {
+            env.storage()
+                .persistent()
+                .get(&PoolKey::Reserved(1))
+                .unwrap()
+        });
+        assert_eq!(reserved, 100);
+    }
+
+    #[test]
+    fn test_reveal_answer_marks_correct_submissions() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let other = Address::generate(&env);
+
+        let payload = Bytes::from_array(&env, &[6]);
+        let wrong = Bytes::from_array(&env, &[7]);
+        let deadline = env.ledger().timestamp() + 100;
+        client.open_question(
+            &1,
+            &hash_answer(&env, &payload),
+            &deadline,
+            &100,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.submit_answer(&player, &1, &payload);
+        client.submit_answer(&other, &1, &wrong);
+
+        // Nothing is marked correct before the reveal.
+        assert!(!client.get_submission(&1, &player).unwrap().correct);
+        let unrevealed = client.try_finalize_round(&admin, &1, &true);
+        assert_eq!(unrevealed, Err(Ok(Error::AnswerNotRevealed)));
+
+        let bad_salt = BytesN::from_array(&env, &[9u8; 32]);
+        let result = client.try_reveal_answer(&1, &payload, &bad_salt, &true);
+        assert_eq!(result, Err(Ok(Error::InvalidAnswerReveal)));
+        let result = client.try_reveal_answer(&1, &wrong, &answer_salt(&env), &true);
+        assert_eq!(result, Err(Ok(Error::InvalidAnswerReveal)));
+
+        assert_eq!(
+            client.reveal_answer(&1, &payload, &answer_salt(&env), &true),
+            1
+        );
+        assert!(client.get_submission(&1, &player).unwrap().correct);
+        assert!(!client.get_submission(&1, &other).unwrap().correct);
+        let again = client.try_reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        assert_eq!(again, Err(Ok(Error::InvalidAnswerReveal)));
+
+        // The revealed answer can no longer be copied into new submissions.
+        let late = client.try_submit_answer(&Address::generate(&env), &1, &payload);
+        assert_eq!(late, Err(Ok(Error::RoundClosed)));
+
+        client.finalize_round(&admin, &1, &true);
+        assert_eq!(client.claim_reward(&player, &1), 100);
+    }
+
+    #[test]
+    fn test_multiple_choice_round_tallies_options() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let others = [Address::generate(&env), Address::generate(&env)];
+
+        let mut options = Vec::new(&env);
+        for text in [b"red", b"tan", b"sky"] {
+            options.push_back(hash_answer(&env, &Bytes::from_array(&env, text)));
+        }
+        let correct = choice_bytes(&env, 2);
+        let commitment = hash_answer(&env, &correct);
+        let deadline = env.ledger().timestamp() + 100;
+        let too_few = Vec::from_array(&env, [options.get(0).unwrap()]);
+        let result = client.try_open_multiple_choice_question(
+            &1,
+            &too_few,
+            &commitment,
+            &deadline,
+            &300,
+            &None,
+        );
+        assert_eq!(result, Err(Ok(Error::InvalidOption)));
+        client.open_multiple_choice_question(&1, &options, &commitment, &deadline, &300, &None);
+        assert_eq!(client.get_options(&1), Some(options));
+
+        let text = client.try_submit_answer(&player, &1, &correct);
+        assert_eq!(text, Err(Ok(Error::WrongAnswerMode)));
+        let out_of_range = client.try_submit_choice(&player, &1, &3);
+        assert_eq!(out_of_range, Err(Ok(Error::InvalidOption)));
+
+        client.submit_choice(&player, &1, &2);
+        client.submit_choice(&others[0], &1, &0);
+        client.submit_choice(&others[1], &1, &2);
+        let counts = client.get_option_counts(&1);
+        assert_eq!(counts, Vec::from_array(&env, [1, 0, 2]));
+
+        assert_eq!(
+            client.reveal_answer(&1, &correct, &answer_salt(&env), &true),
+            2
+        );
+        client.finalize_round(&admin, &1, &true);
+        assert_eq!(client.claim_reward(&player, &1), 150);
+        let wrong = client.try_claim_reward(&others[0], &1);
+        assert_eq!(wrong, Err(Ok(Error::NoRewardAvailable)));
+
+        // Free-text rounds do not take option indexes.
+        client.open_question(&2, &commitment, &deadline, &100, &None, &RoundMode::Shared);
+        let choice = client.try_submit_choice(&player, &2, &0);
+        assert_eq!(choice, Err(Ok(Error::WrongAnswerMode)));
+    }
+
+    #[test]
+    fn test_cancel_round_releases_reservation() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let pool_id = env.as_contract(&client.address, || get_prize_pool(&env).unwrap());
+
+        let payload = Bytes::from_array(&env, &[3]);
+        let deadline = env.ledger().timestamp() + 100;
+        let commitment = hash_answer(&env, &payload);
+        client.open_question(&1, &commitment, &deadline, &700, &None, &RoundMode::Shared);
+        client.open_follow_up_question(&2, &1, &commitment, &deadline, &100, &None);
+        client.submit_answer(&player, &1, &payload);
+
+        client.cancel_round(&1);
+        let round = client.get_round(&1).unwrap();
+        assert_eq!(round.status, RoundStatus::Cancelled);
+        let released: Option<i128> = env.as_contract(&pool_id, || {
+            env.storage().persistent().get(&PoolKey::Released(1))
+        });
+        assert_eq!(released, Some(700));
+
+        let again = client.try_cancel_round(&1);
+        assert_eq!(again, Err(Ok(Error::RoundClosed)));
+        let submit = client.try_submit_answer(&Address::generate(&env), &1, &payload);
+        assert_eq!(submit, Err(Ok(Error::RoundClosed)));
+        let finalize = client.try_finalize_round(&admin, &1, &true);
+        assert_eq!(finalize, Err(Ok(Error::RoundClosed)));
+        let claim = client.try_claim_reward(&player, &1);
+        assert_eq!(claim, Err(Ok(Error::RoundNotOpen)));
+        let follow_up = client.try_submit_answer(&player, &2, &payload);
+        assert_eq!(follow_up, Err(Ok(Error::NotEligible)));
+    }
+
+    #[test]
+    fn test_finalize_waits_for_deadline_unless_forced() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let co_admin = Address::generate(&env);
+        client.add_co_admin(&admin, &co_admin);
+
+        let payload = Bytes::from_array(&env, &[2]);
+        let commitment = hash_answer(&env, &payload);
+        let deadline = env.ledger().timestamp() + 100;
+        client.open_question(&1, &commitment, &deadline, &100, &None, &RoundMode::Shared);
+        client.open_question(&2, &commitment, &deadline, &100, &None, &RoundMode::Shared);
+        client.submit_answer(&player, &1, &payload);
+
+        // Answers are accepted until the deadline, so the reveal waits for it
+        // unless the admin ends entries early.
+        let early = client.try_reveal_answer(&1, &payload, &answer_salt(&env), &false);
+        assert_eq!(early, Err(Ok(Error::DeadlineNotReached)));
+        env.ledger().set_timestamp(deadline);
+        let at_deadline = client.try_reveal_answer(&2, &payload, &answer_salt(&env), &false);
+        assert_eq!(at_deadline, Err(Ok(Error::DeadlineNotReached)));
+        env.ledger().set_timestamp(deadline - 50);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+
+        let early = client.try_finalize_round(&admin, &1, &false);
+        assert_eq!(early, Err(Ok(Error::DeadlineNotReached)));
+        // Only the primary admin may force an early close.
+        let co_forced = client.try_finalize_round(&co_admin, &1, &true);
+        assert_eq!(co_forced, Err(Ok(Error::NotAuthorized)));
+        assert!(client.finalize_round(&admin, &1, &true));
+        assert_eq!(client.claim_reward(&player, &1), 100);
+
+        env.ledger().set_timestamp(deadline + 1);
+        client.reveal_answer(&2, &payload, &answer_salt(&env), &false);
+        assert!(client.finalize_round(&co_admin, &2, &false));
+    }
+
+    #[test]
+    fn test_sweep_unclaimed_releases_remaining_shares_once() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let pool_id = env.as_contract(&client.address, || get_prize_pool(&env).unwrap());
+        client.set_claim_window(&100);
+
+        let payload = Bytes::from_array(&env, &[8]);
+        let commitment = hash_answer(&env, &payload);
+        let now = env.ledger().timestamp();
+        client.open_question(
+            &1,
+            &commitment,
+            &(now + 10),
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+        let others = [Address::generate(&env), Address::generate(&env)];
+        client.submit_answer(&player, &1, &payload);
+        client.submit_answer(&others[0], &1, &payload);
+        client.submit_answer(&others[1], &1, &payload);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
+        assert_eq!(client.claim_reward(&player, &1), 333);
+
+        let early = client.try_sweep_unclaimed(&1);
+        assert_eq!(early, Err(Ok(Error::ClaimWindowOpen)));
+
+        // Two unclaimed shares plus the remainder of 1_000 / 3.
+        env.ledger().set_timestamp(now + 100);
+        assert_eq!(client.sweep_unclaimed(&1), 667);
+        assert_eq!(client.get_swept(&1), Some(667));
+        let released: Option<i128> = env.as_contract(&pool_id, || {
+            env.storage().persistent().get(&PoolKey::Released(1))
+        });
+        assert_eq!(released, Some(667));
+
+        let again = client.try_sweep_unclaimed(&1);
+        assert_eq!(again, Err(Ok(Error::AlreadySwept)));
+    }
+
+    #[test]
+    fn test_archive_releases_unswept_reward() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let pool_id = env.as_contract(&client.address, || get_prize_pool(&env).unwrap());
+        client.set_claim_window(&100);
+
+        let payload = Bytes::from_array(&env, &[8]);
+        let commitment = hash_answer(&env, &payload);
+        let now = env.ledger().timestamp();
+        client.open_question(
+            &1,
+            &commitment,
+            &(now + 10),
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.submit_answer(&player, &1, &payload);
+        client.submit_answer(&Address::generate(&env), &1, &payload);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
+        assert_eq!(client.claim_reward(&player, &1), 500);
+
+        // Archiving before any sweep hands the unclaimed share back.
+        env.ledger().set_timestamp(now + 100);
+        client.archive_round(&1);
+        assert_eq!(client.get_swept(&1), Some(500));
+        let released: Option<i128> = env.as_contract(&pool_id, || {
+            env.storage().persistent().get(&PoolKey::Released(1))
+        });
+        assert_eq!(released, Some(500));
+
+        let sweep = client.try_sweep_unclaimed(&1);
+        assert_eq!(sweep, Err(Ok(Error::RoundArchived)));
+    }
+
+    #[test]
+    fn test_get_submissions_pages_in_submission_order() {
+        let env = Env::default();
+        let (client, _admin, player, _trivia_id, _balance) = setup(&env);
+        env.ledger().set_timestamp(10);
+
+        let payload = Bytes::from_array(&env, &[1]);
+        let commitment = hash_answer(&env, &payload);
+        client.open_question(&1, &commitment, &100, &100, &None, &RoundMode::Shared);
+        let second = Address::generate(&env);
+        let third = Address::generate(&env);
+        client.submit_answer(&player, &1, &payload);
+        env.ledger().set_timestamp(20);
+        client.submit_answer(&second, &1, &Bytes::from_array(&env, &[2]));
+        client.submit_answer(&third, &1, &payload);
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+
+        let page = client.get_submissions(&1, &1, &5);
+        assert_eq!(page.len(), 2);
+        let entry = page.get(0).unwrap();
+        assert_eq!(
+            (entry.player, entry.correct, entry.submitted_at, entry.index),
+            (second, false, 20, 1)
+        );
+        assert!(page.get(1).unwrap().correct);
+        let first = client.get_submissions(&1, &0, &1);
+        assert_eq!(first.get(0).unwrap().player, player);
+        assert!(client.get_submissions(&1, &3, &5).is_empty());
+        let missing = client.try_get_submissions(&9, &0, &5);
+        assert_eq!(missing, Err(Ok(Error::RoundNotFound)));
+    }
+
+    #[test]
+    fn test_streaks_tracked_and_bonus_weighted_into_payouts() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let other = Address::generate(&env);
+        let newcomer = Address::generate(&env);
+        client.set_streak_bonus(&2, &5_000);
+
+        let first = Bytes::from_array(&env, &[1]);
+        let second = Bytes::from_array(&env, &[2]);
+        let deadline = env.ledger().timestamp() + 100;
+        client.open_question(
+            &1,
+            &hash_answer(&env, &first),
+            &deadline,
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.submit_answer(&player, &1, &first);
+        client.submit_answer(&other, &1, &first);
+        client.reveal_answer(&1, &first, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
+        assert_eq!(client.claim_reward(&player, &1), 500);
+
+        client.open_question(
+            &2,
+            &hash_answer(&env, &second),
+            &deadline,
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.submit_answer(&player, &2, &second);
+        client.submit_answer(&other, &2, &first);
+        client.submit_answer(&newcomer, &2, &second);
+        client.reveal_answer(&2, &second, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &2, &true);
+
+        // Weights 15_000 + 10_000 split 1_000 as 600 / 400.
+        assert_eq!(client.claim_reward(&player, &2), 600);
+        assert_eq!(client.claim_reward(&newcomer, &2), 400);
+
+        assert_eq!(
+            client.get_player_stats(&player),
+            PlayerStats {
+                rounds_played: 2,
+                correct_answers: 2,
+                current_streak: 2,
+                best_streak: 2,
+            }
+        );
+        assert_eq!(
+            client.get_player_stats(&other),
+            PlayerStats {
+                rounds_played: 2,
+                correct_answers: 1,
+                current_streak: 0,
+                best_streak: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fastest_finger_pays_earliest_correct_answer() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, balance) = setup(&env);
+        let second = Address::generate(&env);
+        let third = Address::generate(&env);
+
+        let payload = Bytes::from_array(&env, &[4, 2]);
+        let deadline = env.ledger().timestamp() + 100;
+        let commitment = hash_answer(&env, &payload);
+        client.open_question(
+            &1,
+            &commitment,
+            &deadline,
+            &1_000,
+            &None,
+            &RoundMode::FastestFinger,
+        );
+
+        // A wrong answer first, then two correct ones in the same ledger:
+        // the lower submission index wins.
+        client.submit_answer(&third, &1, &Bytes::from_array(&env, &[9]));
+        client.submit_answer(&player, &1, &payload);
+        client.submit_answer(&second, &1, &payload);
+        assert_eq!(
+            client.reveal_answer(&1, &payload, &answer_salt(&env), &true),
+            1
+        );
+        client.finalize_round(&admin, &1, &true);
+
+        let round = client.get_round(&1).unwrap();
+        assert_eq!(round.winning_index, 1);
+        assert_eq!(round.payout_per_winner, 1_000);
+        assert_eq!(client.claim_reward(&player, &1), 1_000);
+        assert_eq!(balance.balance_of(&player), 1_000);
+        assert_eq!(
+            client.try_claim_reward(&second, &1),
+            Err(Ok(Error::NoRewardAvailable))
+        );
+    }
+
+    #[test]
+    fn test_participant_cap_and_counters() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, _balance) = setup(&env);
+        let second = Address::generate(&env);
+        let third = Address::generate(&env);
+
+        let payload = Bytes::from_array(&env, &[6]);
+        let deadline = env.ledger().timestamp() + 100;
+        let commitment = hash_answer(&env, &payload);
+        client.open_question(
+            &1,
+            &commitment,
+            &deadline,
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+        client.set_max_participants(&1, &2);
+
+        client.submit_answer(&player, &1, &payload);
+        client.submit_answer(&second, &1, &Bytes::from_array(&env, &[7]));
+        assert_eq!(
+            client.try_submit_answer(&third, &1, &payload),
+            Err(Ok(Error::RoundFull))
+        );
+        assert_eq!(
+            client.try_set_max_participants(&1, &1),
+            Err(Ok(Error::InvalidAmount))
+        );
+        assert_eq!(
+            client.try_set_max_participants(&1, &(MAX_PARTICIPANTS + 1)),
+            Err(Ok(Error::InvalidAmount))
+        );
+
+        let round = client.get_round(&1).unwrap();
+        assert_eq!((round.participant_count, round.correct_count), (2, 0));
+
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
+        let round = client.get_round(&1).unwrap();
+        assert_eq!((round.participant_count, round.correct_count), (2, 1));
+    }
+
+    #[test]
+    fn test_claim_rewards_batches_rounds() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, balance) = setup(&env);
+        let payload = Bytes::from_array(&env, &[3]);
+        let commitment = hash_answer(&env, &payload);
+        let deadline = env.ledger().timestamp() + 100;
+        for round_id in 1..=3u64 {
+            client.open_question(
+                &round_id,
+                &commitment,
+                &deadline,
+                &(round_id as i128 * 100),
+                &None,
+                &RoundMode::Shared,
+            );
+            client.submit_answer(&player, &round_id, &payload);
+            client.reveal_answer(&round_id, &payload, &answer_salt(&env), &true);
+            client.finalize_round(&admin, &round_id, &true);
+        }
+        client.claim_reward(&player, &2);
+
+        let batch = Vec::from_array(&env, [1u64, 2, 3]);
+        assert_eq!(
+            client.try_claim_rewards(&player, &batch),
+            Err(Ok(Error::AlreadyClaimed))
+        );
+        assert_eq!(balance.balance_of(&player), 200);
+
+        let batch = Vec::from_array(&env, [1u64, 3]);
+        assert_eq!(client.claim_rewards(&player, &batch), 400);
+        assert_eq!(balance.balance_of(&player), 600);
+        assert_eq!(
+            client.try_claim_rewards(&player, &Vec::new(&env)),
+            Err(Ok(Error::InvalidAmount))
+        );
+    }
+
+    #[test]
+    fn test_claim_payout_collects_tracked_wins() {
+        let env = Env::default();
+        let (client, admin, player, _trivia_id, balance) = setup(&env);
+        let payload = Bytes::from_array(&env, &[3]);
+        let commitment = hash_answer(&env, &payload);
+        let deadline = env.ledger().timestamp() + 100;
+        for round_id in 1..=3u64 {
+            client.open_question(
+                &round_id,
+                &commitment,
+                &deadline,
+                &100,
+                &None,
+                &RoundMode::Shared,
+            );
+            client.submit_answer(&player, &round_id, &payload);
+            client.reveal_answer(&round_id, &payload, &answer_salt(&env), &true);
+        }
+        client.finalize_round(&admin, &1, &true);
+        client.finalize_round(&admin, &2, &true);
+        client.claim_reward(&player, &2);
+
+        // Round 2 is already claimed and round 3 is not finalized yet.
+        assert_eq!(client.preview_payout(&player), 100);
+        assert_eq!(client.claim_payout(&player), 100);
+        assert_eq!(balance.balance_of(&player), 200);
+        assert_eq!(client.preview_payout(&player), 0);
+
+        client.finalize_round(&admin, &3, &true);
+        assert_eq!(client.preview_payout(&player), 100);
+        assert_eq!(client.claim_payout(&player), 100);
+        assert_eq!(client.claim_payout(&player), 0);
+        assert_eq!(balance.balance_of(&player), 300);
+    }
+
+    #[test]
+    fn test_uncapped_round_stops_at_max_participants() {
+        let env = Env::default();
+        let (client, admin, _player, _trivia_id, _balance) = setup(&env);
+        client.set_claim_window(&100);
+
+        let payload = Bytes::from_array(&env, &[6]);
+        let now = env.ledger().timestamp();
+        let deadline = now + 100;
+        let commitment = hash_answer(&env, &payload);
+        client.open_question(
+            &1,
+            &commitment,
+            &deadline,
+            &1_000,
+            &None,
+            &RoundMode::Shared,
+        );
+
+        for _ in 0..MAX_PARTICIPANTS {
+            client.submit_answer(&Address::generate(&env), &1, &payload);
+        }
+        assert_eq!(
+            client.try_submit_answer(&Address::generate(&env), &1, &payload),
+            Err(Ok(Error::RoundFull))
+        );
+
+        client.reveal_answer(&1, &payload, &answer_salt(&env), &true);
+        client.finalize_round(&admin, &1, &true);
+        let round = client.get_round(&1).unwrap();
+        assert_eq!(round.participant_count, MAX_PARTICIPANTS);
+        assert_eq!(round.correct_count, MAX_PARTICIPANTS);
+
+        env.ledger().set_timestamp(now + 100);
+        let summary = client.archive_round(&1);
+        assert_eq!(summary.submission_count, MAX_PARTICIPANTS);
+    }
 }
@@ -5,15 +5,19 @@
 //!
 //! ## Storage Strategy
 //! - `instance()`: Admin, FeeContract, RewardContract. Shared config.
-//! - `persistent()`: TournamentData, PlayerRegistration, Scores.
+//! - `persistent()`: TournamentData, PlayerRegistration, Scores, Standings.
 //!   Each tournament and registration is a separate ledger entry.
+//!
+//! Each tournament keeps its top `MAX_STANDINGS` players ranked by score, so
+//! the contract serves as the `StandingsSource` that season-rewards reads
+//! final standings from (the tournament id is the season id).
 
 #![no_std]
 #![allow(unexpected_cfgs)]
 
 use soroban_sdk::{
     contract, contracterror, contractevent, contractimpl, contracttype,
-    Address, BytesN, Env,
+    Address, BytesN, Env, Vec,
 };
 
 // ---------------------------------------------------------------------------
@@ -57,6 +61,14 @@ pub struct TournamentData {
     pub status: TournamentStatus,
 }
 
+/// One ranked entry of a tournament's standings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Standing {
+    pub player: Address,
+    pub score: i128,
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,
@@ -65,10 +77,14 @@ pub enum DataKey {
     Tournament(u64),
     PlayerJoined(u64, Address),
     PlayerScore(u64, Address),
+    Standings(u64),
 }
 
 const PERSISTENT_BUMP_LEDGERS: u32 = 518_400; // ~30 days
 
+/// Players kept in each tournament's ranked standings.
+pub const MAX_STANDINGS: u32 = 50;
+
 // ---------------------------------------------------------------------------
 // Events
 // ---------------------------------------------------------------------------
@@ -234,6 +250,7 @@ impl TournamentSystem {
         let score_key = DataKey::PlayerScore(id, player.clone());
         env.storage().persistent().set(&score_key, &score);
         env.storage().persistent().extend_ttl(&score_key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+        update_standings(&env, id, &player, score);
 
         ResultRecorded { id, player, score }.publish(&env);
 
@@ -278,6 +295,17 @@ impl TournamentSystem {
     pub fn is_joined(env: Env, id: u64, player: Address) -> bool {
         env.storage().persistent().has(&DataKey::PlayerJoined(id, player))
     }
+
+    /// Best `limit` players of tournament `id`, highest score first. Players
+    /// with equal scores keep the order their results were recorded in.
+    pub fn get_standings(env: Env, id: u64, limit: u32) -> Vec<Standing> {
+        let standings: Vec<Standing> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Standings(id))
+            .unwrap_or(Vec::new(&env));
+        standings.slice(0..limit.min(standings.len()))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -297,6 +325,42 @@ fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
     Ok(())
 }
 
+/// Re-rank `player` with their latest `score`, keeping at most
+/// `MAX_STANDINGS` entries.
+fn update_standings(env: &Env, id: u64, player: &Address, score: u64) {
+    let key = DataKey::Standings(id);
+    let mut standings: Vec<Standing> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+
+    if let Some(index) = standings.iter().position(|s| &s.player == player) {
+        standings.remove(index as u32);
+    }
+    let score = score as i128;
+    let rank = standings
+        .iter()
+        .position(|s| s.score < score)
+        .unwrap_or(standings.len() as usize) as u32;
+    if rank >= MAX_STANDINGS {
+        return;
+    }
+    let entry = Standing {
+        player: player.clone(),
+        score,
+    };
+    standings.insert(rank, entry);
+    while standings.len() > MAX_STANDINGS {
+        standings.pop_back();
+    }
+
+    env.storage().persistent().set(&key, &standings);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_BUMP_LEDGERS, PERSISTENT_BUMP_LEDGERS);
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -429,4 +493,41 @@ mod test {
         let result = client.try_create_tournament(&attacker, &1u64, &BytesN::from_array(&env, &[0u8; 32]), &0i128);
         assert_eq!(result, Err(Ok(Error::NotAuthorized)));
     }
+
+    #[test]
+    fn test_standings_rank_latest_scores() {
+        let env = Env::default();
+        let (client, admin, _, _) = setup(&env);
+
+        let id = 1u64;
+        env.mock_all_auths();
+        client.create_tournament(&admin, &id, &BytesN::from_array(&env, &[0u8; 32]), &0i128);
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+        for player in [&alice, &bob, &carol] {
+            client.join_tournament(player, &id);
+        }
+
+        client.record_result(&admin, &id, &alice, &300u64);
+        client.record_result(&admin, &id, &bob, &500u64);
+        client.record_result(&admin, &id, &carol, &300u64);
+        // A new result replaces the player's previous entry.
+        client.record_result(&admin, &id, &bob, &100u64);
+
+        let standings = client.get_standings(&id, &10);
+        assert_eq!(standings.len(), 3);
+        let ranked = [(&alice, 300), (&carol, 300), (&bob, 100)];
+        for (i, (player, score)) in ranked.into_iter().enumerate() {
+            let entry = standings.get(i as u32).unwrap();
+            assert_eq!(&entry.player, player);
+            assert_eq!(entry.score, score);
+        }
+
+        let top = client.get_standings(&id, &1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top.get(0).unwrap().player, alice);
+        assert_eq!(client.get_standings(&2, &10).len(), 0);
+    }
 }
@@ -3,15 +3,37 @@
 //! Core platform treasury for custody and controlled settlement of SEP-41
 //! funds. Supports deposits from external funders and admin-authorized
 //! allocations/releases to downstream contracts and recipients.
+//!
+//! Fees are paid in with `deposit_fees` so they can be told apart from
+//! external funding. An optional buyback policy routes a share of the fees
+//! deposited since the last buyback through a registered swap contract once
+//! per epoch and burns the platform tokens it delivers.
 #![no_std]
 #![allow(unexpected_cfgs)]
 
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, token::TokenClient,
-    Address, Env, Symbol,
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype,
+    token::TokenClient, Address, Env, Symbol,
 };
 
 pub const PERSISTENT_BUMP_LEDGERS: u32 = 518_400;
+pub const BASIS_POINTS: u32 = 10_000;
+
+/// Swap venue used by the buyback policy. The treasury transfers `amount_in`
+/// of `token_in` to the swap contract before calling `swap`; the swap must
+/// send at least `min_out` of `token_out` to `to`. The treasury measures what
+/// arrived rather than trusting the returned amount.
+#[contractclient(name = "SwapClient")]
+pub trait SwapContract {
+    fn swap(
+        env: Env,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_out: i128,
+        to: Address,
+    ) -> i128;
+}
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -27,6 +49,10 @@ pub enum Error {
     ContractPaused = 8,
     AlreadyPaused = 9,
     NotPaused = 10,
+    BuybackNotConfigured = 11,
+    BuybackEpochNotElapsed = 12,
+    NothingToBuyBack = 13,
+    SlippageExceeded = 14,
 }
 
 #[contracttype]
@@ -42,6 +68,18 @@ pub enum DataKey {
     ProcessedDeposit(DepositOp),
     ProcessedAllocation(AllocateOp),
     ProcessedRelease(ReleaseOp),
+    BuybackPolicy,
+    /// Sum of all `deposit_fees` amounts.
+    TotalFees,
+    /// `TotalFees` already accounted for by a buyback.
+    BuybackCursor,
+    /// Buyback share owed but not spent because `available` was short.
+    BuybackCarry,
+    LastBuybackEpoch,
+    TotalBoughtBack,
+    TotalBurned,
+    BuybackCount,
+    Buyback(u32),
 }
 
 #[contracttype]
@@ -65,6 +103,27 @@ pub struct ReleaseOp {
     pub purpose: Symbol,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuybackPolicy {
+    pub swap_contract: Address,
+    pub platform_token: Address,
+    pub share_bps: u32,
+    pub epoch_seconds: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuybackRecord {
+    pub epoch: u64,
+    pub fees_collected: i128,
+    pub spent: i128,
+    pub burned: i128,
+    /// Share left unspent for lack of funds, owed by the next buyback.
+    pub carried_over: i128,
+    pub executed_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TreasuryState {
@@ -75,6 +134,7 @@ pub struct TreasuryState {
     pub total_deposited: i128,
     pub total_allocated: i128,
     pub total_released: i128,
+    pub total_bought_back: i128,
 }
 
 #[contractevent]
@@ -91,6 +151,14 @@ pub struct Deposited {
     pub reason: Symbol,
 }
 
+#[contractevent]
+pub struct FeesDeposited {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+    pub reason: Symbol,
+}
+
 #[contractevent]
 pub struct Allocated {
     #[topic]
@@ -107,6 +175,23 @@ pub struct Released {
     pub purpose: Symbol,
 }
 
+#[contractevent]
+pub struct BuybackPolicySet {
+    pub swap_contract: Address,
+    pub platform_token: Address,
+    pub share_bps: u32,
+    pub epoch_seconds: u64,
+}
+
+#[contractevent]
+pub struct BuybackExecuted {
+    #[topic]
+    pub epoch: u64,
+    pub fees_collected: i128,
+    pub spent: i128,
+    pub burned: i128,
+}
+
 #[contractevent]
 pub struct PauseChanged {
     pub paused: bool,
@@ -135,6 +220,7 @@ impl Treasury {
         set_i128(&env, DataKey::TotalDeposited, 0);
         set_i128(&env, DataKey::TotalAllocated, 0);
         set_i128(&env, DataKey::TotalReleased, 0);
+        set_i128(&env, DataKey::TotalBoughtBack, 0);
 
         Initialized {
             admin,
@@ -176,42 +262,34 @@ impl Treasury {
     }
 
     pub fn deposit(env: Env, from: Address, amount: i128, reason: Symbol) -> Result<(), Error> {
-        require_initialized(&env)?;
-        require_not_paused(&env)?;
+        take_deposit(&env, &from, amount, &reason)?;
 
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-
-        let op = DepositOp {
-            from: from.clone(),
-            reason: reason.clone(),
-        };
-        let op_key = DataKey::ProcessedDeposit(op);
-        if env.storage().persistent().has(&op_key) {
-            return Err(Error::DuplicateOperation);
+        Deposited {
+            from,
+            amount,
+            reason,
         }
+        .publish(&env);
 
-        from.require_auth();
+        Ok(())
+    }
 
-        let token = get_token(&env);
-        let contract_address = env.current_contract_address();
-        TokenClient::new(&env, &token).transfer(&from, &contract_address, &amount);
+    /// Deposit house fees. Accounted exactly like `deposit`, and also counted
+    /// as fees for the buyback policy; plain deposits are never bought back.
+    pub fn deposit_fees(
+        env: Env,
+        from: Address,
+        amount: i128,
+        reason: Symbol,
+    ) -> Result<(), Error> {
+        take_deposit(&env, &from, amount, &reason)?;
 
-        let available = get_available(&env)
-            .checked_add(amount)
-            .ok_or(Error::Overflow)?;
-        let total_deposited = get_total_deposited(&env)
+        let total_fees = get_i128(&env, DataKey::TotalFees)
             .checked_add(amount)
             .ok_or(Error::Overflow)?;
+        set_i128(&env, DataKey::TotalFees, total_fees);
 
-        set_i128(&env, DataKey::Available, available);
-        set_i128(&env, DataKey::TotalDeposited, total_deposited);
-
-        env.storage().persistent().set(&op_key, &());
-        extend_persistent_ttl(&env, &op_key);
-
-        Deposited {
+        FeesDeposited {
             from,
             amount,
             reason,
@@ -333,12 +411,14 @@ impl Treasury {
             total_deposited: get_total_deposited(&env),
             total_allocated: get_total_allocated(&env),
             total_released: get_total_released(&env),
+            total_bought_back: get_i128(&env, DataKey::TotalBoughtBack),
         };
 
         let expected_available = state
             .total_deposited
             .checked_sub(state.total_allocated)
             .and_then(|v| v.checked_sub(state.total_released))
+            .and_then(|v| v.checked_sub(state.total_bought_back))
             .ok_or(Error::Overflow)?;
 
         if expected_available != state.available_balance {
@@ -347,6 +427,231 @@ impl Treasury {
 
         Ok(state)
     }
+
+    /// Configure the buyback policy. `share_bps` of the fees deposited with
+    /// `deposit_fees` since the previous buyback are spent on each execution,
+    /// at most once per `epoch_seconds` window. Fees collected before the
+    /// policy is first set are not eligible.
+    pub fn set_buyback_policy(
+        env: Env,
+        admin: Address,
+        swap_contract: Address,
+        platform_token: Address,
+        share_bps: u32,
+        epoch_seconds: u64,
+    ) -> Result<(), Error> {
+        require_initialized(&env)?;
+        require_admin(&env, &admin)?;
+
+        if share_bps == 0 || share_bps > BASIS_POINTS || epoch_seconds == 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        if !env.storage().instance().has(&DataKey::BuybackPolicy) {
+            set_i128(
+                &env,
+                DataKey::BuybackCursor,
+                get_i128(&env, DataKey::TotalFees),
+            );
+        }
+
+        let policy = BuybackPolicy {
+            swap_contract: swap_contract.clone(),
+            platform_token: platform_token.clone(),
+            share_bps,
+            epoch_seconds,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::BuybackPolicy, &policy);
+
+        BuybackPolicySet {
+            swap_contract,
+            platform_token,
+            share_bps,
+            epoch_seconds,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Spend the configured share of newly collected fees on the platform
+    /// token and burn everything the swap delivers. Admin only, since
+    /// `min_out` is the sole protection against an unfavourable swap price.
+    ///
+    /// The spend is capped at `available`; any shortfall is carried over and
+    /// added to the next epoch's share.
+    pub fn execute_buyback(env: Env, min_out: i128) -> Result<BuybackRecord, Error> {
+        require_initialized(&env)?;
+        require_not_paused(&env)?;
+        require_admin_as_invoker(&env)?;
+
+        if min_out < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let policy: BuybackPolicy = env
+            .storage()
+            .instance()
+            .get(&DataKey::BuybackPolicy)
+            .ok_or(Error::BuybackNotConfigured)?;
+
+        let now = env.ledger().timestamp();
+        let epoch = now / policy.epoch_seconds;
+        let last_epoch: Option<u64> = env.storage().persistent().get(&DataKey::LastBuybackEpoch);
+        if last_epoch.is_some_and(|last| last >= epoch) {
+            return Err(Error::BuybackEpochNotElapsed);
+        }
+
+        let total_fees = get_i128(&env, DataKey::TotalFees);
+        let fees_collected = total_fees
+            .checked_sub(get_i128(&env, DataKey::BuybackCursor))
+            .ok_or(Error::Overflow)?;
+        let owed = (fees_collected
+            .checked_mul(policy.share_bps as i128)
+            .ok_or(Error::Overflow)?
+            / BASIS_POINTS as i128)
+            .checked_add(get_i128(&env, DataKey::BuybackCarry))
+            .ok_or(Error::Overflow)?;
+        let available = get_available(&env);
+        let spent = owed.min(available);
+        if spent <= 0 {
+            return Err(Error::NothingToBuyBack);
+        }
+        let carried_over = owed.checked_sub(spent).ok_or(Error::Overflow)?;
+
+        let new_available = available.checked_sub(spent).ok_or(Error::Overflow)?;
+        let total_bought_back = get_i128(&env, DataKey::TotalBoughtBack)
+            .checked_add(spent)
+            .ok_or(Error::Overflow)?;
+
+        set_i128(&env, DataKey::Available, new_available);
+        set_i128(&env, DataKey::TotalBoughtBack, total_bought_back);
+        set_i128(&env, DataKey::BuybackCursor, total_fees);
+        set_i128(&env, DataKey::BuybackCarry, carried_over);
+        env.storage()
+            .persistent()
+            .set(&DataKey::LastBuybackEpoch, &epoch);
+        extend_persistent_ttl(&env, &DataKey::LastBuybackEpoch);
+
+        let token = get_token(&env);
+        let contract_address = env.current_contract_address();
+        TokenClient::new(&env, &token).transfer(&contract_address, &policy.swap_contract, &spent);
+        let platform = TokenClient::new(&env, &policy.platform_token);
+        let balance_before = platform.balance(&contract_address);
+        SwapClient::new(&env, &policy.swap_contract).swap(
+            &token,
+            &policy.platform_token,
+            &spent,
+            &min_out,
+            &contract_address,
+        );
+        let burned = platform
+            .balance(&contract_address)
+            .checked_sub(balance_before)
+            .ok_or(Error::Overflow)?;
+        if burned < min_out || burned <= 0 {
+            return Err(Error::SlippageExceeded);
+        }
+        platform.burn(&contract_address, &burned);
+
+        let total_burned = get_i128(&env, DataKey::TotalBurned)
+            .checked_add(burned)
+            .ok_or(Error::Overflow)?;
+        set_i128(&env, DataKey::TotalBurned, total_burned);
+
+        let record = BuybackRecord {
+            epoch,
+            fees_collected,
+            spent,
+            burned,
+            carried_over,
+            executed_at: now,
+        };
+        let index: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BuybackCount)
+            .unwrap_or(0);
+        let record_key = DataKey::Buyback(index);
+        env.storage().persistent().set(&record_key, &record);
+        extend_persistent_ttl(&env, &record_key);
+        let count = index.checked_add(1).ok_or(Error::Overflow)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::BuybackCount, &count);
+        extend_persistent_ttl(&env, &DataKey::BuybackCount);
+
+        BuybackExecuted {
+            epoch,
+            fees_collected,
+            spent,
+            burned,
+        }
+        .publish(&env);
+
+        Ok(record)
+    }
+
+    pub fn get_buyback_policy(env: Env) -> Option<BuybackPolicy> {
+        env.storage().instance().get(&DataKey::BuybackPolicy)
+    }
+
+    pub fn get_buyback_count(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BuybackCount)
+            .unwrap_or(0)
+    }
+
+    pub fn get_buyback(env: Env, index: u32) -> Option<BuybackRecord> {
+        env.storage().persistent().get(&DataKey::Buyback(index))
+    }
+
+    pub fn get_total_burned(env: Env) -> i128 {
+        get_i128(&env, DataKey::TotalBurned)
+    }
+}
+
+/// Pull `amount` from `from` into the treasury and account it as a deposit,
+/// guarded by the `(from, reason)` idempotency key.
+fn take_deposit(env: &Env, from: &Address, amount: i128, reason: &Symbol) -> Result<(), Error> {
+    require_initialized(env)?;
+    require_not_paused(env)?;
+
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+
+    let op = DepositOp {
+        from: from.clone(),
+        reason: reason.clone(),
+    };
+    let op_key = DataKey::ProcessedDeposit(op);
+    if env.storage().persistent().has(&op_key) {
+        return Err(Error::DuplicateOperation);
+    }
+
+    from.require_auth();
+
+    let token = get_token(env);
+    let contract_address = env.current_contract_address();
+    TokenClient::new(env, &token).transfer(from, &contract_address, &amount);
+
+    let available = get_available(env)
+        .checked_add(amount)
+        .ok_or(Error::Overflow)?;
+    let total_deposited = get_total_deposited(env)
+        .checked_add(amount)
+        .ok_or(Error::Overflow)?;
+
+    set_i128(env, DataKey::Available, available);
+    set_i128(env, DataKey::TotalDeposited, total_deposited);
+
+    env.storage().persistent().set(&op_key, &());
+    extend_persistent_ttl(env, &op_key);
+    Ok(())
 }
 
 fn require_initialized(env: &Env) -> Result<(), Error> {
@@ -427,6 +732,10 @@ fn get_total_released(env: &Env) -> i128 {
         .unwrap_or(0)
 }
 
+fn get_i128(env: &Env, key: DataKey) -> i128 {
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
 fn set_i128(env: &Env, key: DataKey, value: i128) {
     env.storage().persistent().set(&key, &value);
     extend_persistent_ttl(env, &key);
@@ -442,8 +751,8 @@ fn extend_persistent_ttl(env: &Env, key: &DataKey) {
 mod test {
     use super::*;
     use soroban_sdk::{
-        symbol_short,
-        testutils::{Address as _, Events as _},
+        contract, contractimpl, symbol_short,
+        testutils::{Address as _, Events as _, Ledger as _},
         token::{StellarAssetClient, TokenClient},
         Address, Env,
     };
@@ -470,6 +779,54 @@ mod test {
         (client, admin, funder, token_address, contract_id)
     }
 
+    #[contract]
+    struct MockSwap;
+
+    #[contractimpl]
+    impl MockSwap {
+        /// Fixed rate of two platform tokens per fee token, paid from the
+        /// swap's own inventory.
+        pub fn swap(
+            env: Env,
+            _token_in: Address,
+            token_out: Address,
+            amount_in: i128,
+            _min_out: i128,
+            to: Address,
+        ) -> i128 {
+            let amount_out = amount_in * 2;
+            TokenClient::new(&env, &token_out).transfer(
+                &env.current_contract_address(),
+                &to,
+                &amount_out,
+            );
+            amount_out
+        }
+    }
+
+    #[contract]
+    struct OverreportingSwap;
+
+    #[contractimpl]
+    impl OverreportingSwap {
+        /// Delivers two platform tokens per fee token but claims three.
+        pub fn swap(
+            env: Env,
+            _token_in: Address,
+            token_out: Address,
+            amount_in: i128,
+            _min_out: i128,
+            to: Address,
+        ) -> i128 {
+            TokenClient::new(&env, &token_out).transfer(
+                &env.current_contract_address(),
+                &to,
+                &(amount_in * 2),
+            );
+            amount_in * 3
+        }
+    }
+
     fn token_client<'a>(env: &'a Env, token: &Address) -> TokenClient<'a> {
         TokenClient::new(env, token)
     }
@@ -672,4 +1029,109 @@ mod test {
         let bad_unpause = client.try_unpause(&outsider);
         assert!(bad_unpause.is_err());
     }
+
+    #[test]
+    fn test_buyback_burns_platform_token_once_per_epoch() {
+        let env = Env::default();
+        let (client, admin, funder, token_addr, treasury_addr) = setup(&env);
+        env.mock_all_auths();
+
+        let (platform_token, platform_sac) = create_token(&env, &Address::generate(&env));
+        let swap_id = env.register(MockSwap, ());
+        platform_sac.mint(&swap_id, &10_000);
+
+        assert_eq!(
+            client.try_execute_buyback(&0),
+            Err(Ok(Error::BuybackNotConfigured))
+        );
+
+        // Fees collected before the policy exists are not eligible, and plain
+        // deposits are never treated as fees.
+        client.deposit_fees(&funder, &4_000, &symbol_short!("early"));
+        client.set_buyback_policy(&admin, &swap_id, &platform_token, &2_000, &100);
+        client.deposit(&funder, &1_000, &symbol_short!("funding"));
+        assert_eq!(
+            client.try_execute_buyback(&0),
+            Err(Ok(Error::NothingToBuyBack))
+        );
+
+        client.deposit_fees(&funder, &1_000, &symbol_short!("fees1"));
+        assert_eq!(
+            client.try_execute_buyback(&401),
+            Err(Ok(Error::SlippageExceeded))
+        );
+
+        let record = client.execute_buyback(&400);
+        assert_eq!(record.fees_collected, 1_000);
+        assert_eq!(record.spent, 200);
+        assert_eq!(record.burned, 400);
+        assert_eq!(record.carried_over, 0);
+        assert_eq!(client.get_buyback(&0), Some(record));
+        assert_eq!(client.get_buyback_count(), 1);
+        assert_eq!(client.get_total_burned(), 400);
+        assert_eq!(token_client(&env, &token_addr).balance(&swap_id), 200);
+        assert_eq!(token_client(&env, &platform_token).balance(&swap_id), 9_600);
+        assert_eq!(
+            token_client(&env, &platform_token).balance(&treasury_addr),
+            0
+        );
+
+        let state = client.treasury_state();
+        assert_eq!(state.available_balance, 5_800);
+        assert_eq!(state.total_bought_back, 200);
+
+        client.deposit_fees(&funder, &500, &symbol_short!("fees2"));
+        assert_eq!(
+            client.try_execute_buyback(&0),
+            Err(Ok(Error::BuybackEpochNotElapsed))
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        let record = client.execute_buyback(&0);
+        assert_eq!(record.fees_collected, 500);
+        assert_eq!(record.spent, 100);
+        assert_eq!(client.get_buyback_count(), 2);
+        assert_eq!(client.get_total_burned(), 600);
+    }
+
+    #[test]
+    fn test_buyback_carries_shortfall_and_burns_measured_amount() {
+        let env = Env::default();
+        let (client, admin, funder, _token_addr, treasury_addr) = setup(&env);
+        env.mock_all_auths();
+
+        let (platform_token, platform_sac) = create_token(&env, &Address::generate(&env));
+        let swap_id = env.register(OverreportingSwap, ());
+        platform_sac.mint(&swap_id, &10_000);
+        client.set_buyback_policy(&admin, &swap_id, &platform_token, &5_000, &100);
+
+        // Half of 1_000 in fees is owed, but only 300 is left after a release.
+        client.deposit_fees(&funder, &1_000, &symbol_short!("fees1"));
+        client.release(&Address::generate(&env), &700, &symbol_short!("payout"));
+
+        // A swap reporting more than it delivered cannot satisfy min_out.
+        assert_eq!(
+            client.try_execute_buyback(&601),
+            Err(Ok(Error::SlippageExceeded))
+        );
+
+        let record = client.execute_buyback(&600);
+        assert_eq!(record.spent, 300);
+        assert_eq!(record.burned, 600);
+        assert_eq!(record.carried_over, 200);
+        assert_eq!(client.get_total_burned(), 600);
+        assert_eq!(
+            token_client(&env, &platform_token).balance(&treasury_addr),
+            0
+        );
+
+        // The shortfall is spent next epoch even without new fees.
+        client.deposit(&funder, &1_000, &symbol_short!("funding"));
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        let record = client.execute_buyback(&0);
+        assert_eq!(record.fees_collected, 0);
+        assert_eq!(record.spent, 200);
+        assert_eq!(record.carried_over, 0);
+        assert_eq!(client.treasury_state().available_balance, 800);
+    }
 }